@@ -0,0 +1,15 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use the bundled protoc so builds do not depend on one being installed.
+    if std::env::var_os("PROTOC").is_none() {
+        // SAFETY: build scripts are single-threaded.
+        unsafe { std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?) };
+    }
+    println!("cargo:rerun-if-changed=proto/espo.proto");
+    // Messages deserialize from the JSON-RPC result documents they mirror, so a field
+    // that drifts on either side fails the conversion instead of reading as a default.
+    tonic_build::configure()
+        .build_client(false)
+        .type_attribute(".espo", "#[derive(serde::Deserialize)]")
+        .compile(&["proto/espo.proto"], &["proto"])?;
+    Ok(())
+}
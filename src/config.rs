@@ -1,18 +1,22 @@
 use crate::alkanes::metashrew::MetashrewAdapter;
 use crate::runtime::{
-    dbpaths::get_sdb_path_for_metashrew, mdb::Mdb, sdb::SDB, tree_db::VersionedTreeDb,
+    dbpaths::get_sdb_path_for_metashrew,
+    export::{ExportFormat, ExportKind, ExportParams},
+    mdb::Mdb,
+    sdb::SDB,
+    tree_db::VersionedTreeDb,
 };
 use crate::utils::electrum_like::{ElectrumLike, ElectrumRpcClient, EsploraElectrumLike};
 use crate::{ESPO_HEIGHT, SAFE_TIP};
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use electrum_client::Client;
 use rocksdb::{DB, Options};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::str::FromStr;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::{
     fs,
     path::Path,
@@ -39,6 +43,9 @@ static BLOCK_SOURCE: OnceLock<BlkOrRpcBlockSource> = OnceLock::new();
 // NEW: Global bitcoin::Network
 static NETWORK: OnceLock<Network> = OnceLock::new();
 
+// Runtime override of `debug`, set through `admin.set_debug`.
+static DEBUG_OVERRIDE: OnceLock<AtomicBool> = OnceLock::new();
+
 fn parse_network(s: &str) -> Result<Network> {
     let normalized = s.trim().to_ascii_lowercase();
     let mapped = match normalized.as_str() {
@@ -161,6 +168,15 @@ pub struct DebugBackupConfig {
     pub dir: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdminConfig {
+    /// Bearer token required for `admin.*` RPC methods.
+    pub token: String,
+    /// Where `admin.snapshot` copies the db; falls back to `debug_backup.dir`.
+    #[serde(default)]
+    pub snapshot_dir: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct StrictModeConfig {
     pub check_utxos: bool,
@@ -232,6 +248,8 @@ pub struct ConfigFile {
     #[serde(default = "default_port")]
     pub port: u16,
     #[serde(default)]
+    pub grpc_host: Option<SocketAddr>,
+    #[serde(default)]
     pub explorer_host: Option<SocketAddr>,
     #[serde(default = "default_explorer_base_path")]
     pub explorer_base_path: String,
@@ -250,6 +268,8 @@ pub struct ConfigFile {
     #[serde(default)]
     pub debug_backup: Option<DebugBackupConfig>,
     #[serde(default)]
+    pub admin: Option<AdminConfig>,
+    #[serde(default)]
     pub safe_tip_hook_script: Option<String>,
     #[serde(default = "default_block_source_mode")]
     pub block_source_mode: String,
@@ -281,6 +301,7 @@ pub struct AppConfig {
     pub sdb_poll_ms: u16,
     pub indexer_block_delay_ms: u64,
     pub port: u16,
+    pub grpc_host: Option<SocketAddr>,
     pub explorer_host: Option<SocketAddr>,
     pub explorer_base_path: String,
     pub explorer_pizza_tv_endpoint: String,
@@ -290,6 +311,7 @@ pub struct AppConfig {
     pub debug: bool,
     pub debug_ignore_ms: u64,
     pub debug_backup: Option<DebugBackupConfig>,
+    pub admin: Option<AdminConfig>,
     pub safe_tip_hook_script: Option<String>,
     pub block_source_mode: BlockFetchMode,
     pub compact_tx_trace_rows: bool,
@@ -309,6 +331,63 @@ pub struct CliArgs {
     /// Serve existing data without running the indexer or mempool service.
     #[arg(long, default_value_t = false)]
     pub view_only: bool,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum CliCommand {
+    /// Dump a dataset as CSV / NDJSON and exit. Opens the ESPO db directly, so run it
+    /// against a stopped instance (or use `GET /export/{kind}` on a running one).
+    Export(ExportArgs),
+    /// Write the holder list of one or more alkanes at a height as an airdrop CSV and print
+    /// its sha256. Opens the ESPO db directly, like `export`.
+    Airdrop(AirdropArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportArgs {
+    /// Dataset to export.
+    #[arg(value_enum)]
+    pub kind: ExportKind,
+
+    #[command(flatten)]
+    pub params: ExportParams,
+
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+
+    /// Output file; defaults to stdout.
+    #[arg(long)]
+    pub out: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AirdropArgs {
+    /// Token to include, as "block:tx" or "block:tx=<weight>"; repeat to merge several.
+    #[arg(long = "token", required = true)]
+    pub tokens: Vec<String>,
+
+    /// Height to read holders at (defaults to the indexed tip).
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    /// Ignore balances of a token below this amount.
+    #[arg(long, default_value_t = 0)]
+    pub min_balance: u128,
+
+    /// Leave out alkane holders (pools, vaults and other contracts).
+    #[arg(long, default_value_t = false)]
+    pub exclude_contracts: bool,
+
+    /// Address to leave out; repeatable.
+    #[arg(long = "exclude-address")]
+    pub exclude_addresses: Vec<String>,
+
+    /// Output file; defaults to stdout.
+    #[arg(long)]
+    pub out: Option<String>,
 }
 
 fn load_config_file(path: &str) -> Result<ConfigFile> {
@@ -318,6 +397,14 @@ fn load_config_file(path: &str) -> Result<ConfigFile> {
 }
 
 impl AppConfig {
+    /// Directory used by `admin.snapshot`.
+    pub fn snapshot_dir(&self) -> Option<&str> {
+        self.admin
+            .as_ref()
+            .and_then(|admin| admin.snapshot_dir.as_deref())
+            .or_else(|| self.debug_backup.as_ref().map(|backup| backup.dir.as_str()))
+    }
+
     fn from_file(file: ConfigFile, view_only: bool) -> Result<Self> {
         let network = parse_network(&file.network)?;
         let block_source_mode =
@@ -329,6 +416,19 @@ impl AppConfig {
         let explorer_networks = file.explorer_networks.and_then(|n| n.normalized());
         let google_analytics_tag = normalize_optional_string(file.google_analytics_tag);
         let debug_backup = file.debug_backup;
+        let admin = match file.admin {
+            Some(admin) => {
+                let token = admin.token.trim().to_string();
+                if token.is_empty() {
+                    anyhow::bail!("admin.token must be a non-empty string");
+                }
+                Some(AdminConfig {
+                    token,
+                    snapshot_dir: normalize_optional_string(admin.snapshot_dir),
+                })
+            }
+            None => None,
+        };
 
         Ok(Self {
             readonly_metashrew_db_dir: file.readonly_metashrew_db_dir,
@@ -345,6 +445,7 @@ impl AppConfig {
             sdb_poll_ms: file.sdb_poll_ms,
             indexer_block_delay_ms: file.indexer_block_delay_ms,
             port: file.port,
+            grpc_host: file.grpc_host,
             explorer_host: file.explorer_host,
             explorer_base_path,
             explorer_pizza_tv_endpoint,
@@ -354,6 +455,7 @@ impl AppConfig {
             debug: file.debug,
             debug_ignore_ms: file.debug_ignore_ms,
             debug_backup,
+            admin,
             safe_tip_hook_script: normalize_optional_string(file.safe_tip_hook_script),
             block_source_mode,
             compact_tx_trace_rows: file.compact_tx_trace_rows,
@@ -512,10 +614,13 @@ pub fn init_config_from(cfg: AppConfig) -> Result<()> {
     Ok(())
 }
 
-pub fn init_config() -> Result<()> {
+/// Parses the CLI, initializes global config from it, and hands back the parsed args
+/// so the caller can dispatch subcommands.
+pub fn init_config() -> Result<CliArgs> {
     let cli = CliArgs::parse();
     let cfg = load_config_from_path(&cli.config_path, cli.view_only)?;
-    init_config_from(cfg)
+    init_config_from(cfg)?;
+    Ok(cli)
 }
 
 pub fn load_config_from_path(path: &str, view_only: bool) -> Result<AppConfig> {
@@ -549,6 +654,11 @@ pub fn get_module_config(name: &str) -> Option<&'static serde_json::Value> {
     get_config().modules.get(name)
 }
 
+/// Like `get_config`, but returns None instead of panicking before `init_config()`.
+pub fn try_get_config() -> Option<&'static AppConfig> {
+    CONFIG.get()
+}
+
 pub fn get_electrum_client() -> Option<Arc<Client>> {
     ELECTRUM_CLIENT.get().cloned()
 }
@@ -672,7 +782,16 @@ pub fn strict_check_trace_mismatches() -> bool {
 }
 
 pub fn debug_enabled() -> bool {
-    CONFIG.get().map(|cfg| cfg.debug).unwrap_or(false)
+    match DEBUG_OVERRIDE.get() {
+        Some(flag) => flag.load(Ordering::Relaxed),
+        None => CONFIG.get().map(|cfg| cfg.debug).unwrap_or(false),
+    }
+}
+
+pub fn set_debug_enabled(enabled: bool) {
+    DEBUG_OVERRIDE
+        .get_or_init(|| AtomicBool::new(enabled))
+        .store(enabled, Ordering::Relaxed);
 }
 
 pub fn debug_ignore_ms() -> u64 {
@@ -16,8 +16,9 @@ use crate::modules::ammdata::storage::{
     AmmDataProvider, GetTokenSearchIndexPageParams, RpcGetCandlesParams, SearchIndexField,
 };
 use crate::modules::essentials::storage::{
-    BlockSummary, EssentialsProvider, EssentialsTable, GetAlkaneIdsByNamePrefixPageParams,
-    GetListEntriesDescParams, HoldersCountEntry, get_cached_block_summary, load_creation_record,
+    BlockSummary, EssentialsProvider, EssentialsTable, GetAddressBalanceHistoryParams,
+    GetAlkaneIdsByNamePrefixPageParams, GetListEntriesDescParams, GetRawValueParams,
+    HoldersCountEntry, decode_u128_value, get_cached_block_summary, load_creation_record,
 };
 use crate::modules::essentials::utils::names::normalize_alkane_name;
 use crate::runtime::mdb::Mdb;
@@ -724,7 +725,7 @@ pub async fn address_chart(Query(q): Query<AddressChartQuery>) -> Json<AddressCh
     };
 
     let range = normalize_address_chart_range(q.range.as_deref());
-    let (lookback_blocks, range_interval) = address_chart_range_params(&range);
+    let (lookback_blocks, _) = address_chart_range_params(&range);
     let Some((indexed_min, indexed_max)) =
         get_espo_module_mdb("essentials").indexed_height_bounds().ok().flatten()
     else {
@@ -753,79 +754,60 @@ pub async fn address_chart(Query(q): Query<AddressChartQuery>) -> Json<AddressCh
         });
     }
 
-    let body = json!({
-        "jsonrpc": "2.0",
-        "id": format!("address-chart:{}:{}:{}", address, alkane.block, alkane.tx),
-        "method": "get_method_line_chart",
-        "params": {
-            "method": "essentials.get_address_balances",
-            "body": {
-                "address": address,
-            },
-            "range_min": range_min,
-            "range_max": range_max,
-            "range_interval": range_interval,
-            "key": format!("balances.{}", alkane_id_str(&alkane)),
-        },
-    });
-
-    let rpc_url = format!("http://127.0.0.1:{}/rpc", get_config().port);
-    let resp_json: Value = match Client::new().post(&rpc_url).json(&body).send().await {
-        Ok(resp) => match resp.error_for_status() {
-            Ok(ok) => match ok.json().await {
-                Ok(v) => v,
-                Err(_) => {
-                    return Json(AddressChartResponse {
-                        ok: false,
-                        available: false,
-                        range,
-                        points: Vec::new(),
-                        error: Some("response_decode_failed".to_string()),
-                    });
-                }
-            },
-            Err(_) => {
-                return Json(AddressChartResponse {
-                    ok: false,
-                    available: false,
-                    range,
-                    points: Vec::new(),
-                    error: Some("metashrew_http_error".to_string()),
-                });
-            }
-        },
+    // Built from the per-address balance change log rather than replaying balances per height.
+    let essentials = EssentialsProvider::new(get_espo_module_mdb("essentials"));
+    let history = match essentials.get_address_balance_history(GetAddressBalanceHistoryParams {
+        blockhash: StateAt::Latest,
+        address: address.clone(),
+        alkane: Some(alkane),
+        from_height: range_min,
+        to_height: range_max,
+        after: None,
+        limit: usize::MAX,
+    }) {
+        Ok(history) => history,
         Err(_) => {
             return Json(AddressChartResponse {
                 ok: false,
                 available: false,
                 range,
                 points: Vec::new(),
-                error: Some("metashrew_request_failed".to_string()),
+                error: Some("balance_history_failed".to_string()),
             });
         }
     };
+    let changes: Vec<(u32, u128)> = history
+        .entries
+        .iter()
+        .map(|(_, height, _, entry)| (*height, entry.balance))
+        .collect();
+    let opening = match history.opening.get(&alkane) {
+        Some(balance) => *balance,
+        // No change before the range: start from the balance before the first change, or
+        // hold the current balance flat when nothing changed in range either.
+        None => match history.entries.first() {
+            Some((_, _, _, first)) => {
+                let (is_negative, amount) = first.delta.as_parts();
+                if is_negative {
+                    first.balance.saturating_add(amount)
+                } else {
+                    first.balance.saturating_sub(amount)
+                }
+            }
+            None => essentials
+                .get_raw_value(GetRawValueParams {
+                    blockhash: StateAt::Latest,
+                    key: essentials.table().address_balance_key(&address, &alkane),
+                })
+                .ok()
+                .and_then(|res| res.value)
+                .and_then(|raw| decode_u128_value(&raw).ok())
+                .unwrap_or(0),
+        },
+    };
 
-    if let Some(err) = resp_json.get("error") {
-        let detail = err
-            .get("data")
-            .and_then(|d| d.get("detail"))
-            .and_then(|d| d.as_str())
-            .map(str::to_string);
-        let message = err.get("message").and_then(|m| m.as_str()).map(str::to_string);
-        let fallback = err.as_str().map(str::to_string);
-        return Json(AddressChartResponse {
-            ok: false,
-            available: false,
-            range,
-            points: Vec::new(),
-            error: detail.or(message).or(fallback).or(Some("line_chart_failed".to_string())),
-        });
-    }
-
-    let points = parse_address_chart_points(
-        resp_json.get("result").and_then(|r| r.get("points")).and_then(|v| v.as_array()),
-    );
-    let available = !points.is_empty();
+    let points = address_balance_points(opening, &changes, range_min, range_max);
+    let available = points.iter().any(|p| p.value > 0.0);
 
     Json(AddressChartResponse { ok: true, available, range, points, error: None })
 }
@@ -1617,6 +1599,7 @@ fn rpc_get_candles_value(
             page: Some(1),
             side: None,
             now: None,
+            blocks: None,
         })
         .ok()
         .map(|resp| resp.value)
@@ -1646,6 +1629,30 @@ fn parse_candles(value: &Value) -> Vec<AlkaneChartPoint> {
         .unwrap_or_default()
 }
 
+/// Step series over `range_min..=range_max`: the opening balance, then the balance after the
+/// last change of each height that changed, held to `range_max`.
+fn address_balance_points(
+    opening: u128,
+    changes: &[(u32, u128)],
+    range_min: u32,
+    range_max: u32,
+) -> Vec<AddressChartPoint> {
+    let scaled = |v: u128| v as f64 / (ALKANE_SCALE as f64);
+    let mut points = vec![AddressChartPoint { height: range_min, value: scaled(opening) }];
+    let mut last = opening;
+    for (height, balance) in changes {
+        match points.last_mut() {
+            Some(point) if point.height == *height => point.value = scaled(*balance),
+            _ => points.push(AddressChartPoint { height: *height, value: scaled(*balance) }),
+        }
+        last = *balance;
+    }
+    if points.last().is_some_and(|p| p.height < range_max) {
+        points.push(AddressChartPoint { height: range_max, value: scaled(last) });
+    }
+    points
+}
+
 fn parse_address_chart_points(points: Option<&Vec<Value>>) -> Vec<AddressChartPoint> {
     points
         .map(|arr| {
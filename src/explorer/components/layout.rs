@@ -189,6 +189,7 @@ pub fn layout_with_meta(
                             div class="navlinks-container" {
                                 a class="navlink" href=(explorer_path("/")) { "Blocks" }
                                 a class="navlink" href=(explorer_path("/alkanes")) { "Alkanes" }
+                                a class="navlink" href=(explorer_path("/mev")) { "MEV" }
                             }
                             div class="nav-actions" {
                                 button class="nav-icon-btn nav-search-toggle" type="button" aria-label="Search" data-search-toggle="" {
@@ -201,6 +202,7 @@ pub fn layout_with_meta(
                             div class="nav-menu" data-menu="" aria-hidden="true" {
                                 a class="nav-menu-link" href=(explorer_path("/")) { "Blocks" }
                                 a class="nav-menu-link" href=(explorer_path("/alkanes")) { "Alkanes" }
+                                a class="nav-menu-link" href=(explorer_path("/mev")) { "MEV" }
                             }
                         }
                     }
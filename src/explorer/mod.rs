@@ -24,6 +24,7 @@ use pages::alkane::alkane_page;
 use pages::alkanes::alkanes_page;
 use pages::block::block_page;
 use pages::home::home_page;
+use pages::mev::mev_page;
 use pages::search::search;
 use pages::state::ExplorerState;
 use pages::tx::tx_page;
@@ -43,7 +44,8 @@ pub fn explorer_router(state: ExplorerState) -> Router {
         .route("/tx/{txid}", get(tx_page))
         .route("/address/{address}", get(address_page))
         .route("/alkane/{alkane}", get(alkane_page))
-        .route("/alkanes", get(alkanes_page));
+        .route("/alkanes", get(alkanes_page))
+        .route("/mev", get(mev_page));
 
     let api = Router::new()
         .route("/api/blocks/carousel", get(carousel_blocks))
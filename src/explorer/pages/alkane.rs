@@ -5,6 +5,7 @@ use axum::response::{IntoResponse, Response};
 use hex;
 use maud::{Markup, PreEscaped, html};
 use serde::Deserialize;
+use serde_json::Value;
 
 use crate::explorer::components::alk_balances::render_alkane_balance_cards;
 use crate::explorer::components::header::header_scripts;
@@ -16,23 +17,31 @@ use crate::explorer::components::table::holders_table;
 use crate::explorer::components::tx_view::{
     AlkaneMetaCache, alkane_icon_url_unfiltered, alkane_meta, icon_bg_style,
 };
-use crate::explorer::pages::common::fmt_alkane_amount;
+use crate::explorer::pages::common::{fmt_alkane_amount, fmt_usd};
 use crate::explorer::pages::state::ExplorerState;
 use crate::explorer::paths::{current_language, explorer_path};
 use crate::modules::ammdata::config::AmmDataConfig;
 use crate::modules::ammdata::schemas::Timeframe;
-use crate::modules::ammdata::storage::{AmmDataProvider, AmmDataTable, GetListKeysByPrefixParams};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, AmmDataTable, GetListKeysByPrefixParams, GetPoolDefsParams,
+    GetTraderLeaderboardParams, address_str_from_spk,
+};
+use crate::modules::ammdata::utils::leaderboards::{
+    LEADERBOARD_WINDOWS, LeaderboardMetric, LeaderboardScope, net_buy_usd, window_days,
+};
 use crate::modules::essentials::storage::{
-    BalanceEntry, EssentialsProvider, GetRawValueParams, HolderId, load_creation_record,
+    BalanceEntry, CallStats, EssentialsProvider, GetCallStatsParams, GetRawValueParams, HolderId,
+    RpcGetKeysParams, load_creation_record,
 };
 use crate::modules::essentials::utils::balances::{
     get_alkane_balances, get_holders_for_alkane, get_total_received_for_alkane,
     get_transfer_volume_for_alkane,
 };
+use crate::modules::essentials::utils::call_stats::ALL_TIME_DAY;
 use crate::modules::essentials::utils::inspections::{StoredInspectionMethod, load_inspection};
 use crate::modules::pizzafun::storage::{GetSeriesByAlkaneParams, PizzafunProvider};
 use crate::schemas::SchemaAlkaneId;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 const ADDR_SUFFIX_LEN: usize = 8;
@@ -45,6 +54,8 @@ pub struct PageQuery {
     pub tab: Option<String>,
     pub page: Option<usize>,
     pub limit: Option<usize>,
+    pub window: Option<String>,
+    pub metric: Option<String>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -53,14 +64,28 @@ enum AlkaneTab {
     Inspect,
     TransferVolume,
     TotalReceived,
+    Traders,
+    Storage,
+    Usage,
 }
 
+const TRADER_METRICS: [(LeaderboardMetric, &str); 5] = [
+    (LeaderboardMetric::Volume, "Volume"),
+    (LeaderboardMetric::Trades, "Trades"),
+    (LeaderboardMetric::NetBuy, "Net buy"),
+    (LeaderboardMetric::NetSell, "Net sell"),
+    (LeaderboardMetric::Liquidity, "Liquidity"),
+];
+
 impl AlkaneTab {
     fn from_query(raw: Option<&str>) -> Self {
         match raw {
             Some("inspect") => AlkaneTab::Inspect,
             Some("transfer_volume") => AlkaneTab::TransferVolume,
             Some("total_received") => AlkaneTab::TotalReceived,
+            Some("traders") => AlkaneTab::Traders,
+            Some("storage") => AlkaneTab::Storage,
+            Some("usage") => AlkaneTab::Usage,
             _ => AlkaneTab::Holders,
         }
     }
@@ -401,6 +426,192 @@ pub async fn alkane_page(
         }
     };
 
+    let traders_window = q
+        .window
+        .as_deref()
+        .filter(|w| window_days(w).is_some())
+        .unwrap_or("7d")
+        .to_string();
+    let traders_metric = q
+        .metric
+        .as_deref()
+        .and_then(LeaderboardMetric::parse)
+        .unwrap_or(LeaderboardMetric::Volume);
+    let traders_table_markup = if tab == AlkaneTab::Traders {
+        // Pools rank their own traders and LPs; every other alkane ranks by the token's trades.
+        let amm_provider = AmmDataProvider::new(
+            crate::config::get_espo_module_mdb("ammdata"),
+            Arc::new(state.essentials_provider()),
+        );
+        let is_pool = amm_provider
+            .get_pool_defs(GetPoolDefsParams { blockhash: StateAt::Latest, pool: alk })
+            .map(|res| res.defs.is_some())
+            .unwrap_or(false);
+        let rows: Vec<Vec<Markup>> = amm_provider
+            .get_trader_leaderboard(GetTraderLeaderboardParams {
+                blockhash: StateAt::Latest,
+                scope: if is_pool { LeaderboardScope::Pool } else { LeaderboardScope::Token },
+                id: alk,
+                days: window_days(&traders_window).unwrap_or(7),
+                metric: traders_metric,
+                limit,
+            })
+            .map(|res| res.rows)
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (spk, stats))| {
+                let address = address_str_from_spk(&spk);
+                let (addr_prefix, addr_suffix) = addr_prefix_suffix(&address);
+                let lp_net = (stats.lp_added_usd as i128) - (stats.lp_removed_usd as i128);
+                vec![
+                    html! {
+                        a class="link mono addr-inline" href=(explorer_path(&format!("/address/{address}"))) {
+                            span class="addr-rank" { (format!("{}.", idx + 1)) }
+                            span class="addr-prefix" { (addr_prefix) }
+                            span class="addr-suffix" { (addr_suffix) }
+                        }
+                    },
+                    html! { span class="mono" { (fmt_usd(stats.volume_usd as i128)) } },
+                    html! { span class="mono" { (stats.trades) } },
+                    html! { span class="mono" { (fmt_usd(net_buy_usd(&stats))) } },
+                    html! { span class="mono" { (fmt_usd(lp_net)) } },
+                ]
+            })
+            .collect();
+        if rows.is_empty() {
+            html! { div class="alkane-panel" { p class="muted" { "No trades in this window." } } }
+        } else {
+            html! {
+                div class="alkane-panel alkane-holders-card alkane-activity-card" {
+                    (holders_table(&["Address", "Volume", "Trades", "Net buy", "Net liquidity"], rows))
+                }
+            }
+        }
+    } else {
+        html! {}
+    };
+
+    // Storage keys come back sorted; values are decoded by the contract's storage schema.
+    let (storage_label, storage_total, storage_has_more, storage_table_markup) = if tab
+        == AlkaneTab::Storage
+    {
+        let resp = state
+            .essentials_provider()
+            .rpc_get_keys(RpcGetKeysParams {
+                alkane: Some(alk_str.clone()),
+                try_decode_utf8: Some(true),
+                limit: Some(limit as u64),
+                page: Some(page as u64),
+                keys: None,
+            })
+            .map(|res| res.value)
+            .unwrap_or_default();
+        let rows: Vec<Vec<Markup>> = resp
+                .get("items")
+                .and_then(|v| v.as_object())
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(|(key, item)| {
+                            let field = item
+                                .pointer("/schema/label")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("—")
+                                .to_string();
+                            let value = storage_value_display(item);
+                            let last_txid =
+                                item.get("last_txid").and_then(|v| v.as_str()).map(str::to_string);
+                            vec![
+                                html! { span class="mono" { (key.clone()) } },
+                                html! { span { (field) } },
+                                html! { span class="mono" { (value) } },
+                                html! {
+                                    @if let Some(txid) = last_txid {
+                                        a class="link mono" href=(explorer_path(&format!("/tx/{txid}"))) { (short_hex(&txid)) }
+                                    } @else {
+                                        span class="muted" { "—" }
+                                    }
+                                },
+                            ]
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+        let markup = if rows.is_empty() {
+            html! { div class="alkane-panel" { p class="muted" { "No storage keys." } } }
+        } else {
+            html! {
+                div class="alkane-panel alkane-holders-card alkane-activity-card" {
+                    (holders_table(&["Key", "Field", "Value", "Last tx"], rows))
+                }
+            }
+        };
+        (
+            resp.get("contract_label")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Contract")
+                .to_string(),
+            resp.get("total").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+            resp.get("has_more").and_then(|v| v.as_bool()).unwrap_or(false),
+            markup,
+        )
+    } else {
+        (String::new(), 0, false, html! {})
+    };
+
+    let usage_table_markup = if tab == AlkaneTab::Usage {
+        let provider = state.essentials_provider();
+        // The week ends on the day of the newest indexed block.
+        let rows = provider
+            .get_call_stats(GetCallStatsParams { blockhash: StateAt::Latest, alkane: alk, days: 7 })
+            .map(|res| res.rows)
+            .unwrap_or_default();
+        let method_names: HashMap<u128, String> = load_inspection(&provider, &alk)
+            .ok()
+            .flatten()
+            .and_then(|i| i.metadata)
+            .map(|meta| meta.methods.into_iter().map(|m| (m.opcode, m.name)).collect())
+            .unwrap_or_default();
+        let mut week_calls: BTreeMap<u128, u64> = BTreeMap::new();
+        let mut totals: Vec<(u128, CallStats)> = Vec::new();
+        for (opcode, day, stats) in rows {
+            if day == ALL_TIME_DAY {
+                totals.push((opcode, stats));
+            } else {
+                *week_calls.entry(opcode).or_default() += stats.calls;
+            }
+        }
+        totals.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then_with(|| a.0.cmp(&b.0)));
+        let table_rows: Vec<Vec<Markup>> = totals
+            .iter()
+            .map(|(opcode, stats)| {
+                vec![
+                    html! {
+                        span { (method_names.get(opcode).cloned().unwrap_or_else(|| "unknown".to_string())) }
+                        " "
+                        span class="trace-opcode" { (format!("opcode {opcode}")) }
+                    },
+                    html! { span class="mono" { (stats.calls) } },
+                    html! { span class="mono" { (week_calls.get(opcode).copied().unwrap_or(0)) } },
+                    html! { span class="mono" { (format!("{:.1}%", stats.revert_rate() * 100.0)) } },
+                    html! { span class="mono" { (stats.unique_callers) } },
+                ]
+            })
+            .collect();
+        if table_rows.is_empty() {
+            html! { div class="alkane-panel" { p class="muted" { "No calls recorded." } } }
+        } else {
+            html! {
+                div class="alkane-panel alkane-holders-card alkane-activity-card" {
+                    (holders_table(&["Method", "Calls", "Calls (7d)", "Revert rate", "Unique callers"], table_rows))
+                }
+            }
+        }
+    } else {
+        html! {}
+    };
+
     let balances_markup = if balance_entries.is_empty() {
         html! { p class="muted" { "No alkanes tracked for this alkane." } }
     } else {
@@ -590,6 +801,12 @@ pub async fn alkane_page(
                                 href=(explorer_path(&format!("/alkane/{alk_str}?tab=transfer_volume&page={page}&limit={limit}"))) { "Transfer Volume" }
                             a class=(format!("alkane-tab{}", if tab == AlkaneTab::TotalReceived { " active" } else { "" }))
                                 href=(explorer_path(&format!("/alkane/{alk_str}?tab=total_received&page={page}&limit={limit}"))) { "Total Received" }
+                            a class=(format!("alkane-tab{}", if tab == AlkaneTab::Traders { " active" } else { "" }))
+                                href=(explorer_path(&format!("/alkane/{alk_str}?tab=traders&limit={limit}"))) { "Top Traders" }
+                            a class=(format!("alkane-tab{}", if tab == AlkaneTab::Storage { " active" } else { "" }))
+                                href=(explorer_path(&format!("/alkane/{alk_str}?tab=storage&page=1&limit={limit}"))) { "Storage" }
+                            a class=(format!("alkane-tab{}", if tab == AlkaneTab::Usage { " active" } else { "" }))
+                                href=(explorer_path(&format!("/alkane/{alk_str}?tab=usage"))) { "Usage" }
                             a class=(format!("alkane-tab{}", if tab == AlkaneTab::Inspect { " active" } else { "" }))
                                 href=(explorer_path(&format!("/alkane/{alk_str}?tab=inspect&page={page}&limit={limit}"))) { "Inspect contract" }
                         }
@@ -635,6 +852,39 @@ pub async fn alkane_page(
                                         span class="pill disabled iconbtn" aria-hidden="true" { (icon_skip_right()) }
                                     }
                                 }
+                            } @else if tab == AlkaneTab::Traders {
+                                div class="pager" {
+                                    @for (label, _) in LEADERBOARD_WINDOWS {
+                                        a class=(format!("pill small{}", if traders_window == label { " active" } else { "" }))
+                                            href=(explorer_path(&format!("/alkane/{alk_str}?tab=traders&window={label}&metric={}&limit={limit}", traders_metric.as_str()))) { (label) }
+                                    }
+                                    @for (metric, label) in TRADER_METRICS {
+                                        a class=(format!("pill small{}", if traders_metric == metric { " active" } else { "" }))
+                                            href=(explorer_path(&format!("/alkane/{alk_str}?tab=traders&window={traders_window}&metric={}&limit={limit}", metric.as_str()))) { (label) }
+                                    }
+                                }
+                                (traders_table_markup)
+                            } @else if tab == AlkaneTab::Usage {
+                                (usage_table_markup)
+                            } @else if tab == AlkaneTab::Storage {
+                                p class="muted" { (format!("{storage_label} · {storage_total} keys")) }
+                                (storage_table_markup)
+                                div class="pager" {
+                                    @if page > 1 {
+                                        a class="pill iconbtn" href=(explorer_path(&format!("/alkane/{alk_str}?tab=storage&page={}&limit={limit}", page - 1))) aria-label="Previous page" {
+                                            (icon_left())
+                                        }
+                                    } @else {
+                                        span class="pill disabled iconbtn" aria-hidden="true" { (icon_left()) }
+                                    }
+                                    @if storage_has_more {
+                                        a class="pill iconbtn" href=(explorer_path(&format!("/alkane/{alk_str}?tab=storage&page={}&limit={limit}", page + 1))) aria-label="Next page" {
+                                            (icon_right())
+                                        }
+                                    } @else {
+                                        span class="pill disabled iconbtn" aria-hidden="true" { (icon_right()) }
+                                    }
+                                }
                             } @else if tab == AlkaneTab::TransferVolume || tab == AlkaneTab::TotalReceived {
                                 (activity_table_markup)
                                 div class="pager" {
@@ -765,6 +1015,21 @@ fn short_hex(s: &str) -> String {
     format!("{}...{}", &s[..KEEP], &s[s.len() - KEEP..])
 }
 
+/// Schema-decoded value when the key is known, else the UTF-8 or shortened hex value.
+fn storage_value_display(item: &Value) -> String {
+    match item.pointer("/schema/value") {
+        Some(Value::String(s)) => return s.clone(),
+        Some(Value::Array(ids)) => {
+            return ids.iter().filter_map(|id| id.as_str()).collect::<Vec<_>>().join(", ");
+        }
+        _ => {}
+    }
+    if let Some(s) = item.get("value_str").and_then(|v| v.as_str()) {
+        return s.to_string();
+    }
+    short_hex(item.get("value_hex").and_then(|v| v.as_str()).unwrap_or("0x"))
+}
+
 fn parse_alkane_id(s: &str) -> Option<crate::schemas::SchemaAlkaneId> {
     let (a, b) = s.split_once(':')?;
     let block = parse_u32_any(a)?;
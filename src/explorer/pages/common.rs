@@ -20,16 +20,6 @@ pub fn fmt_amount(amount: Amount) -> String {
 }
 
 pub fn fmt_alkane_amount(raw: u128) -> String {
-    fn with_commas(n: u128) -> String {
-        let mut s = n.to_string();
-        let mut i = s.len() as isize - 3;
-        while i > 0 {
-            s.insert(i as usize, ',');
-            i -= 3;
-        }
-        s
-    }
-
     let whole = raw / ALKANE_SCALE;
     let frac = (raw % ALKANE_SCALE) as u64;
     if frac == 0 {
@@ -40,6 +30,24 @@ pub fn fmt_alkane_amount(raw: u128) -> String {
     format!("{}.{}", with_commas(whole), frac)
 }
 
+/// USD value in ammdata's PRICE_SCALE (1e16) fixed point, rounded down to cents.
+pub fn fmt_usd(value: i128) -> String {
+    const UNITS_PER_CENT: u128 = 100_000_000_000_000;
+    let cents = value.unsigned_abs() / UNITS_PER_CENT;
+    let sign = if value < 0 && cents > 0 { "-" } else { "" };
+    format!("{sign}${}.{:02}", with_commas(cents / 100), cents % 100)
+}
+
+fn with_commas(n: u128) -> String {
+    let mut s = n.to_string();
+    let mut i = s.len() as isize - 3;
+    while i > 0 {
+        s.insert(i as usize, ',');
+        i -= 3;
+    }
+    s
+}
+
 fn trim_fraction(mut s: String) -> String {
     while s.ends_with('0') {
         s.pop();
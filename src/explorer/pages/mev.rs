@@ -0,0 +1,183 @@
+use crate::runtime::state_at::StateAt;
+use axum::extract::{Query, State};
+use axum::response::Html;
+use maud::{Markup, html};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::config::get_espo_module_mdb;
+use crate::explorer::components::layout::layout_with_meta;
+use crate::explorer::components::table::table;
+use crate::explorer::pages::common::{fmt_alkane_amount, fmt_usd};
+use crate::explorer::pages::state::ExplorerState;
+use crate::explorer::paths::explorer_path;
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetArbitrageBlocksParams, GetArbitrageLeaderboardParams,
+    GetArbitragePageParams, address_str_from_spk,
+};
+use crate::schemas::SchemaAlkaneId;
+
+#[derive(Deserialize)]
+pub struct MevQuery {
+    pub limit: Option<usize>,
+}
+
+fn alkane_link(id: &SchemaAlkaneId) -> Markup {
+    let id = format!("{}:{}", id.block, id.tx);
+    html! { a class="link mono" href=(explorer_path(&format!("/alkane/{id}"))) { (id) } }
+}
+
+fn address_link(spk: &[u8]) -> Markup {
+    let address = address_str_from_spk(spk);
+    html! {
+        a class="link mono ellipsis" href=(explorer_path(&format!("/address/{address}"))) { (address) }
+    }
+}
+
+fn block_link(height: u32) -> Markup {
+    html! { a class="link mono" href=(explorer_path(&format!("/block/{height}"))) { (height) } }
+}
+
+fn section(title: &str, headers: &[&str], rows: Vec<Vec<Markup>>, empty: &str) -> Markup {
+    html! {
+        div class="card" {
+            div class="row" {
+                h2 class="h2" { (title) }
+            }
+            @if rows.is_empty() {
+                p class="muted" { (empty) }
+            } @else {
+                (table(headers, rows))
+            }
+        }
+    }
+}
+
+pub async fn mev_page(
+    State(state): State<ExplorerState>,
+    Query(q): Query<MevQuery>,
+) -> Html<String> {
+    let limit = q.limit.unwrap_or(25).clamp(1, 100);
+    let provider =
+        AmmDataProvider::new(get_espo_module_mdb("ammdata"), Arc::new(state.essentials_provider()));
+
+    let leaders = provider
+        .get_arbitrage_leaderboard(GetArbitrageLeaderboardParams {
+            blockhash: StateAt::Latest,
+            limit,
+        })
+        .map(|res| res.actors)
+        .unwrap_or_default();
+    let recent = provider
+        .get_arbitrage_page(GetArbitragePageParams {
+            blockhash: StateAt::Latest,
+            actor_spk: None,
+            from_height: None,
+            to_height: None,
+            limit,
+        })
+        .map(|res| res.entries)
+        .unwrap_or_default();
+    let blocks = provider
+        .get_arbitrage_blocks(GetArbitrageBlocksParams {
+            blockhash: StateAt::Latest,
+            from_height: None,
+            to_height: None,
+            limit,
+        })
+        .map(|res| res.blocks)
+        .unwrap_or_default();
+
+    let leader_rows: Vec<Vec<Markup>> = leaders
+        .iter()
+        .enumerate()
+        .map(|(i, (spk, totals))| {
+            vec![
+                html! { span class="mono" { (i + 1) } },
+                address_link(spk),
+                html! { span class="mono" { (totals.count) } },
+                html! { span class="mono" { (fmt_usd(totals.profit_usd)) } },
+                block_link(totals.last_height),
+            ]
+        })
+        .collect();
+
+    let recent_rows: Vec<Vec<Markup>> = recent
+        .iter()
+        .map(|(height, rec)| {
+            let txid = hex::encode(rec.txid.iter().rev().copied().collect::<Vec<u8>>());
+            // The cycle starts and ends in `rec.token`, so its gain is in that token's units.
+            let gain = rec.amount_out as i128 - rec.amount_in as i128;
+            vec![
+                block_link(*height),
+                html! {
+                    a class="link mono ellipsis" href=(explorer_path(&format!("/tx/{txid}"))) { (txid) }
+                },
+                address_link(&rec.actor_spk),
+                html! {
+                    @for (i, token) in rec.path.iter().enumerate() {
+                        @if i > 0 {
+                            " → "
+                        }
+                        (alkane_link(token))
+                    }
+                },
+                html! {
+                    span class="mono" {
+                        @if gain < 0 {
+                            "-"
+                        }
+                        (fmt_alkane_amount(gain.unsigned_abs()))
+                        " "
+                        (alkane_link(&rec.token))
+                    }
+                },
+                html! { span class="mono" { (fmt_usd(rec.profit_usd)) } },
+                html! { span class="muted" { @if rec.single_tx { "Single tx" } @else { "Multi tx" } } },
+            ]
+        })
+        .collect();
+
+    let block_rows: Vec<Vec<Markup>> = blocks
+        .iter()
+        .map(|(height, totals)| {
+            vec![
+                block_link(*height),
+                html! { span class="mono" { (totals.count) } },
+                html! { span class="mono" { (fmt_usd(totals.profit_usd)) } },
+            ]
+        })
+        .collect();
+
+    layout_with_meta(
+        "MEV",
+        "/mev",
+        None,
+        html! {
+            div class="row" {
+                h1 class="h1" { "MEV" }
+            }
+            p class="muted" {
+                "Swap cycles that started and ended in the same token, within one transaction or across one address's transactions in a block."
+            }
+            (section(
+                "Top arbitrageurs",
+                &["#", "Address", "Cycles", "Profit (USD)", "Last block"],
+                leader_rows,
+                "No arbitrage indexed yet.",
+            ))
+            (section(
+                "Recent arbitrage",
+                &["Block", "Tx", "Address", "Path", "Gain", "Profit (USD)", "Scope"],
+                recent_rows,
+                "No arbitrage indexed yet.",
+            ))
+            (section(
+                "MEV per block",
+                &["Block", "Cycles", "Profit (USD)"],
+                block_rows,
+                "No arbitrage indexed yet.",
+            ))
+        },
+    )
+}
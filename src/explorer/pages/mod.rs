@@ -4,6 +4,7 @@ pub mod alkanes;
 pub mod block;
 pub mod common;
 pub mod home;
+pub mod mev;
 pub mod search;
 pub mod state;
 pub mod tx;
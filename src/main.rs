@@ -22,6 +22,7 @@ pub mod schemas;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod utils;
 
+use std::io::Write;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::process::Command;
@@ -35,7 +36,13 @@ use crate::config::get_metashrew_sdb;
 use crate::config::get_network;
 use crate::modules::ammdata::main::AmmData;
 use crate::modules::essentials::main::Essentials;
-use crate::modules::essentials::storage::preload_block_summary_cache;
+use crate::modules::essentials::storage::{
+    EssentialsProvider, GetIndexHeightParams, preload_block_summary_cache,
+};
+use crate::modules::essentials::utils::airdrop::{
+    AirdropFilter, airdrop_csv, airdrop_hash, build_airdrop_list, parse_airdrop_token,
+};
+use crate::modules::graphql::main::Graphql;
 use crate::modules::oylapi::main::OylApi;
 use crate::modules::pizzafun::main::Pizzafun;
 use crate::modules::subfrost::main::Subfrost;
@@ -46,23 +53,26 @@ use crate::explorer::run_explorer;
 use crate::{
     alkanes::{trace::get_espo_block, utils::get_safe_tip},
     config::{
-        get_bitcoind_rpc_client, get_config, get_espo_module_mdb, get_module_config, init_config,
-        update_safe_tip,
+        AirdropArgs, CliCommand, ExportArgs, get_bitcoind_rpc_client, get_config, get_espo_db,
+        get_espo_module_mdb, get_module_config, init_config, update_safe_tip,
     },
     consts::alkanes_genesis_block,
     modules::defs::ModuleRegistry,
+    runtime::admin::{indexer_control, now_unix_secs},
+    runtime::export::ExportJob,
+    runtime::grpc::run_grpc,
+    runtime::mdb::Mdb,
     runtime::mempool::{
         purge_confirmed_from_chain, purge_confirmed_txids, reset_mempool_store, run_mempool_service,
     },
     runtime::rpc::run_rpc,
+    runtime::state_at::StateAt,
 };
 use bitcoin::Txid;
 use bitcoincore_rpc::RpcApi;
 pub use espo::{ESPO_HEIGHT, SAFE_TIP};
 use tokio::runtime::Builder as TokioBuilder;
 
-const NO_REWIND: u32 = u32::MAX;
-
 fn run_debug_backup(db_path: &str, backup: &DebugBackupConfig, block: u32) -> std::io::Result<()> {
     let db_root = Path::new(db_path);
     let backup_root = Path::new(&backup.dir);
@@ -86,6 +96,85 @@ fn run_debug_backup(db_path: &str, backup: &DebugBackupConfig, block: u32) -> st
     Ok(())
 }
 
+/// Writes a RocksDB checkpoint of the shared db and of every module db under `dest`, laid out
+/// like `<db_path>/espo` so the snapshot can be used as a `db_path` directly.
+fn run_snapshot(
+    db_path: &str,
+    dest: &Path,
+    shared: Arc<rocksdb::DB>,
+    modules: Vec<(&'static str, Arc<Mdb>)>,
+) -> Result<()> {
+    if dest.starts_with(Path::new(db_path)) {
+        anyhow::bail!("snapshot dir may not be inside db_path");
+    }
+    let espo_dir = dest.join("espo");
+    std::fs::create_dir_all(&espo_dir)
+        .with_context(|| format!("failed to create {}", espo_dir.display()))?;
+    let checkpoint = |name: &str, db: &rocksdb::DB| -> Result<()> {
+        rocksdb::checkpoint::Checkpoint::new(db)
+            .and_then(|cp| cp.create_checkpoint(espo_dir.join(name)))
+            .with_context(|| format!("checkpoint of '{name}' failed"))
+    };
+    checkpoint("_shared", &shared)?;
+    for (name, mdb) in &modules {
+        checkpoint(name, mdb.inner_db())?;
+    }
+    Ok(())
+}
+
+fn run_export_command(args: ExportArgs) -> Result<()> {
+    let job = ExportJob::prepare(args.kind, &args.params).map_err(|e| anyhow::anyhow!(e))?;
+    let t0 = std::time::Instant::now();
+    let rows = match args.out.as_deref() {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("failed to create export file {path}"))?;
+            job.write_to(args.format, std::io::BufWriter::new(file))?
+        }
+        None => job.write_to(args.format, std::io::BufWriter::new(std::io::stdout().lock()))?,
+    };
+    eprintln!("[export] {} rows of {} in {:?}", rows, args.kind.code(), t0.elapsed());
+    Ok(())
+}
+
+fn run_airdrop_command(args: AirdropArgs) -> Result<()> {
+    let tokens = args
+        .tokens
+        .iter()
+        .map(|raw| parse_airdrop_token(raw).ok_or_else(|| anyhow::anyhow!("invalid --token {raw}")))
+        .collect::<Result<Vec<_>>>()?;
+    let filter = AirdropFilter {
+        min_balance: args.min_balance,
+        exclude_contracts: args.exclude_contracts,
+        exclude_addresses: args.exclude_addresses.into_iter().collect(),
+    };
+    let essentials = EssentialsProvider::new(get_espo_module_mdb("essentials"))
+        .with_height(args.height.map(u64::from), args.height.is_some())?;
+    let height = match args.height {
+        Some(h) => Some(h),
+        None => {
+            essentials
+                .get_index_height(GetIndexHeightParams { blockhash: StateAt::Latest })?
+                .height
+        }
+    };
+
+    let entries = build_airdrop_list(&essentials, &tokens, &filter)?;
+    let csv = airdrop_csv(&entries);
+    match args.out.as_deref() {
+        Some(path) => std::fs::write(path, &csv)
+            .with_context(|| format!("failed to write airdrop file {path}"))?,
+        None => std::io::stdout().lock().write_all(csv.as_bytes())?,
+    }
+    eprintln!(
+        "[airdrop] {} holders at height {} sha256={}",
+        entries.len(),
+        height.map(|h| h.to_string()).unwrap_or_else(|| "?".to_string()),
+        airdrop_hash(&csv)
+    );
+    Ok(())
+}
+
 fn detect_first_divergence_height(
     indexed_tip: u32,
     safe_tip: u32,
@@ -129,11 +218,7 @@ fn detect_first_divergence_height(
     }
 }
 
-async fn run_reorg_poller(
-    rewind_target: Arc<AtomicU32>,
-    shutdown_requested: Arc<AtomicBool>,
-    genesis_height: u32,
-) {
+async fn run_reorg_poller(shutdown_requested: Arc<AtomicBool>, genesis_height: u32) {
     const REORG_POLL_INTERVAL: Duration = Duration::from_secs(10);
 
     loop {
@@ -164,23 +249,11 @@ async fn run_reorg_poller(
         if let Some(divergence_height) =
             detect_first_divergence_height(indexed_tip, safe_tip, genesis_height)
         {
-            let mut current = rewind_target.load(Ordering::Relaxed);
-            while divergence_height < current {
-                match rewind_target.compare_exchange(
-                    current,
-                    divergence_height,
-                    Ordering::SeqCst,
-                    Ordering::SeqCst,
-                ) {
-                    Ok(_) => {
-                        eprintln!(
-                            "[reorg] detected divergence at height {} (indexed_tip={}, safe_tip={})",
-                            divergence_height, indexed_tip, safe_tip
-                        );
-                        break;
-                    }
-                    Err(observed) => current = observed,
-                }
+            if indexer_control().request_rewind(divergence_height) {
+                eprintln!(
+                    "[reorg] detected divergence at height {} (indexed_tip={}, safe_tip={})",
+                    divergence_height, indexed_tip, safe_tip
+                );
             }
         }
 
@@ -232,8 +305,10 @@ async fn run_indexer_loop(
     shutdown_requested: Arc<AtomicBool>,
 ) {
     const POLL_INTERVAL: Duration = Duration::from_secs(5);
+    const PAUSE_POLL_INTERVAL: Duration = Duration::from_secs(1);
     let genesis_height = alkanes_genesis_block(network);
-    let rewind_target = Arc::new(AtomicU32::new(NO_REWIND));
+    let control = indexer_control();
+    control.set_running(true);
     let mut last_tip: Option<u32> = None;
     let mut mempool_started = false;
     let mut logged_start = false;
@@ -261,16 +336,66 @@ async fn run_indexer_loop(
             break;
         }
 
-        let requested_rewind = rewind_target.swap(NO_REWIND, Ordering::SeqCst);
-        if requested_rewind != NO_REWIND && requested_rewind < next_height {
-            next_height = requested_rewind;
-            if let Some(h) = ESPO_HEIGHT.get() {
-                h.store(next_height, Ordering::Relaxed);
+        if let Some(requested_rewind) = control.take_rewind() {
+            if requested_rewind < next_height {
+                next_height = requested_rewind.max(genesis_height);
+                // Modules skip blocks their tree already holds, so drop them before re-indexing.
+                match mods.rollback_to_height(next_height) {
+                    Ok(dropped) => {
+                        for (name, n) in dropped {
+                            eprintln!("[reorg] module {name}: rolled back {n} indexed block(s)");
+                        }
+                    }
+                    Err(e) => eprintln!("[reorg] failed to roll back indexed state: {e:?}"),
+                }
+                if let Some(h) = ESPO_HEIGHT.get() {
+                    h.store(next_height, Ordering::Relaxed);
+                }
+                if let Err(e) = reset_mempool_store() {
+                    eprintln!("[mempool] failed to reset store after reorg switch: {e:?}");
+                }
+                eprintln!("[reorg] switching indexer to height {}", next_height);
             }
-            if let Err(e) = reset_mempool_store() {
-                eprintln!("[mempool] failed to reset store after reorg switch: {e:?}");
+        }
+
+        if control.take_snapshot_request() {
+            let indexed = next_height.saturating_sub(1);
+            match cfg.snapshot_dir() {
+                Some(dir) => {
+                    let path = Path::new(dir).join(format!("snap-{}-{}", indexed, now_unix_secs()));
+                    eprintln!(
+                        "[admin] snapshot at height {}: checkpoint to '{}'",
+                        indexed,
+                        path.display()
+                    );
+                    let modules: Vec<_> = mods
+                        .modules()
+                        .iter()
+                        .filter_map(|m| m.get_mdb().map(|mdb| (m.get_name(), mdb)))
+                        .collect();
+                    let (db_path, dest) = (cfg.db_path.clone(), path.clone());
+                    let result = tokio::task::spawn_blocking(move || {
+                        run_snapshot(&db_path, &dest, get_espo_db(), modules)
+                    })
+                    .await
+                    .map_err(|e| format!("snapshot task failed: {e}"))
+                    .and_then(|res| res.map_err(|e| format!("{e:#}")));
+                    if let Err(e) = &result {
+                        eprintln!("[admin] snapshot failed: {e}");
+                    }
+                    control.record_snapshot(indexed, path.display().to_string(), result);
+                }
+                None => control.record_snapshot(
+                    indexed,
+                    String::new(),
+                    Err("no snapshot dir configured".to_string()),
+                ),
             }
-            eprintln!("[reorg] switching indexer to height {}", next_height);
+        }
+
+        if control.is_paused() {
+            tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+            continue;
         }
 
         if let Err(e) = metashrew_sdb.catch_up_now() {
@@ -347,12 +472,20 @@ async fn run_indexer_loop(
                     for m in mods.modules() {
                         if next_height >= m.get_genesis_block(network) {
                             let Some(mdb) = m.get_mdb() else {
-                                if let Err(e) = m.index_block(espo_block.clone()) {
-                                    eprintln!(
-                                        "[module:{}] height {}: {e:?}",
-                                        m.get_name(),
-                                        next_height
-                                    );
+                                match m.index_block(espo_block.clone()) {
+                                    Ok(()) => control.record_indexed(m.get_name(), next_height),
+                                    Err(e) => {
+                                        eprintln!(
+                                            "[module:{}] height {}: {e:?}",
+                                            m.get_name(),
+                                            next_height
+                                        );
+                                        control.record_error(
+                                            m.get_name(),
+                                            next_height,
+                                            format!("{e:#}"),
+                                        );
+                                    }
                                 }
                                 continue;
                             };
@@ -365,6 +498,7 @@ async fn run_indexer_loop(
                                         next_height,
                                         block_hash
                                     );
+                                    control.record_indexed(m.get_name(), next_height);
                                     continue;
                                 }
                                 Ok(false) => {}
@@ -375,6 +509,7 @@ async fn run_indexer_loop(
                                         next_height,
                                         block_hash
                                     );
+                                    control.record_error(m.get_name(), next_height, e.to_string());
                                     continue;
                                 }
                             }
@@ -390,6 +525,7 @@ async fn run_indexer_loop(
                                     next_height,
                                     block_hash
                                 );
+                                control.record_error(m.get_name(), next_height, e.to_string());
                                 continue;
                             }
 
@@ -399,17 +535,22 @@ async fn run_indexer_loop(
                                     m.get_name(),
                                     next_height
                                 );
+                                control.record_error(m.get_name(), next_height, format!("{e:#}"));
                                 mdb.abort_block();
                                 continue;
                             }
 
-                            if let Err(e) = mdb.finish_block() {
-                                eprintln!(
-                                    "[module:{}] failed to finish block {} ({}): {e:?}",
-                                    m.get_name(),
-                                    next_height,
-                                    block_hash
-                                );
+                            match mdb.finish_block() {
+                                Ok(()) => control.record_indexed(m.get_name(), next_height),
+                                Err(e) => {
+                                    eprintln!(
+                                        "[module:{}] failed to finish block {} ({}): {e:?}",
+                                        m.get_name(),
+                                        next_height,
+                                        block_hash
+                                    );
+                                    control.record_error(m.get_name(), next_height, e.to_string());
+                                }
                             }
                         }
                     }
@@ -473,11 +614,9 @@ async fn run_indexer_loop(
             if !reorg_poller_started {
                 reorg_poller_started = true;
                 let shutdown_for_poller = shutdown_requested.clone();
-                let rewind_target_for_poller = rewind_target.clone();
                 tokio::spawn(async move {
                     eprintln!("[reorg] poller started (10s cadence) after reaching safe tip");
-                    run_reorg_poller(rewind_target_for_poller, shutdown_for_poller, genesis_height)
-                        .await;
+                    run_reorg_poller(shutdown_for_poller, genesis_height).await;
                 });
             }
             // Caught up; chill then poll again
@@ -511,7 +650,20 @@ async fn run_indexer_loop(
 #[cfg(not(target_arch = "wasm32"))]
 #[tokio::main]
 async fn main() -> Result<()> {
-    init_config()?;
+    let cli = init_config()?;
+    match cli.command {
+        Some(CliCommand::Export(args)) => {
+            return tokio::task::spawn_blocking(move || run_export_command(args))
+                .await
+                .context("export task panicked")?;
+        }
+        Some(CliCommand::Airdrop(args)) => {
+            return tokio::task::spawn_blocking(move || run_airdrop_command(args))
+                .await
+                .context("airdrop task panicked")?;
+        }
+        None => {}
+    }
     let cfg = get_config().clone();
     let network = get_network();
     let view_only = cfg.view_only;
@@ -544,6 +696,11 @@ async fn main() -> Result<()> {
     } else {
         eprintln!("[modules] oylapi disabled (missing config)");
     }
+    if get_module_config("graphql").is_some() {
+        mods.register_module(Graphql::new());
+    } else {
+        eprintln!("[modules] graphql disabled (missing config)");
+    }
     // mods.register_module(TracesData::new());
 
     let essentials_mdb = get_espo_module_mdb("essentials");
@@ -562,6 +719,16 @@ async fn main() -> Result<()> {
     });
     eprintln!("[rpc] listening on {}", addr);
 
+    // Optional gRPC server over the same registry
+    if let Some(grpc_addr) = cfg.grpc_host {
+        let grpc_router = mods.router.clone();
+        tokio::spawn(async move {
+            if let Err(e) = run_grpc(grpc_router, grpc_addr).await {
+                eprintln!("[grpc] server error: {e:?}");
+            }
+        });
+    }
+
     // Optional SSR explorer server
     if let Some(explorer_addr) = cfg.explorer_host {
         let explorer_handle = tokio::spawn(async move {
@@ -595,6 +762,14 @@ async fn main() -> Result<()> {
         .unwrap_or(global_genesis)
         .max(global_genesis);
 
+    for m in mods.modules() {
+        indexer_control().register_module(
+            m.get_name(),
+            m.get_genesis_block(network),
+            m.get_index_height(),
+        );
+    }
+
     let height_cell = Arc::new(AtomicU32::new(start_height));
 
     ESPO_HEIGHT
@@ -0,0 +1,124 @@
+use crate::alkanes::trace::{
+    EspoAlkanesTransaction, EspoHostFunctionValues, EspoSandshrewLikeTraceInvokeData,
+};
+use crate::modules::ammdata::consts::current_default_fee_bps;
+use crate::modules::ammdata::schemas::{ActivityDirection, ActivityKind, SchemaMarketDefs};
+use crate::modules::ammdata::utils::candles::{price_base_per_quote, price_quote_per_base};
+use crate::modules::ammdata::utils::pathfinder::{xyk_in_for_exact_out, xyk_out_exact_in};
+use crate::modules::ammdata::utils::reserves::{
+    LpTransfer, NewPoolInfo, extract_lp_transfers_from_espo_transaction,
+};
+use crate::modules::essentials::storage::AlkaneTxSummary;
+use crate::modules::essentials::utils::inspections::StoredInspectionResult;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Protocol-specific knowledge needed to index one family of Alkanes AMM pools.
+///
+/// Everything downstream of an adapter (reserve snapshots, activity, candles, the
+/// pathfinder graph) only sees `SchemaMarketDefs` and base/quote reserves, so pools
+/// from every adapter share the same indexes.
+pub trait PoolAdapter: Send + Sync {
+    /// Stable identifier persisted per pool, e.g. "oylswap".
+    fn name(&self) -> &'static str;
+
+    /// Whether an inspected contract is a pool factory for this protocol.
+    fn is_factory(&self, inspection: &StoredInspectionResult) -> bool;
+
+    /// Factory targeted by `inv` when it is a pool-creating call, else None.
+    fn factory_for_create_call(
+        &self,
+        inv: &EspoSandshrewLikeTraceInvokeData,
+        factories: &HashSet<SchemaAlkaneId>,
+    ) -> Option<SchemaAlkaneId>;
+
+    /// Pools deployed by a transaction, decoded from its traces.
+    fn extract_new_pools(
+        &self,
+        transaction: &EspoAlkanesTransaction,
+        host_function_values: &EspoHostFunctionValues,
+    ) -> Result<Vec<NewPoolInfo>>;
+
+    /// (base, quote, factory) for a pool from its stored creation transaction, used when
+    /// backfilling pools whose creation block was indexed before the factory was known.
+    fn pool_defs_from_summary(
+        &self,
+        summary: &AlkaneTxSummary,
+        pool_id: SchemaAlkaneId,
+    ) -> Option<(SchemaAlkaneId, SchemaAlkaneId, Option<SchemaAlkaneId>)>;
+
+    /// Pre-trade (base, quote) reserves that pools in `pools` reported while handling
+    /// `transaction`, in trace order. Indexing resyncs its running snapshot to these before
+    /// applying the matching balance change, so drift from missed deltas does not compound.
+    /// The default reports nothing and the snapshot follows balance changes alone.
+    fn reported_reserves(
+        &self,
+        _transaction: &EspoAlkanesTransaction,
+        _pools: &HashMap<SchemaAlkaneId, SchemaMarketDefs>,
+    ) -> Result<Vec<(SchemaAlkaneId, (u128, u128))>> {
+        Ok(Vec::new())
+    }
+
+    /// Activity kind for one balance change of the pool, or None to ignore it.
+    fn classify_delta(
+        &self,
+        base_delta: i128,
+        quote_delta: i128,
+    ) -> Option<(ActivityKind, Option<ActivityDirection>)>;
+
+    /// Reserves after applying a balance change. The default treats the pool's token
+    /// balances as its reserves.
+    fn apply_delta(
+        &self,
+        reserves: (u128, u128),
+        base_delta: i128,
+        quote_delta: i128,
+    ) -> (u128, u128) {
+        (
+            crate::modules::ammdata::apply_delta_u128(reserves.0, base_delta),
+            crate::modules::ammdata::apply_delta_u128(reserves.1, quote_delta),
+        )
+    }
+
+    /// LP tokens each pool in `pools` minted and burned in `transaction`. The default reads
+    /// pools whose LP token is the pool alkane itself.
+    fn lp_transfers(
+        &self,
+        transaction: &EspoAlkanesTransaction,
+        pools: &HashMap<SchemaAlkaneId, SchemaMarketDefs>,
+    ) -> Result<HashMap<SchemaAlkaneId, LpTransfer>> {
+        extract_lp_transfers_from_espo_transaction(transaction, pools)
+    }
+
+    /// Swap fee in basis points, charged on the input leg and left in the pool for LPs.
+    fn fee_bps(&self) -> u32 {
+        current_default_fee_bps()
+    }
+
+    /// Output of swapping `amount_in` against `(reserve_in, reserve_out)` at `fee_bps`, or
+    /// None when the pool cannot fill it. The default is the constant-product curve.
+    fn amount_out(&self, reserves: (u128, u128), amount_in: u128, fee_bps: u32) -> Option<u128> {
+        xyk_out_exact_in(reserves.0, reserves.1, amount_in, fee_bps)
+    }
+
+    /// Input needed to receive exactly `amount_out`; the inverse of `amount_out`.
+    fn amount_in_for(
+        &self,
+        reserves: (u128, u128),
+        amount_out: u128,
+        fee_bps: u32,
+    ) -> Option<u128> {
+        xyk_in_for_exact_out(reserves.0, reserves.1, amount_out, fee_bps)
+    }
+
+    /// Marginal prices (base per quote, quote per base) at the given reserves. The
+    /// default is the constant-product reserve ratio; curves with a different marginal
+    /// price (stable-swap, concentrated liquidity) override it.
+    fn spot_prices(&self, base_reserve: u128, quote_reserve: u128) -> (u128, u128) {
+        (
+            price_base_per_quote(base_reserve, quote_reserve),
+            price_quote_per_base(base_reserve, quote_reserve),
+        )
+    }
+}
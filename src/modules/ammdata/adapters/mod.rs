@@ -0,0 +1,30 @@
+pub mod defs;
+pub mod oylswap;
+
+pub use defs::PoolAdapter;
+
+use oylswap::OylswapAdapter;
+
+static OYLSWAP: OylswapAdapter = OylswapAdapter;
+
+/// Registered adapters in discovery order. A pool is claimed by the first adapter that
+/// decodes it, so the default (and most permissive) adapter goes last.
+static POOL_ADAPTERS: [&dyn PoolAdapter; 1] = [&OYLSWAP];
+
+pub fn pool_adapters() -> &'static [&'static dyn PoolAdapter] {
+    &POOL_ADAPTERS
+}
+
+/// Adapter for pools indexed before protocols were recorded.
+pub fn default_pool_adapter() -> &'static dyn PoolAdapter {
+    &OYLSWAP
+}
+
+/// Resolves a stored protocol name, falling back to the default adapter.
+pub fn pool_adapter_by_name(name: &str) -> &'static dyn PoolAdapter {
+    pool_adapters()
+        .iter()
+        .copied()
+        .find(|a| a.name() == name)
+        .unwrap_or_else(default_pool_adapter)
+}
@@ -0,0 +1,146 @@
+use super::defs::PoolAdapter;
+use crate::alkanes::trace::{
+    EspoAlkanesTransaction, EspoHostFunctionValues, EspoSandshrewLikeTraceEvent,
+    EspoSandshrewLikeTraceInvokeData, EspoSandshrewLikeTraceShortId,
+};
+use crate::modules::ammdata::schemas::{ActivityDirection, ActivityKind};
+use crate::modules::ammdata::utils::reserves::{
+    NewPoolInfo, extract_new_pools_from_espo_transaction,
+};
+use crate::modules::essentials::storage::AlkaneTxSummary;
+use crate::modules::essentials::utils::inspections::StoredInspectionResult;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use std::collections::HashSet;
+
+/// Oylswap constant-product pools: created through factory opcode `DEPLOY_AMM_OPCODE`,
+/// tokens stored under `/alkane/0` and `/alkane/1`, reserves equal to the pool balances.
+pub struct OylswapAdapter;
+
+fn decode_storage_id_hex_word(raw_hex: &str) -> Option<SchemaAlkaneId> {
+    let hex = raw_hex.strip_prefix("0x").unwrap_or(raw_hex);
+    if hex.len() != 64 {
+        return None;
+    }
+    let raw = hex::decode(hex).ok()?;
+    let block_bytes: [u8; 16] = raw[0..16].try_into().ok()?;
+    let tx_bytes: [u8; 16] = raw[16..32].try_into().ok()?;
+    let block = u128::from_le_bytes(block_bytes);
+    let tx = u128::from_le_bytes(tx_bytes);
+    if block > u32::MAX as u128 || tx > u64::MAX as u128 {
+        return None;
+    }
+    Some(SchemaAlkaneId { block: block as u32, tx: tx as u64 })
+}
+
+fn short_to_schema_id(id: &EspoSandshrewLikeTraceShortId) -> Option<SchemaAlkaneId> {
+    Some(SchemaAlkaneId {
+        block: crate::modules::ammdata::parse_hex_u32(&id.block)?,
+        tx: crate::modules::ammdata::parse_hex_u64(&id.tx)?,
+    })
+}
+
+impl PoolAdapter for OylswapAdapter {
+    fn name(&self) -> &'static str {
+        "oylswap"
+    }
+
+    fn is_factory(&self, inspection: &StoredInspectionResult) -> bool {
+        crate::modules::ammdata::inspection_is_amm_factory(inspection)
+    }
+
+    fn factory_for_create_call(
+        &self,
+        inv: &EspoSandshrewLikeTraceInvokeData,
+        factories: &HashSet<SchemaAlkaneId>,
+    ) -> Option<SchemaAlkaneId> {
+        crate::modules::ammdata::parse_factory_create_call(inv, factories)
+    }
+
+    fn extract_new_pools(
+        &self,
+        transaction: &EspoAlkanesTransaction,
+        host_function_values: &EspoHostFunctionValues,
+    ) -> Result<Vec<NewPoolInfo>> {
+        extract_new_pools_from_espo_transaction(transaction, host_function_values)
+    }
+
+    fn pool_defs_from_summary(
+        &self,
+        summary: &AlkaneTxSummary,
+        pool_id: SchemaAlkaneId,
+    ) -> Option<(SchemaAlkaneId, SchemaAlkaneId, Option<SchemaAlkaneId>)> {
+        let mut stack: Vec<EspoSandshrewLikeTraceShortId> = Vec::new();
+        for trace in &summary.traces {
+            for ev in &trace.events {
+                match ev {
+                    EspoSandshrewLikeTraceEvent::Invoke(inv) => {
+                        stack.push(inv.context.myself.clone());
+                    }
+                    EspoSandshrewLikeTraceEvent::Return(ret) => {
+                        let Some(leaving) = stack.pop() else { continue };
+                        let Some(leaving_id) = short_to_schema_id(&leaving) else {
+                            continue;
+                        };
+                        if leaving_id != pool_id {
+                            continue;
+                        }
+
+                        let mut alk0: Option<SchemaAlkaneId> = None;
+                        let mut alk1: Option<SchemaAlkaneId> = None;
+                        let mut factory: Option<SchemaAlkaneId> = None;
+                        for kv in &ret.response.storage {
+                            match kv.key.as_str() {
+                                "/alkane/0" => alk0 = decode_storage_id_hex_word(&kv.value),
+                                "/alkane/1" => alk1 = decode_storage_id_hex_word(&kv.value),
+                                "/factory_id" => factory = decode_storage_id_hex_word(&kv.value),
+                                _ => {}
+                            }
+                        }
+                        if let (Some(base), Some(quote)) = (alk0, alk1) {
+                            return Some((base, quote, factory));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    fn classify_delta(
+        &self,
+        base_delta: i128,
+        quote_delta: i128,
+    ) -> Option<(ActivityKind, Option<ActivityDirection>)> {
+        match (base_delta.signum(), quote_delta.signum()) {
+            (1, -1) => Some((ActivityKind::TradeSell, Some(ActivityDirection::BaseIn))),
+            (-1, 1) => Some((ActivityKind::TradeBuy, Some(ActivityDirection::QuoteIn))),
+            (1, 1) => Some((ActivityKind::LiquidityAdd, None)),
+            (-1, -1) => Some((ActivityKind::LiquidityRemove, None)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_by_delta_signs() {
+        let a = OylswapAdapter;
+        assert_eq!(
+            a.classify_delta(10, -5),
+            Some((ActivityKind::TradeSell, Some(ActivityDirection::BaseIn)))
+        );
+        assert_eq!(
+            a.classify_delta(-10, 5),
+            Some((ActivityKind::TradeBuy, Some(ActivityDirection::QuoteIn)))
+        );
+        assert_eq!(a.classify_delta(1, 1), Some((ActivityKind::LiquidityAdd, None)));
+        assert_eq!(a.classify_delta(-1, -1), Some((ActivityKind::LiquidityRemove, None)));
+        assert_eq!(a.classify_delta(0, 7), None);
+        assert_eq!(a.apply_delta((100, 50), -30, 200), (70, 250));
+    }
+}
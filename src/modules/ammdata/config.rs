@@ -1,5 +1,8 @@
+use crate::modules::ammdata::consts::{CanonicalQuote, CanonicalQuoteUnit};
+use crate::modules::ammdata::price_feeds::PriceFeedSpec;
 use crate::schemas::SchemaAlkaneId;
 use anyhow::{Result, anyhow};
+use bitcoin::Network;
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -22,51 +25,70 @@ pub struct DerivedLiquidityConfig {
     pub derived_quotes: Vec<DerivedQuoteConfig>,
 }
 
+/// Per-network AMM parameters from `ammdata.networks.<network>`. Unset fields keep the
+/// built-in values in `consts`.
+#[derive(Clone, Debug, Default)]
+pub struct AmmNetworkConfig {
+    pub factories: Vec<SchemaAlkaneId>,
+    pub genesis_height: Option<u32>,
+    pub canonical_quotes: Option<Vec<CanonicalQuote>>,
+    pub default_fee_bps: Option<u32>,
+}
+
 #[derive(Clone, Debug)]
 pub struct AmmDataConfig {
-    pub eth_rpc: String,
+    pub eth_rpc: Option<String>,
     pub eth_rpc_headers: HashMap<String, String>,
     pub eth_call_throttle_ms: u64,
-    pub use_historical_backfill: bool,
+    pub price_feed: PriceFeedSpec,
     pub search_index_enabled: bool,
     pub search_prefix_min_len: u8,
     pub search_prefix_max_len: u8,
     pub search_fallback_scan_cap: u64,
     pub search_limit_cap: u64,
     pub derived_liquidity: Option<DerivedLiquidityConfig>,
+    pub networks: HashMap<String, AmmNetworkConfig>,
 }
 
 impl AmmDataConfig {
     pub fn spec() -> &'static str {
-        "{ \"eth_rpc\": \"<url>\", \"eth_call_throttle\": <ms>, \"use_historical_backfill\": <bool=true>, \"search_index_enabled\": <bool>, \"search_prefix_min\": <2>, \"search_prefix_max\": <6>, \"search_fallback_scan_cap\": <num>, \"search_limit_cap\": <num>, \"derived_liquidity\": [ { \"alkane\": \"2:0\", \"strategy\": \"neutral|neutral-vwap|optimistic|pessimistic\" } ] }"
+        "{ \"eth_rpc\": \"<url>\"?, \"eth_call_throttle\": <ms>, \"use_historical_backfill\": <bool=true>, \"price_feed\": <feed>?, \"search_index_enabled\": <bool>, \"search_prefix_min\": <2>, \"search_prefix_max\": <6>, \"search_fallback_scan_cap\": <num>, \"search_limit_cap\": <num>, \"derived_liquidity\": [ { \"alkane\": \"2:0\", \"strategy\": \"neutral|neutral-vwap|optimistic|pessimistic\" } ], \"networks\": { \"regtest\": { \"amm_factories\": [\"4:1\"], \"genesis_height\": <num>, \"canonical_quotes\": [ { \"id\": \"32:0\", \"unit\": \"btc|usd\" } ], \"default_fee_bps\": <num> } } }"
     }
 
     pub fn from_value(value: &Value) -> Result<Self> {
         let obj = value.as_object().ok_or_else(|| {
             anyhow!("ammdata config must be an object; expected: {}", Self::spec())
         })?;
-        let eth_rpc_val = obj
-            .get("eth_rpc")
-            .ok_or_else(|| anyhow!("ammdata.eth_rpc missing; expected: {}", Self::spec()))?;
-        let eth_call_throttle_val = obj.get("eth_call_throttle").ok_or_else(|| {
-            anyhow!("ammdata.eth_call_throttle missing; expected: {}", Self::spec())
-        })?;
-        let eth_rpc = eth_rpc_val
-            .as_str()
-            .ok_or_else(|| anyhow!("ammdata.eth_rpc must be a string; expected: {}", Self::spec()))?
-            .trim()
-            .to_string();
-        if eth_rpc.is_empty() {
-            anyhow::bail!("ammdata.eth_rpc must be set; expected: {}", Self::spec());
-        }
-        let eth_call_throttle_ms = eth_call_throttle_val.as_u64().ok_or_else(|| {
-            anyhow!(
-                "ammdata.eth_call_throttle must be a non-negative integer; expected: {}",
-                Self::spec()
-            )
-        })?;
+        let eth_rpc = match obj.get("eth_rpc") {
+            None | Some(Value::Null) => None,
+            Some(v) => {
+                let url = v.as_str().ok_or_else(|| {
+                    anyhow!("ammdata.eth_rpc must be a string; expected: {}", Self::spec())
+                })?;
+                let url = url.trim().to_string();
+                (!url.is_empty()).then_some(url)
+            }
+        };
+        let eth_call_throttle_ms = match obj.get("eth_call_throttle") {
+            None | Some(Value::Null) => 0,
+            Some(v) => v.as_u64().ok_or_else(|| {
+                anyhow!(
+                    "ammdata.eth_call_throttle must be a non-negative integer; expected: {}",
+                    Self::spec()
+                )
+            })?,
+        };
         let use_historical_backfill =
             obj.get("use_historical_backfill").and_then(|v| v.as_bool()).unwrap_or(true);
+        let price_feed = match obj.get("price_feed") {
+            None | Some(Value::Null) => {
+                PriceFeedSpec::default_chain(use_historical_backfill, eth_rpc.is_some())
+            }
+            Some(v) => PriceFeedSpec::from_value(v)?,
+        };
+        if price_feed.uses_uniswap() && eth_rpc.is_none() {
+            anyhow::bail!("ammdata.price_feed uses uniswap but ammdata.eth_rpc is not set");
+        }
 
         let eth_rpc_headers = match obj.get("eth_rpc_headers") {
             Some(Value::Object(map)) => {
@@ -171,26 +193,35 @@ impl AmmDataConfig {
             }
         };
 
-        let parsed = reqwest::Url::parse(&eth_rpc)
-            .map_err(|e| anyhow!("ammdata.eth_rpc must be an absolute URL (http/https): {e}"))?;
-        if parsed.scheme() != "http" && parsed.scheme() != "https" {
-            anyhow::bail!(
-                "ammdata.eth_rpc must be an http/https URL; got scheme '{}'",
-                parsed.scheme()
-            );
+        let networks = match obj.get("networks") {
+            None | Some(Value::Null) => HashMap::new(),
+            Some(v) => parse_amm_networks(v)?,
+        };
+
+        if let Some(eth_rpc) = eth_rpc.as_deref() {
+            let parsed = reqwest::Url::parse(eth_rpc).map_err(|e| {
+                anyhow!("ammdata.eth_rpc must be an absolute URL (http/https): {e}")
+            })?;
+            if parsed.scheme() != "http" && parsed.scheme() != "https" {
+                anyhow::bail!(
+                    "ammdata.eth_rpc must be an http/https URL; got scheme '{}'",
+                    parsed.scheme()
+                );
+            }
         }
 
         Ok(Self {
             eth_rpc,
             eth_rpc_headers,
             eth_call_throttle_ms,
-            use_historical_backfill,
+            price_feed,
             search_index_enabled,
             search_prefix_min_len,
             search_prefix_max_len,
             search_fallback_scan_cap,
             search_limit_cap,
             derived_liquidity,
+            networks,
         })
     }
 
@@ -206,6 +237,98 @@ impl AmmDataConfig {
     }
 }
 
+/// Key used for `network` under `ammdata.networks`.
+pub fn network_key(network: Network) -> &'static str {
+    match network {
+        Network::Bitcoin => "mainnet",
+        Network::Testnet => "testnet",
+        Network::Signet => "signet",
+        Network::Regtest => "regtest",
+        _ => "testnet4",
+    }
+}
+
+fn parse_amm_networks(value: &Value) -> Result<HashMap<String, AmmNetworkConfig>> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| anyhow!("ammdata.networks must be an object keyed by network name"))?;
+    let mut out = HashMap::new();
+    for (name, entry) in obj {
+        let key = match name.trim().to_ascii_lowercase().as_str() {
+            "mainnet" | "bitcoin" => "mainnet",
+            "testnet" | "testnet3" => "testnet",
+            "testnet4" => "testnet4",
+            "signet" => "signet",
+            "regtest" => "regtest",
+            other => anyhow::bail!(
+                "ammdata.networks key must be mainnet|testnet|testnet4|signet|regtest; got {other}"
+            ),
+        };
+        let entry_obj = entry
+            .as_object()
+            .ok_or_else(|| anyhow!("ammdata.networks.{key} must be an object"))?;
+
+        let mut factories = Vec::new();
+        match entry_obj.get("amm_factories") {
+            None | Some(Value::Null) => {}
+            Some(Value::Array(arr)) => {
+                for v in arr {
+                    let raw = v.as_str().unwrap_or_default();
+                    factories.push(parse_alkane_id_str(raw).ok_or_else(|| {
+                        anyhow!("ammdata.networks.{key}.amm_factories entries must be like \"4:1\"")
+                    })?);
+                }
+            }
+            _ => anyhow::bail!("ammdata.networks.{key}.amm_factories must be an array"),
+        }
+
+        let genesis_height = match entry_obj.get("genesis_height") {
+            None | Some(Value::Null) => None,
+            Some(v) => Some(v.as_u64().and_then(|n| u32::try_from(n).ok()).ok_or_else(|| {
+                anyhow!("ammdata.networks.{key}.genesis_height must be a block height")
+            })?),
+        };
+
+        let canonical_quotes = match entry_obj.get("canonical_quotes") {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(arr)) => {
+                let mut quotes = Vec::new();
+                for q in arr {
+                    let id = q.get("id").and_then(|v| v.as_str()).and_then(parse_alkane_id_str);
+                    let unit = match q.get("unit").and_then(|v| v.as_str()) {
+                        Some(u) if u.eq_ignore_ascii_case("btc") => Some(CanonicalQuoteUnit::Btc),
+                        Some(u) if u.eq_ignore_ascii_case("usd") => Some(CanonicalQuoteUnit::Usd),
+                        _ => None,
+                    };
+                    match (id, unit) {
+                        (Some(id), Some(unit)) => quotes.push(CanonicalQuote { id, unit }),
+                        _ => anyhow::bail!(
+                            "ammdata.networks.{key}.canonical_quotes entries must be {{ \"id\": \"32:0\", \"unit\": \"btc|usd\" }}"
+                        ),
+                    }
+                }
+                Some(quotes)
+            }
+            _ => anyhow::bail!("ammdata.networks.{key}.canonical_quotes must be an array"),
+        };
+
+        let default_fee_bps = match entry_obj.get("default_fee_bps") {
+            None | Some(Value::Null) => None,
+            Some(v) => {
+                Some(v.as_u64().filter(|n| *n < 10_000).map(|n| n as u32).ok_or_else(|| {
+                    anyhow!("ammdata.networks.{key}.default_fee_bps must be an integer below 10000")
+                })?)
+            }
+        };
+
+        out.insert(
+            key.to_string(),
+            AmmNetworkConfig { factories, genesis_height, canonical_quotes, default_fee_bps },
+        );
+    }
+    Ok(out)
+}
+
 fn parse_alkane_id_str(raw: &str) -> Option<SchemaAlkaneId> {
     let mut parts = raw.split(':');
     let block = parts.next()?.parse::<u32>().ok()?;
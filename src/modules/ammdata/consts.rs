@@ -1,18 +1,67 @@
+use crate::config::{get_network, try_get_config};
+use crate::modules::ammdata::config::{AmmNetworkConfig, network_key};
+use crate::modules::ammdata::utils::pathfinder::DEFAULT_FEE_BPS;
+use crate::runtime::timeseries::SeriesDef;
 use crate::schemas::SchemaAlkaneId;
 use anyhow::{Result, anyhow};
 use bitcoin::Network;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+static AMM_NETWORK_OVERRIDES: OnceLock<HashMap<String, AmmNetworkConfig>> = OnceLock::new();
+
+/// Installs `ammdata.networks` once the module config has been parsed, so a bad override
+/// fails config load instead of silently falling back. The first call wins.
+pub fn init_network_overrides(networks: HashMap<String, AmmNetworkConfig>) {
+    let _ = AMM_NETWORK_OVERRIDES.set(networks);
+}
+
+/// `ammdata.networks.<network>` from the loaded config, if any. Built-in values below are
+/// only used for the fields an override leaves unset.
+fn network_override(network: Network) -> Option<&'static AmmNetworkConfig> {
+    AMM_NETWORK_OVERRIDES.get()?.get(network_key(network))
+}
 
 pub fn ammdata_genesis_block(network: Network) -> u32 {
+    if let Some(height) = network_override(network).and_then(|o| o.genesis_height) {
+        return height;
+    }
     match network {
         Network::Bitcoin => 904_647,
         _ => 0,
     }
 }
 
+/// Factory ids configured for `network`. These are registered as AMM factories up front
+/// instead of waiting for inspection to recognise them.
+pub fn configured_amm_factories(network: Network) -> Vec<SchemaAlkaneId> {
+    network_override(network).map(|o| o.factories.clone()).unwrap_or_default()
+}
+
 pub fn get_amm_contract(network: Network) -> Result<SchemaAlkaneId> {
+    if let Some(factory) = configured_amm_factories(network).first() {
+        return Ok(*factory);
+    }
     match network {
         Network::Bitcoin => Ok(SchemaAlkaneId { block: 4u32, tx: 65522u64 }),
-        _ => Err(anyhow!("AMMDATA ERROR: Amm contract not defined for this network")),
+        _ => Err(anyhow!(
+            "AMMDATA ERROR: Amm contract not defined for this network; set ammdata.networks.{}.amm_factories",
+            network_key(network)
+        )),
+    }
+}
+
+pub fn amm_default_fee_bps(network: Network) -> u32 {
+    network_override(network)
+        .and_then(|o| o.default_fee_bps)
+        .unwrap_or(DEFAULT_FEE_BPS)
+}
+
+/// Default fee for the running network; falls back to `DEFAULT_FEE_BPS` before config init.
+pub fn current_default_fee_bps() -> u32 {
+    match try_get_config() {
+        Some(_) => amm_default_fee_bps(get_network()),
+        None => DEFAULT_FEE_BPS,
     }
 }
 
@@ -25,6 +74,22 @@ pub const AMOUNT_SCALE: u128 = 100_000_000; // on-chain token amount precision (
 pub const SATS_PER_BTC: u128 = AMOUNT_SCALE;
 pub const K_TOLERANCE_BPS: u128 = 10; // 0.1%
 
+pub const SERIES_POOL_TVL_USD: &str = "pool_tvl_usd";
+pub const SERIES_POOL_TVL_SATS: &str = "pool_tvl_sats";
+
+pub const AMMDATA_SERIES: &[SeriesDef] = &[
+    SeriesDef {
+        name: SERIES_POOL_TVL_USD,
+        keyed_by: "pool",
+        description: "pool TVL in USD (PRICE_SCALE fixed point)",
+    },
+    SeriesDef {
+        name: SERIES_POOL_TVL_SATS,
+        keyed_by: "pool",
+        description: "pool TVL in sats (PRICE_SCALE fixed point)",
+    },
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CanonicalQuoteUnit {
     Btc,
@@ -38,15 +103,20 @@ pub struct CanonicalQuote {
 }
 
 pub fn canonical_quotes(network: Network) -> Vec<CanonicalQuote> {
-    let mainnet = vec![
-        CanonicalQuote { id: SchemaAlkaneId { block: 32, tx: 0 }, unit: CanonicalQuoteUnit::Btc },
-        CanonicalQuote {
-            id: SchemaAlkaneId { block: 2, tx: 56801 },
-            unit: CanonicalQuoteUnit::Usd,
-        },
-    ];
+    if let Some(quotes) = network_override(network).and_then(|o| o.canonical_quotes.clone()) {
+        return quotes;
+    }
+    let frbtc =
+        CanonicalQuote { id: SchemaAlkaneId { block: 32, tx: 0 }, unit: CanonicalQuoteUnit::Btc };
     match network {
-        Network::Bitcoin => mainnet,
-        _ => mainnet,
+        Network::Bitcoin => vec![
+            frbtc,
+            CanonicalQuote {
+                id: SchemaAlkaneId { block: 2, tx: 56801 },
+                unit: CanonicalQuoteUnit::Usd,
+            },
+        ],
+        // frBTC lives at 32:0 everywhere; USD stables differ per network and must be configured.
+        _ => vec![frbtc],
     }
 }
@@ -11,7 +11,9 @@ use crate::debug;
 use crate::modules::ammdata::config::{AmmDataConfig, DerivedMergeStrategy, DerivedQuoteConfig};
 use crate::modules::ammdata::consts::{
     AMOUNT_SCALE, CanonicalQuoteUnit, PRICE_SCALE, ammdata_genesis_block, canonical_quotes,
+    init_network_overrides,
 };
+use crate::modules::ammdata::price_feeds::PriceFeedSpec;
 use crate::modules::defs::{EspoModule, RpcNsRegistrar};
 use crate::modules::essentials::storage::{
     AlkaneBalanceTxEntry, EssentialsProvider,
@@ -24,6 +26,7 @@ use crate::modules::essentials::utils::inspections::{
 };
 use crate::runtime::mdb::Mdb;
 use crate::runtime::state_at::StateAt;
+use crate::runtime::timeseries::SeriesDef;
 use crate::schemas::SchemaAlkaneId;
 use anyhow::{Result, anyhow};
 use bitcoin::Network;
@@ -579,6 +582,15 @@ impl AmmData {
         Self { provider: None }
     }
 
+    /// Reads essentials from `essentials` instead of the essentials module db. Call after
+    /// `set_mdb`; used where essentials is not opened through the module registry.
+    pub fn set_essentials(&mut self, essentials: Arc<EssentialsProvider>) {
+        if let Some(provider) = self.provider.take() {
+            let mdb = Arc::new(provider.mdb().clone());
+            self.provider = Some(Arc::new(AmmDataProvider::new(mdb, essentials)));
+        }
+    }
+
     #[inline]
     fn provider(&self) -> &AmmDataProvider {
         self.provider.as_ref().expect("ModuleRegistry must call set_mdb()").as_ref()
@@ -654,8 +666,10 @@ impl EspoModule for AmmData {
         let search_cfg = AmmDataConfig::load_from_global_config().ok();
         let search_index_enabled =
             search_cfg.as_ref().map(|c| c.search_index_enabled).unwrap_or(false);
-        let use_historical_backfill =
-            search_cfg.as_ref().map(|c| c.use_historical_backfill).unwrap_or(true);
+        let price_feed = search_cfg
+            .as_ref()
+            .map(|c| c.price_feed.clone())
+            .unwrap_or(PriceFeedSpec::HistoricalBackfill);
         let mut search_prefix_min =
             search_cfg.as_ref().map(|c| c.search_prefix_min_len as usize).unwrap_or(2);
         let mut search_prefix_max =
@@ -674,7 +688,10 @@ impl EspoModule for AmmData {
 
         let timer = debug::start_if(debug);
         let reserves_snapshot =
-            crate::modules::ammdata::utils::index_snapshot::load_reserves_snapshot(&provider)?;
+            crate::modules::ammdata::utils::index_snapshot::load_reserves_snapshot(
+                &provider,
+                StateAt::Latest,
+            )?;
         let pools_map = crate::modules::ammdata::utils::index_snapshot::pools_map_from_snapshot(
             &reserves_snapshot,
         );
@@ -716,7 +733,7 @@ impl EspoModule for AmmData {
         }
         debug::log_elapsed(module, "bootstrap_pools_from_creation_records", timer);
 
-        let frames = vec![Timeframe::M10];
+        let frames = vec![Timeframe::Min1, Timeframe::M10];
 
         let timer = debug::start_if(debug);
         let discovery = crate::modules::ammdata::utils::index_pools::discover_new_pools(
@@ -740,11 +757,19 @@ impl EspoModule for AmmData {
             essentials,
             &canonical_quote_units,
             &frames,
-            &discovery.tx_meta,
+            &discovery,
             &mut state,
-        );
+        )?;
         debug::log_elapsed(module, "process_traces_activity", timer);
 
+        let backfilled =
+            crate::modules::ammdata::utils::index_activity::backfill_pool_swaps_by_height(
+                provider, essentials, &mut state,
+            )?;
+        if let Some(added) = backfilled.filter(|n| *n > 0) {
+            eprintln!("[AMMDATA] backfilled {added} swaps into the pool swaps height index");
+        }
+
         let timer = debug::start_if(debug);
         crate::modules::ammdata::utils::index_tokens::derive_token_data(
             block_ts,
@@ -753,7 +778,7 @@ impl EspoModule for AmmData {
             essentials,
             &canonical_quote_units,
             &derived_quotes,
-            use_historical_backfill,
+            &price_feed,
             search_index_enabled,
             search_prefix_min,
             search_prefix_max,
@@ -761,6 +786,61 @@ impl EspoModule for AmmData {
         )?;
         debug::log_elapsed(module, "derive_token_metrics", timer);
 
+        let backfilled =
+            crate::modules::ammdata::utils::index_tokens::backfill_fdv_index(provider, &mut state)?;
+        if backfilled > 0 {
+            eprintln!("[AMMDATA] backfilled {backfilled} tokens into the fdv index");
+        }
+
+        let timer = debug::start_if(debug);
+        crate::modules::ammdata::utils::index_leaderboards::record_trader_stats(
+            block_ts,
+            provider,
+            &canonical_quote_units,
+            &mut state,
+        )?;
+        debug::log_elapsed(module, "trader_stats", timer);
+
+        let timer = debug::start_if(debug);
+        crate::modules::ammdata::utils::index_positions::apply_lp_events(
+            height,
+            block_ts,
+            provider,
+            &canonical_quote_units,
+            &mut state,
+        )?;
+        debug::log_elapsed(module, "lp_positions", timer);
+
+        let timer = debug::start_if(debug);
+        crate::modules::ammdata::utils::index_arbitrage::detect_arbitrage(
+            height,
+            block_ts,
+            provider,
+            &canonical_quote_units,
+            &mut state,
+        )?;
+        debug::log_elapsed(module, "arbitrage", timer);
+
+        let timer = debug::start_if(debug);
+        crate::modules::ammdata::utils::index_pnl::apply_swap_events(
+            height,
+            block_ts,
+            provider,
+            &canonical_quote_units,
+            &mut state,
+        )?;
+        debug::log_elapsed(module, "address_pnl", timer);
+
+        let timer = debug::start_if(debug);
+        crate::modules::ammdata::utils::index_pool_fees::record_pool_fees(
+            height,
+            block_ts,
+            provider,
+            &canonical_quote_units,
+            &mut state,
+        )?;
+        debug::log_elapsed(module, "pool_fees", timer);
+
         let timer = debug::start_if(debug);
         crate::modules::ammdata::utils::index_pool_metrics::derive_pool_metrics(
             blockhash_state.clone(),
@@ -775,10 +855,23 @@ impl EspoModule for AmmData {
         debug::log_elapsed(module, "pool_metrics_tvl", timer);
 
         let timer = debug::start_if(debug);
-        let finalize =
+        crate::modules::ammdata::utils::index_pool_state::record_pool_states(
+            height, block_ts, provider, &mut state,
+        )?;
+        debug::log_elapsed(module, "pool_state", timer);
+
+        let timer = debug::start_if(debug);
+        let mut finalize =
             crate::modules::ammdata::utils::index_finalize::prepare_batch(provider, &mut state)?;
+        let al_cnt = crate::modules::ammdata::utils::index_alerts::evaluate_alerts(
+            height,
+            block_ts,
+            provider,
+            &state,
+            &mut finalize,
+        )?;
         eprintln!(
-            "[AMMDATA] block #{h} prepare writes: candles={c_cnt}, token_usd_candles={tc_cnt}, token_mcusd_candles={tmc_cnt}, token_derived_usd_candles={tdc_cnt}, token_derived_mcusd_candles={tdmc_cnt}, chart_changes={cc_cnt}, token_metrics={tm_cnt}, token_metrics_index={tmi_cnt}, token_search_index={tsi_cnt}, token_derived_metrics={tdm_cnt}, token_derived_metrics_index={tdmi_cnt}, token_derived_search_index={tdsi_cnt}, btc_usd_price={btc_cnt}, btc_usd_line={btcl_cnt}, canonical_pools={cp_cnt}, pool_name_index={pn_cnt}, amm_factories={af_cnt}, factory_pools={fp_cnt}, pool_factory={pf_cnt}, pool_creation_info={pc_cnt}, pool_creations={pcg_cnt}, token_pools={tp_cnt}, pool_defs={pd_cnt}, pool_metrics={pm_cnt}, pool_metrics_index={pmi_cnt}, pool_lp_supply={pls_cnt}, pool_details_snapshot={pds_cnt}, tvl_versioned={tvl_cnt}, token_swaps={ts_cnt}, address_pool_swaps={aps_cnt}, address_token_swaps={ats_cnt}, address_pool_creations={apc_cnt}, address_pool_mints={apm_cnt}, address_pool_burns={apb_cnt}, address_amm_history={aah_cnt}, amm_history_all={ah_cnt}, activity={a_cnt}, indexes+counts={i_cnt}, reserves_snapshot=1",
+            "[AMMDATA] block #{h} prepare writes: candles={c_cnt}, token_usd_candles={tc_cnt}, token_mcusd_candles={tmc_cnt}, token_derived_usd_candles={tdc_cnt}, token_derived_mcusd_candles={tdmc_cnt}, chart_changes={cc_cnt}, token_metrics={tm_cnt}, token_metrics_index={tmi_cnt}, token_search_index={tsi_cnt}, token_derived_metrics={tdm_cnt}, token_derived_metrics_index={tdmi_cnt}, token_derived_search_index={tdsi_cnt}, btc_usd_price={btc_cnt}, btc_usd_line={btcl_cnt}, canonical_pools={cp_cnt}, pool_name_index={pn_cnt}, amm_factories={af_cnt}, factory_pools={fp_cnt}, pool_factory={pf_cnt}, pool_protocol={pp_cnt}, lp_positions={lpp_cnt}, address_pnl={pnl_cnt}, pool_state={pst_cnt}, arbitrage={arb_cnt}, pool_fees={pfee_cnt}, trader_stats={trs_cnt}, alert_events={al_cnt}, pool_creation_info={pc_cnt}, pool_creations={pcg_cnt}, token_pools={tp_cnt}, pool_defs={pd_cnt}, pool_metrics={pm_cnt}, pool_metrics_index={pmi_cnt}, pool_lp_supply={pls_cnt}, pool_details_snapshot={pds_cnt}, tvl_versioned={tvl_cnt}, timeseries={tsr_cnt}, token_swaps={ts_cnt}, address_pool_swaps={aps_cnt}, address_token_swaps={ats_cnt}, address_pool_creations={apc_cnt}, address_pool_mints={apm_cnt}, address_pool_burns={apb_cnt}, address_amm_history={aah_cnt}, amm_history_all={ah_cnt}, activity={a_cnt}, indexes+counts={i_cnt}, reserves_snapshot=1",
             h = block.height,
             c_cnt = finalize.stats.candle_writes,
             tc_cnt = finalize.stats.token_usd_candles,
@@ -799,6 +892,13 @@ impl EspoModule for AmmData {
             af_cnt = finalize.stats.amm_factories,
             fp_cnt = finalize.stats.factory_pools,
             pf_cnt = finalize.stats.pool_factory,
+            pp_cnt = finalize.stats.pool_protocol,
+            lpp_cnt = finalize.stats.lp_positions,
+            pnl_cnt = finalize.stats.address_pnl,
+            pst_cnt = finalize.stats.pool_state,
+            arb_cnt = finalize.stats.arbitrage,
+            pfee_cnt = finalize.stats.pool_fees,
+            trs_cnt = finalize.stats.trader_stats,
             pc_cnt = finalize.stats.pool_creation_info,
             pcg_cnt = finalize.stats.pool_creations,
             pd_cnt = finalize.stats.pool_defs,
@@ -806,6 +906,7 @@ impl EspoModule for AmmData {
             pmi_cnt = finalize.stats.pool_metrics_index,
             pls_cnt = finalize.stats.pool_lp_supply,
             tvl_cnt = finalize.stats.tvl_versioned,
+            tsr_cnt = finalize.stats.timeseries,
             ts_cnt = finalize.stats.token_swaps,
             apc_cnt = finalize.stats.address_pool_creations,
             apm_cnt = finalize.stats.address_pool_mints,
@@ -850,6 +951,10 @@ impl EspoModule for AmmData {
         self.load_index_height().ok().flatten()
     }
 
+    fn timeseries(&self) -> &'static [SeriesDef] {
+        AMMDATA_SERIES
+    }
+
     fn register_rpc(&self, reg: &RpcNsRegistrar) {
         let provider = self.provider.as_ref().expect("ModuleRegistry must call set_mdb()").clone();
         register_rpc(reg, provider);
@@ -860,6 +965,8 @@ impl EspoModule for AmmData {
     }
 
     fn set_config(&mut self, config: &serde_json::Value) -> Result<()> {
-        AmmDataConfig::from_value(config).map(|_| ())
+        let parsed = AmmDataConfig::from_value(config)?;
+        init_network_overrides(parsed.networks);
+        Ok(())
     }
 }
@@ -1,3 +1,4 @@
+pub mod adapters;
 pub mod config;
 pub mod consts;
 pub mod main;
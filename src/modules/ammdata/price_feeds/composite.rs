@@ -0,0 +1,138 @@
+use super::defs::PriceFeed;
+use super::health::{fetch_price, record_rejected};
+use anyhow::{Result, anyhow};
+
+/// First source that answers wins; sources are tried in order.
+pub struct FallbackPriceFeed<'a> {
+    sources: Vec<Box<dyn PriceFeed + 'a>>,
+}
+
+impl<'a> FallbackPriceFeed<'a> {
+    pub fn new(sources: Vec<Box<dyn PriceFeed + 'a>>) -> Self {
+        Self { sources }
+    }
+}
+
+impl PriceFeed for FallbackPriceFeed<'_> {
+    fn name(&self) -> String {
+        "fallback".to_string()
+    }
+
+    fn get_bitcoin_price_usd_at_block_height(&self, height: u64) -> Result<u128> {
+        let mut errors = Vec::new();
+        for source in &self.sources {
+            match fetch_price(source.as_ref(), height) {
+                Ok(price) => return Ok(price),
+                Err(e) => errors.push(format!("{}: {e}", source.name())),
+            }
+        }
+        Err(anyhow!("all sources failed ({})", errors.join("; ")))
+    }
+}
+
+/// Median of every answering source after dropping quotes further than
+/// `max_deviation_bps` from the raw median. Fails when fewer than `min_sources` survive.
+pub struct MedianPriceFeed<'a> {
+    sources: Vec<Box<dyn PriceFeed + 'a>>,
+    max_deviation_bps: u32,
+    min_sources: usize,
+}
+
+impl<'a> MedianPriceFeed<'a> {
+    pub fn new(
+        sources: Vec<Box<dyn PriceFeed + 'a>>,
+        max_deviation_bps: u32,
+        min_sources: usize,
+    ) -> Self {
+        Self { sources, max_deviation_bps, min_sources }
+    }
+}
+
+impl PriceFeed for MedianPriceFeed<'_> {
+    fn name(&self) -> String {
+        "median".to_string()
+    }
+
+    fn get_bitcoin_price_usd_at_block_height(&self, height: u64) -> Result<u128> {
+        let quotes: Vec<(String, u128)> = self
+            .sources
+            .iter()
+            .filter_map(|s| fetch_price(s.as_ref(), height).ok().map(|p| (s.name(), p)))
+            .collect();
+        let (kept, rejected) = reject_outliers(&quotes, self.max_deviation_bps)
+            .ok_or_else(|| anyhow!("no source answered for height {height}"))?;
+        for name in &rejected {
+            record_rejected(name);
+        }
+        if kept.len() < self.min_sources.max(1) {
+            anyhow::bail!(
+                "only {} of {} sources within {} bps of the median (need {})",
+                kept.len(),
+                self.sources.len(),
+                self.max_deviation_bps,
+                self.min_sources
+            );
+        }
+        median(&kept).ok_or_else(|| anyhow!("no quotes left after outlier rejection"))
+    }
+}
+
+/// Splits quotes into the prices kept and the names of the sources rejected.
+fn reject_outliers(
+    quotes: &[(String, u128)],
+    max_deviation_bps: u32,
+) -> Option<(Vec<u128>, Vec<String>)> {
+    let prices: Vec<u128> = quotes.iter().map(|(_, p)| *p).collect();
+    let mid = median(&prices)?;
+    let mut kept = Vec::new();
+    let mut rejected = Vec::new();
+    for (name, price) in quotes {
+        let deviation = price.abs_diff(mid).saturating_mul(10_000);
+        if deviation > mid.saturating_mul(max_deviation_bps as u128) {
+            rejected.push(name.clone());
+        } else {
+            kept.push(*price);
+        }
+    }
+    Some((kept, rejected))
+}
+
+fn median(values: &[u128]) -> Option<u128> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let n = sorted.len();
+    if n == 0 {
+        return None;
+    }
+    if n % 2 == 1 {
+        return Some(sorted[n / 2]);
+    }
+    let (lo, hi) = (sorted[n / 2 - 1], sorted[n / 2]);
+    Some(lo + (hi - lo) / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_odd_and_even_sets() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[5, 1, 3]), Some(3));
+        assert_eq!(median(&[4, 1, 3, 2]), Some(2));
+        assert_eq!(median(&[u128::MAX, u128::MAX]), Some(u128::MAX));
+    }
+
+    #[test]
+    fn outliers_beyond_threshold_are_rejected() {
+        let quotes = vec![
+            ("a".to_string(), 100_000),
+            ("b".to_string(), 100_500),
+            ("c".to_string(), 99_800),
+            ("bad".to_string(), 150_000),
+        ];
+        let (kept, rejected) = reject_outliers(&quotes, 200).unwrap();
+        assert_eq!(rejected, vec!["bad".to_string()]);
+        assert_eq!(median(&kept), Some(100_000));
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::Result;
 
 pub trait PriceFeed {
+    /// Label used for health reporting, e.g. "uniswap" or "json_file:/data/btc.json".
+    fn name(&self) -> String;
     fn get_bitcoin_price_usd_at_block_height(&self, height: u64) -> Result<u128>;
 }
@@ -0,0 +1,75 @@
+use super::defs::PriceFeed;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Outcome counters for one price source, kept in memory for `get_btc_usd_price`.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FeedHealth {
+    pub source: String,
+    pub healthy: bool,
+    pub ok_count: u64,
+    pub error_count: u64,
+    /// Quotes discarded by a median aggregator as outliers.
+    pub rejected_count: u64,
+    pub last_height: Option<u64>,
+    pub last_price: Option<String>,
+    pub last_ok_at: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<u64>,
+}
+
+static FEED_HEALTH: OnceLock<RwLock<BTreeMap<String, FeedHealth>>> = OnceLock::new();
+
+fn registry() -> &'static RwLock<BTreeMap<String, FeedHealth>> {
+    FEED_HEALTH.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn update(source: &str, f: impl FnOnce(&mut FeedHealth)) {
+    let mut map = registry().write().expect("price feed health poisoned");
+    let entry = map
+        .entry(source.to_string())
+        .or_insert_with(|| FeedHealth { source: source.to_string(), ..Default::default() });
+    f(entry);
+}
+
+/// Queries `feed` and records the outcome under its name.
+pub fn fetch_price(feed: &dyn PriceFeed, height: u64) -> Result<u128> {
+    let result = feed.get_bitcoin_price_usd_at_block_height(height);
+    match &result {
+        Ok(price) => update(&feed.name(), |h| {
+            h.healthy = true;
+            h.ok_count += 1;
+            h.last_height = Some(height);
+            h.last_price = Some(price.to_string());
+            h.last_ok_at = Some(now_secs());
+        }),
+        Err(e) => update(&feed.name(), |h| {
+            h.healthy = false;
+            h.error_count += 1;
+            h.last_height = Some(height);
+            h.last_error = Some(format!("{e:#}"));
+            h.last_error_at = Some(now_secs());
+        }),
+    }
+    result
+}
+
+pub fn record_rejected(source: &str) {
+    update(source, |h| h.rejected_count += 1);
+}
+
+pub fn feed_health() -> Vec<FeedHealth> {
+    registry()
+        .read()
+        .expect("price feed health poisoned")
+        .values()
+        .cloned()
+        .collect()
+}
@@ -1,3 +1,4 @@
+use super::defs::PriceFeed;
 use anyhow::{Context, Result, anyhow};
 use serde::Deserialize;
 use std::collections::BTreeMap;
@@ -28,12 +29,31 @@ pub fn get_historical_btc_usd_price(height: u64) -> Result<Option<u128>> {
     }
 }
 
+/// Serves the bundled `resources/btc_usd_historical.json` as a feed.
+pub struct HistoricalBackfillFeed;
+
+impl PriceFeed for HistoricalBackfillFeed {
+    fn name(&self) -> String {
+        "historical_backfill".to_string()
+    }
+
+    fn get_bitcoin_price_usd_at_block_height(&self, height: u64) -> Result<u128> {
+        get_historical_btc_usd_price(height)?
+            .ok_or_else(|| anyhow!("no backfill point at or before height {height}"))
+    }
+}
+
 fn load_historical_backfill() -> Result<BTreeMap<u64, u128>> {
     let path = historical_backfill_path();
     let raw =
         fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
-    let parsed: BtcUsdHistoricalFile = serde_json::from_str(&raw)
-        .with_context(|| format!("failed to parse {}", path.display()))?;
+    parse_price_points(&raw).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Parses the `{ "points": [{ "height", "price_scaled" }] }` layout shared by the bundled
+/// backfill and JSON feeds.
+pub(crate) fn parse_price_points(raw: &str) -> Result<BTreeMap<u64, u128>> {
+    let parsed: BtcUsdHistoricalFile = serde_json::from_str(raw)?;
 
     let mut prices = BTreeMap::new();
     for point in parsed.points {
@@ -0,0 +1,151 @@
+use super::defs::PriceFeed;
+use super::historical_backfill::parse_price_points;
+use crate::config::get_last_safe_tip;
+use crate::modules::ammdata::consts::PRICE_SCALE_DECIMALS;
+use anyhow::{Context, Result, anyhow};
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+#[derive(Clone, Debug)]
+pub enum JsonSource {
+    File(String),
+    Http { url: String, headers: HashMap<String, String> },
+}
+
+/// BTC/USD from a local file or an HTTP endpoint returning JSON.
+///
+/// A document with a `points` array uses the backfill layout and is looked up by height.
+/// Anything else is a current price read at `pointer`; it is only served for heights
+/// within `max_lag_blocks` of the safe tip so a catch-up never stamps old blocks with
+/// today's price.
+pub struct JsonPriceFeed {
+    source: JsonSource,
+    pointer: String,
+    /// The value at `pointer` is already PRICE_SCALE fixed point rather than plain USD.
+    scaled: bool,
+    max_lag_blocks: u64,
+    client: Option<Client>,
+}
+
+impl JsonPriceFeed {
+    pub fn new(source: JsonSource, pointer: String, scaled: bool, max_lag_blocks: u64) -> Self {
+        let client = match &source {
+            JsonSource::File(_) => None,
+            JsonSource::Http { headers, .. } => {
+                let mut default_headers = HeaderMap::new();
+                for (k, v) in headers {
+                    match (HeaderName::from_bytes(k.as_bytes()), HeaderValue::from_str(v)) {
+                        (Ok(name), Ok(value)) => {
+                            default_headers.insert(name, value);
+                        }
+                        _ => eprintln!("[ammdata] warning: skipping invalid header: {k}: {v}"),
+                    }
+                }
+                Client::builder()
+                    .timeout(Duration::from_secs(10))
+                    .default_headers(default_headers)
+                    .build()
+                    .ok()
+            }
+        };
+        Self { source, pointer, scaled, max_lag_blocks, client }
+    }
+
+    fn load(&self) -> Result<String> {
+        match &self.source {
+            JsonSource::File(path) => {
+                fs::read_to_string(path).with_context(|| format!("failed to read {path}"))
+            }
+            JsonSource::Http { url, .. } => {
+                let client =
+                    self.client.as_ref().ok_or_else(|| anyhow!("http client unavailable"))?;
+                Ok(client.get(url).send()?.error_for_status()?.text()?)
+            }
+        }
+    }
+}
+
+impl PriceFeed for JsonPriceFeed {
+    fn name(&self) -> String {
+        match &self.source {
+            JsonSource::File(path) => format!("json_file:{path}"),
+            JsonSource::Http { url, .. } => format!("http_json:{url}"),
+        }
+    }
+
+    fn get_bitcoin_price_usd_at_block_height(&self, height: u64) -> Result<u128> {
+        let raw = self.load()?;
+        let doc: Value = serde_json::from_str(&raw).context("invalid json")?;
+        if doc.get("points").is_some_and(|v| v.is_array()) {
+            let points = parse_price_points(&raw)?;
+            return points
+                .range(..=height)
+                .next_back()
+                .map(|(_h, p)| *p)
+                .ok_or_else(|| anyhow!("no point at or before height {height}"));
+        }
+
+        if let Some(tip) = get_last_safe_tip() {
+            if height.saturating_add(self.max_lag_blocks) < tip as u64 {
+                anyhow::bail!("current-price feed not used for height {height} (tip {tip})");
+            }
+        }
+        let value = doc
+            .pointer(&self.pointer)
+            .ok_or_else(|| anyhow!("no value at json pointer {}", self.pointer))?;
+        let text = match value {
+            Value::String(s) => s.trim().to_string(),
+            Value::Number(n) => n.to_string(),
+            _ => anyhow::bail!("value at {} must be a number or string", self.pointer),
+        };
+        let decimals = if self.scaled { 0 } else { PRICE_SCALE_DECIMALS };
+        let price = parse_decimal_scaled(&text, decimals)
+            .ok_or_else(|| anyhow!("invalid price '{text}' at {}", self.pointer))?;
+        if price == 0 {
+            anyhow::bail!("price at {} is zero", self.pointer);
+        }
+        Ok(price)
+    }
+}
+
+/// Parses a plain decimal ("67123.45") into an integer scaled by 10^decimals, truncating
+/// extra fraction digits.
+fn parse_decimal_scaled(text: &str, decimals: u32) -> Option<u128> {
+    let (int_part, frac_part) = text.split_once('.').unwrap_or((text, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if !all_digits(int_part) || !all_digits(frac_part) {
+        return None;
+    }
+    let scale = 10u128.checked_pow(decimals)?;
+    let int_val = if int_part.is_empty() { 0 } else { int_part.parse::<u128>().ok()? };
+    let mut frac_val = 0u128;
+    let mut frac_scale = scale;
+    for b in frac_part.bytes().take(decimals as usize) {
+        frac_scale /= 10;
+        frac_val += (b - b'0') as u128 * frac_scale;
+    }
+    int_val.checked_mul(scale)?.checked_add(frac_val)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_decimal_scaled;
+
+    #[test]
+    fn decimal_prices_scale_exactly() {
+        assert_eq!(parse_decimal_scaled("67123.45", 2), Some(6_712_345));
+        assert_eq!(parse_decimal_scaled("67123", 16), Some(67_123 * 10u128.pow(16)));
+        assert_eq!(parse_decimal_scaled("0.123456", 3), Some(123));
+        assert_eq!(parse_decimal_scaled("12", 0), Some(12));
+        assert_eq!(parse_decimal_scaled("1e5", 2), None);
+        assert_eq!(parse_decimal_scaled("-1", 2), None);
+        assert_eq!(parse_decimal_scaled(".", 2), None);
+    }
+}
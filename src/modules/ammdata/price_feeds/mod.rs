@@ -1,7 +1,12 @@
+pub mod composite;
 pub mod defs;
+pub mod health;
 pub mod historical_backfill;
+pub mod json_feed;
+pub mod pool;
+pub mod spec;
 pub mod uniswap;
 
 pub use defs::PriceFeed;
-pub use historical_backfill::get_historical_btc_usd_price;
-pub use uniswap::UniswapPriceFeed;
+pub use health::{feed_health, fetch_price};
+pub use spec::{FeedContext, PriceFeedSpec};
@@ -0,0 +1,66 @@
+use super::defs::PriceFeed;
+use crate::config::get_network;
+use crate::modules::ammdata::consts::{CanonicalQuoteUnit, PRICE_SCALE, canonical_quotes};
+use crate::modules::ammdata::schemas::SchemaPoolSnapshot;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+
+/// BTC/USD derived on-chain from the deepest pool pairing a BTC-unit canonical quote
+/// (frBTC) with a USD-unit one.
+///
+/// Reads the reserves it was built with, so the answer is only meaningful for the block
+/// those reserves belong to; `height` is not consulted.
+pub struct PoolPriceFeed<'a> {
+    reserves: &'a HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    /// Pools with less USD-side liquidity than this (base units) are ignored.
+    min_usd_reserve: u128,
+}
+
+impl<'a> PoolPriceFeed<'a> {
+    pub fn new(
+        reserves: &'a HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+        min_usd_reserve: u128,
+    ) -> Self {
+        Self { reserves, min_usd_reserve }
+    }
+}
+
+impl PriceFeed for PoolPriceFeed<'_> {
+    fn name(&self) -> String {
+        "pool:frbtc_usd".to_string()
+    }
+
+    fn get_bitcoin_price_usd_at_block_height(&self, _height: u64) -> Result<u128> {
+        let units: HashMap<SchemaAlkaneId, CanonicalQuoteUnit> =
+            canonical_quotes(get_network()).into_iter().map(|cq| (cq.id, cq.unit)).collect();
+
+        // (btc_reserve, usd_reserve) of the pool with the most USD liquidity.
+        let mut best: Option<(u128, u128)> = None;
+        for snap in self.reserves.values() {
+            let sides = match (units.get(&snap.base_id), units.get(&snap.quote_id)) {
+                (Some(CanonicalQuoteUnit::Btc), Some(CanonicalQuoteUnit::Usd)) => {
+                    (snap.base_reserve, snap.quote_reserve)
+                }
+                (Some(CanonicalQuoteUnit::Usd), Some(CanonicalQuoteUnit::Btc)) => {
+                    (snap.quote_reserve, snap.base_reserve)
+                }
+                _ => continue,
+            };
+            if sides.0 == 0 || sides.1 < self.min_usd_reserve.max(1) {
+                continue;
+            }
+            if best.is_none_or(|(_, usd)| sides.1 > usd) {
+                best = Some(sides);
+            }
+        }
+
+        let (btc, usd) =
+            best.ok_or_else(|| anyhow!("no frBTC/USD pool with enough liquidity in snapshot"))?;
+        let price = usd.saturating_mul(PRICE_SCALE) / btc;
+        if price == 0 {
+            anyhow::bail!("frBTC/USD pool price rounds to zero");
+        }
+        Ok(price)
+    }
+}
@@ -0,0 +1,256 @@
+use super::composite::{FallbackPriceFeed, MedianPriceFeed};
+use super::defs::PriceFeed;
+use super::historical_backfill::HistoricalBackfillFeed;
+use super::json_feed::{JsonPriceFeed, JsonSource};
+use super::pool::PoolPriceFeed;
+use super::uniswap::UniswapPriceFeed;
+use crate::modules::ammdata::schemas::SchemaPoolSnapshot;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const DEFAULT_JSON_POINTER: &str = "/price";
+const DEFAULT_MAX_LAG_BLOCKS: u64 = 6;
+const DEFAULT_MAX_DEVIATION_BPS: u32 = 200;
+
+/// Parsed `ammdata.price_feed` config. Built into a live feed once per block.
+#[derive(Clone, Debug)]
+pub enum PriceFeedSpec {
+    HistoricalBackfill,
+    Uniswap,
+    Json { source: JsonSource, pointer: String, scaled: bool, max_lag_blocks: u64 },
+    Pool { min_usd_reserve: u128 },
+    Median { sources: Vec<PriceFeedSpec>, max_deviation_bps: u32, min_sources: usize },
+    Fallback { sources: Vec<PriceFeedSpec> },
+}
+
+/// Per-block inputs that on-chain feeds derive their price from.
+pub struct FeedContext<'a> {
+    pub reserves: &'a HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+}
+
+/// Stands in for a source that could not be constructed so the failure shows up in
+/// feed health instead of disappearing from the chain.
+struct UnavailableFeed {
+    name: String,
+    error: String,
+}
+
+impl PriceFeed for UnavailableFeed {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_bitcoin_price_usd_at_block_height(&self, _height: u64) -> Result<u128> {
+        Err(anyhow!("{}", self.error))
+    }
+}
+
+impl PriceFeedSpec {
+    pub fn spec() -> &'static str {
+        "\"historical_backfill\" | \"uniswap\" | \"pool\" | [<feed>, ...] | { \"type\": \"json_file\", \"path\": \"<file>\" } | { \"type\": \"http_json\", \"url\": \"<url>\", \"headers\": {} } | { \"type\": \"pool\", \"min_usd_reserve\": <num> } | { \"type\": \"median\", \"sources\": [<feed>, ...], \"max_deviation_bps\": <200>, \"min_sources\": <1> } | { \"type\": \"fallback\", \"sources\": [<feed>, ...] }"
+    }
+
+    /// Chain used when `price_feed` is not configured: the bundled backfill (unless
+    /// disabled), then Uniswap when an eth RPC is set, otherwise the on-chain pool.
+    pub fn default_chain(use_historical_backfill: bool, has_eth_rpc: bool) -> Self {
+        let mut sources = Vec::new();
+        if use_historical_backfill {
+            sources.push(Self::HistoricalBackfill);
+        }
+        sources.push(if has_eth_rpc { Self::Uniswap } else { Self::Pool { min_usd_reserve: 0 } });
+        Self::Fallback { sources }
+    }
+
+    pub fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::String(s) => Self::from_name(s),
+            Value::Array(arr) => Ok(Self::Fallback { sources: Self::list(arr)? }),
+            Value::Object(obj) => {
+                let kind = obj
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow!("ammdata.price_feed object needs a \"type\""))?;
+                let str_field = |key: &str| -> Result<String> {
+                    obj.get(key)
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .ok_or_else(|| anyhow!("ammdata.price_feed {kind} needs \"{key}\""))
+                };
+                let sources = || -> Result<Vec<Self>> {
+                    let arr = obj.get("sources").and_then(|v| v.as_array()).ok_or_else(|| {
+                        anyhow!("ammdata.price_feed {kind} needs a \"sources\" array")
+                    })?;
+                    Self::list(arr)
+                };
+                let json = |source: JsonSource| Self::Json {
+                    source,
+                    pointer: obj
+                        .get("pointer")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(DEFAULT_JSON_POINTER)
+                        .to_string(),
+                    scaled: obj.get("scaled").and_then(|v| v.as_bool()).unwrap_or(false),
+                    max_lag_blocks: obj
+                        .get("max_lag_blocks")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(DEFAULT_MAX_LAG_BLOCKS),
+                };
+
+                match kind {
+                    "json_file" => Ok(json(JsonSource::File(str_field("path")?))),
+                    "http_json" => {
+                        let url = str_field("url")?;
+                        reqwest::Url::parse(&url)
+                            .map_err(|e| anyhow!("ammdata.price_feed http_json url: {e}"))?;
+                        let mut headers = HashMap::new();
+                        if let Some(map) = obj.get("headers").and_then(|v| v.as_object()) {
+                            for (k, v) in map {
+                                let v = v.as_str().ok_or_else(|| {
+                                    anyhow!("ammdata.price_feed header '{k}' must be a string")
+                                })?;
+                                headers.insert(k.clone(), v.to_string());
+                            }
+                        }
+                        Ok(json(JsonSource::Http { url, headers }))
+                    }
+                    "pool" => {
+                        let min_usd_reserve = match obj.get("min_usd_reserve") {
+                            None | Some(Value::Null) => 0,
+                            Some(Value::String(s)) => s.trim().parse::<u128>().map_err(|_| {
+                                anyhow!(
+                                    "ammdata.price_feed pool min_usd_reserve must be an integer"
+                                )
+                            })?,
+                            Some(v) => v.as_u64().map(|n| n as u128).ok_or_else(|| {
+                                anyhow!(
+                                    "ammdata.price_feed pool min_usd_reserve must be an integer"
+                                )
+                            })?,
+                        };
+                        Ok(Self::Pool { min_usd_reserve })
+                    }
+                    "median" => Ok(Self::Median {
+                        sources: sources()?,
+                        max_deviation_bps: obj
+                            .get("max_deviation_bps")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as u32)
+                            .unwrap_or(DEFAULT_MAX_DEVIATION_BPS),
+                        min_sources: obj
+                            .get("min_sources")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as usize)
+                            .unwrap_or(1),
+                    }),
+                    "fallback" => Ok(Self::Fallback { sources: sources()? }),
+                    other => Self::from_name(other),
+                }
+            }
+            _ => Err(anyhow!("ammdata.price_feed must be one of: {}", Self::spec())),
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "historical_backfill" | "backfill" => Ok(Self::HistoricalBackfill),
+            "uniswap" => Ok(Self::Uniswap),
+            "pool" => Ok(Self::Pool { min_usd_reserve: 0 }),
+            other => Err(anyhow!(
+                "unknown ammdata.price_feed type '{other}'; expected one of: {}",
+                Self::spec()
+            )),
+        }
+    }
+
+    fn list(arr: &[Value]) -> Result<Vec<Self>> {
+        if arr.is_empty() {
+            anyhow::bail!("ammdata.price_feed source lists must not be empty");
+        }
+        arr.iter().map(Self::from_value).collect()
+    }
+
+    /// True when any source in the tree needs `ammdata.eth_rpc`.
+    pub fn uses_uniswap(&self) -> bool {
+        match self {
+            Self::Uniswap => true,
+            Self::Median { sources, .. } | Self::Fallback { sources } => {
+                sources.iter().any(Self::uses_uniswap)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn build<'a>(&self, ctx: &FeedContext<'a>) -> Box<dyn PriceFeed + 'a> {
+        match self {
+            Self::HistoricalBackfill => Box::new(HistoricalBackfillFeed),
+            Self::Uniswap => match UniswapPriceFeed::from_global_config() {
+                Ok(feed) => Box::new(feed),
+                Err(e) => Box::new(UnavailableFeed {
+                    name: "uniswap".to_string(),
+                    error: format!("init failed: {e}"),
+                }),
+            },
+            Self::Json { source, pointer, scaled, max_lag_blocks } => Box::new(JsonPriceFeed::new(
+                source.clone(),
+                pointer.clone(),
+                *scaled,
+                *max_lag_blocks,
+            )),
+            Self::Pool { min_usd_reserve } => {
+                Box::new(PoolPriceFeed::new(ctx.reserves, *min_usd_reserve))
+            }
+            Self::Median { sources, max_deviation_bps, min_sources } => {
+                Box::new(MedianPriceFeed::new(
+                    sources.iter().map(|s| s.build(ctx)).collect(),
+                    *max_deviation_bps,
+                    *min_sources,
+                ))
+            }
+            Self::Fallback { sources } => {
+                Box::new(FallbackPriceFeed::new(sources.iter().map(|s| s.build(ctx)).collect()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_nested_feed_config() {
+        let spec = PriceFeedSpec::from_value(&json!([
+            "historical_backfill",
+            {
+                "type": "median",
+                "max_deviation_bps": 150,
+                "sources": [
+                    { "type": "json_file", "path": "/data/btc.json" },
+                    { "type": "pool", "min_usd_reserve": "1000000000" },
+                    "uniswap"
+                ]
+            }
+        ]))
+        .unwrap();
+        let PriceFeedSpec::Fallback { sources } = &spec else { panic!("expected fallback") };
+        assert!(matches!(sources[0], PriceFeedSpec::HistoricalBackfill));
+        let PriceFeedSpec::Median { sources: inner, max_deviation_bps, min_sources } = &sources[1]
+        else {
+            panic!("expected median")
+        };
+        assert_eq!((*max_deviation_bps, *min_sources), (150, 1));
+        assert!(matches!(
+            &inner[0],
+            PriceFeedSpec::Json { source: JsonSource::File(p), pointer, .. }
+                if p == "/data/btc.json" && pointer == "/price"
+        ));
+        assert!(matches!(inner[1], PriceFeedSpec::Pool { min_usd_reserve: 1_000_000_000 }));
+        assert!(spec.uses_uniswap());
+        assert!(PriceFeedSpec::from_value(&json!("coingecko")).is_err());
+        assert!(PriceFeedSpec::from_value(&json!({ "type": "fallback", "sources": [] })).is_err());
+    }
+}
@@ -85,7 +85,8 @@ impl UniswapPriceFeed {
 
     pub fn from_global_config() -> Result<Self> {
         let cfg = AmmDataConfig::load_from_global_config()?;
-        Ok(Self::new(cfg.eth_rpc, cfg.eth_call_throttle_ms, cfg.eth_rpc_headers))
+        let eth_rpc = cfg.eth_rpc.ok_or_else(|| anyhow!("ammdata.eth_rpc is not configured"))?;
+        Ok(Self::new(eth_rpc, cfg.eth_call_throttle_ms, cfg.eth_rpc_headers))
     }
 
     fn rpc_call_with_url<T: DeserializeOwned>(
@@ -275,6 +276,10 @@ impl UniswapPriceFeed {
 }
 
 impl PriceFeed for UniswapPriceFeed {
+    fn name(&self) -> String {
+        "uniswap".to_string()
+    }
+
     fn get_bitcoin_price_usd_at_block_height(&self, height: u64) -> Result<u128> {
         self.price_at_bitcoin_height(height)
     }
@@ -1,7 +1,12 @@
 use crate::modules::ammdata::storage::{
-    AmmDataProvider, RpcFindBestSwapPathParams, RpcGetActivityParams, RpcGetAmmFactoriesParams,
-    RpcGetBestMevSwapParams, RpcGetBtcUsdPriceParams, RpcGetCandlesParams,
-    RpcGetChartChangeBlockParams, RpcGetChartChangesBlockParams, RpcGetPoolsParams, RpcPingParams,
+    AmmDataProvider, RpcFindBestSplitSwapParams, RpcFindBestSwapPathParams, RpcGetActivityParams,
+    RpcGetAddressPnlParams, RpcGetAlertEventsParams, RpcGetAlertsParams, RpcGetAmmFactoriesParams,
+    RpcGetArbitrageLeaderboardParams, RpcGetArbitrageParams, RpcGetBestMevSwapParams,
+    RpcGetBtcUsdPriceParams, RpcGetCandlesParams, RpcGetChartChangeBlockParams,
+    RpcGetChartChangesBlockParams, RpcGetLpPositionsParams, RpcGetMevBlocksParams,
+    RpcGetPoolFeesParams, RpcGetPoolStateAtParams, RpcGetPoolsParams, RpcGetTokenScreenerParams,
+    RpcGetTraderLeaderboardParams, RpcGetTwapParams, RpcPingParams, RpcRegisterAlertParams,
+    RpcRemoveAlertParams, RpcSimulateSwapAtParams,
 };
 use crate::modules::defs::RpcNsRegistrar;
 use serde_json::{Value, json};
@@ -32,6 +37,7 @@ pub fn register_rpc(reg: &RpcNsRegistrar, provider: Arc<AmmDataProvider>) {
                         page: payload.get("page").and_then(|v| v.as_u64()),
                         side: payload.get("side").and_then(|v| v.as_str()).map(|s| s.to_string()),
                         now: payload.get("now").and_then(|v| v.as_u64()),
+                        blocks: payload.get("blocks").and_then(|v| v.as_u64()),
                     };
                     let view = match mdb.with_height(
                         payload.get("height").and_then(|v| v.as_u64()),
@@ -276,6 +282,53 @@ pub fn register_rpc(reg: &RpcNsRegistrar, provider: Arc<AmmDataProvider>) {
             .await;
     });
 
+    let reg_split = reg.clone();
+    let mdb_for_split_swap: Arc<AmmDataProvider> = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_split
+            .register("find_best_split_swap", move |_cx, payload| {
+                let mdb_for_handler = Arc::clone(&mdb_for_split_swap);
+                async move {
+                    let params = RpcFindBestSplitSwapParams {
+                        mode: payload.get("mode").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        token_in: payload
+                            .get("token_in")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        token_out: payload
+                            .get("token_out")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        fee_bps: payload.get("fee_bps").and_then(|v| v.as_u64()),
+                        max_hops: payload.get("max_hops").and_then(|v| v.as_u64()),
+                        max_routes: payload.get("max_routes").and_then(|v| v.as_u64()),
+                        parts: payload.get("parts").and_then(|v| v.as_u64()),
+                        amount_in: payload.get("amount_in").cloned(),
+                        amount_out_min: payload.get("amount_out_min").cloned(),
+                        amount_out: payload.get("amount_out").cloned(),
+                        amount_in_max: payload.get("amount_in_max").cloned(),
+                    };
+                    let view = match mdb_for_handler.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    view.rpc_find_best_split_swap(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
     let reg_mev = reg.clone();
     let mdb_mev_swap_ptr = Arc::clone(&mdb_ptr);
     tokio::spawn(async move {
@@ -339,6 +392,545 @@ pub fn register_rpc(reg: &RpcNsRegistrar, provider: Arc<AmmDataProvider>) {
             .await;
     });
 
+    let reg_lp = reg.clone();
+    let mdb_lp = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_lp
+            .register("get_lp_positions", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_lp);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetLpPositionsParams {
+                        address: payload
+                            .get("address")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        pool: payload.get("pool").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        include_closed: payload.get("include_closed").and_then(|v| v.as_bool()),
+                    };
+                    view.rpc_get_lp_positions(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_pnl = reg.clone();
+    let mdb_pnl = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_pnl
+            .register("get_address_pnl", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_pnl);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetAddressPnlParams {
+                        address: payload
+                            .get("address")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        token: payload.get("token").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        from_height: payload.get("from_height").and_then(|v| v.as_u64()),
+                        to_height: payload.get("to_height").and_then(|v| v.as_u64()),
+                        limit: payload.get("limit").and_then(|v| v.as_u64()),
+                        page: payload.get("page").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_address_pnl(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    // `height` doubles as the historical view, so the lookup only sees history up to it.
+    let reg_pool_state = reg.clone();
+    let mdb_pool_state = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_pool_state
+            .register("get_pool_state_at", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_pool_state);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetPoolStateAtParams {
+                        pool: payload.get("pool").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        height: payload.get("height").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_pool_state_at(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_twap = reg.clone();
+    let mdb_twap = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_twap
+            .register("get_twap", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_twap);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetTwapParams {
+                        pool: payload.get("pool").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        from_height: payload.get("from_height").and_then(|v| v.as_u64()),
+                        to_height: payload.get("to_height").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_twap(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_arb = reg.clone();
+    let mdb_arb = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_arb
+            .register("get_arbitrage", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_arb);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetArbitrageParams {
+                        address: payload
+                            .get("address")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        from_height: payload.get("from_height").and_then(|v| v.as_u64()),
+                        to_height: payload.get("to_height").and_then(|v| v.as_u64()),
+                        limit: payload.get("limit").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_arbitrage(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_arb_lb = reg.clone();
+    let mdb_arb_lb = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_arb_lb
+            .register("get_arbitrage_leaderboard", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_arb_lb);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetArbitrageLeaderboardParams {
+                        limit: payload.get("limit").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_arbitrage_leaderboard(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_mev_blocks = reg.clone();
+    let mdb_mev_blocks = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_mev_blocks
+            .register("get_mev_blocks", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_mev_blocks);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetMevBlocksParams {
+                        from_height: payload.get("from_height").and_then(|v| v.as_u64()),
+                        to_height: payload.get("to_height").and_then(|v| v.as_u64()),
+                        limit: payload.get("limit").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_mev_blocks(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_simulate = reg.clone();
+    let mdb_simulate = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_simulate
+            .register("simulate_swap_at", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_simulate);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcSimulateSwapAtParams {
+                        height: payload.get("height").and_then(|v| v.as_u64()),
+                        mode: payload.get("mode").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        token_in: payload
+                            .get("token_in")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        token_out: payload
+                            .get("token_out")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        max_hops: payload.get("max_hops").and_then(|v| v.as_u64()),
+                        amount_in: payload.get("amount_in").cloned(),
+                        amount_out_min: payload.get("amount_out_min").cloned(),
+                        amount_out: payload.get("amount_out").cloned(),
+                        amount_in_max: payload.get("amount_in_max").cloned(),
+                    };
+                    view.rpc_simulate_swap_at(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_pool_fees = reg.clone();
+    let mdb_pool_fees = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_pool_fees
+            .register("get_pool_fees", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_pool_fees);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetPoolFeesParams {
+                        pool: payload.get("pool").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        days: payload.get("days").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_pool_fees(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_screener = reg.clone();
+    let mdb_screener = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_screener
+            .register("get_token_screener", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_screener);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let params = RpcGetTokenScreenerParams {
+                        sort: payload.get("sort").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        dir: payload.get("dir").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        filters: payload.get("filters").cloned(),
+                        cursor: payload
+                            .get("cursor")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        limit: payload.get("limit").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_token_screener(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_traders = reg.clone();
+    let mdb_traders = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_traders
+            .register("get_trader_leaderboard", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_traders);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let str_param = |name: &str| {
+                        payload.get(name).and_then(|v| v.as_str()).map(|s| s.to_string())
+                    };
+                    let params = RpcGetTraderLeaderboardParams {
+                        pool: str_param("pool"),
+                        token: str_param("token"),
+                        window: str_param("window"),
+                        metric: str_param("metric"),
+                        limit: payload.get("limit").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_trader_leaderboard(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_register_alert = reg.clone();
+    let mdb_register_alert = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_register_alert
+            .register("register_alert", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_register_alert);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let str_param = |name: &str| {
+                        payload.get(name).and_then(|v| v.as_str()).map(|s| s.to_string())
+                    };
+                    let params = RpcRegisterAlertParams {
+                        watcher: str_param("watcher"),
+                        token: str_param("token"),
+                        condition: payload.get("condition").cloned(),
+                    };
+                    view.rpc_register_alert(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_remove_alert = reg.clone();
+    let mdb_remove_alert = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_remove_alert
+            .register("remove_alert", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_remove_alert);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let str_param = |name: &str| {
+                        payload.get(name).and_then(|v| v.as_str()).map(|s| s.to_string())
+                    };
+                    let params = RpcRemoveAlertParams {
+                        watcher: str_param("watcher"),
+                        token: str_param("token"),
+                        alert_id: str_param("alert_id"),
+                    };
+                    view.rpc_remove_alert(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_alerts = reg.clone();
+    let mdb_alerts = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_alerts
+            .register("get_alerts", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_alerts);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let str_param = |name: &str| {
+                        payload.get(name).and_then(|v| v.as_str()).map(|s| s.to_string())
+                    };
+                    let params = RpcGetAlertsParams {
+                        watcher: str_param("watcher"),
+                        token: str_param("token"),
+                    };
+                    view.rpc_get_alerts(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
+    let reg_alert_events = reg.clone();
+    let mdb_alert_events = Arc::clone(&mdb_ptr);
+    tokio::spawn(async move {
+        reg_alert_events
+            .register("get_alert_events", move |_cx, payload| {
+                let mdb = Arc::clone(&mdb_alert_events);
+                async move {
+                    let view = match mdb.with_height(
+                        payload.get("height").and_then(|v| v.as_u64()),
+                        payload.get("height").is_some(),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return json!({
+                                "ok": false,
+                                "error": "missing_or_invalid_height",
+                                "detail": e.to_string()
+                            });
+                        }
+                    };
+                    let str_param = |name: &str| {
+                        payload.get(name).and_then(|v| v.as_str()).map(|s| s.to_string())
+                    };
+                    let params = RpcGetAlertEventsParams {
+                        watcher: str_param("watcher"),
+                        token: str_param("token"),
+                        cursor: str_param("cursor"),
+                        limit: payload.get("limit").and_then(|v| v.as_u64()),
+                    };
+                    view.rpc_get_alert_events(params)
+                        .map(|resp| resp.value)
+                        .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                }
+            })
+            .await;
+    });
+
     let reg_ping = reg.clone();
     let mdb_ping = Arc::clone(&mdb_ptr);
     tokio::spawn(async move {
@@ -100,6 +100,182 @@ pub struct SchemaPoolCreationInfoV1 {
     pub initial_lp_supply: u128,
 }
 
+/// One liquidity deposit still (partly) held. Amounts shrink pro rata as LP is burned.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct SchemaLpLotV1 {
+    pub txid: [u8; 32],
+    pub mint_height: u32,
+    pub mint_ts: u64,
+    pub lp_tokens: u128,
+    pub token0_deposited: u128,
+    pub token1_deposited: u128,
+    /// USD (PRICE_SCALE) value of the deposit at mint.
+    pub entry_value_usd: u128,
+    /// sqrt(reserve0 * reserve1) per LP token (PRICE_SCALE) after the mint; its growth
+    /// since entry is the share of the position that came from fees.
+    pub entry_sqrt_k_per_lp: u128,
+}
+
+/// Per (address, pool) LP position: open lots in mint order plus totals realized by burns.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct SchemaLpPositionV1 {
+    pub lots: Vec<SchemaLpLotV1>,
+    pub lp_burned: u128,
+    pub token0_withdrawn: u128,
+    pub token1_withdrawn: u128,
+    pub withdrawn_value_usd: u128,
+    /// Withdrawn value minus the entry value of the lots consumed (USD, PRICE_SCALE).
+    pub realized_pnl_usd: i128,
+}
+
+/// Tokens acquired by one swap and not yet sold (FIFO lot).
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct SchemaCostLotV1 {
+    pub height: u32,
+    pub timestamp: u64,
+    pub amount: u128,
+    /// USD (PRICE_SCALE) paid for `amount`.
+    pub cost_usd: u128,
+}
+
+/// Per (address, token) cost basis from AMM swaps, kept in both FIFO and average-cost form.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct SchemaCostBasisV1 {
+    pub lots: Vec<SchemaCostLotV1>,
+    /// Amount covered by a cost basis (sum of `lots`).
+    pub held: u128,
+    /// Average-cost basis of `held`.
+    pub avg_cost_usd: u128,
+    pub bought: u128,
+    pub bought_cost_usd: u128,
+    pub sold: u128,
+    pub sold_proceeds_usd: u128,
+    pub realized_fifo_usd: i128,
+    pub realized_avg_usd: i128,
+    /// Bought or sold while neither leg had a USD price; not part of the cost basis.
+    pub unpriced_bought: u128,
+    pub unpriced_sold: u128,
+    pub trade_count: u64,
+}
+
+/// One side of a swap from the trader's point of view, with the PnL it realized.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SchemaPnlTradeV1 {
+    pub txid: [u8; 32],
+    pub pool: SchemaAlkaneId,
+    pub timestamp: u64,
+    pub is_buy: bool,
+    pub amount: u128,
+    /// `None` when neither leg of the swap had a USD price.
+    pub value_usd: Option<u128>,
+    pub realized_fifo_usd: i128,
+    pub realized_avg_usd: i128,
+}
+
+/// Pool state after a block that touched the pool, with Uniswap-v2 style accumulators.
+/// The `cum_*` fields sum, per block since the pool's first record, the spot price in effect
+/// when the block started, so a TWAP only moves with prices that survived a block boundary.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct SchemaPoolStateV1 {
+    pub timestamp: u64,
+    pub base_reserve: u128,
+    pub quote_reserve: u128,
+    pub lp_supply: u128,
+    /// Quote per base (PRICE_SCALE) after the block.
+    pub price_quote_per_base: u128,
+    /// Base per quote (PRICE_SCALE) after the block.
+    pub price_base_per_quote: u128,
+    pub cum_quote_per_base: u128,
+    pub cum_base_per_quote: u128,
+}
+
+/// A swap cycle by one address that started and ended in `token`, either inside one
+/// transaction or across several of its transactions in the same block.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SchemaArbitrageV1 {
+    /// Transaction of the first leg.
+    pub txid: [u8; 32],
+    pub timestamp: u64,
+    pub actor_spk: Vec<u8>,
+    pub single_tx: bool,
+    pub token: SchemaAlkaneId,
+    pub amount_in: u128,
+    pub amount_out: u128,
+    pub profit_usd: i128,
+    pub pools: Vec<SchemaAlkaneId>,
+    /// Tokens visited, starting and ending with `token`.
+    pub path: Vec<SchemaAlkaneId>,
+}
+
+/// Running arbitrage totals, kept per actor and per block.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct SchemaArbitrageTotalsV1 {
+    pub count: u64,
+    pub profit_usd: i128,
+    pub last_height: u32,
+}
+
+/// Swap fees a pool kept for its LPs, per block or summed per UTC day. Token amounts are
+/// raw; `fees_usd` and `fees_sats` are valued at the block's prices.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct SchemaPoolFeesV1 {
+    pub base_fees: u128,
+    pub quote_fees: u128,
+    pub fees_usd: u128,
+    pub fees_sats: u128,
+    pub trades: u64,
+}
+
+/// One address's trading and liquidity in a pool or token, per UTC day or all-time. USD
+/// values (PRICE_SCALE) are taken at each block's prices. For a pool, bought and sold
+/// refer to its base token.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct SchemaTraderStatsV1 {
+    pub volume_usd: u128,
+    pub trades: u64,
+    pub bought_usd: u128,
+    pub sold_usd: u128,
+    pub lp_added_usd: u128,
+    pub lp_removed_usd: u128,
+}
+
+/// What an alert watches. Prices are PRICE_SCALE USD; `change_bps` is the TVL move within one
+/// block; a `LargeSwap` without a pool watches every pool.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub enum SchemaAlertConditionV1 {
+    PriceAbove { token: SchemaAlkaneId, price_usd: u128 },
+    PriceBelow { token: SchemaAlkaneId, price_usd: u128 },
+    TvlChange { pool: SchemaAlkaneId, change_bps: u32 },
+    LargeSwap { pool: Option<SchemaAlkaneId>, min_sats: u128 },
+}
+
+/// A registered alert. Stored outside the versioned tree, like its watcher.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SchemaAlertV1 {
+    pub watcher: String,
+    pub condition: SchemaAlertConditionV1,
+    pub created_height: u32,
+}
+
+/// A watcher: the SHA-256 of the secret token issued with its first alert, and how many
+/// alerts it has.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SchemaAlertWatcherV1 {
+    pub token_hash: [u8; 32],
+    pub alerts: u32,
+}
+
+/// One firing of an alert. `value` is the price (PRICE_SCALE USD), the TVL change in bps or
+/// the swap value in sats, depending on the condition.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SchemaAlertEventV1 {
+    pub alert_id: u64,
+    pub height: u32,
+    pub timestamp: u64,
+    pub value: u128,
+    pub txid: Option<[u8; 32]>,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Copy)]
 pub struct SchemaMarketDefs {
     pub base_alkane_id: SchemaAlkaneId,
@@ -153,6 +329,14 @@ pub enum Timeframe {
     D1,
     W1,
     M1,
+    /// One minute (`M1` is the month bucket). Stored for pool candles only.
+    Min1,
+    // Read-only frames, resampled on the fly from a stored frame.
+    Min5,
+    Min15,
+    Min30,
+    H12,
+    D3,
 }
 
 impl Timeframe {
@@ -165,6 +349,12 @@ impl Timeframe {
             Timeframe::D1 => 24 * 60 * 60,
             Timeframe::W1 => 7 * 24 * 60 * 60,
             Timeframe::M1 => 30 * 24 * 60 * 60, // simple month bucket (30d)
+            Timeframe::Min1 => 60,
+            Timeframe::Min5 => 5 * 60,
+            Timeframe::Min15 => 15 * 60,
+            Timeframe::Min30 => 30 * 60,
+            Timeframe::H12 => 12 * 60 * 60,
+            Timeframe::D3 => 3 * 24 * 60 * 60,
         }
     }
     /// Short ASCII code used in keys (keeps keys compact & lexicographically nice)
@@ -177,7 +367,26 @@ impl Timeframe {
             Timeframe::D1 => "1d",
             Timeframe::W1 => "1w",
             Timeframe::M1 => "1M",
+            Timeframe::Min1 => "1m",
+            Timeframe::Min5 => "5m",
+            Timeframe::Min15 => "15m",
+            Timeframe::Min30 => "30m",
+            Timeframe::H12 => "12h",
+            Timeframe::D3 => "3d",
+        }
+    }
+    /// Frame to read when serving `self` from the `stored` frames: itself when stored,
+    /// otherwise the coarsest stored frame whose duration divides it.
+    pub fn resample_source(&self, stored: &[Timeframe]) -> Option<Timeframe> {
+        if stored.contains(self) {
+            return Some(*self);
         }
+        let d = self.duration_secs();
+        stored
+            .iter()
+            .copied()
+            .filter(|s| s.duration_secs() < d && d % s.duration_secs() == 0)
+            .max_by_key(|s| s.duration_secs())
     }
 }
 pub fn active_timeframes() -> Vec<Timeframe> {
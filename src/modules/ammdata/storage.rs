@@ -1,37 +1,79 @@
 use super::schemas::{
-    ActivityKind, SchemaActivityV1, SchemaCandleV1, SchemaCanonicalPoolEntry, SchemaMarketDefs,
-    SchemaPoolCreationInfoV1, SchemaPoolDetailsSnapshot, SchemaPoolMetricsV1, SchemaPoolMetricsV2,
-    SchemaPoolSnapshot, SchemaReservesSnapshot, SchemaTokenMetricsV1, Timeframe,
+    ActivityKind, SchemaActivityV1, SchemaAlertEventV1, SchemaAlertV1, SchemaAlertWatcherV1,
+    SchemaArbitrageTotalsV1, SchemaArbitrageV1, SchemaCandleV1, SchemaCanonicalPoolEntry,
+    SchemaCostBasisV1, SchemaLpPositionV1, SchemaMarketDefs, SchemaPnlTradeV1,
+    SchemaPoolCreationInfoV1, SchemaPoolDetailsSnapshot, SchemaPoolFeesV1, SchemaPoolMetricsV1,
+    SchemaPoolMetricsV2, SchemaPoolSnapshot, SchemaPoolStateV1, SchemaReservesSnapshot,
+    SchemaTokenMetricsV1, SchemaTraderStatsV1, Timeframe, active_timeframes,
 };
 use crate::config::get_network;
+use crate::modules::ammdata::adapters::default_pool_adapter;
 use crate::modules::ammdata::consts::{
     CanonicalQuoteUnit, KEY_INDEX_HEIGHT, PRICE_SCALE, SATS_PER_BTC, canonical_quotes,
 };
+use crate::modules::ammdata::price_feeds::feed_health;
 use crate::modules::ammdata::schemas::SchemaFullCandleV1;
 use crate::modules::ammdata::utils::activity::{
     ActivityFilter, ActivityPage, ActivitySideFilter, ActivitySortKey, SortDir, decode_activity_v1,
     read_activity_for_pool, read_activity_for_pool_sorted,
 };
-use crate::modules::ammdata::utils::candles::{CandleSlice, PriceSide, read_candles_v1};
+use crate::modules::ammdata::utils::alerts::{
+    ALERT_MAX_PER_WATCHER, AlertSubject, alert_id, alert_id_str, condition_json, new_watcher_token,
+    parse_alert_id, parse_condition, valid_watcher, watcher_token_hash,
+};
+use crate::modules::ammdata::utils::candles::{
+    CandleSlice, PriceSide, pool_candle_total, read_candles_v1, read_height_candles_v1,
+    read_resampled,
+};
+use crate::modules::ammdata::utils::index_snapshot::{load_pool_adapters, load_reserves_snapshot};
+use crate::modules::ammdata::utils::leaderboards::{
+    LeaderboardMetric, LeaderboardScope, net_buy_usd, window_days,
+};
 use crate::modules::ammdata::utils::live_reserves::fetch_all_pools;
+use crate::modules::ammdata::utils::lp_positions::{
+    LpPoolState, sqrt_k_per_lp, token_price_usd, value_position,
+};
 use crate::modules::ammdata::utils::pathfinder::{
-    DEFAULT_FEE_BPS, plan_best_mev_swap, plan_exact_in_default_fee, plan_exact_out_default_fee,
-    plan_implicit_default_fee, plan_swap_exact_tokens_for_tokens,
-    plan_swap_exact_tokens_for_tokens_implicit, plan_swap_tokens_for_exact_tokens,
+    Hop, RoutePools, SPLIT_MAX_PARTS, SplitParams, plan_best_mev_swap, plan_exact_in_default_fee,
+    plan_exact_out_default_fee, plan_split_exact_in, plan_split_exact_out,
+    plan_swap_exact_tokens_for_tokens, plan_swap_exact_tokens_for_tokens_implicit,
+    plan_swap_tokens_for_exact_tokens, quote_price_impact_default_fee,
 };
-use crate::modules::essentials::storage::EssentialsProvider;
+use crate::modules::ammdata::utils::pnl::{fifo_cost_usd, unrealized};
+use crate::modules::ammdata::utils::pool_fees::{
+    FEE_WINDOWS, add_fees, fee_apr, share_price_growth,
+};
+use crate::modules::ammdata::utils::screener::{
+    SCREENER_CHUNK, SCREENER_MAX_SCAN, ScreenerFilters, ScreenerRow, ScreenerSort,
+};
+use crate::modules::ammdata::utils::twap::{cumulative_at, twap};
+use crate::modules::essentials::storage::{
+    EssentialsProvider, GetCreationCountParams, GetCreationIdsBySeqParams,
+    GetCreationRecordsByIdParams, GetHoldersCountsByIdParams, GetHoldersOrderedAfterParams,
+    HolderId,
+};
+use crate::modules::essentials::utils::balances::get_holders_for_alkane;
+use crate::modules::essentials::utils::inspections::AlkaneCreationRecord;
 use crate::runtime::mdb::{Mdb, MdbBatch};
 use crate::runtime::pointers::{CursorScanPage, KvPointer, ListPointer};
 use crate::runtime::state_at::StateAt;
+use crate::runtime::tree_db::{next_key, prefix_end_exclusive};
 use crate::schemas::SchemaAlkaneId;
 use anyhow::{Result, anyhow};
-use bitcoin::BlockHash;
+use bitcoin::{Address, BlockHash, Script};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde_json::{Value, json, map::Map};
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::sync::Arc;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Pool state records read per seek while looking for the next trade.
+const NEXT_TRADE_SCAN_CHUNK: usize = 64;
+
+/// Serializes alert registration and removal, which read a watcher's record and write it back.
+static ALERT_WRITE_LOCK: Mutex<()> = Mutex::new(());
+
 fn dedupe_batch_ops(
     puts: Vec<(Vec<u8>, Vec<u8>)>,
     deletes: Vec<Vec<u8>>,
@@ -71,6 +113,7 @@ pub struct AmmDataTable<'a> {
     pub POOLS: KvPointer<'a>,
     // Candle series (fc1:<blk>:<tx>:<tf>:...).
     pub CANDLES: ListPointer<'a>,
+    pub HEIGHT_CANDLES: ListPointer<'a>,
     pub TOKEN_USD_CANDLES: ListPointer<'a>,
     pub TOKEN_DERIVED_USD_CANDLES: ListPointer<'a>,
     pub TOKEN_MCAP_USD_CANDLES: ListPointer<'a>,
@@ -89,6 +132,8 @@ pub struct AmmDataTable<'a> {
     pub TOKEN_METRICS_INDEX: ListPointer<'a>,
     pub TOKEN_DERIVED_METRICS_INDEX: ListPointer<'a>,
     pub TOKEN_METRICS_INDEX_COUNT: KvPointer<'a>,
+    // Set once tokens indexed before the fdv sort have been added to it.
+    pub TOKEN_METRICS_FDV_BACKFILLED: KvPointer<'a>,
     pub TOKEN_DERIVED_METRICS_INDEX_COUNT: KvPointer<'a>,
     pub POOL_METRICS_INDEX: ListPointer<'a>,
     pub POOL_METRICS_INDEX_COUNT: KvPointer<'a>,
@@ -101,6 +146,7 @@ pub struct AmmDataTable<'a> {
     pub POOL_BOOTSTRAP_CREATION_COUNT: KvPointer<'a>,
     pub FACTORY_POOLS: ListPointer<'a>,
     pub POOL_FACTORY: KvPointer<'a>,
+    pub POOL_PROTOCOL: KvPointer<'a>,
     pub POOL_METRICS: KvPointer<'a>,
     pub POOL_METRICS_V2: KvPointer<'a>,
     pub POOL_CREATION_INFO: KvPointer<'a>,
@@ -108,6 +154,10 @@ pub struct AmmDataTable<'a> {
     pub POOL_DETAILS_SNAPSHOT: KvPointer<'a>,
     pub TVL_VERSIONED: KvPointer<'a>,
     pub TOKEN_SWAPS: ListPointer<'a>,
+    pub POOL_SWAPS_BY_HEIGHT: ListPointer<'a>,
+    // Resume key of the backfill of swaps indexed before POOL_SWAPS_BY_HEIGHT existed;
+    // empty once it has walked all activity.
+    pub POOL_SWAPS_BY_HEIGHT_BACKFILL: KvPointer<'a>,
     pub POOL_CREATIONS: ListPointer<'a>,
     pub ADDRESS_POOL_SWAPS: ListPointer<'a>,
     pub ADDRESS_TOKEN_SWAPS: ListPointer<'a>,
@@ -115,6 +165,29 @@ pub struct AmmDataTable<'a> {
     pub ADDRESS_POOL_MINTS: ListPointer<'a>,
     pub ADDRESS_POOL_BURNS: ListPointer<'a>,
     pub ADDRESS_AMM_HISTORY: ListPointer<'a>,
+    pub LP_POSITIONS: ListPointer<'a>,
+    pub COST_BASIS: ListPointer<'a>,
+    pub ADDRESS_PNL_TRADES: ListPointer<'a>,
+    pub POOL_STATE: ListPointer<'a>,
+    pub POOL_STATE_LATEST: ListPointer<'a>,
+    pub ARBITRAGE: ListPointer<'a>,
+    pub ACTOR_ARBITRAGE: ListPointer<'a>,
+    pub ARBITRAGE_ACTOR_TOTALS: ListPointer<'a>,
+    pub ARBITRAGE_ACTOR_RANK: ListPointer<'a>,
+    pub ARBITRAGE_BLOCK_TOTALS: ListPointer<'a>,
+    pub POOL_FEES_BLOCK: ListPointer<'a>,
+    pub POOL_FEES_DAY: ListPointer<'a>,
+    pub TRADER_STATS_DAY: ListPointer<'a>,
+    pub TRADER_STATS_ALL: ListPointer<'a>,
+    pub TRADER_STATS_WINDOW: ListPointer<'a>,
+    pub TRADER_STATS_BY_DAY: ListPointer<'a>,
+    pub TRADER_STATS_TIP_DAY: KvPointer<'a>,
+    pub TRADER_RANK: ListPointer<'a>,
+    pub ALERTS: ListPointer<'a>,
+    pub ALERT_EVENTS: ListPointer<'a>,
+    pub ALERTS_BY_SUBJECT: ListPointer<'a>,
+    pub ALERTS_BY_WATCHER: ListPointer<'a>,
+    pub ALERT_WATCHERS: ListPointer<'a>,
     pub AMM_HISTORY_ALL: ListPointer<'a>,
     pub TOKEN_POOLS: ListPointer<'a>,
 }
@@ -128,6 +201,7 @@ impl<'a> AmmDataTable<'a> {
             RESERVES_SNAPSHOT: root.list_keyword("/reserves_snapshot/v2/pool/"),
             POOLS: root.keyword("/pools/"),
             CANDLES: root.list_keyword("fc1:"),
+            HEIGHT_CANDLES: root.list_keyword("hc1:"),
             TOKEN_USD_CANDLES: root.list_keyword("tuc1:"),
             TOKEN_DERIVED_USD_CANDLES: root.list_keyword("tud1:"),
             TOKEN_MCAP_USD_CANDLES: root.list_keyword("tmc1:"),
@@ -144,6 +218,7 @@ impl<'a> AmmDataTable<'a> {
             TOKEN_METRICS_INDEX: root.list_keyword("/token_metrics/index/"),
             TOKEN_DERIVED_METRICS_INDEX: root.list_keyword("/token_metrics/derived/index/"),
             TOKEN_METRICS_INDEX_COUNT: root.keyword("/token_metrics/index_count"),
+            TOKEN_METRICS_FDV_BACKFILLED: root.keyword("/token_metrics/fdv_backfilled/v1"),
             TOKEN_DERIVED_METRICS_INDEX_COUNT: root.keyword("/token_metrics/derived/index_count"),
             POOL_METRICS_INDEX: root.list_keyword("/pool_metrics/index/"),
             POOL_METRICS_INDEX_COUNT: root.keyword("/pool_metrics/index_count"),
@@ -156,6 +231,7 @@ impl<'a> AmmDataTable<'a> {
             POOL_BOOTSTRAP_CREATION_COUNT: root.keyword("/bootstrap/pools/creation_count/v1"),
             FACTORY_POOLS: root.list_keyword("/factory_pools/v1/"),
             POOL_FACTORY: root.keyword("/pool_factory/v1/"),
+            POOL_PROTOCOL: root.keyword("/pool_protocol/v1/"),
             POOL_METRICS: root.keyword("/pool_metrics/v1/"),
             POOL_METRICS_V2: root.keyword("/pool_metrics/v2/"),
             POOL_CREATION_INFO: root.keyword("/pool_creation_info/v1/"),
@@ -163,6 +239,8 @@ impl<'a> AmmDataTable<'a> {
             POOL_DETAILS_SNAPSHOT: root.keyword("/pool_details/v2/"),
             TVL_VERSIONED: root.keyword("/tvlVersioned/"),
             TOKEN_SWAPS: root.list_keyword("/token_swaps/v1/"),
+            POOL_SWAPS_BY_HEIGHT: root.list_keyword("/pool_swaps_height/v1/"),
+            POOL_SWAPS_BY_HEIGHT_BACKFILL: root.keyword("/pool_swaps_height/backfill/v1"),
             POOL_CREATIONS: root.list_keyword("/pool_creations/v1/"),
             ADDRESS_POOL_SWAPS: root.list_keyword("/address_pool_swaps/v1/"),
             ADDRESS_TOKEN_SWAPS: root.list_keyword("/address_token_swaps/v1/"),
@@ -170,6 +248,29 @@ impl<'a> AmmDataTable<'a> {
             ADDRESS_POOL_MINTS: root.list_keyword("/address_pool_mints/v1/"),
             ADDRESS_POOL_BURNS: root.list_keyword("/address_pool_burns/v1/"),
             ADDRESS_AMM_HISTORY: root.list_keyword("/address_amm_history/v1/"),
+            LP_POSITIONS: root.list_keyword("/lp_positions/v1/"),
+            COST_BASIS: root.list_keyword("/cost_basis/v1/"),
+            ADDRESS_PNL_TRADES: root.list_keyword("/address_pnl_trades/v1/"),
+            POOL_STATE: root.list_keyword("/pool_state/v1/"),
+            POOL_STATE_LATEST: root.list_keyword("/pool_state_latest/v1/"),
+            ARBITRAGE: root.list_keyword("/arbitrage/v1/"),
+            ACTOR_ARBITRAGE: root.list_keyword("/actor_arbitrage/v1/"),
+            ARBITRAGE_ACTOR_TOTALS: root.list_keyword("/arbitrage_actor_totals/v1/"),
+            ARBITRAGE_ACTOR_RANK: root.list_keyword("/arbitrage_actor_rank/v1/"),
+            ARBITRAGE_BLOCK_TOTALS: root.list_keyword("/arbitrage_block_totals/v1/"),
+            POOL_FEES_BLOCK: root.list_keyword("/pool_fees/block/v1/"),
+            POOL_FEES_DAY: root.list_keyword("/pool_fees/day/v1/"),
+            TRADER_STATS_DAY: root.list_keyword("/trader_stats/day/v1/"),
+            TRADER_STATS_ALL: root.list_keyword("/trader_stats/all/v1/"),
+            TRADER_STATS_WINDOW: root.list_keyword("/trader_stats/window/v1/"),
+            TRADER_STATS_BY_DAY: root.list_keyword("/trader_stats/by_day/v1/"),
+            TRADER_STATS_TIP_DAY: root.keyword("/trader_stats/tip_day/v1"),
+            TRADER_RANK: root.list_keyword("/trader_rank/v1/"),
+            ALERTS: root.list_keyword("/alerts/v1/"),
+            ALERT_EVENTS: root.list_keyword("/alerts/events/v1/"),
+            ALERTS_BY_SUBJECT: root.list_keyword("/alerts/by_subject/v1/"),
+            ALERTS_BY_WATCHER: root.list_keyword("/alerts/by_watcher/v1/"),
+            ALERT_WATCHERS: root.list_keyword("/alerts/watchers/v1/"),
             AMM_HISTORY_ALL: root.list_keyword("/amm_history_all/v1/"),
             TOKEN_POOLS: root.list_keyword("/token_pools/v1/"),
         }
@@ -180,6 +281,7 @@ impl<'a> AmmDataTable<'a> {
 pub enum TokenMetricsIndexField {
     PriceUsd,
     MarketcapUsd,
+    FdvUsd,
     Volume1d,
     Volume7d,
     Volume30d,
@@ -195,6 +297,7 @@ impl TokenMetricsIndexField {
         match self {
             TokenMetricsIndexField::PriceUsd => "price",
             TokenMetricsIndexField::MarketcapUsd => "marketcap",
+            TokenMetricsIndexField::FdvUsd => "fdv",
             TokenMetricsIndexField::Volume1d => "volume_1d",
             TokenMetricsIndexField::Volume7d => "volume_7d",
             TokenMetricsIndexField::Volume30d => "volume_30d",
@@ -417,6 +520,18 @@ impl<'a> AmmDataTable<'a> {
         k
     }
 
+    pub fn height_candle_ns_prefix(&self, pool: &SchemaAlkaneId) -> Vec<u8> {
+        let suffix = format!("{:x}:{:x}:", pool.block, pool.tx);
+        self.HEIGHT_CANDLES.select(suffix.as_bytes()).key().to_vec()
+    }
+
+    /// Heights are zero-padded so key order is height order.
+    pub fn height_candle_key(&self, pool: &SchemaAlkaneId, height: u32) -> Vec<u8> {
+        let mut k = self.height_candle_ns_prefix(pool);
+        k.extend_from_slice(format!("{height:010}").as_bytes());
+        k
+    }
+
     pub fn token_usd_candle_ns_prefix(&self, token: &SchemaAlkaneId, tf: Timeframe) -> Vec<u8> {
         let blk_hex = format!("{:x}", token.block);
         let tx_hex = format!("{:x}", token.tx);
@@ -1021,6 +1136,13 @@ impl<'a> AmmDataTable<'a> {
         self.POOL_FACTORY.select(&suffix).key().to_vec()
     }
 
+    pub fn pool_protocol_key(&self, pool: &SchemaAlkaneId) -> Vec<u8> {
+        let mut suffix = Vec::with_capacity(12);
+        suffix.extend_from_slice(&pool.block.to_be_bytes());
+        suffix.extend_from_slice(&pool.tx.to_be_bytes());
+        self.POOL_PROTOCOL.select(&suffix).key().to_vec()
+    }
+
     pub fn pool_metrics_key(&self, pool: &SchemaAlkaneId) -> Vec<u8> {
         let mut suffix = Vec::with_capacity(12);
         suffix.extend_from_slice(&pool.block.to_be_bytes());
@@ -1081,6 +1203,43 @@ impl<'a> AmmDataTable<'a> {
         k
     }
 
+    pub fn pool_swaps_by_height_prefix(&self, pool: &SchemaAlkaneId) -> Vec<u8> {
+        let mut suffix = Vec::with_capacity(12);
+        suffix.extend_from_slice(&pool.block.to_be_bytes());
+        suffix.extend_from_slice(&pool.tx.to_be_bytes());
+        self.POOL_SWAPS_BY_HEIGHT.select(&suffix).key().to_vec()
+    }
+
+    /// `<pool><height><ts><seq>`, all big-endian; points at `activity_key(pool, ts, seq)`.
+    pub fn pool_swaps_by_height_key(
+        &self,
+        pool: &SchemaAlkaneId,
+        height: u32,
+        ts: u64,
+        seq: u32,
+    ) -> Vec<u8> {
+        let mut k = self.pool_swaps_by_height_prefix(pool);
+        k.extend_from_slice(&height.to_be_bytes());
+        k.extend_from_slice(&ts.to_be_bytes());
+        k.extend_from_slice(&seq.to_be_bytes());
+        k
+    }
+
+    pub fn parse_pool_swaps_by_height_key(
+        &self,
+        pool: &SchemaAlkaneId,
+        key: &[u8],
+    ) -> Option<(u32, u64, u32)> {
+        let rest = key.strip_prefix(self.pool_swaps_by_height_prefix(pool).as_slice())?;
+        if rest.len() != 4 + 8 + 4 {
+            return None;
+        }
+        let height = u32::from_be_bytes(rest[..4].try_into().ok()?);
+        let ts = u64::from_be_bytes(rest[4..12].try_into().ok()?);
+        let seq = u32::from_be_bytes(rest[12..].try_into().ok()?);
+        Some((height, ts, seq))
+    }
+
     pub fn token_swaps_prefix(&self, token: &SchemaAlkaneId) -> Vec<u8> {
         let mut suffix = Vec::with_capacity(12 + 1);
         suffix.extend_from_slice(&token.block.to_be_bytes());
@@ -1276,6 +1435,313 @@ impl<'a> AmmDataTable<'a> {
         k
     }
 
+    pub fn lp_positions_prefix(&self, address_spk: &[u8]) -> Vec<u8> {
+        let mut k = self.LP_POSITIONS.key().to_vec();
+        push_spk(&mut k, address_spk);
+        k
+    }
+
+    pub fn lp_position_key(&self, address_spk: &[u8], pool: &SchemaAlkaneId) -> Vec<u8> {
+        let mut k = self.lp_positions_prefix(address_spk);
+        k.extend_from_slice(&pool.block.to_be_bytes());
+        k.extend_from_slice(&pool.tx.to_be_bytes());
+        k
+    }
+
+    pub fn cost_basis_prefix(&self, address_spk: &[u8]) -> Vec<u8> {
+        let mut k = self.COST_BASIS.key().to_vec();
+        push_spk(&mut k, address_spk);
+        k
+    }
+
+    pub fn cost_basis_key(&self, address_spk: &[u8], token: &SchemaAlkaneId) -> Vec<u8> {
+        let mut k = self.cost_basis_prefix(address_spk);
+        k.extend_from_slice(&token.block.to_be_bytes());
+        k.extend_from_slice(&token.tx.to_be_bytes());
+        k
+    }
+
+    pub fn address_pnl_trades_prefix(&self, address_spk: &[u8], token: &SchemaAlkaneId) -> Vec<u8> {
+        let mut k = self.ADDRESS_PNL_TRADES.key().to_vec();
+        push_spk(&mut k, address_spk);
+        k.extend_from_slice(&token.block.to_be_bytes());
+        k.extend_from_slice(&token.tx.to_be_bytes());
+        k
+    }
+
+    pub fn address_pnl_trade_key(
+        &self,
+        address_spk: &[u8],
+        token: &SchemaAlkaneId,
+        height: u32,
+        seq: u32,
+    ) -> Vec<u8> {
+        let mut k = self.address_pnl_trades_prefix(address_spk, token);
+        k.extend_from_slice(&height.to_be_bytes());
+        k.extend_from_slice(&seq.to_be_bytes());
+        k
+    }
+
+    pub fn pool_state_prefix(&self, pool: &SchemaAlkaneId) -> Vec<u8> {
+        let mut k = self.POOL_STATE.key().to_vec();
+        k.extend_from_slice(&pool.block.to_be_bytes());
+        k.extend_from_slice(&pool.tx.to_be_bytes());
+        k
+    }
+
+    pub fn pool_state_key(&self, pool: &SchemaAlkaneId, height: u32) -> Vec<u8> {
+        let mut k = self.pool_state_prefix(pool);
+        k.extend_from_slice(&height.to_be_bytes());
+        k
+    }
+
+    /// The pool's newest state record with its height, rewritten with every record.
+    pub fn pool_state_latest_key(&self, pool: &SchemaAlkaneId) -> Vec<u8> {
+        let mut k = self.POOL_STATE_LATEST.key().to_vec();
+        k.extend_from_slice(&pool.block.to_be_bytes());
+        k.extend_from_slice(&pool.tx.to_be_bytes());
+        k
+    }
+
+    pub fn arbitrage_key(&self, height: u32, seq: u32) -> Vec<u8> {
+        let mut k = self.ARBITRAGE.key().to_vec();
+        k.extend_from_slice(&height.to_be_bytes());
+        k.extend_from_slice(&seq.to_be_bytes());
+        k
+    }
+
+    pub fn actor_arbitrage_prefix(&self, actor_spk: &[u8]) -> Vec<u8> {
+        let mut k = self.ACTOR_ARBITRAGE.key().to_vec();
+        push_spk(&mut k, actor_spk);
+        k
+    }
+
+    pub fn actor_arbitrage_key(&self, actor_spk: &[u8], height: u32, seq: u32) -> Vec<u8> {
+        let mut k = self.actor_arbitrage_prefix(actor_spk);
+        k.extend_from_slice(&height.to_be_bytes());
+        k.extend_from_slice(&seq.to_be_bytes());
+        k
+    }
+
+    pub fn arbitrage_actor_totals_key(&self, actor_spk: &[u8]) -> Vec<u8> {
+        let mut k = self.ARBITRAGE_ACTOR_TOTALS.key().to_vec();
+        push_spk(&mut k, actor_spk);
+        k
+    }
+
+    /// Actors ordered by total profit: `<profit (sign-flipped BE)><spk>`.
+    pub fn arbitrage_actor_rank_key(&self, profit_usd: i128, actor_spk: &[u8]) -> Vec<u8> {
+        let mut k = self.ARBITRAGE_ACTOR_RANK.key().to_vec();
+        k.extend_from_slice(&signed_rank_bytes(profit_usd));
+        k.extend_from_slice(actor_spk);
+        k
+    }
+
+    pub fn arbitrage_block_totals_key(&self, height: u32) -> Vec<u8> {
+        let mut k = self.ARBITRAGE_BLOCK_TOTALS.key().to_vec();
+        k.extend_from_slice(&height.to_be_bytes());
+        k
+    }
+
+    pub fn pool_fees_block_prefix(&self, pool: &SchemaAlkaneId) -> Vec<u8> {
+        let mut k = self.POOL_FEES_BLOCK.key().to_vec();
+        k.extend_from_slice(&pool.block.to_be_bytes());
+        k.extend_from_slice(&pool.tx.to_be_bytes());
+        k
+    }
+
+    pub fn pool_fees_block_key(&self, pool: &SchemaAlkaneId, height: u32) -> Vec<u8> {
+        let mut k = self.pool_fees_block_prefix(pool);
+        k.extend_from_slice(&height.to_be_bytes());
+        k
+    }
+
+    pub fn pool_fees_day_prefix(&self, pool: &SchemaAlkaneId) -> Vec<u8> {
+        let mut k = self.POOL_FEES_DAY.key().to_vec();
+        k.extend_from_slice(&pool.block.to_be_bytes());
+        k.extend_from_slice(&pool.tx.to_be_bytes());
+        k
+    }
+
+    pub fn pool_fees_day_key(&self, pool: &SchemaAlkaneId, day_ts: u64) -> Vec<u8> {
+        let mut k = self.pool_fees_day_prefix(pool);
+        k.extend_from_slice(&day_ts.to_be_bytes());
+        k
+    }
+
+    pub fn trader_stats_day_prefix(
+        &self,
+        scope: LeaderboardScope,
+        id: &SchemaAlkaneId,
+        day_ts: u64,
+    ) -> Vec<u8> {
+        let mut k = self.TRADER_STATS_DAY.key().to_vec();
+        k.push(scope.tag());
+        k.extend_from_slice(&id.block.to_be_bytes());
+        k.extend_from_slice(&id.tx.to_be_bytes());
+        k.extend_from_slice(&day_ts.to_be_bytes());
+        k
+    }
+
+    pub fn trader_stats_day_key(
+        &self,
+        scope: LeaderboardScope,
+        id: &SchemaAlkaneId,
+        day_ts: u64,
+        spk: &[u8],
+    ) -> Vec<u8> {
+        let mut k = self.trader_stats_day_prefix(scope, id, day_ts);
+        k.extend_from_slice(spk);
+        k
+    }
+
+    pub fn trader_stats_all_prefix(&self, scope: LeaderboardScope, id: &SchemaAlkaneId) -> Vec<u8> {
+        let mut k = self.TRADER_STATS_ALL.key().to_vec();
+        k.push(scope.tag());
+        k.extend_from_slice(&id.block.to_be_bytes());
+        k.extend_from_slice(&id.tx.to_be_bytes());
+        k
+    }
+
+    pub fn trader_stats_all_key(
+        &self,
+        scope: LeaderboardScope,
+        id: &SchemaAlkaneId,
+        spk: &[u8],
+    ) -> Vec<u8> {
+        let mut k = self.trader_stats_all_prefix(scope, id);
+        k.extend_from_slice(spk);
+        k
+    }
+
+    /// Running totals of a 1d/7d/30d window ending on the indexed tip's UTC day.
+    pub fn trader_stats_window_key(
+        &self,
+        scope: LeaderboardScope,
+        id: &SchemaAlkaneId,
+        days: u32,
+        spk: &[u8],
+    ) -> Vec<u8> {
+        let mut k = self.TRADER_STATS_WINDOW.key().to_vec();
+        k.push(scope.tag());
+        k.extend_from_slice(&id.block.to_be_bytes());
+        k.extend_from_slice(&id.tx.to_be_bytes());
+        k.extend_from_slice(&days.to_be_bytes());
+        k.extend_from_slice(spk);
+        k
+    }
+
+    /// Day stats by day first, so the days leaving a window are one range.
+    pub fn trader_stats_by_day_prefix(&self, day_ts: u64) -> Vec<u8> {
+        let mut k = self.TRADER_STATS_BY_DAY.key().to_vec();
+        k.extend_from_slice(&day_ts.to_be_bytes());
+        k
+    }
+
+    pub fn trader_stats_by_day_key(
+        &self,
+        day_ts: u64,
+        scope: LeaderboardScope,
+        id: &SchemaAlkaneId,
+        spk: &[u8],
+    ) -> Vec<u8> {
+        let mut k = self.trader_stats_by_day_prefix(day_ts);
+        k.push(scope.tag());
+        k.extend_from_slice(&id.block.to_be_bytes());
+        k.extend_from_slice(&id.tx.to_be_bytes());
+        k.extend_from_slice(spk);
+        k
+    }
+
+    /// One board: a pool or token, a window (0 days is all-time) and a metric.
+    pub fn trader_rank_prefix(
+        &self,
+        scope: LeaderboardScope,
+        id: &SchemaAlkaneId,
+        days: u32,
+        metric: LeaderboardMetric,
+    ) -> Vec<u8> {
+        let mut k = self.TRADER_RANK.key().to_vec();
+        k.push(scope.tag());
+        k.extend_from_slice(&id.block.to_be_bytes());
+        k.extend_from_slice(&id.tx.to_be_bytes());
+        k.extend_from_slice(&days.to_be_bytes());
+        k.push(metric.code());
+        k
+    }
+
+    pub fn trader_rank_key(
+        &self,
+        scope: LeaderboardScope,
+        id: &SchemaAlkaneId,
+        days: u32,
+        metric: LeaderboardMetric,
+        score: i128,
+        spk: &[u8],
+    ) -> Vec<u8> {
+        let mut k = self.trader_rank_prefix(scope, id, days, metric);
+        k.extend_from_slice(&signed_rank_bytes(score));
+        k.extend_from_slice(spk);
+        k
+    }
+
+    pub fn alert_key(&self, alert_id: u64) -> Vec<u8> {
+        let mut k = self.ALERTS.key().to_vec();
+        k.extend_from_slice(&alert_id.to_be_bytes());
+        k
+    }
+
+    /// Alerts on one token or pool: subject tag + alkane id, none for swaps in any pool.
+    pub fn alert_subject_prefix(&self, subject: &AlertSubject) -> Vec<u8> {
+        let mut k = self.ALERTS_BY_SUBJECT.key().to_vec();
+        k.push(subject.tag());
+        if let Some(id) = subject.id() {
+            k.extend_from_slice(&id.block.to_be_bytes());
+            k.extend_from_slice(&id.tx.to_be_bytes());
+        }
+        k
+    }
+
+    pub fn alert_subject_key(&self, subject: &AlertSubject, alert_id: u64) -> Vec<u8> {
+        let mut k = self.alert_subject_prefix(subject);
+        k.extend_from_slice(&alert_id.to_be_bytes());
+        k
+    }
+
+    pub fn watcher_alerts_prefix(&self, watcher: &str) -> Vec<u8> {
+        let mut k = self.ALERTS_BY_WATCHER.key().to_vec();
+        k.push(watcher.len() as u8);
+        k.extend_from_slice(watcher.as_bytes());
+        k
+    }
+
+    pub fn watcher_alert_key(&self, watcher: &str, alert_id: u64) -> Vec<u8> {
+        let mut k = self.watcher_alerts_prefix(watcher);
+        k.extend_from_slice(&alert_id.to_be_bytes());
+        k
+    }
+
+    pub fn alert_watcher_key(&self, watcher: &str) -> Vec<u8> {
+        let mut k = self.ALERT_WATCHERS.key().to_vec();
+        k.extend_from_slice(watcher.as_bytes());
+        k
+    }
+
+    /// Events of one watcher, in block order: len(watcher) + watcher + height BE + seq BE.
+    pub fn alert_events_prefix(&self, watcher: &str) -> Vec<u8> {
+        let mut k = self.ALERT_EVENTS.key().to_vec();
+        k.push(watcher.len() as u8);
+        k.extend_from_slice(watcher.as_bytes());
+        k
+    }
+
+    pub fn alert_event_key(&self, watcher: &str, height: u32, seq: u32) -> Vec<u8> {
+        let mut k = self.alert_events_prefix(watcher);
+        k.extend_from_slice(&height.to_be_bytes());
+        k.extend_from_slice(&seq.to_be_bytes());
+        k
+    }
+
     pub fn token_pools_prefix(&self, token: &SchemaAlkaneId) -> Vec<u8> {
         let mut k = self.TOKEN_POOLS.key().to_vec();
         k.extend_from_slice(&token.block.to_be_bytes());
@@ -1331,6 +1797,11 @@ fn parse_ts_seq_from_tail_be(bytes: &[u8]) -> Option<(u64, u32)> {
     Some((u64::from_be_bytes(ts_arr), u32::from_be_bytes(seq_arr)))
 }
 
+/// Big-endian bytes of `v` that sort in numeric order, negatives first.
+pub fn signed_rank_bytes(v: i128) -> [u8; 16] {
+    ((v as u128) ^ (1u128 << 127)).to_be_bytes()
+}
+
 fn push_spk(dst: &mut Vec<u8>, spk: &[u8]) {
     let len = spk.len().min(u16::MAX as usize) as u16;
     dst.extend_from_slice(&len.to_be_bytes());
@@ -1500,6 +1971,23 @@ impl AmmDataProvider {
         Ok(self.with_view_blockhash(Some(blockhash)))
     }
 
+    /// This view capped at `height`: itself when it already ends at or below `height`, the
+    /// state as of that block otherwise. None when `height` is older than the indexed history.
+    pub fn view_through(&self, height: u32) -> Result<Option<Self>> {
+        let shown = match self.view_blockhash {
+            Some(hash) => self.mdb.height_for_blockhash(&hash)?,
+            None => self.mdb.indexed_height_bounds()?.map(|(_, tip)| tip),
+        };
+        // Unversioned storage keeps a single state.
+        if shown.is_none_or(|shown| height >= shown) {
+            return Ok(Some(self.clone()));
+        }
+        Ok(self
+            .mdb
+            .blockhash_for_height(height)?
+            .map(|hash| self.with_view_blockhash(Some(hash))))
+    }
+
     pub fn table(&self) -> AmmDataTable<'_> {
         AmmDataTable::new(self.mdb.as_ref())
     }
@@ -1634,6 +2122,31 @@ impl AmmDataProvider {
         Ok(GetListEntriesDescResult { entries: page.entries })
     }
 
+    /// Bounded seek over `[start, end)`; see `Mdb::scan_range_entries`.
+    pub fn get_range_entries(
+        &self,
+        params: GetRangeEntriesParams,
+    ) -> Result<GetRangeEntriesResult> {
+        let end = params.end.as_deref();
+        let entries = match params.blockhash.resolve(self.view_blockhash) {
+            Some(blockhash) => self
+                .mdb
+                .scan_range_entries_at_blockhash(
+                    &blockhash,
+                    &params.start,
+                    end,
+                    params.limit,
+                    params.reverse,
+                )
+                .map_err(|e| anyhow!("mdb.scan_range_entries_at_blockhash failed: {e}"))?,
+            None => self
+                .mdb
+                .scan_range_entries(&params.start, end, params.limit, params.reverse)
+                .map_err(|e| anyhow!("mdb.scan_range_entries failed: {e}"))?,
+        };
+        Ok(GetRangeEntriesResult { entries })
+    }
+
     pub fn get_list_entries_desc_cursor(
         &self,
         params: GetListEntriesDescCursorParams,
@@ -1705,10 +2218,36 @@ impl AmmDataProvider {
 
     pub fn get_reserves_snapshot(
         &self,
-        _params: GetReservesSnapshotParams,
+        params: GetReservesSnapshotParams,
     ) -> Result<GetReservesSnapshotResult> {
         crate::debug_timer_log!("get_reserves_snapshot");
-        let out = fetch_all_pools(self)?;
+        // Live reserves come from metashrew; a past block is served from the per-pool
+        // snapshots the indexer stored as of that block.
+        let out = match params.blockhash.resolve(self.view_blockhash) {
+            None => fetch_all_pools(self)?,
+            Some(blockhash) => {
+                let table = self.table();
+                let prefix = table.reserves_snapshot_pool_prefix();
+                let entries = self
+                    .get_list_entries_desc(GetListEntriesDescParams {
+                        blockhash: StateAt::Block(blockhash),
+                        prefix: prefix.clone(),
+                    })?
+                    .entries;
+                let mut out = HashMap::new();
+                for (k, v) in entries {
+                    let Some(pool) =
+                        k.strip_prefix(prefix.as_slice()).and_then(decode_alkane_id_be)
+                    else {
+                        continue;
+                    };
+                    if let Ok(snap) = decode_pool_snapshot(&v) {
+                        out.insert(pool, snap);
+                    }
+                }
+                out
+            }
+        };
         if out.is_empty() {
             return Ok(GetReservesSnapshotResult { snapshot: None });
         }
@@ -2188,6 +2727,21 @@ impl AmmDataProvider {
         Ok(GetPoolFactoryResult { factory })
     }
 
+    pub fn get_pool_protocol(
+        &self,
+        params: GetPoolProtocolParams,
+    ) -> Result<GetPoolProtocolResult> {
+        let table = self.table();
+        let protocol = self
+            .get_raw_value(GetRawValueParams {
+                blockhash: params.blockhash,
+                key: table.pool_protocol_key(&params.pool),
+            })?
+            .value
+            .and_then(|raw| String::from_utf8(raw).ok());
+        Ok(GetPoolProtocolResult { protocol })
+    }
+
     pub fn get_pool_metrics(&self, params: GetPoolMetricsParams) -> Result<GetPoolMetricsResult> {
         crate::debug_timer_log!("get_pool_metrics");
         let table = self.table();
@@ -2255,20 +2809,602 @@ impl AmmDataProvider {
         Ok(GetPoolLpSupplyLatestResult { supply })
     }
 
-    pub fn get_pool_details_snapshot(
+    pub fn get_lp_position(&self, params: GetLpPositionParams) -> Result<GetLpPositionResult> {
+        crate::debug_timer_log!("get_lp_position");
+        let table = self.table();
+        let position = self
+            .get_raw_value(GetRawValueParams {
+                blockhash: params.blockhash,
+                key: table.lp_position_key(&params.address_spk, &params.pool),
+            })?
+            .value
+            .and_then(|raw| decode_lp_position(&raw).ok());
+        Ok(GetLpPositionResult { position })
+    }
+
+    pub fn get_address_lp_positions(
         &self,
-        params: GetPoolDetailsSnapshotParams,
-    ) -> Result<GetPoolDetailsSnapshotResult> {
-        crate::debug_timer_log!("get_pool_details_snapshot");
+        params: GetAddressLpPositionsParams,
+    ) -> Result<GetAddressLpPositionsResult> {
+        crate::debug_timer_log!("get_address_lp_positions");
+        let prefix = self.table().lp_positions_prefix(&params.address_spk);
+        let entries = self
+            .get_list_entries_desc(GetListEntriesDescParams {
+                blockhash: params.blockhash,
+                prefix: prefix.clone(),
+            })?
+            .entries;
+        let mut positions = Vec::new();
+        for (k, v) in entries {
+            if k.len() != prefix.len() + 12 || !k.starts_with(&prefix) {
+                continue;
+            }
+            let Some(pool) = decode_alkane_id_be(&k[prefix.len()..]) else { continue };
+            let Ok(position) = decode_lp_position(&v) else { continue };
+            positions.push((pool, position));
+        }
+        positions.sort_by_key(|(pool, _)| *pool);
+        Ok(GetAddressLpPositionsResult { positions })
+    }
+
+    pub fn get_cost_basis(&self, params: GetCostBasisParams) -> Result<GetCostBasisResult> {
+        crate::debug_timer_log!("get_cost_basis");
         let table = self.table();
-        let keys = vec![
-            table.pool_details_snapshot_field_key(&params.pool, "token0_tvl_usd"),
-            table.pool_details_snapshot_field_key(&params.pool, "token1_tvl_usd"),
-            table.pool_details_snapshot_field_key(&params.pool, "token0_tvl_sats"),
-            table.pool_details_snapshot_field_key(&params.pool, "token1_tvl_sats"),
-            table.pool_details_snapshot_field_key(&params.pool, "pool_tvl_usd"),
-            table.pool_details_snapshot_field_key(&params.pool, "pool_volume_1d_usd"),
-            table.pool_details_snapshot_field_key(&params.pool, "pool_volume_30d_usd"),
+        let basis = self
+            .get_raw_value(GetRawValueParams {
+                blockhash: params.blockhash,
+                key: table.cost_basis_key(&params.address_spk, &params.token),
+            })?
+            .value
+            .and_then(|raw| decode_cost_basis(&raw).ok());
+        Ok(GetCostBasisResult { basis })
+    }
+
+    pub fn get_address_cost_bases(
+        &self,
+        params: GetAddressCostBasesParams,
+    ) -> Result<GetAddressCostBasesResult> {
+        crate::debug_timer_log!("get_address_cost_bases");
+        let prefix = self.table().cost_basis_prefix(&params.address_spk);
+        let entries = self
+            .get_list_entries_desc(GetListEntriesDescParams {
+                blockhash: params.blockhash,
+                prefix: prefix.clone(),
+            })?
+            .entries;
+        let mut bases = Vec::new();
+        for (k, v) in entries {
+            if k.len() != prefix.len() + 12 || !k.starts_with(&prefix) {
+                continue;
+            }
+            let Some(token) = decode_alkane_id_be(&k[prefix.len()..]) else { continue };
+            let Ok(basis) = decode_cost_basis(&v) else { continue };
+            bases.push((token, basis));
+        }
+        bases.sort_by_key(|(token, _)| *token);
+        Ok(GetAddressCostBasesResult { bases })
+    }
+
+    /// Trades of one token by one address, newest first, limited to heights in
+    /// `[from_height, to_height]`. Seeks to the height range and, with a `limit`, reads only
+    /// the skipped `offset` rows, the page, and one row to tell whether more follow.
+    pub fn get_address_pnl_trades(
+        &self,
+        params: GetAddressPnlTradesParams,
+    ) -> Result<GetAddressPnlTradesResult> {
+        crate::debug_timer_log!("get_address_pnl_trades");
+        let prefix = self.table().address_pnl_trades_prefix(&params.address_spk, &params.token);
+        let mut start = prefix.clone();
+        start.extend_from_slice(&params.from_height.unwrap_or(0).to_be_bytes());
+        let end = match params.to_height.and_then(|h| h.checked_add(1)) {
+            Some(next) => {
+                let mut end = prefix.clone();
+                end.extend_from_slice(&next.to_be_bytes());
+                Some(end)
+            }
+            None => prefix_end_exclusive(&prefix),
+        };
+        let scan_limit = match params.limit {
+            Some(limit) => params.offset.saturating_add(limit).saturating_add(1),
+            None => usize::MAX,
+        };
+        let entries = self
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: params.blockhash,
+                start,
+                end,
+                limit: scan_limit,
+                reverse: true,
+            })?
+            .entries;
+        let mut trades = Vec::new();
+        for (k, v) in entries {
+            if k.len() != prefix.len() + 8 || !k.starts_with(&prefix) {
+                continue;
+            }
+            let mut height_bytes = [0u8; 4];
+            height_bytes.copy_from_slice(&k[prefix.len()..prefix.len() + 4]);
+            let height = u32::from_be_bytes(height_bytes);
+            let Ok(trade) = decode_pnl_trade(&v) else { continue };
+            trades.push((height, trade));
+        }
+        let mut trades: Vec<_> = trades.into_iter().skip(params.offset).collect();
+        let has_more = params.limit.is_some_and(|limit| trades.len() > limit);
+        if let Some(limit) = params.limit {
+            trades.truncate(limit);
+        }
+        Ok(GetAddressPnlTradesResult { trades, has_more })
+    }
+
+    /// Latest pool state recorded at or before `height` (or the latest overall when None),
+    /// with the height it was recorded at.
+    pub fn get_pool_state_at(&self, params: GetPoolStateAtParams) -> Result<GetPoolStateAtResult> {
+        crate::debug_timer_log!("get_pool_state_at");
+        let table = self.table();
+        if params.height.is_none() {
+            let latest = self
+                .get_raw_value(GetRawValueParams {
+                    blockhash: params.blockhash,
+                    key: table.pool_state_latest_key(&params.pool),
+                })?
+                .value
+                .and_then(|raw| decode_latest_pool_state(&raw).ok());
+            if latest.is_some() {
+                return Ok(GetPoolStateAtResult { state: latest });
+            }
+        }
+        let prefix = table.pool_state_prefix(&params.pool);
+        let cursor = params
+            .height
+            .and_then(|h| h.checked_add(1))
+            .map(|next| table.pool_state_key(&params.pool, next));
+        let entries = self
+            .get_list_entries_desc_cursor(GetListEntriesDescCursorParams {
+                blockhash: params.blockhash,
+                prefix: prefix.clone(),
+                cursor,
+                limit: 1,
+            })?
+            .entries;
+        let state = entries.into_iter().next().and_then(|(k, v)| {
+            if k.len() != prefix.len() + 4 || !k.starts_with(&prefix) {
+                return None;
+            }
+            let mut height_bytes = [0u8; 4];
+            height_bytes.copy_from_slice(&k[prefix.len()..]);
+            let state = decode_pool_state(&v).ok()?;
+            Some((u32::from_be_bytes(height_bytes), state))
+        });
+        Ok(GetPoolStateAtResult { state })
+    }
+
+    pub fn get_arbitrage_actor_totals(
+        &self,
+        params: GetArbitrageActorTotalsParams,
+    ) -> Result<GetArbitrageActorTotalsResult> {
+        crate::debug_timer_log!("get_arbitrage_actor_totals");
+        let table = self.table();
+        let totals = self
+            .get_raw_value(GetRawValueParams {
+                blockhash: params.blockhash,
+                key: table.arbitrage_actor_totals_key(&params.actor_spk),
+            })?
+            .value
+            .and_then(|raw| decode_arbitrage_totals(&raw).ok());
+        Ok(GetArbitrageActorTotalsResult { totals })
+    }
+
+    /// Arbitrage records newest first, for one actor or everyone, with heights in
+    /// `[from_height, to_height]`.
+    pub fn get_arbitrage_page(
+        &self,
+        params: GetArbitragePageParams,
+    ) -> Result<GetArbitragePageResult> {
+        crate::debug_timer_log!("get_arbitrage_page");
+        let table = self.table();
+        let prefix = match params.actor_spk.as_deref() {
+            Some(spk) => table.actor_arbitrage_prefix(spk),
+            None => table.ARBITRAGE.key().to_vec(),
+        };
+        let cursor = params.to_height.and_then(|h| h.checked_add(1)).map(|next| {
+            let mut k = prefix.clone();
+            k.extend_from_slice(&next.to_be_bytes());
+            k
+        });
+        let page = self.get_list_entries_desc_cursor(GetListEntriesDescCursorParams {
+            blockhash: params.blockhash,
+            prefix: prefix.clone(),
+            cursor,
+            limit: params.limit,
+        })?;
+        let from = params.from_height.unwrap_or(0);
+        let mut entries = Vec::new();
+        for (k, v) in page.entries {
+            if k.len() != prefix.len() + 8 || !k.starts_with(&prefix) {
+                continue;
+            }
+            let mut height_bytes = [0u8; 4];
+            height_bytes.copy_from_slice(&k[prefix.len()..prefix.len() + 4]);
+            let height = u32::from_be_bytes(height_bytes);
+            if height < from {
+                break;
+            }
+            let Ok(record) = decode_arbitrage(&v) else { continue };
+            entries.push((height, record));
+        }
+        Ok(GetArbitragePageResult { entries })
+    }
+
+    /// Actors by total arbitrage profit, best first, read off the rank index.
+    pub fn get_arbitrage_leaderboard(
+        &self,
+        params: GetArbitrageLeaderboardParams,
+    ) -> Result<GetArbitrageLeaderboardResult> {
+        crate::debug_timer_log!("get_arbitrage_leaderboard");
+        let table = self.table();
+        let prefix = table.ARBITRAGE_ACTOR_RANK.key().to_vec();
+        let entries = self
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: params.blockhash,
+                start: prefix.clone(),
+                end: prefix_end_exclusive(&prefix),
+                limit: params.limit,
+                reverse: true,
+            })?
+            .entries;
+        let mut actors = Vec::new();
+        for (k, v) in entries {
+            let Some(rest) = k.strip_prefix(prefix.as_slice()) else { continue };
+            if rest.len() < 16 {
+                continue;
+            }
+            let Ok(totals) = decode_arbitrage_totals(&v) else { continue };
+            actors.push((rest[16..].to_vec(), totals));
+        }
+        Ok(GetArbitrageLeaderboardResult { actors })
+    }
+
+    /// Per-block arbitrage totals newest first, for blocks in `[from_height, to_height]` that
+    /// had any.
+    pub fn get_arbitrage_blocks(
+        &self,
+        params: GetArbitrageBlocksParams,
+    ) -> Result<GetArbitrageBlocksResult> {
+        crate::debug_timer_log!("get_arbitrage_blocks");
+        let table = self.table();
+        let prefix = table.ARBITRAGE_BLOCK_TOTALS.key().to_vec();
+        let cursor = params
+            .to_height
+            .and_then(|h| h.checked_add(1))
+            .map(|next| table.arbitrage_block_totals_key(next));
+        let page = self.get_list_entries_desc_cursor(GetListEntriesDescCursorParams {
+            blockhash: params.blockhash,
+            prefix: prefix.clone(),
+            cursor,
+            limit: params.limit,
+        })?;
+        let from = params.from_height.unwrap_or(0);
+        let mut blocks = Vec::new();
+        for (k, v) in page.entries {
+            if k.len() != prefix.len() + 4 || !k.starts_with(&prefix) {
+                continue;
+            }
+            let mut height_bytes = [0u8; 4];
+            height_bytes.copy_from_slice(&k[prefix.len()..]);
+            let height = u32::from_be_bytes(height_bytes);
+            if height < from {
+                break;
+            }
+            let Ok(totals) = decode_arbitrage_totals(&v) else { continue };
+            blocks.push((height, totals));
+        }
+        Ok(GetArbitrageBlocksResult { blocks })
+    }
+
+    /// The first successful trade in `pool` after block `after` (at `after_height`), earliest
+    /// by (timestamp, seq). Walks the pool's state records forward from the next height, one
+    /// per block that touched the pool, and reads only that block's activity by its timestamp.
+    pub fn get_next_pool_trade(
+        &self,
+        params: GetNextPoolTradeParams,
+    ) -> Result<GetNextPoolTradeResult> {
+        crate::debug_timer_log!("get_next_pool_trade");
+        let table = self.table();
+        let Some(next_height) = params.after_height.checked_add(1) else {
+            return Ok(GetNextPoolTradeResult { trade: None });
+        };
+        let state_prefix = table.pool_state_prefix(&params.pool);
+        let state_end = prefix_end_exclusive(&state_prefix);
+        let activity_prefix = table.activity_ns_prefix(&params.pool);
+        let mut start = table.pool_state_key(&params.pool, next_height);
+        loop {
+            let records = self
+                .get_range_entries(GetRangeEntriesParams {
+                    blockhash: params.blockhash,
+                    start,
+                    end: state_end.clone(),
+                    limit: NEXT_TRADE_SCAN_CHUNK,
+                    reverse: false,
+                })?
+                .entries;
+            for (_, v) in &records {
+                let Ok(record) = decode_pool_state(v) else { continue };
+                let mut ts_prefix = activity_prefix.clone();
+                ts_prefix.extend_from_slice(format!("{}:", record.timestamp).as_bytes());
+                let mut trades: Vec<(u32, Vec<u8>, SchemaActivityV1)> = self
+                    .get_range_entries(GetRangeEntriesParams {
+                        blockhash: params.blockhash,
+                        start: ts_prefix.clone(),
+                        end: prefix_end_exclusive(&ts_prefix),
+                        limit: usize::MAX,
+                        reverse: false,
+                    })?
+                    .entries
+                    .into_iter()
+                    .filter_map(|(k, v)| {
+                        let (_, seq) = k
+                            .strip_prefix(activity_prefix.as_slice())
+                            .and_then(parse_ts_seq_from_key)?;
+                        let activity = decode_activity_v1(&v).ok()?;
+                        let is_trade = matches!(
+                            activity.kind,
+                            ActivityKind::TradeBuy | ActivityKind::TradeSell
+                        );
+                        (is_trade && activity.success).then_some((seq, k, activity))
+                    })
+                    .collect();
+                trades.sort_by_key(|(seq, _, _)| *seq);
+                for (_, key, activity) in trades {
+                    // An earlier block can share the timestamp; skip what `after` already had.
+                    let seen = self
+                        .get_raw_value(GetRawValueParams {
+                            blockhash: StateAt::Block(params.after),
+                            key,
+                        })?
+                        .value
+                        .is_some();
+                    if !seen {
+                        return Ok(GetNextPoolTradeResult { trade: Some(activity) });
+                    }
+                }
+            }
+            match records.last() {
+                Some((last, _)) if records.len() == NEXT_TRADE_SCAN_CHUNK => start = next_key(last),
+                _ => break,
+            }
+        }
+        Ok(GetNextPoolTradeResult { trade: None })
+    }
+
+    pub fn get_pool_fees_day(&self, params: GetPoolFeesDayParams) -> Result<GetPoolFeesDayResult> {
+        crate::debug_timer_log!("get_pool_fees_day");
+        let table = self.table();
+        let fees = self
+            .get_raw_value(GetRawValueParams {
+                blockhash: params.blockhash,
+                key: table.pool_fees_day_key(&params.pool, params.day_ts),
+            })?
+            .value
+            .and_then(|raw| decode_pool_fees(&raw).ok());
+        Ok(GetPoolFeesDayResult { fees })
+    }
+
+    /// Daily fee totals for a pool, newest day first.
+    pub fn get_pool_fee_days(&self, params: GetPoolFeeDaysParams) -> Result<GetPoolFeeDaysResult> {
+        crate::debug_timer_log!("get_pool_fee_days");
+        let table = self.table();
+        let prefix = table.pool_fees_day_prefix(&params.pool);
+        let page = self.get_list_entries_desc_cursor(GetListEntriesDescCursorParams {
+            blockhash: params.blockhash,
+            prefix: prefix.clone(),
+            cursor: None,
+            limit: params.limit,
+        })?;
+        let days = page
+            .entries
+            .into_iter()
+            .filter_map(|(k, v)| {
+                let day_ts =
+                    k.strip_prefix(prefix.as_slice())?.try_into().ok().map(u64::from_be_bytes)?;
+                Some((day_ts, decode_pool_fees(&v).ok()?))
+            })
+            .collect();
+        Ok(GetPoolFeeDaysResult { days })
+    }
+
+    /// Fees a pool earned in blocks at or above `from_height`, summed.
+    pub fn get_pool_fees_since(
+        &self,
+        params: GetPoolFeesSinceParams,
+    ) -> Result<GetPoolFeesSinceResult> {
+        crate::debug_timer_log!("get_pool_fees_since");
+        let table = self.table();
+        let prefix = table.pool_fees_block_prefix(&params.pool);
+        let entries = self
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: params.blockhash,
+                start: table.pool_fees_block_key(&params.pool, params.from_height),
+                end: prefix_end_exclusive(&prefix),
+                limit: usize::MAX,
+                reverse: false,
+            })?
+            .entries;
+        let mut fees = SchemaPoolFeesV1::default();
+        for (_, v) in entries {
+            if let Ok(block) = decode_pool_fees(&v) {
+                add_fees(&mut fees, &block);
+            }
+        }
+        Ok(GetPoolFeesSinceResult { fees })
+    }
+
+    /// One address's stats in a pool or token for a UTC day, or all-time when `day_ts` is None.
+    pub fn get_trader_stats(&self, params: GetTraderStatsParams) -> Result<GetTraderStatsResult> {
+        crate::debug_timer_log!("get_trader_stats");
+        let table = self.table();
+        let key = match params.day_ts {
+            Some(day_ts) => {
+                table.trader_stats_day_key(params.scope, &params.id, day_ts, &params.address_spk)
+            }
+            None => table.trader_stats_all_key(params.scope, &params.id, &params.address_spk),
+        };
+        let stats = self
+            .get_raw_value(GetRawValueParams { blockhash: params.blockhash, key })?
+            .value
+            .and_then(|raw| decode_trader_stats(&raw).ok());
+        Ok(GetTraderStatsResult { stats })
+    }
+
+    /// Top addresses of a pool or token by `metric` over the last `days` UTC days up to the
+    /// indexed tip's day, or all-time when `days` is 0, read off the rank index.
+    pub fn get_trader_leaderboard(
+        &self,
+        params: GetTraderLeaderboardParams,
+    ) -> Result<GetTraderLeaderboardResult> {
+        crate::debug_timer_log!("get_trader_leaderboard");
+        let prefix =
+            self.table()
+                .trader_rank_prefix(params.scope, &params.id, params.days, params.metric);
+        let entries = self
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: params.blockhash,
+                start: prefix.clone(),
+                end: prefix_end_exclusive(&prefix),
+                limit: params.limit,
+                reverse: true,
+            })?
+            .entries;
+        let mut rows = Vec::with_capacity(entries.len());
+        for (k, v) in entries {
+            let Some(rest) = k.strip_prefix(prefix.as_slice()) else { continue };
+            if rest.len() < 16 {
+                continue;
+            }
+            let Ok(stats) = decode_trader_stats(&v) else { continue };
+            rows.push((rest[16..].to_vec(), stats));
+        }
+        Ok(GetTraderLeaderboardResult { rows })
+    }
+
+    /// Alert definitions and watchers live in the unversioned store, so they are the same in
+    /// every view and are not dropped when blocks are rolled back. Only the events fired
+    /// while indexing are versioned.
+    fn alerts_mdb(&self) -> Mdb {
+        self.mdb.unversioned()
+    }
+
+    pub fn get_alert(&self, params: GetAlertParams) -> Result<GetAlertResult> {
+        crate::debug_timer_log!("get_alert");
+        let alert = self
+            .alerts_mdb()
+            .get(&self.table().alert_key(params.alert_id))
+            .map_err(|e| anyhow!("mdb.get failed: {e}"))?
+            .and_then(|raw| decode_alert(&raw).ok());
+        Ok(GetAlertResult { alert })
+    }
+
+    /// Alert ids listed under `prefix`, with their alerts; ids whose alert is gone are skipped.
+    fn alerts_under(&self, prefix: Vec<u8>, limit: usize) -> Result<Vec<(u64, SchemaAlertV1)>> {
+        let end = prefix_end_exclusive(&prefix);
+        let entries = self
+            .alerts_mdb()
+            .scan_range_entries(&prefix, end.as_deref(), limit, false)
+            .map_err(|e| anyhow!("mdb.scan_range_entries failed: {e}"))?;
+        let mut alerts = Vec::with_capacity(entries.len());
+        for (key, _) in entries {
+            let Some(id) = key.strip_prefix(prefix.as_slice()).and_then(|b| b.try_into().ok())
+            else {
+                continue;
+            };
+            let id = u64::from_be_bytes(id);
+            if let Some(alert) = self.get_alert(GetAlertParams { alert_id: id })?.alert {
+                alerts.push((id, alert));
+            }
+        }
+        Ok(alerts)
+    }
+
+    /// Alerts checked against one token or pool.
+    pub fn get_subject_alerts(
+        &self,
+        params: GetSubjectAlertsParams,
+    ) -> Result<GetSubjectAlertsResult> {
+        crate::debug_timer_log!("get_subject_alerts");
+        let prefix = self.table().alert_subject_prefix(&params.subject);
+        let alerts = self.alerts_under(prefix, usize::MAX)?;
+        Ok(GetSubjectAlertsResult { alerts })
+    }
+
+    pub fn get_watcher_alerts(
+        &self,
+        params: GetWatcherAlertsParams,
+    ) -> Result<GetWatcherAlertsResult> {
+        crate::debug_timer_log!("get_watcher_alerts");
+        let prefix = self.table().watcher_alerts_prefix(&params.watcher);
+        let alerts = self.alerts_under(prefix, ALERT_MAX_PER_WATCHER as usize)?;
+        Ok(GetWatcherAlertsResult { alerts })
+    }
+
+    pub fn get_alert_watcher(
+        &self,
+        params: GetAlertWatcherParams,
+    ) -> Result<GetAlertWatcherResult> {
+        crate::debug_timer_log!("get_alert_watcher");
+        let watcher = self
+            .alerts_mdb()
+            .get(&self.table().alert_watcher_key(&params.watcher))
+            .map_err(|e| anyhow!("mdb.get failed: {e}"))?
+            .and_then(|raw| decode_alert_watcher(&raw).ok());
+        Ok(GetAlertWatcherResult { watcher })
+    }
+
+    /// A watcher's alert events oldest first, starting after the `after` position (the key
+    /// suffix of the last event a client has seen). Reads at most `limit + 1` events.
+    pub fn get_alert_events(&self, params: GetAlertEventsParams) -> Result<GetAlertEventsResult> {
+        crate::debug_timer_log!("get_alert_events");
+        let prefix = self.table().alert_events_prefix(&params.watcher);
+        let start = match &params.after {
+            Some(after) => {
+                let mut k = prefix.clone();
+                k.extend_from_slice(after);
+                next_key(&k)
+            }
+            None => prefix.clone(),
+        };
+        let entries = self
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: params.blockhash,
+                start,
+                end: prefix_end_exclusive(&prefix),
+                limit: params.limit.saturating_add(1),
+                reverse: false,
+            })?
+            .entries;
+        let has_more = entries.len() > params.limit;
+        let mut events = Vec::with_capacity(params.limit);
+        for (key, raw) in entries.into_iter().take(params.limit) {
+            let Some(pos) = key.strip_prefix(prefix.as_slice()) else { continue };
+            if let Ok(event) = decode_alert_event(&raw) {
+                events.push((pos.to_vec(), event));
+            }
+        }
+        Ok(GetAlertEventsResult { events, has_more })
+    }
+
+    pub fn get_pool_details_snapshot(
+        &self,
+        params: GetPoolDetailsSnapshotParams,
+    ) -> Result<GetPoolDetailsSnapshotResult> {
+        crate::debug_timer_log!("get_pool_details_snapshot");
+        let table = self.table();
+        let keys = vec![
+            table.pool_details_snapshot_field_key(&params.pool, "token0_tvl_usd"),
+            table.pool_details_snapshot_field_key(&params.pool, "token1_tvl_usd"),
+            table.pool_details_snapshot_field_key(&params.pool, "token0_tvl_sats"),
+            table.pool_details_snapshot_field_key(&params.pool, "token1_tvl_sats"),
+            table.pool_details_snapshot_field_key(&params.pool, "pool_tvl_usd"),
+            table.pool_details_snapshot_field_key(&params.pool, "pool_volume_1d_usd"),
+            table.pool_details_snapshot_field_key(&params.pool, "pool_volume_30d_usd"),
             table.pool_details_snapshot_field_key(&params.pool, "pool_apr"),
             table.pool_details_snapshot_field_key(&params.pool, "tvl_change_24h"),
             table.pool_details_snapshot_field_key(&params.pool, "lp_supply"),
@@ -2722,6 +3858,19 @@ impl AmmDataProvider {
             }
         };
 
+        if let Some(blocks) = params.blocks {
+            let Some(pool) = parse_id_from_str(pool_raw) else {
+                return Ok(RpcGetCandlesResult {
+                    value: json!({
+                        "ok": false,
+                        "error": "missing_or_invalid_pool",
+                        "hint": "block candles are only available for pools, e.g. \"2:68441\""
+                    }),
+                });
+            };
+            return self.rpc_get_height_candles(pool, blocks, limit, page, side);
+        }
+
         let parse_token_or_derived =
             |raw: &str| -> Result<(SchemaAlkaneId, Option<SchemaAlkaneId>)> {
                 if let Some((token_part, quote_part)) = raw.split_once("-derived_") {
@@ -2828,25 +3977,41 @@ impl AmmDataProvider {
             }
         };
 
+        // Pool candles are read only as far back as the requested page (plus the candle
+        // before it, which the sats conversion opens from).
+        let offset = limit.saturating_mul(page.saturating_sub(1));
+        let window = offset.saturating_add(limit).saturating_add(1);
+
+        // Token and BTC/USD series store the index-time frames only; others are resampled.
+        let token_frames = active_timeframes();
         let slice = if is_mcusd {
-            if let Some(quote) = derived_quote {
-                read_token_derived_mcusd_candles_v1(self, pool, quote, tf, now)
-            } else {
-                read_token_mcusd_candles_v1(self, pool, tf, now)
-            }
+            read_resampled(tf, &token_frames, |src| {
+                if let Some(quote) = derived_quote {
+                    read_token_derived_mcusd_candles_v1(self, pool, quote, src, now)
+                } else {
+                    read_token_mcusd_candles_v1(self, pool, src, now)
+                }
+            })
         } else if is_usd {
-            if let Some(quote) = derived_quote {
-                read_token_derived_usd_candles_v1(self, pool, quote, tf, now)
-            } else {
-                read_token_usd_candles_v1(self, pool, tf, now)
-            }
+            read_resampled(tf, &token_frames, |src| {
+                if let Some(quote) = derived_quote {
+                    read_token_derived_usd_candles_v1(self, pool, quote, src, now)
+                } else {
+                    read_token_usd_candles_v1(self, pool, src, now)
+                }
+            })
         } else {
-            read_candles_v1(self, pool, tf, /*unused*/ limit, now, side)
+            read_candles_v1(self, pool, tf, window, now, side)
         };
 
         match slice {
             Ok(slice) => {
-                let total = slice.candles_newest_first.len();
+                let len = slice.candles_newest_first.len();
+                let total = if is_usd || is_mcusd {
+                    len
+                } else {
+                    pool_candle_total(self, pool, tf, now).unwrap_or(len)
+                };
                 let dur = tf.duration_secs();
                 let newest_ts = slice.newest_ts;
 
@@ -2876,16 +4041,16 @@ impl AmmDataProvider {
                     None
                 };
 
-                let offset = limit.saturating_mul(page.saturating_sub(1));
                 let end = (offset + limit).min(total);
-                let page_slice = if offset >= total {
+                let page_slice = if offset >= len {
                     &[][..]
                 } else {
-                    &slice.candles_newest_first[offset..end]
+                    &slice.candles_newest_first[offset..end.min(len)]
                 };
 
                 let btc_slice = if is_sats || is_mcsats {
-                    read_btc_usd_line_v1(self, tf, now).ok()
+                    read_resampled(tf, &token_frames, |src| read_btc_usd_line_v1(self, src, now))
+                        .ok()
                 } else {
                     None
                 };
@@ -2941,7 +4106,7 @@ impl AmmDataProvider {
                             candle.low = usd_to_sats_scaled(candle.low, btc_price);
                             candle.close = usd_to_sats_scaled(candle.close, btc_price);
                             candle.volume = usd_to_sats_scaled(candle.volume, btc_price);
-                            candle.open = if global_idx + 1 < total {
+                            candle.open = if global_idx + 1 < len {
                                 let prev_ts =
                                     newest_ts.saturating_sub(((global_idx + 1) as u64) * dur);
                                 let prev_close_usd =
@@ -3024,6 +4189,67 @@ impl AmmDataProvider {
         }
     }
 
+    fn rpc_get_height_candles(
+        &self,
+        pool: SchemaAlkaneId,
+        blocks: u64,
+        limit: usize,
+        page: usize,
+        side: PriceSide,
+    ) -> Result<RpcGetCandlesResult> {
+        let blocks = blocks.clamp(1, 10_000) as u32;
+        let tip = self
+            .get_index_height(GetIndexHeightParams { blockhash: StateAt::Latest })?
+            .height
+            .unwrap_or(0);
+        let offset = limit.saturating_mul(page.saturating_sub(1));
+        let window = offset.saturating_add(limit);
+        let slice = match read_height_candles_v1(self, pool, blocks, window, tip, side) {
+            Ok(slice) => slice,
+            Err(e) => {
+                return Ok(RpcGetCandlesResult {
+                    value: json!({ "ok": false, "error": format!("read_failed: {e}") }),
+                });
+            }
+        };
+        let total = slice.total;
+        let len = slice.candles_newest_first.len();
+        let end = (offset + limit).min(total);
+        let page_slice =
+            if offset >= len { &[][..] } else { &slice.candles_newest_first[offset..end.min(len)] };
+        let arr: Vec<Value> = page_slice
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let height = slice.newest_height.saturating_sub(((offset + i) as u32) * blocks);
+                json!({
+                    "height": height,
+                    "open":   c.open.to_string(),
+                    "high":   c.high.to_string(),
+                    "low":    c.low.to_string(),
+                    "close":  c.close.to_string(),
+                    "volume": c.volume.to_string(),
+                })
+            })
+            .collect();
+        Ok(RpcGetCandlesResult {
+            value: json!({
+                "ok": true,
+                "pool": id_str(&pool),
+                "blocks": blocks,
+                "side": match side {
+                    PriceSide::Base => "base",
+                    PriceSide::Quote => "quote",
+                },
+                "page": page,
+                "limit": limit,
+                "total": total,
+                "has_more": end < total,
+                "candles": arr
+            }),
+        })
+    }
+
     pub fn rpc_get_chart_change_block(
         &self,
         params: RpcGetChartChangeBlockParams,
@@ -3396,8 +4622,11 @@ impl AmmDataProvider {
             }
         };
 
-        let fee_bps = params.fee_bps.map(|n| n as u32).unwrap_or(DEFAULT_FEE_BPS);
+        // Without an explicit fee each hop charges its pool's adapter fee.
+        let fee_bps = params.fee_bps.map(|n| n as u32);
         let max_hops = params.max_hops.map(|n| n as usize).unwrap_or(3).max(1).min(6);
+        let adapters = load_pool_adapters(self, StateAt::Latest, &snapshot_map)?;
+        let route = RoutePools { snapshot: &snapshot_map, adapters: &adapters };
 
         let plan = match mode.as_str() {
             "exact_in" => {
@@ -3411,26 +4640,9 @@ impl AmmDataProvider {
                 };
                 let min_out = parse_u128_arg(params.amount_out_min.as_ref()).unwrap_or(0u128);
 
-                if params.fee_bps.is_some() {
-                    plan_swap_exact_tokens_for_tokens(
-                        &snapshot_map,
-                        token_in,
-                        token_out,
-                        amount_in,
-                        min_out,
-                        fee_bps,
-                        max_hops,
-                    )
-                } else {
-                    plan_exact_in_default_fee(
-                        &snapshot_map,
-                        token_in,
-                        token_out,
-                        amount_in,
-                        min_out,
-                        max_hops,
-                    )
-                }
+                plan_swap_exact_tokens_for_tokens(
+                    route, token_in, token_out, amount_in, min_out, fee_bps, max_hops,
+                )
             }
             "exact_out" => {
                 let amount_out = match parse_u128_arg(params.amount_out.as_ref()) {
@@ -3443,26 +4655,9 @@ impl AmmDataProvider {
                 };
                 let in_max = parse_u128_arg(params.amount_in_max.as_ref()).unwrap_or(u128::MAX);
 
-                if params.fee_bps.is_some() {
-                    plan_swap_tokens_for_exact_tokens(
-                        &snapshot_map,
-                        token_in,
-                        token_out,
-                        amount_out,
-                        in_max,
-                        fee_bps,
-                        max_hops,
-                    )
-                } else {
-                    plan_exact_out_default_fee(
-                        &snapshot_map,
-                        token_in,
-                        token_out,
-                        amount_out,
-                        in_max,
-                        max_hops,
-                    )
-                }
+                plan_swap_tokens_for_exact_tokens(
+                    route, token_in, token_out, amount_out, in_max, fee_bps, max_hops,
+                )
             }
             "implicit" => {
                 let available_in = match parse_u128_arg(
@@ -3477,26 +4672,15 @@ impl AmmDataProvider {
                 };
                 let min_out = parse_u128_arg(params.amount_out_min.as_ref()).unwrap_or(0u128);
 
-                if params.fee_bps.is_some() {
-                    plan_swap_exact_tokens_for_tokens_implicit(
-                        &snapshot_map,
-                        token_in,
-                        token_out,
-                        available_in,
-                        min_out,
-                        fee_bps,
-                        max_hops,
-                    )
-                } else {
-                    plan_implicit_default_fee(
-                        &snapshot_map,
-                        token_in,
-                        token_out,
-                        available_in,
-                        min_out,
-                        max_hops,
-                    )
-                }
+                plan_swap_exact_tokens_for_tokens_implicit(
+                    route,
+                    token_in,
+                    token_out,
+                    available_in,
+                    min_out,
+                    fee_bps,
+                    max_hops,
+                )
             }
             _ => {
                 return Ok(RpcFindBestSwapPathResult {
@@ -3545,15 +4729,15 @@ impl AmmDataProvider {
         }
     }
 
-    pub fn rpc_get_best_mev_swap(
+    pub fn rpc_find_best_split_swap(
         &self,
-        params: RpcGetBestMevSwapParams,
-    ) -> Result<RpcGetBestMevSwapResult> {
+        params: RpcFindBestSplitSwapParams,
+    ) -> Result<RpcFindBestSplitSwapResult> {
         let snapshot_map: HashMap<SchemaAlkaneId, SchemaPoolSnapshot> = match fetch_all_pools(self)
         {
-            Ok(m) => m,
-            Err(_) => {
-                return Ok(RpcGetBestMevSwapResult {
+            Ok(m) if !m.is_empty() => m,
+            _ => {
+                return Ok(RpcFindBestSplitSwapResult {
                     value: json!({
                         "ok": false,
                         "error": "no_liquidity",
@@ -3563,8 +4747,125 @@ impl AmmDataProvider {
             }
         };
 
-        if snapshot_map.is_empty() {
-            return Ok(RpcGetBestMevSwapResult {
+        let mode = params.mode.as_deref().unwrap_or("exact_in").to_ascii_lowercase();
+        let token_in = match params.token_in.as_deref().and_then(parse_id_from_str) {
+            Some(t) => t,
+            None => {
+                return Ok(RpcFindBestSplitSwapResult {
+                    value: json!({"ok": false, "error": "missing_or_invalid_token_in"}),
+                });
+            }
+        };
+        let token_out = match params.token_out.as_deref().and_then(parse_id_from_str) {
+            Some(t) => t,
+            None => {
+                return Ok(RpcFindBestSplitSwapResult {
+                    value: json!({"ok": false, "error": "missing_or_invalid_token_out"}),
+                });
+            }
+        };
+
+        let split_params = SplitParams {
+            fee_bps: params.fee_bps.map(|n| n as u32),
+            max_hops: params.max_hops.map(|n| n as usize).unwrap_or(3).clamp(1, 4),
+            max_routes: params.max_routes.map(|n| n as usize).unwrap_or(4).clamp(1, 8),
+            parts: params.parts.map(|n| n.min(SPLIT_MAX_PARTS as u64) as u32).unwrap_or(20),
+        };
+        let adapters = load_pool_adapters(self, StateAt::Latest, &snapshot_map)?;
+        let route = RoutePools { snapshot: &snapshot_map, adapters: &adapters };
+
+        let plan = match mode.as_str() {
+            "exact_in" => {
+                let Some(amount_in) = parse_u128_arg(params.amount_in.as_ref()) else {
+                    return Ok(RpcFindBestSplitSwapResult {
+                        value: json!({"ok": false, "error": "missing_or_invalid_amount_in"}),
+                    });
+                };
+                let min_out = parse_u128_arg(params.amount_out_min.as_ref()).unwrap_or(0u128);
+                plan_split_exact_in(route, token_in, token_out, amount_in, min_out, split_params)
+            }
+            "exact_out" => {
+                let Some(amount_out) = parse_u128_arg(params.amount_out.as_ref()) else {
+                    return Ok(RpcFindBestSplitSwapResult {
+                        value: json!({"ok": false, "error": "missing_or_invalid_amount_out"}),
+                    });
+                };
+                let in_max = parse_u128_arg(params.amount_in_max.as_ref()).unwrap_or(u128::MAX);
+                plan_split_exact_out(route, token_in, token_out, amount_out, in_max, split_params)
+            }
+            _ => {
+                return Ok(RpcFindBestSplitSwapResult {
+                    value: json!({
+                        "ok": false,
+                        "error": "invalid_mode",
+                        "hint": "use exact_in | exact_out"
+                    }),
+                });
+            }
+        };
+
+        let Some(sq) = plan else {
+            return Ok(RpcFindBestSplitSwapResult {
+                value: json!({"ok": false, "error": "no_path_found"}),
+            });
+        };
+
+        let routes: Vec<Value> = sq
+            .routes
+            .iter()
+            .map(|r| {
+                let share = match mode.as_str() {
+                    "exact_out" => ratio(r.quote.amount_out, sq.amount_out),
+                    _ => ratio(r.quote.amount_in, sq.amount_in),
+                };
+                json!({
+                    "amount_in":  r.quote.amount_in.to_string(),
+                    "amount_out": r.quote.amount_out.to_string(),
+                    "share": share,
+                    "price_impact": r.price_impact,
+                    "hops": hops_json(&r.quote.hops),
+                })
+            })
+            .collect();
+
+        Ok(RpcFindBestSplitSwapResult {
+            value: json!({
+                "ok": true,
+                "mode": mode,
+                "token_in":  id_str(&token_in),
+                "token_out": id_str(&token_out),
+                "fee_bps": split_params.fee_bps,
+                "max_hops": split_params.max_hops,
+                "max_routes": split_params.max_routes,
+                "parts": split_params.parts,
+                "amount_in":  sq.amount_in.to_string(),
+                "amount_out": sq.amount_out.to_string(),
+                "price_impact": sq.price_impact,
+                "routes": routes
+            }),
+        })
+    }
+
+    pub fn rpc_get_best_mev_swap(
+        &self,
+        params: RpcGetBestMevSwapParams,
+    ) -> Result<RpcGetBestMevSwapResult> {
+        let snapshot_map: HashMap<SchemaAlkaneId, SchemaPoolSnapshot> = match fetch_all_pools(self)
+        {
+            Ok(m) => m,
+            Err(_) => {
+                return Ok(RpcGetBestMevSwapResult {
+                    value: json!({
+                        "ok": false,
+                        "error": "no_liquidity",
+                        "hint": "live reserves unavailable"
+                    }),
+                });
+            }
+        };
+
+        if snapshot_map.is_empty() {
+            return Ok(RpcGetBestMevSwapResult {
                 value: json!({
                     "ok": false,
                     "error": "no_liquidity",
@@ -3581,10 +4882,12 @@ impl AmmDataProvider {
                 });
             }
         };
-        let fee_bps = params.fee_bps.map(|n| n as u32).unwrap_or(DEFAULT_FEE_BPS);
+        let fee_bps = params.fee_bps.map(|n| n as u32);
         let max_hops = params.max_hops.map(|n| n as usize).unwrap_or(3).clamp(2, 6);
+        let adapters = load_pool_adapters(self, StateAt::Latest, &snapshot_map)?;
+        let route = RoutePools { snapshot: &snapshot_map, adapters: &adapters };
 
-        match plan_best_mev_swap(&snapshot_map, token, fee_bps, max_hops) {
+        match plan_best_mev_swap(route, token, fee_bps, max_hops) {
             Some(pq) => {
                 let hops: Vec<Value> = pq
                     .hops
@@ -3619,56 +4922,1330 @@ impl AmmDataProvider {
         }
     }
 
-    pub fn rpc_get_btc_usd_price(
-        &self,
-        params: RpcGetBtcUsdPriceParams,
-    ) -> Result<RpcGetBtcUsdPriceResult> {
+    pub fn rpc_get_btc_usd_price(
+        &self,
+        params: RpcGetBtcUsdPriceParams,
+    ) -> Result<RpcGetBtcUsdPriceResult> {
+        let table = self.table();
+        let mut out = if let Some(height) = params.height {
+            let key = table.btc_usd_price_key(height);
+            let value =
+                self.get_raw_value(GetRawValueParams { blockhash: StateAt::Latest, key })?;
+            match value.value.and_then(|raw| decode_u128_value(&raw).ok()).filter(|v| *v > 0) {
+                Some(price) => json!({
+                    "ok": true,
+                    "height": height,
+                    "price": price.to_string(),
+                    "source": "ammdata_index"
+                }),
+                None => match self.get_latest_btc_usd_price_entry(GetLatestBtcUsdPriceParams {
+                    blockhash: StateAt::Latest,
+                })? {
+                    Some((fallback_height, price)) => json!({
+                        "ok": true,
+                        "height": fallback_height,
+                        "requested_height": height,
+                        "price": price.to_string(),
+                        "source": "ammdata_index_last_active"
+                    }),
+                    None => json!({
+                        "ok": false,
+                        "error": "price_unavailable",
+                        "height": height
+                    }),
+                },
+            }
+        } else {
+            match self.get_latest_btc_usd_price_entry(GetLatestBtcUsdPriceParams {
+                blockhash: StateAt::Latest,
+            })? {
+                Some((height, price)) => json!({
+                    "ok": true,
+                    "height": height,
+                    "price": price.to_string(),
+                    "source": "ammdata_index"
+                }),
+                None => json!({
+                    "ok": false,
+                    "error": "price_unavailable"
+                }),
+            }
+        };
+        // Health of the configured feed sources as seen by this process's indexer.
+        if let Some(obj) = out.as_object_mut() {
+            obj.insert("sources".to_string(), json!(feed_health()));
+        }
+        Ok(RpcGetBtcUsdPriceResult { value: out })
+    }
+
+    pub fn rpc_get_lp_positions(
+        &self,
+        params: RpcGetLpPositionsParams,
+    ) -> Result<RpcGetLpPositionsResult> {
+        let Some(address_spk) = params.address.as_deref().and_then(address_spk_from_str) else {
+            return Ok(RpcGetLpPositionsResult {
+                value: json!({ "ok": false, "error": "missing_or_invalid_address" }),
+            });
+        };
+        let pool_filter = match params.pool.as_deref() {
+            Some(raw) => match parse_id_from_str(raw) {
+                Some(p) => Some(p),
+                None => {
+                    return Ok(RpcGetLpPositionsResult {
+                        value: json!({ "ok": false, "error": "invalid_pool" }),
+                    });
+                }
+            },
+            None => None,
+        };
+        let include_closed = params.include_closed.unwrap_or(false);
+
+        let positions = self
+            .get_address_lp_positions(GetAddressLpPositionsParams {
+                blockhash: StateAt::Latest,
+                address_spk,
+            })?
+            .positions;
+        let reserves = self
+            .get_reserves_snapshot(GetReservesSnapshotParams { blockhash: StateAt::Latest })?
+            .snapshot
+            .unwrap_or_default();
+        let units: HashMap<SchemaAlkaneId, CanonicalQuoteUnit> =
+            canonical_quotes(get_network()).into_iter().map(|cq| (cq.id, cq.unit)).collect();
+        let btc_usd_price = self
+            .get_latest_btc_usd_price_entry(GetLatestBtcUsdPriceParams {
+                blockhash: StateAt::Latest,
+            })?
+            .map(|(_, price)| price);
+        let price_of = |token: SchemaAlkaneId| {
+            token_price_usd(&token, &units, btc_usd_price, || {
+                self.get_token_metrics(GetTokenMetricsParams { blockhash: StateAt::Latest, token })
+                    .map(|res| res.metrics.price_usd)
+                    .unwrap_or(0)
+            })
+        };
+
+        let mut out = Vec::new();
+        for (pool, position) in positions {
+            if pool_filter.is_some_and(|p| p != pool) {
+                continue;
+            }
+            if position.lots.is_empty() && !include_closed {
+                continue;
+            }
+            let Some(snap) = reserves.get(&pool) else { continue };
+            let lp_supply = self
+                .get_pool_lp_supply_latest(GetPoolLpSupplyLatestParams {
+                    blockhash: StateAt::Latest,
+                    pool,
+                })?
+                .supply;
+            let v = value_position(
+                &position,
+                &LpPoolState {
+                    reserve0: snap.base_reserve,
+                    reserve1: snap.quote_reserve,
+                    lp_supply,
+                    price0_usd: price_of(snap.base_id),
+                    price1_usd: price_of(snap.quote_id),
+                },
+            );
+            let lots: Vec<Value> = position
+                .lots
+                .iter()
+                .map(|lot| {
+                    json!({
+                        "txid": hex::encode(lot.txid.iter().rev().copied().collect::<Vec<u8>>()),
+                        "mint_height": lot.mint_height,
+                        "mint_timestamp": lot.mint_ts,
+                        "lp_tokens": lot.lp_tokens.to_string(),
+                        "token0_deposited": lot.token0_deposited.to_string(),
+                        "token1_deposited": lot.token1_deposited.to_string(),
+                        "entry_value_usd": lot.entry_value_usd.to_string(),
+                    })
+                })
+                .collect();
+            out.push(json!({
+                "pool": id_str(&pool),
+                "token0": id_str(&snap.base_id),
+                "token1": id_str(&snap.quote_id),
+                "lp_tokens": v.lp_tokens.to_string(),
+                "underlying0": v.underlying0.to_string(),
+                "underlying1": v.underlying1.to_string(),
+                "value_usd": v.value_usd.to_string(),
+                "entry_value_usd": v.entry_value_usd.to_string(),
+                "hodl_value_usd": v.hodl_value_usd.to_string(),
+                "fees_earned0": v.fees0.to_string(),
+                "fees_earned1": v.fees1.to_string(),
+                "fees_earned_usd": v.fees_value_usd.to_string(),
+                "impermanent_loss_usd": v.impermanent_loss_usd.to_string(),
+                "unrealized_pnl_usd": v.unrealized_pnl_usd.to_string(),
+                "realized_pnl_usd": position.realized_pnl_usd.to_string(),
+                "lp_burned": position.lp_burned.to_string(),
+                "token0_withdrawn": position.token0_withdrawn.to_string(),
+                "token1_withdrawn": position.token1_withdrawn.to_string(),
+                "withdrawn_value_usd": position.withdrawn_value_usd.to_string(),
+                "lots": lots,
+            }));
+        }
+
+        Ok(RpcGetLpPositionsResult {
+            value: json!({
+                "ok": true,
+                "address": params.address,
+                "total": out.len(),
+                "positions": out,
+            }),
+        })
+    }
+
+    pub fn rpc_get_address_pnl(
+        &self,
+        params: RpcGetAddressPnlParams,
+    ) -> Result<RpcGetAddressPnlResult> {
+        let Some(address_spk) = params.address.as_deref().and_then(address_spk_from_str) else {
+            return Ok(RpcGetAddressPnlResult {
+                value: json!({ "ok": false, "error": "missing_or_invalid_address" }),
+            });
+        };
+        let token_filter = match params.token.as_deref() {
+            Some(raw) => match parse_id_from_str(raw) {
+                Some(t) => Some(t),
+                None => {
+                    return Ok(RpcGetAddressPnlResult {
+                        value: json!({ "ok": false, "error": "invalid_token" }),
+                    });
+                }
+            },
+            None => None,
+        };
+        let from_height = params.from_height.map(|h| h.min(u32::MAX as u64) as u32);
+        let to_height = params.to_height.map(|h| h.min(u32::MAX as u64) as u32);
+        if from_height.zip(to_height).is_some_and(|(from, to)| from > to) {
+            return Ok(RpcGetAddressPnlResult {
+                value: json!({ "ok": false, "error": "invalid_height_range" }),
+            });
+        }
+
+        let limit = params.limit.map(|n| n as usize).unwrap_or(50).clamp(1, 500);
+        let page = params.page.map(|n| n as usize).unwrap_or(1).max(1);
+        let ranged = from_height.is_some() || to_height.is_some();
+        // Holdings, their cost and the prices marking them are read as of `to_height`.
+        let marked = match to_height {
+            Some(to) => match self.view_through(to)? {
+                Some(view) => view,
+                None => {
+                    return Ok(RpcGetAddressPnlResult {
+                        value: json!({ "ok": false, "error": "to_height_not_indexed" }),
+                    });
+                }
+            },
+            None => self.clone(),
+        };
+
+        let bases = marked
+            .get_address_cost_bases(GetAddressCostBasesParams {
+                blockhash: StateAt::Latest,
+                address_spk: address_spk.clone(),
+            })?
+            .bases;
+        let units: HashMap<SchemaAlkaneId, CanonicalQuoteUnit> =
+            canonical_quotes(get_network()).into_iter().map(|cq| (cq.id, cq.unit)).collect();
+        let btc_usd_price = marked
+            .get_latest_btc_usd_price_entry(GetLatestBtcUsdPriceParams {
+                blockhash: StateAt::Latest,
+            })?
+            .map(|(_, price)| price);
+
+        let mut tokens = Vec::new();
+        let mut total_realized_fifo = 0i128;
+        let mut total_realized_avg = 0i128;
+        let mut total_unrealized_fifo = 0i128;
+        let mut total_unrealized_avg = 0i128;
+        for (token, basis) in bases {
+            if token_filter.is_some_and(|t| t != token) {
+                continue;
+            }
+            // Without a height range the running totals on the cost basis are the answer;
+            // with one, sum the trades inside it.
+            let mut totals = PnlTotals {
+                realized_fifo: basis.realized_fifo_usd,
+                realized_avg: basis.realized_avg_usd,
+                bought: basis.bought,
+                bought_usd: basis.bought_cost_usd,
+                sold: basis.sold,
+                sold_usd: basis.sold_proceeds_usd,
+                unpriced_bought: basis.unpriced_bought,
+                unpriced_sold: basis.unpriced_sold,
+                trade_count: basis.trade_count,
+            };
+            if ranged {
+                let trades = self
+                    .get_address_pnl_trades(GetAddressPnlTradesParams {
+                        blockhash: StateAt::Latest,
+                        address_spk: address_spk.clone(),
+                        token,
+                        from_height,
+                        to_height,
+                        offset: 0,
+                        limit: None,
+                    })?
+                    .trades;
+                totals = PnlTotals::default();
+                for (_, t) in &trades {
+                    totals.add(t);
+                }
+            }
+
+            let price_usd = token_price_usd(&token, &units, btc_usd_price, || {
+                marked
+                    .get_token_metrics(GetTokenMetricsParams { blockhash: StateAt::Latest, token })
+                    .map(|res| res.metrics.price_usd)
+                    .unwrap_or(0)
+            });
+            // Holdings of a token with no price have no value to mark against their cost.
+            let open = (price_usd > 0).then(|| unrealized(&basis, price_usd));
+            total_realized_fifo = total_realized_fifo.saturating_add(totals.realized_fifo);
+            total_realized_avg = total_realized_avg.saturating_add(totals.realized_avg);
+            if let Some(open) = open {
+                total_unrealized_fifo = total_unrealized_fifo.saturating_add(open.fifo_usd);
+                total_unrealized_avg = total_unrealized_avg.saturating_add(open.avg_usd);
+            }
+
+            let mut row = json!({
+                "token": id_str(&token),
+                "held": basis.held.to_string(),
+                "priced": open.is_some(),
+                "price_usd": open.map(|_| price_usd.to_string()),
+                "fifo_cost_usd": fifo_cost_usd(&basis).to_string(),
+                "avg_cost_usd": basis.avg_cost_usd.to_string(),
+                "unrealized_fifo_usd": open.map(|o| o.fifo_usd.to_string()),
+                "unrealized_avg_usd": open.map(|o| o.avg_usd.to_string()),
+                "realized_fifo_usd": totals.realized_fifo.to_string(),
+                "realized_avg_usd": totals.realized_avg.to_string(),
+                "bought": totals.bought.to_string(),
+                "bought_usd": totals.bought_usd.to_string(),
+                "sold": totals.sold.to_string(),
+                "sold_usd": totals.sold_usd.to_string(),
+                "unpriced_bought": totals.unpriced_bought.to_string(),
+                "unpriced_sold": totals.unpriced_sold.to_string(),
+                "trade_count": totals.trade_count,
+            });
+            if token_filter.is_some() {
+                let res = self.get_address_pnl_trades(GetAddressPnlTradesParams {
+                    blockhash: StateAt::Latest,
+                    address_spk: address_spk.clone(),
+                    token,
+                    from_height,
+                    to_height,
+                    offset: (page - 1).saturating_mul(limit),
+                    limit: Some(limit),
+                })?;
+                let rows: Vec<Value> = res
+                    .trades
+                    .iter()
+                    .map(|(height, t)| {
+                        json!({
+                            "height": height,
+                            "timestamp": t.timestamp,
+                            "txid": hex::encode(t.txid.iter().rev().copied().collect::<Vec<u8>>()),
+                            "pool": id_str(&t.pool),
+                            "side": if t.is_buy { "buy" } else { "sell" },
+                            "amount": t.amount.to_string(),
+                            "priced": t.value_usd.is_some(),
+                            "value_usd": t.value_usd.map(|v| v.to_string()),
+                            "realized_fifo_usd": t.realized_fifo_usd.to_string(),
+                            "realized_avg_usd": t.realized_avg_usd.to_string(),
+                        })
+                    })
+                    .collect();
+                row["trades"] = Value::Array(rows);
+                row["page"] = json!(page);
+                row["limit"] = json!(limit);
+                row["has_more"] = json!(res.has_more);
+            }
+            tokens.push(row);
+        }
+
+        Ok(RpcGetAddressPnlResult {
+            value: json!({
+                "ok": true,
+                "address": params.address,
+                "from_height": from_height,
+                "to_height": to_height,
+                "realized_fifo_usd": total_realized_fifo.to_string(),
+                "realized_avg_usd": total_realized_avg.to_string(),
+                "unrealized_fifo_usd": total_unrealized_fifo.to_string(),
+                "unrealized_avg_usd": total_unrealized_avg.to_string(),
+                "tokens": tokens,
+            }),
+        })
+    }
+
+    pub fn rpc_get_pool_state_at(
+        &self,
+        params: RpcGetPoolStateAtParams,
+    ) -> Result<RpcGetPoolStateAtResult> {
+        let Some(pool) = params.pool.as_deref().and_then(parse_id_from_str) else {
+            return Ok(RpcGetPoolStateAtResult {
+                value: json!({ "ok": false, "error": "missing_or_invalid_pool" }),
+            });
+        };
+        let height = params.height.map(|h| h.min(u32::MAX as u64) as u32);
+        let Some((recorded_height, state)) = self
+            .get_pool_state_at(GetPoolStateAtParams { blockhash: StateAt::Latest, pool, height })?
+            .state
+        else {
+            return Ok(RpcGetPoolStateAtResult {
+                value: json!({ "ok": false, "error": "no_state_at_height" }),
+            });
+        };
+
+        Ok(RpcGetPoolStateAtResult {
+            value: json!({
+                "ok": true,
+                "pool": id_str(&pool),
+                "height": height.unwrap_or(recorded_height),
+                "recorded_height": recorded_height,
+                "timestamp": state.timestamp,
+                "base_reserve": state.base_reserve.to_string(),
+                "quote_reserve": state.quote_reserve.to_string(),
+                "lp_supply": state.lp_supply.to_string(),
+                "price_quote_per_base": state.price_quote_per_base.to_string(),
+                "price_base_per_quote": state.price_base_per_quote.to_string(),
+            }),
+        })
+    }
+
+    pub fn rpc_get_twap(&self, params: RpcGetTwapParams) -> Result<RpcGetTwapResult> {
+        let Some(pool) = params.pool.as_deref().and_then(parse_id_from_str) else {
+            return Ok(RpcGetTwapResult {
+                value: json!({ "ok": false, "error": "missing_or_invalid_pool" }),
+            });
+        };
+        let (Some(from_height), Some(to_height)) = (params.from_height, params.to_height) else {
+            return Ok(RpcGetTwapResult {
+                value: json!({ "ok": false, "error": "missing_height_range" }),
+            });
+        };
+        let from_height = from_height.min(u32::MAX as u64) as u32;
+        let to_height = to_height.min(u32::MAX as u64) as u32;
+        if from_height >= to_height {
+            return Ok(RpcGetTwapResult {
+                value: json!({ "ok": false, "error": "invalid_height_range" }),
+            });
+        }
+        // Extrapolating past the tip would assume the current price holds for unseen blocks.
+        let tip = self
+            .get_index_height(GetIndexHeightParams { blockhash: StateAt::Latest })?
+            .height;
+        if tip.is_none_or(|tip| to_height > tip) {
+            return Ok(RpcGetTwapResult {
+                value: json!({ "ok": false, "error": "to_height_above_tip", "tip": tip }),
+            });
+        }
+
+        let state_at = |height: u32| {
+            self.get_pool_state_at(GetPoolStateAtParams {
+                blockhash: StateAt::Latest,
+                pool,
+                height: Some(height),
+            })
+            .map(|res| res.state)
+        };
+        let (Some((from_rec_height, from_rec)), Some((to_rec_height, to_rec))) =
+            (state_at(from_height)?, state_at(to_height)?)
+        else {
+            return Ok(RpcGetTwapResult {
+                value: json!({ "ok": false, "error": "insufficient_history" }),
+            });
+        };
+        let (twap_quote_per_base, twap_base_per_quote) = twap(
+            cumulative_at(from_rec_height, &from_rec, from_height),
+            cumulative_at(to_rec_height, &to_rec, to_height),
+            to_height - from_height,
+        );
+
+        Ok(RpcGetTwapResult {
+            value: json!({
+                "ok": true,
+                "pool": id_str(&pool),
+                "from_height": from_height,
+                "to_height": to_height,
+                "blocks": to_height - from_height,
+                "twap_quote_per_base": twap_quote_per_base.to_string(),
+                "twap_base_per_quote": twap_base_per_quote.to_string(),
+                "spot_quote_per_base": to_rec.price_quote_per_base.to_string(),
+                "spot_base_per_quote": to_rec.price_base_per_quote.to_string(),
+            }),
+        })
+    }
+
+    pub fn rpc_get_arbitrage(
+        &self,
+        params: RpcGetArbitrageParams,
+    ) -> Result<RpcGetArbitrageResult> {
+        let actor_spk = match params.address.as_deref() {
+            Some(raw) => match address_spk_from_str(raw) {
+                Some(spk) => Some(spk),
+                None => {
+                    return Ok(RpcGetArbitrageResult {
+                        value: json!({ "ok": false, "error": "invalid_address" }),
+                    });
+                }
+            },
+            None => None,
+        };
+        let from_height = params.from_height.map(|h| h.min(u32::MAX as u64) as u32);
+        let to_height = params.to_height.map(|h| h.min(u32::MAX as u64) as u32);
+        if from_height.zip(to_height).is_some_and(|(from, to)| from > to) {
+            return Ok(RpcGetArbitrageResult {
+                value: json!({ "ok": false, "error": "invalid_height_range" }),
+            });
+        }
+        let limit = params.limit.map(|n| n as usize).unwrap_or(50).clamp(1, 500);
+
+        let entries = self
+            .get_arbitrage_page(GetArbitragePageParams {
+                blockhash: StateAt::Latest,
+                actor_spk: actor_spk.clone(),
+                from_height,
+                to_height,
+                limit,
+            })?
+            .entries;
+        let totals = match actor_spk {
+            Some(spk) => self
+                .get_arbitrage_actor_totals(GetArbitrageActorTotalsParams {
+                    blockhash: StateAt::Latest,
+                    actor_spk: spk,
+                })?
+                .totals
+                .map(|t| arbitrage_totals_json(&t)),
+            None => None,
+        };
+        let items: Vec<Value> =
+            entries.iter().map(|(height, rec)| arbitrage_json(*height, rec)).collect();
+
+        Ok(RpcGetArbitrageResult {
+            value: json!({
+                "ok": true,
+                "address": params.address,
+                "totals": totals,
+                "items": items,
+            }),
+        })
+    }
+
+    pub fn rpc_get_arbitrage_leaderboard(
+        &self,
+        params: RpcGetArbitrageLeaderboardParams,
+    ) -> Result<RpcGetArbitrageLeaderboardResult> {
+        let limit = params.limit.map(|n| n as usize).unwrap_or(50).clamp(1, 500);
+        let actors = self
+            .get_arbitrage_leaderboard(GetArbitrageLeaderboardParams {
+                blockhash: StateAt::Latest,
+                limit,
+            })?
+            .actors;
+        let items: Vec<Value> = actors
+            .iter()
+            .enumerate()
+            .map(|(i, (spk, totals))| {
+                let mut row = arbitrage_totals_json(totals);
+                row["rank"] = json!(i + 1);
+                row["address"] = json!(address_str_from_spk(spk));
+                row
+            })
+            .collect();
+        Ok(RpcGetArbitrageLeaderboardResult { value: json!({ "ok": true, "items": items }) })
+    }
+
+    pub fn rpc_get_mev_blocks(
+        &self,
+        params: RpcGetMevBlocksParams,
+    ) -> Result<RpcGetMevBlocksResult> {
+        let from_height = params.from_height.map(|h| h.min(u32::MAX as u64) as u32);
+        let to_height = params.to_height.map(|h| h.min(u32::MAX as u64) as u32);
+        if from_height.zip(to_height).is_some_and(|(from, to)| from > to) {
+            return Ok(RpcGetMevBlocksResult {
+                value: json!({ "ok": false, "error": "invalid_height_range" }),
+            });
+        }
+        let limit = params.limit.map(|n| n as usize).unwrap_or(100).clamp(1, 1_000);
+        let blocks = self
+            .get_arbitrage_blocks(GetArbitrageBlocksParams {
+                blockhash: StateAt::Latest,
+                from_height,
+                to_height,
+                limit,
+            })?
+            .blocks;
+        let items: Vec<Value> = blocks
+            .iter()
+            .map(|(height, totals)| {
+                json!({
+                    "height": height,
+                    "count": totals.count,
+                    "profit_usd": totals.profit_usd.to_string(),
+                })
+            })
+            .collect();
+        Ok(RpcGetMevBlocksResult { value: json!({ "ok": true, "items": items }) })
+    }
+
+    /// Replays a swap against the reserves as of the view's block and sets each hop beside the
+    /// next trade that actually hit its pool, so quotes can be checked against what happened.
+    pub fn rpc_simulate_swap_at(
+        &self,
+        params: RpcSimulateSwapAtParams,
+    ) -> Result<RpcSimulateSwapAtResult> {
+        let (Some(height), Some(blockhash)) = (params.height, self.view_blockhash) else {
+            return Ok(RpcSimulateSwapAtResult {
+                value: json!({ "ok": false, "error": "missing_height" }),
+            });
+        };
+        let Some(token_in) = params.token_in.as_deref().and_then(parse_id_from_str) else {
+            return Ok(RpcSimulateSwapAtResult {
+                value: json!({ "ok": false, "error": "missing_or_invalid_token_in" }),
+            });
+        };
+        let Some(token_out) = params.token_out.as_deref().and_then(parse_id_from_str) else {
+            return Ok(RpcSimulateSwapAtResult {
+                value: json!({ "ok": false, "error": "missing_or_invalid_token_out" }),
+            });
+        };
+        let mode = params.mode.as_deref().unwrap_or("exact_in").to_ascii_lowercase();
+        let max_hops = params.max_hops.map(|n| n as usize).unwrap_or(3).clamp(1, 6);
+
+        let snapshot = load_reserves_snapshot(self, StateAt::Block(blockhash))?;
+        if snapshot.is_empty() {
+            return Ok(RpcSimulateSwapAtResult {
+                value: json!({ "ok": false, "error": "no_liquidity" }),
+            });
+        }
+        let adapters = load_pool_adapters(self, StateAt::Block(blockhash), &snapshot)?;
+        let route = RoutePools { snapshot: &snapshot, adapters: &adapters };
+        let plan = match mode.as_str() {
+            "exact_in" => {
+                let Some(amount_in) = parse_u128_arg(params.amount_in.as_ref()) else {
+                    return Ok(RpcSimulateSwapAtResult {
+                        value: json!({ "ok": false, "error": "missing_or_invalid_amount_in" }),
+                    });
+                };
+                let min_out = parse_u128_arg(params.amount_out_min.as_ref()).unwrap_or(0);
+                plan_exact_in_default_fee(route, token_in, token_out, amount_in, min_out, max_hops)
+            }
+            "exact_out" => {
+                let Some(amount_out) = parse_u128_arg(params.amount_out.as_ref()) else {
+                    return Ok(RpcSimulateSwapAtResult {
+                        value: json!({ "ok": false, "error": "missing_or_invalid_amount_out" }),
+                    });
+                };
+                let in_max = parse_u128_arg(params.amount_in_max.as_ref()).unwrap_or(u128::MAX);
+                plan_exact_out_default_fee(route, token_in, token_out, amount_out, in_max, max_hops)
+            }
+            _ => {
+                return Ok(RpcSimulateSwapAtResult {
+                    value: json!({
+                        "ok": false,
+                        "error": "invalid_mode",
+                        "hint": "use exact_in | exact_out"
+                    }),
+                });
+            }
+        };
+        let Some(quote) = plan else {
+            return Ok(RpcSimulateSwapAtResult {
+                value: json!({ "ok": false, "error": "no_path_found" }),
+            });
+        };
+        let price_impact = quote_price_impact_default_fee(route, &quote, mode == "exact_out");
+
+        let tip = self.with_view_blockhash(None);
+        let mut hops = Vec::with_capacity(quote.hops.len());
+        for hop in &quote.hops {
+            let base_is_in = snapshot.get(&hop.pool).is_some_and(|p| p.base_id == hop.token_in);
+            let (base, quote_amount) = if base_is_in {
+                (hop.amount_in, hop.amount_out)
+            } else {
+                (hop.amount_out, hop.amount_in)
+            };
+            let next_trade = tip
+                .get_next_pool_trade(GetNextPoolTradeParams {
+                    blockhash: StateAt::Latest,
+                    pool: hop.pool,
+                    after: blockhash,
+                    after_height: height,
+                })?
+                .trade
+                .map(|t| {
+                    let base = t.base_delta.unsigned_abs();
+                    let quote = t.quote_delta.unsigned_abs();
+                    json!({
+                        "timestamp": t.timestamp,
+                        "txid": hex::encode(t.txid.iter().rev().copied().collect::<Vec<u8>>()),
+                        "side": if t.kind == ActivityKind::TradeBuy { "buy" } else { "sell" },
+                        "address": address_str_from_spk(&t.address_spk),
+                        "base_delta": t.base_delta.to_string(),
+                        "quote_delta": t.quote_delta.to_string(),
+                        "price_quote_per_base": scaled_price(quote, base),
+                        "price_base_per_quote": scaled_price(base, quote),
+                    })
+                });
+            let fee_bps =
+                adapters.get(&hop.pool).copied().unwrap_or_else(default_pool_adapter).fee_bps();
+            hops.push(json!({
+                "pool": id_str(&hop.pool),
+                "token_in": id_str(&hop.token_in),
+                "token_out": id_str(&hop.token_out),
+                "amount_in": hop.amount_in.to_string(),
+                "amount_out": hop.amount_out.to_string(),
+                "fee_bps": fee_bps,
+                "price_quote_per_base": scaled_price(quote_amount, base),
+                "price_base_per_quote": scaled_price(base, quote_amount),
+                "next_trade": next_trade,
+            }));
+        }
+
+        Ok(RpcSimulateSwapAtResult {
+            value: json!({
+                "ok": true,
+                "height": height,
+                "mode": mode,
+                "token_in": id_str(&token_in),
+                "token_out": id_str(&token_out),
+                "max_hops": max_hops,
+                "amount_in": quote.amount_in.to_string(),
+                "amount_out": quote.amount_out.to_string(),
+                "price_impact": price_impact,
+                "hops": hops,
+            }),
+        })
+    }
+
+    /// Realized fee revenue for a pool: 1d/7d/30d totals with the fee APR on current TVL and
+    /// the LP share price growth over each window, plus the daily fee series.
+    pub fn rpc_get_pool_fees(&self, params: RpcGetPoolFeesParams) -> Result<RpcGetPoolFeesResult> {
+        let Some(pool) = params.pool.as_deref().and_then(parse_id_from_str) else {
+            return Ok(RpcGetPoolFeesResult {
+                value: json!({ "ok": false, "error": "missing_or_invalid_pool" }),
+            });
+        };
+        let Some(height) = self
+            .get_index_height(GetIndexHeightParams { blockhash: StateAt::Latest })?
+            .height
+        else {
+            return Ok(RpcGetPoolFeesResult {
+                value: json!({ "ok": false, "error": "not_indexed" }),
+            });
+        };
+        let days = params.days.map(|n| n as usize).unwrap_or(30).clamp(1, 365);
+        let tvl_usd = self
+            .get_pool_metrics_v2(GetPoolMetricsV2Params { blockhash: StateAt::Latest, pool })?
+            .metrics
+            .map(|m| m.pool_tvl_usd)
+            .unwrap_or(0);
+        let share_at = |height: Option<u32>| -> Result<Option<u128>> {
+            Ok(self
+                .get_pool_state_at(GetPoolStateAtParams {
+                    blockhash: StateAt::Latest,
+                    pool,
+                    height,
+                })?
+                .state
+                .map(|(_, s)| sqrt_k_per_lp(s.base_reserve, s.quote_reserve, s.lp_supply)))
+        };
+        let share_now = share_at(None)?;
+
+        let mut windows = Map::new();
+        for (label, window_days, blocks) in FEE_WINDOWS {
+            let fees = self
+                .get_pool_fees_since(GetPoolFeesSinceParams {
+                    blockhash: StateAt::Latest,
+                    pool,
+                    from_height: height.saturating_sub(blocks - 1),
+                })?
+                .fees;
+            // No growth figure until the pool's state history covers the window.
+            let share_growth = match (share_at(height.checked_sub(blocks))?, share_now) {
+                (Some(from), Some(to)) => json!(share_price_growth(from, to)),
+                _ => Value::Null,
+            };
+            windows.insert(
+                label.to_string(),
+                json!({
+                    "base_fees": fees.base_fees.to_string(),
+                    "quote_fees": fees.quote_fees.to_string(),
+                    "fees_usd": fees.fees_usd.to_string(),
+                    "fees_sats": fees.fees_sats.to_string(),
+                    "trades": fees.trades,
+                    "fee_apr": fee_apr(fees.fees_usd, tvl_usd, window_days),
+                    "lp_share_growth": share_growth,
+                }),
+            );
+        }
+
+        let daily: Vec<Value> = self
+            .get_pool_fee_days(GetPoolFeeDaysParams {
+                blockhash: StateAt::Latest,
+                pool,
+                limit: days,
+            })?
+            .days
+            .into_iter()
+            .map(|(day_ts, fees)| {
+                json!({
+                    "day_ts": day_ts,
+                    "base_fees": fees.base_fees.to_string(),
+                    "quote_fees": fees.quote_fees.to_string(),
+                    "fees_usd": fees.fees_usd.to_string(),
+                    "fees_sats": fees.fees_sats.to_string(),
+                    "trades": fees.trades,
+                })
+            })
+            .collect();
+
+        Ok(RpcGetPoolFeesResult {
+            value: json!({
+                "ok": true,
+                "pool": id_str(&pool),
+                "height": height,
+                "tvl_usd": tvl_usd.to_string(),
+                "windows": windows,
+                "daily": daily,
+            }),
+        })
+    }
+
+    /// Screens tokens with any mix of metric, holders, age and pool filters, walking one sort
+    /// index. The cursor is the last index position looked at, so a selective filter may return
+    /// a short page with a cursor once it has scanned `SCREENER_MAX_SCAN` candidates.
+    pub fn rpc_get_token_screener(
+        &self,
+        params: RpcGetTokenScreenerParams,
+    ) -> Result<RpcGetTokenScreenerResult> {
+        let err = |error: &str| RpcGetTokenScreenerResult {
+            value: json!({ "ok": false, "error": error }),
+        };
+        let sort_name = params.sort.as_deref().unwrap_or("marketcap");
+        let Some(sort) = ScreenerSort::parse(sort_name) else {
+            return Ok(err("invalid_sort"));
+        };
+        let desc = match params.dir.as_deref() {
+            None | Some("desc") => true,
+            Some("asc") => false,
+            Some(_) => return Ok(err("invalid_dir")),
+        };
+        let filters = match ScreenerFilters::from_json(params.filters.as_ref()) {
+            Ok(filters) => filters,
+            Err(e) => return Ok(err(&e)),
+        };
+        let cursor = match params.cursor.as_deref().map(hex::decode) {
+            None => None,
+            Some(Ok(cursor)) => Some(cursor),
+            Some(Err(_)) => return Ok(err("invalid_cursor")),
+        };
+        let limit = params.limit.map(|n| n as usize).unwrap_or(50).clamp(1, 200);
+
+        let mut items: Vec<Value> = Vec::new();
+        let mut scanned = 0usize;
+        let mut after = cursor;
+        let mut exhausted = false;
+        'scan: while scanned < SCREENER_MAX_SCAN {
+            let want = SCREENER_CHUNK.min(SCREENER_MAX_SCAN - scanned);
+            let (chunk, more) = self.screener_positions(sort, desc, after.as_deref(), want)?;
+            if chunk.is_empty() {
+                exhausted = true;
+                break;
+            }
+            let ids: Vec<SchemaAlkaneId> = chunk.iter().map(|(_, id)| *id).collect();
+            let metrics = self
+                .get_token_metrics_by_id(GetTokenMetricsByIdParams {
+                    blockhash: StateAt::Latest,
+                    tokens: ids.clone(),
+                })?
+                .metrics;
+            let holders = self
+                .essentials
+                .get_holders_counts_by_id(GetHoldersCountsByIdParams {
+                    blockhash: StateAt::Latest,
+                    alkanes: ids.clone(),
+                })?
+                .counts;
+            let records = self
+                .essentials
+                .get_creation_records_by_id(GetCreationRecordsByIdParams {
+                    blockhash: StateAt::Latest,
+                    alkanes: ids.clone(),
+                })?
+                .records;
+            for (i, (pos, id)) in chunk.iter().enumerate() {
+                scanned += 1;
+                after = Some(pos.clone());
+                let record = records.get(i).and_then(|r| r.as_ref());
+                let row = ScreenerRow {
+                    metrics: metrics.get(i).cloned().flatten().unwrap_or_default(),
+                    holders: holders.get(i).copied().unwrap_or(0),
+                    creation_height: record.map(|r| r.creation_height),
+                    has_pool: self.token_has_pool(id)?,
+                };
+                if !filters.matches(&row) {
+                    continue;
+                }
+                items.push(screener_item_json(id, &row, record));
+                if items.len() >= limit {
+                    exhausted = !more && i + 1 == chunk.len();
+                    break 'scan;
+                }
+            }
+            if !more {
+                exhausted = true;
+                break;
+            }
+        }
+        let next_cursor = if exhausted { None } else { after.map(hex::encode) };
+        Ok(RpcGetTokenScreenerResult {
+            value: json!({
+                "ok": true,
+                "sort": sort_name,
+                "dir": if desc { "desc" } else { "asc" },
+                "items": items,
+                "scanned": scanned,
+                "next_cursor": next_cursor,
+            }),
+        })
+    }
+
+    /// Up to `limit` (position, token) pairs of the sort's index past the `after` position, in
+    /// walk order, and whether the index goes on. Metric sorts seek the token metrics index,
+    /// holders the essentials holders ordering and age the creation sequence.
+    fn screener_positions(
+        &self,
+        sort: ScreenerSort,
+        desc: bool,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<(Vec<(Vec<u8>, SchemaAlkaneId)>, bool)> {
+        let mut positions: Vec<(Vec<u8>, SchemaAlkaneId)> = match sort {
+            ScreenerSort::Metrics(field) => {
+                let table = self.table();
+                let prefix = table.token_metrics_index_prefix(field);
+                let bound = after.map(|pos| [prefix.as_slice(), pos].concat());
+                let (start, end) = if desc {
+                    (prefix.clone(), bound.or_else(|| prefix_end_exclusive(&prefix)))
+                } else {
+                    (
+                        bound.map(|k| next_key(&k)).unwrap_or_else(|| prefix.clone()),
+                        prefix_end_exclusive(&prefix),
+                    )
+                };
+                self.get_range_entries(GetRangeEntriesParams {
+                    blockhash: StateAt::Latest,
+                    start,
+                    end,
+                    limit: limit.saturating_add(1),
+                    reverse: desc,
+                })?
+                .entries
+                .into_iter()
+                .filter_map(|(key, _)| {
+                    let id = table.parse_token_metrics_index_key(field, &key)?;
+                    Some((key[prefix.len()..].to_vec(), id))
+                })
+                .collect()
+            }
+            ScreenerSort::Holders => {
+                let after = after.and_then(|pos| {
+                    let count = u64::from_be_bytes(pos.get(..8)?.try_into().ok()?);
+                    Some((count, decode_alkane_id_be(pos.get(8..)?)?))
+                });
+                self.essentials
+                    .get_holders_ordered_after(GetHoldersOrderedAfterParams {
+                        blockhash: StateAt::Latest,
+                        after,
+                        desc,
+                        limit: limit.saturating_add(1),
+                    })?
+                    .entries
+                    .into_iter()
+                    .map(|(count, id)| {
+                        let mut pos = count.to_be_bytes().to_vec();
+                        pos.extend_from_slice(&encode_alkane_id_be(&id));
+                        (pos, id)
+                    })
+                    .collect()
+            }
+            ScreenerSort::CreationHeight => {
+                let total = self
+                    .essentials
+                    .get_creation_count(GetCreationCountParams { blockhash: StateAt::Latest })?
+                    .count;
+                let after = after.and_then(|c| <[u8; 8]>::try_from(c).ok()).map(u64::from_be_bytes);
+                let seqs: Vec<u64> = if desc {
+                    let end = after.map_or(total, |seq| seq.min(total));
+                    (end.saturating_sub(limit as u64)..end).rev().collect()
+                } else {
+                    let start = after.map_or(0, |seq| seq.saturating_add(1));
+                    (start..total.min(start.saturating_add(limit as u64))).collect()
+                };
+                let more = match (desc, seqs.last()) {
+                    (true, Some(last)) => *last > 0,
+                    (false, Some(last)) => last + 1 < total,
+                    (_, None) => false,
+                };
+                let ids = self
+                    .essentials
+                    .get_creation_ids_by_seq(GetCreationIdsBySeqParams {
+                        blockhash: StateAt::Latest,
+                        seqs: seqs.clone(),
+                    })?
+                    .ids;
+                let positions = seqs
+                    .into_iter()
+                    .zip(ids)
+                    .filter_map(|(seq, id)| Some((seq.to_be_bytes().to_vec(), id?)))
+                    .collect();
+                return Ok((positions, more));
+            }
+        };
+        let more = positions.len() > limit;
+        positions.truncate(limit);
+        Ok((positions, more))
+    }
+
+    /// Whether `token` appears in at least one pool.
+    fn token_has_pool(&self, token: &SchemaAlkaneId) -> Result<bool> {
+        let prefix = self.table().token_pools_prefix(token);
+        let first = self
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: StateAt::Latest,
+                start: prefix.clone(),
+                end: prefix_end_exclusive(&prefix),
+                limit: 1,
+                reverse: false,
+            })?
+            .entries;
+        Ok(!first.is_empty())
+    }
+
+    /// Top traders or liquidity providers of a pool or token over a 1d/7d/30d/all window. The
+    /// all-time `lp` board of a pool ranks current holders of its LP token by share of supply.
+    pub fn rpc_get_trader_leaderboard(
+        &self,
+        params: RpcGetTraderLeaderboardParams,
+    ) -> Result<RpcGetTraderLeaderboardResult> {
+        let err = |error: &str| RpcGetTraderLeaderboardResult {
+            value: json!({ "ok": false, "error": error }),
+        };
+        let pool = params.pool.as_deref().and_then(parse_id_from_str);
+        let token = params.token.as_deref().and_then(parse_id_from_str);
+        let (scope, id) = match (pool, token) {
+            (Some(pool), None) => (LeaderboardScope::Pool, pool),
+            (None, Some(token)) => (LeaderboardScope::Token, token),
+            _ => return Ok(err("missing_or_invalid_pool_or_token")),
+        };
+        let window = params.window.as_deref().unwrap_or("7d");
+        let Some(days) = window_days(window) else {
+            return Ok(err("invalid_window"));
+        };
+        let Some(metric) = LeaderboardMetric::parse(params.metric.as_deref().unwrap_or("volume"))
+        else {
+            return Ok(err("invalid_metric"));
+        };
+        let limit = params.limit.map(|n| n as usize).unwrap_or(50).clamp(1, 500);
+        let scope_name = match scope {
+            LeaderboardScope::Pool => "pool",
+            LeaderboardScope::Token => "token",
+        };
+        let subject = id_str(&id);
+
+        if scope == LeaderboardScope::Pool && days == 0 && metric == LeaderboardMetric::Liquidity {
+            let (_holders, supply, entries) =
+                get_holders_for_alkane(StateAt::Latest, self.essentials(), id, 1, limit)?;
+            let items: Vec<Value> = entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    let mut row = json!({
+                        "rank": i + 1,
+                        "lp_balance": entry.amount.to_string(),
+                        "lp_share": ratio(entry.amount, supply),
+                    });
+                    match &entry.holder {
+                        HolderId::Address(address) => row["address"] = json!(address),
+                        HolderId::Alkane(alkane) => row["alkane"] = json!(id_str(alkane)),
+                    }
+                    row
+                })
+                .collect();
+            let mut value = json!({
+                "ok": true,
+                "window": window,
+                "metric": metric.as_str(),
+                "lp_supply": supply.to_string(),
+                "items": items,
+            });
+            value[scope_name] = json!(subject);
+            return Ok(RpcGetTraderLeaderboardResult { value });
+        }
+
+        let rows = self
+            .get_trader_leaderboard(GetTraderLeaderboardParams {
+                blockhash: StateAt::Latest,
+                scope,
+                id,
+                days,
+                metric,
+                limit,
+            })?
+            .rows;
+        let items: Vec<Value> = rows
+            .iter()
+            .enumerate()
+            .map(|(i, (spk, stats))| {
+                let mut row = trader_stats_json(stats);
+                row["rank"] = json!(i + 1);
+                row["address"] = json!(address_str_from_spk(spk));
+                row
+            })
+            .collect();
+        let mut value = json!({
+            "ok": true,
+            "window": window,
+            "metric": metric.as_str(),
+            "items": items,
+        });
+        value[scope_name] = json!(subject);
+        Ok(RpcGetTraderLeaderboardResult { value })
+    }
+
+    /// Checks `token` against the watcher's stored hash; the watcher and its record, or the
+    /// RPC error.
+    fn authorize_watcher(
+        &self,
+        watcher: Option<String>,
+        token: Option<&str>,
+    ) -> Result<std::result::Result<(String, SchemaAlertWatcherV1), &'static str>> {
+        let Some(watcher) = watcher.filter(|w| valid_watcher(w)) else {
+            return Ok(Err("missing_or_invalid_watcher"));
+        };
+        let Some(record) = self
+            .get_alert_watcher(GetAlertWatcherParams { watcher: watcher.clone() })?
+            .watcher
+        else {
+            return Ok(Err("unknown_watcher"));
+        };
+        if token.map(watcher_token_hash) != Some(record.token_hash) {
+            return Ok(Err("invalid_token"));
+        }
+        Ok(Ok((watcher, record)))
+    }
+
+    /// Applies alert and watcher writes to the unversioned store.
+    fn write_alerts(&self, puts: Vec<(Vec<u8>, Vec<u8>)>, deletes: Vec<Vec<u8>>) -> Result<()> {
+        self.alerts_mdb()
+            .bulk_write(|wb: &mut MdbBatch<'_>| {
+                for key in &deletes {
+                    wb.delete(key);
+                }
+                for (key, value) in &puts {
+                    wb.put(key, value);
+                }
+            })
+            .map_err(|e| anyhow!("mdb.bulk_write failed: {e}"))
+    }
+
+    /// Registers an alert for `watcher`. It is stored right away, outside the versioned tree,
+    /// and evaluated from the next indexed block. A watcher's first alert issues the watcher's
+    /// `token`, which every later call for it must pass.
+    pub fn rpc_register_alert(
+        &self,
+        params: RpcRegisterAlertParams,
+    ) -> Result<RpcRegisterAlertResult> {
+        let err =
+            |error: &str| RpcRegisterAlertResult { value: json!({ "ok": false, "error": error }) };
+        if self.view_blockhash.is_some() {
+            return Ok(err("historical_view_is_read_only"));
+        }
+        let Some(watcher) = params.watcher.filter(|w| valid_watcher(w)) else {
+            return Ok(err("missing_or_invalid_watcher"));
+        };
+        let Some(raw) = params.condition else {
+            return Ok(err("missing_condition"));
+        };
+        let condition = match parse_condition(&raw) {
+            Ok(condition) => condition,
+            Err(error) => return Ok(err(&error)),
+        };
+        let _guard = ALERT_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let stored = self
+            .get_alert_watcher(GetAlertWatcherParams { watcher: watcher.clone() })?
+            .watcher;
+        let (mut record, issued) = match stored {
+            Some(record) => {
+                if params.token.as_deref().map(watcher_token_hash) != Some(record.token_hash) {
+                    return Ok(err("invalid_token"));
+                }
+                (record, None)
+            }
+            None => {
+                let token = new_watcher_token()?;
+                (
+                    SchemaAlertWatcherV1 { token_hash: watcher_token_hash(&token), alerts: 0 },
+                    Some(token),
+                )
+            }
+        };
+
+        let table = self.table();
+        let id = alert_id(&watcher, &condition);
+        let exists = self.get_alert(GetAlertParams { alert_id: id })?.alert.is_some();
+        let mut puts = Vec::new();
+        if !exists {
+            if record.alerts >= ALERT_MAX_PER_WATCHER {
+                return Ok(err("too_many_alerts"));
+            }
+            let created_height = self
+                .get_index_height(GetIndexHeightParams { blockhash: StateAt::Latest })?
+                .height
+                .unwrap_or(0);
+            let alert = SchemaAlertV1 {
+                watcher: watcher.clone(),
+                condition: condition.clone(),
+                created_height,
+            };
+            puts.push((table.alert_key(id), encode_alert(&alert)?));
+            puts.push((table.alert_subject_key(&AlertSubject::of(&condition), id), Vec::new()));
+            puts.push((table.watcher_alert_key(&watcher, id), Vec::new()));
+            record.alerts += 1;
+        }
+        if !exists || issued.is_some() {
+            puts.push((table.alert_watcher_key(&watcher), encode_alert_watcher(&record)?));
+            self.write_alerts(puts, Vec::new())?;
+        }
+        let mut value = json!({
+            "ok": true,
+            "watcher": watcher,
+            "alert_id": alert_id_str(id),
+            "condition": condition_json(&condition),
+            "created": !exists,
+        });
+        if let Some(token) = issued {
+            value["token"] = json!(token);
+        }
+        Ok(RpcRegisterAlertResult { value })
+    }
+
+    /// Removes one of `watcher`'s alerts right away; its past events stay in the queue.
+    pub fn rpc_remove_alert(&self, params: RpcRemoveAlertParams) -> Result<RpcRemoveAlertResult> {
+        let err =
+            |error: &str| RpcRemoveAlertResult { value: json!({ "ok": false, "error": error }) };
+        if self.view_blockhash.is_some() {
+            return Ok(err("historical_view_is_read_only"));
+        }
+        let _guard = ALERT_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (watcher, mut record) =
+            match self.authorize_watcher(params.watcher, params.token.as_deref())? {
+                Ok(authorized) => authorized,
+                Err(error) => return Ok(err(error)),
+            };
+        let Some(id) = params.alert_id.as_deref().and_then(parse_alert_id) else {
+            return Ok(err("missing_or_invalid_alert_id"));
+        };
+        let Some(alert) = self
+            .get_alert(GetAlertParams { alert_id: id })?
+            .alert
+            .filter(|alert| alert.watcher == watcher)
+        else {
+            return Ok(err("unknown_alert"));
+        };
         let table = self.table();
-        let out = if let Some(height) = params.height {
-            let key = table.btc_usd_price_key(height);
-            let value =
-                self.get_raw_value(GetRawValueParams { blockhash: StateAt::Latest, key })?;
-            match value.value.and_then(|raw| decode_u128_value(&raw).ok()).filter(|v| *v > 0) {
-                Some(price) => json!({
-                    "ok": true,
-                    "height": height,
-                    "price": price.to_string(),
-                    "source": "ammdata_index"
-                }),
-                None => match self.get_latest_btc_usd_price_entry(GetLatestBtcUsdPriceParams {
-                    blockhash: StateAt::Latest,
-                })? {
-                    Some((fallback_height, price)) => json!({
-                        "ok": true,
-                        "height": fallback_height,
-                        "requested_height": height,
-                        "price": price.to_string(),
-                        "source": "ammdata_index_last_active"
-                    }),
-                    None => json!({
-                        "ok": false,
-                        "error": "price_unavailable",
-                        "height": height
-                    }),
-                },
-            }
-        } else {
-            match self.get_latest_btc_usd_price_entry(GetLatestBtcUsdPriceParams {
-                blockhash: StateAt::Latest,
-            })? {
-                Some((height, price)) => json!({
-                    "ok": true,
-                    "height": height,
-                    "price": price.to_string(),
-                    "source": "ammdata_index"
-                }),
-                None => json!({
-                    "ok": false,
-                    "error": "price_unavailable"
-                }),
+        record.alerts = record.alerts.saturating_sub(1);
+        self.write_alerts(
+            vec![(table.alert_watcher_key(&watcher), encode_alert_watcher(&record)?)],
+            vec![
+                table.alert_key(id),
+                table.alert_subject_key(&AlertSubject::of(&alert.condition), id),
+                table.watcher_alert_key(&watcher, id),
+            ],
+        )?;
+        Ok(RpcRemoveAlertResult {
+            value: json!({ "ok": true, "alert_id": alert_id_str(id), "removed": true }),
+        })
+    }
+
+    pub fn rpc_get_alerts(&self, params: RpcGetAlertsParams) -> Result<RpcGetAlertsResult> {
+        let watcher = match self.authorize_watcher(params.watcher, params.token.as_deref())? {
+            Ok((watcher, _)) => watcher,
+            Err(error) => {
+                return Ok(RpcGetAlertsResult { value: json!({ "ok": false, "error": error }) });
             }
         };
-        Ok(RpcGetBtcUsdPriceResult { value: out })
+        let items: Vec<Value> = self
+            .get_watcher_alerts(GetWatcherAlertsParams { watcher: watcher.clone() })?
+            .alerts
+            .into_iter()
+            .map(|(id, alert)| {
+                json!({
+                    "alert_id": alert_id_str(id),
+                    "condition": condition_json(&alert.condition),
+                    "created_height": alert.created_height,
+                })
+            })
+            .collect();
+        Ok(RpcGetAlertsResult { value: json!({ "ok": true, "watcher": watcher, "items": items }) })
+    }
+
+    /// Polls a watcher's triggered alerts. Pass the returned `cursor` back to get only newer
+    /// events; it stays put when nothing new has fired.
+    pub fn rpc_get_alert_events(
+        &self,
+        params: RpcGetAlertEventsParams,
+    ) -> Result<RpcGetAlertEventsResult> {
+        let err =
+            |error: &str| RpcGetAlertEventsResult { value: json!({ "ok": false, "error": error }) };
+        let watcher = match self.authorize_watcher(params.watcher, params.token.as_deref())? {
+            Ok((watcher, _)) => watcher,
+            Err(error) => return Ok(err(error)),
+        };
+        let after = match params.cursor.as_deref() {
+            None | Some("") => None,
+            Some(raw) => match hex::decode(raw) {
+                Ok(pos) => Some(pos),
+                Err(_) => return Ok(err("invalid_cursor")),
+            },
+        };
+        let limit = params.limit.map(|n| n as usize).unwrap_or(100).clamp(1, 1000);
+        let page = self.get_alert_events(GetAlertEventsParams {
+            blockhash: StateAt::Latest,
+            watcher: watcher.clone(),
+            after: after.clone(),
+            limit,
+        })?;
+        let cursor = page.events.last().map(|(pos, _)| pos.clone()).or(after);
+        let items: Vec<Value> = page
+            .events
+            .iter()
+            .map(|(_, ev)| {
+                json!({
+                    "alert_id": alert_id_str(ev.alert_id),
+                    "height": ev.height,
+                    "timestamp": ev.timestamp,
+                    "value": ev.value.to_string(),
+                    "txid": ev.txid.map(|txid| hex::encode(txid.iter().rev().copied().collect::<Vec<u8>>())),
+                })
+            })
+            .collect();
+        Ok(RpcGetAlertEventsResult {
+            value: json!({
+                "ok": true,
+                "watcher": watcher,
+                "items": items,
+                "cursor": cursor.map(hex::encode),
+                "has_more": page.has_more,
+            }),
+        })
     }
 
     pub fn rpc_ping(&self, _params: RpcPingParams) -> Result<RpcPingResult> {
@@ -3730,6 +6307,21 @@ pub struct GetListEntriesDescCursorResult {
     pub has_more: bool,
 }
 
+pub struct GetRangeEntriesParams {
+    pub blockhash: StateAt,
+
+    pub start: Vec<u8>,
+    /// Exclusive; `None` runs to the end of the module's keyspace.
+    pub end: Option<Vec<u8>>,
+    pub limit: usize,
+    /// Walk down from `end` instead of up from `start`.
+    pub reverse: bool,
+}
+
+pub struct GetRangeEntriesResult {
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
 pub struct SetRawValueParams {
     pub blockhash: StateAt,
 
@@ -3913,86 +6505,324 @@ pub struct GetPoolIdsByNamePrefixParams {
     pub prefix: String,
 }
 
-pub struct GetPoolIdsByNamePrefixResult {
-    pub ids: Vec<SchemaAlkaneId>,
+pub struct GetPoolIdsByNamePrefixResult {
+    pub ids: Vec<SchemaAlkaneId>,
+}
+
+pub struct GetAmmFactoriesParams {
+    pub blockhash: StateAt,
+}
+
+pub struct GetAmmFactoriesResult {
+    pub factories: Vec<SchemaAlkaneId>,
+}
+
+pub struct GetFactoryPoolsParams {
+    pub blockhash: StateAt,
+
+    pub factory: SchemaAlkaneId,
+}
+
+pub struct GetFactoryPoolsResult {
+    pub pools: Vec<SchemaAlkaneId>,
+}
+
+pub struct GetPoolDefsParams {
+    pub blockhash: StateAt,
+
+    pub pool: SchemaAlkaneId,
+}
+
+pub struct GetPoolDefsResult {
+    pub defs: Option<SchemaMarketDefs>,
+}
+
+pub struct GetPoolFactoryParams {
+    pub blockhash: StateAt,
+
+    pub pool: SchemaAlkaneId,
+}
+
+pub struct GetPoolFactoryResult {
+    pub factory: Option<SchemaAlkaneId>,
+}
+
+pub struct GetPoolProtocolParams {
+    pub blockhash: StateAt,
+
+    pub pool: SchemaAlkaneId,
+}
+
+pub struct GetPoolProtocolResult {
+    pub protocol: Option<String>,
+}
+
+pub struct GetPoolMetricsParams {
+    pub blockhash: StateAt,
+
+    pub pool: SchemaAlkaneId,
+}
+
+pub struct GetPoolMetricsResult {
+    pub metrics: SchemaPoolMetricsV1,
+}
+
+pub struct GetPoolMetricsV2Params {
+    pub blockhash: StateAt,
+
+    pub pool: SchemaAlkaneId,
+}
+
+pub struct GetPoolMetricsV2Result {
+    pub metrics: Option<SchemaPoolMetricsV2>,
+}
+
+pub struct GetPoolCreationInfoParams {
+    pub blockhash: StateAt,
+
+    pub pool: SchemaAlkaneId,
+}
+
+pub struct GetPoolCreationInfoResult {
+    pub info: Option<SchemaPoolCreationInfoV1>,
+}
+
+pub struct GetPoolLpSupplyLatestParams {
+    pub blockhash: StateAt,
+
+    pub pool: SchemaAlkaneId,
+}
+
+pub struct GetPoolLpSupplyLatestResult {
+    pub supply: u128,
+}
+
+pub struct GetLpPositionParams {
+    pub blockhash: StateAt,
+
+    pub address_spk: Vec<u8>,
+    pub pool: SchemaAlkaneId,
+}
+
+pub struct GetLpPositionResult {
+    pub position: Option<SchemaLpPositionV1>,
+}
+
+pub struct GetAddressLpPositionsParams {
+    pub blockhash: StateAt,
+
+    pub address_spk: Vec<u8>,
+}
+
+pub struct GetAddressLpPositionsResult {
+    pub positions: Vec<(SchemaAlkaneId, SchemaLpPositionV1)>,
+}
+
+pub struct GetCostBasisParams {
+    pub blockhash: StateAt,
+
+    pub address_spk: Vec<u8>,
+    pub token: SchemaAlkaneId,
+}
+
+pub struct GetCostBasisResult {
+    pub basis: Option<SchemaCostBasisV1>,
+}
+
+pub struct GetAddressCostBasesParams {
+    pub blockhash: StateAt,
+
+    pub address_spk: Vec<u8>,
+}
+
+pub struct GetAddressCostBasesResult {
+    pub bases: Vec<(SchemaAlkaneId, SchemaCostBasisV1)>,
+}
+
+pub struct GetAddressPnlTradesParams {
+    pub blockhash: StateAt,
+
+    pub address_spk: Vec<u8>,
+    pub token: SchemaAlkaneId,
+    pub from_height: Option<u32>,
+    pub to_height: Option<u32>,
+    pub offset: usize,
+    /// `None` reads every trade in the height range.
+    pub limit: Option<usize>,
+}
+
+pub struct GetAddressPnlTradesResult {
+    pub trades: Vec<(u32, SchemaPnlTradeV1)>,
+    pub has_more: bool,
+}
+
+pub struct GetPoolStateAtParams {
+    pub blockhash: StateAt,
+
+    pub pool: SchemaAlkaneId,
+    pub height: Option<u32>,
+}
+
+pub struct GetPoolStateAtResult {
+    pub state: Option<(u32, SchemaPoolStateV1)>,
+}
+
+pub struct GetArbitrageActorTotalsParams {
+    pub blockhash: StateAt,
+
+    pub actor_spk: Vec<u8>,
+}
+
+pub struct GetArbitrageActorTotalsResult {
+    pub totals: Option<SchemaArbitrageTotalsV1>,
+}
+
+pub struct GetArbitragePageParams {
+    pub blockhash: StateAt,
+
+    pub actor_spk: Option<Vec<u8>>,
+    pub from_height: Option<u32>,
+    pub to_height: Option<u32>,
+    pub limit: usize,
+}
+
+pub struct GetArbitragePageResult {
+    pub entries: Vec<(u32, SchemaArbitrageV1)>,
+}
+
+pub struct GetArbitrageLeaderboardParams {
+    pub blockhash: StateAt,
+
+    pub limit: usize,
+}
+
+pub struct GetArbitrageLeaderboardResult {
+    pub actors: Vec<(Vec<u8>, SchemaArbitrageTotalsV1)>,
+}
+
+pub struct GetArbitrageBlocksParams {
+    pub blockhash: StateAt,
+
+    pub from_height: Option<u32>,
+    pub to_height: Option<u32>,
+    pub limit: usize,
+}
+
+pub struct GetArbitrageBlocksResult {
+    pub blocks: Vec<(u32, SchemaArbitrageTotalsV1)>,
+}
+
+pub struct GetNextPoolTradeParams {
+    pub blockhash: StateAt,
+    pub pool: SchemaAlkaneId,
+    pub after: BlockHash,
+    pub after_height: u32,
+}
+
+pub struct GetNextPoolTradeResult {
+    pub trade: Option<SchemaActivityV1>,
 }
 
-pub struct GetAmmFactoriesParams {
+pub struct GetPoolFeesDayParams {
     pub blockhash: StateAt,
+    pub pool: SchemaAlkaneId,
+    pub day_ts: u64,
 }
 
-pub struct GetAmmFactoriesResult {
-    pub factories: Vec<SchemaAlkaneId>,
+pub struct GetPoolFeesDayResult {
+    pub fees: Option<SchemaPoolFeesV1>,
 }
 
-pub struct GetFactoryPoolsParams {
+pub struct GetPoolFeeDaysParams {
     pub blockhash: StateAt,
-
-    pub factory: SchemaAlkaneId,
+    pub pool: SchemaAlkaneId,
+    pub limit: usize,
 }
 
-pub struct GetFactoryPoolsResult {
-    pub pools: Vec<SchemaAlkaneId>,
+pub struct GetPoolFeeDaysResult {
+    pub days: Vec<(u64, SchemaPoolFeesV1)>,
 }
 
-pub struct GetPoolDefsParams {
+pub struct GetPoolFeesSinceParams {
     pub blockhash: StateAt,
-
     pub pool: SchemaAlkaneId,
+    pub from_height: u32,
 }
 
-pub struct GetPoolDefsResult {
-    pub defs: Option<SchemaMarketDefs>,
+pub struct GetPoolFeesSinceResult {
+    pub fees: SchemaPoolFeesV1,
 }
 
-pub struct GetPoolFactoryParams {
+pub struct GetTraderStatsParams {
     pub blockhash: StateAt,
 
-    pub pool: SchemaAlkaneId,
+    pub scope: LeaderboardScope,
+    pub id: SchemaAlkaneId,
+    pub day_ts: Option<u64>,
+    pub address_spk: Vec<u8>,
 }
 
-pub struct GetPoolFactoryResult {
-    pub factory: Option<SchemaAlkaneId>,
+pub struct GetTraderStatsResult {
+    pub stats: Option<SchemaTraderStatsV1>,
 }
 
-pub struct GetPoolMetricsParams {
+pub struct GetTraderLeaderboardParams {
     pub blockhash: StateAt,
 
-    pub pool: SchemaAlkaneId,
+    pub scope: LeaderboardScope,
+    pub id: SchemaAlkaneId,
+    pub days: u32,
+    pub metric: LeaderboardMetric,
+    pub limit: usize,
 }
 
-pub struct GetPoolMetricsResult {
-    pub metrics: SchemaPoolMetricsV1,
+pub struct GetTraderLeaderboardResult {
+    pub rows: Vec<(Vec<u8>, SchemaTraderStatsV1)>,
 }
 
-pub struct GetPoolMetricsV2Params {
-    pub blockhash: StateAt,
+pub struct GetSubjectAlertsParams {
+    pub subject: AlertSubject,
+}
 
-    pub pool: SchemaAlkaneId,
+pub struct GetSubjectAlertsResult {
+    pub alerts: Vec<(u64, SchemaAlertV1)>,
 }
 
-pub struct GetPoolMetricsV2Result {
-    pub metrics: Option<SchemaPoolMetricsV2>,
+pub struct GetWatcherAlertsParams {
+    pub watcher: String,
 }
 
-pub struct GetPoolCreationInfoParams {
-    pub blockhash: StateAt,
+pub struct GetWatcherAlertsResult {
+    pub alerts: Vec<(u64, SchemaAlertV1)>,
+}
 
-    pub pool: SchemaAlkaneId,
+pub struct GetAlertWatcherParams {
+    pub watcher: String,
 }
 
-pub struct GetPoolCreationInfoResult {
-    pub info: Option<SchemaPoolCreationInfoV1>,
+pub struct GetAlertWatcherResult {
+    pub watcher: Option<SchemaAlertWatcherV1>,
 }
 
-pub struct GetPoolLpSupplyLatestParams {
+pub struct GetAlertParams {
+    pub alert_id: u64,
+}
+
+pub struct GetAlertResult {
+    pub alert: Option<SchemaAlertV1>,
+}
+
+pub struct GetAlertEventsParams {
     pub blockhash: StateAt,
 
-    pub pool: SchemaAlkaneId,
+    pub watcher: String,
+    pub after: Option<Vec<u8>>,
+    pub limit: usize,
 }
 
-pub struct GetPoolLpSupplyLatestResult {
-    pub supply: u128,
+pub struct GetAlertEventsResult {
+    pub events: Vec<(Vec<u8>, SchemaAlertEventV1)>,
+    pub has_more: bool,
 }
 
 pub struct GetPoolDetailsSnapshotParams {
@@ -4226,6 +7056,8 @@ pub struct RpcGetCandlesParams {
     pub page: Option<u64>,
     pub side: Option<String>,
     pub now: Option<u64>,
+    /// Block-height candles of this many blocks each, instead of time buckets.
+    pub blocks: Option<u64>,
 }
 
 pub struct RpcGetCandlesResult {
@@ -4299,6 +7131,24 @@ pub struct RpcFindBestSwapPathResult {
     pub value: Value,
 }
 
+pub struct RpcFindBestSplitSwapParams {
+    pub mode: Option<String>,
+    pub token_in: Option<String>,
+    pub token_out: Option<String>,
+    pub fee_bps: Option<u64>,
+    pub max_hops: Option<u64>,
+    pub max_routes: Option<u64>,
+    pub parts: Option<u64>,
+    pub amount_in: Option<Value>,
+    pub amount_out_min: Option<Value>,
+    pub amount_out: Option<Value>,
+    pub amount_in_max: Option<Value>,
+}
+
+pub struct RpcFindBestSplitSwapResult {
+    pub value: Value,
+}
+
 pub struct RpcGetBestMevSwapParams {
     pub token: Option<String>,
     pub fee_bps: Option<u64>,
@@ -4317,6 +7167,198 @@ pub struct RpcGetBtcUsdPriceResult {
     pub value: Value,
 }
 
+pub struct RpcGetLpPositionsParams {
+    pub address: Option<String>,
+    pub pool: Option<String>,
+    pub include_closed: Option<bool>,
+}
+
+pub struct RpcGetLpPositionsResult {
+    pub value: Value,
+}
+
+pub struct RpcGetAddressPnlParams {
+    pub address: Option<String>,
+    pub token: Option<String>,
+    pub from_height: Option<u64>,
+    pub to_height: Option<u64>,
+    pub limit: Option<u64>,
+    pub page: Option<u64>,
+}
+
+pub struct RpcGetAddressPnlResult {
+    pub value: Value,
+}
+
+/// Per-token trade totals reported by `rpc_get_address_pnl`.
+#[derive(Default)]
+struct PnlTotals {
+    realized_fifo: i128,
+    realized_avg: i128,
+    bought: u128,
+    bought_usd: u128,
+    sold: u128,
+    sold_usd: u128,
+    unpriced_bought: u128,
+    unpriced_sold: u128,
+    trade_count: u64,
+}
+
+impl PnlTotals {
+    fn add(&mut self, t: &SchemaPnlTradeV1) {
+        self.realized_fifo = self.realized_fifo.saturating_add(t.realized_fifo_usd);
+        self.realized_avg = self.realized_avg.saturating_add(t.realized_avg_usd);
+        self.trade_count += 1;
+        let (amount, usd, unpriced) = if t.is_buy {
+            (&mut self.bought, &mut self.bought_usd, &mut self.unpriced_bought)
+        } else {
+            (&mut self.sold, &mut self.sold_usd, &mut self.unpriced_sold)
+        };
+        *amount = amount.saturating_add(t.amount);
+        match t.value_usd {
+            Some(v) => *usd = usd.saturating_add(v),
+            None => *unpriced = unpriced.saturating_add(t.amount),
+        }
+    }
+}
+
+pub struct RpcGetPoolStateAtParams {
+    pub pool: Option<String>,
+    pub height: Option<u64>,
+}
+
+pub struct RpcGetPoolStateAtResult {
+    pub value: Value,
+}
+
+pub struct RpcGetTwapParams {
+    pub pool: Option<String>,
+    pub from_height: Option<u64>,
+    pub to_height: Option<u64>,
+}
+
+pub struct RpcGetTwapResult {
+    pub value: Value,
+}
+
+pub struct RpcGetArbitrageParams {
+    pub address: Option<String>,
+    pub from_height: Option<u64>,
+    pub to_height: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+pub struct RpcGetArbitrageResult {
+    pub value: Value,
+}
+
+pub struct RpcGetArbitrageLeaderboardParams {
+    pub limit: Option<u64>,
+}
+
+pub struct RpcGetArbitrageLeaderboardResult {
+    pub value: Value,
+}
+
+pub struct RpcGetMevBlocksParams {
+    pub from_height: Option<u64>,
+    pub to_height: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+pub struct RpcGetMevBlocksResult {
+    pub value: Value,
+}
+
+pub struct RpcSimulateSwapAtParams {
+    pub height: Option<u64>,
+    pub mode: Option<String>,
+    pub token_in: Option<String>,
+    pub token_out: Option<String>,
+    pub max_hops: Option<u64>,
+    pub amount_in: Option<Value>,
+    pub amount_out_min: Option<Value>,
+    pub amount_out: Option<Value>,
+    pub amount_in_max: Option<Value>,
+}
+
+pub struct RpcSimulateSwapAtResult {
+    pub value: Value,
+}
+
+pub struct RpcGetPoolFeesParams {
+    pub pool: Option<String>,
+    pub days: Option<u64>,
+}
+
+pub struct RpcGetPoolFeesResult {
+    pub value: Value,
+}
+
+pub struct RpcGetTokenScreenerParams {
+    pub sort: Option<String>,
+    pub dir: Option<String>,
+    pub filters: Option<Value>,
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub struct RpcGetTokenScreenerResult {
+    pub value: Value,
+}
+
+pub struct RpcGetTraderLeaderboardParams {
+    pub pool: Option<String>,
+    pub token: Option<String>,
+    pub window: Option<String>,
+    pub metric: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub struct RpcGetTraderLeaderboardResult {
+    pub value: Value,
+}
+
+pub struct RpcRegisterAlertParams {
+    pub watcher: Option<String>,
+    pub token: Option<String>,
+    pub condition: Option<Value>,
+}
+
+pub struct RpcRegisterAlertResult {
+    pub value: Value,
+}
+
+pub struct RpcRemoveAlertParams {
+    pub watcher: Option<String>,
+    pub token: Option<String>,
+    pub alert_id: Option<String>,
+}
+
+pub struct RpcRemoveAlertResult {
+    pub value: Value,
+}
+
+pub struct RpcGetAlertsParams {
+    pub watcher: Option<String>,
+    pub token: Option<String>,
+}
+
+pub struct RpcGetAlertsResult {
+    pub value: Value,
+}
+
+pub struct RpcGetAlertEventsParams {
+    pub watcher: Option<String>,
+    pub token: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub struct RpcGetAlertEventsResult {
+    pub value: Value,
+}
+
 pub struct RpcPingParams;
 
 pub struct RpcPingResult {
@@ -4407,6 +7449,114 @@ pub fn encode_pool_creation_info(v: &SchemaPoolCreationInfoV1) -> anyhow::Result
     Ok(borsh::to_vec(v)?)
 }
 
+pub fn decode_lp_position(bytes: &[u8]) -> anyhow::Result<SchemaLpPositionV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaLpPositionV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_lp_position(v: &SchemaLpPositionV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_cost_basis(bytes: &[u8]) -> anyhow::Result<SchemaCostBasisV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaCostBasisV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_cost_basis(v: &SchemaCostBasisV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_pnl_trade(bytes: &[u8]) -> anyhow::Result<SchemaPnlTradeV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaPnlTradeV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_pnl_trade(v: &SchemaPnlTradeV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_pool_state(bytes: &[u8]) -> anyhow::Result<SchemaPoolStateV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaPoolStateV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_pool_state(v: &SchemaPoolStateV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_latest_pool_state(bytes: &[u8]) -> anyhow::Result<(u32, SchemaPoolStateV1)> {
+    use borsh::BorshDeserialize;
+    Ok(<(u32, SchemaPoolStateV1)>::try_from_slice(bytes)?)
+}
+
+pub fn encode_latest_pool_state(height: u32, v: &SchemaPoolStateV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(&(height, v))?)
+}
+
+pub fn decode_arbitrage(bytes: &[u8]) -> anyhow::Result<SchemaArbitrageV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaArbitrageV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_arbitrage(v: &SchemaArbitrageV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_arbitrage_totals(bytes: &[u8]) -> anyhow::Result<SchemaArbitrageTotalsV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaArbitrageTotalsV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_arbitrage_totals(v: &SchemaArbitrageTotalsV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_pool_fees(bytes: &[u8]) -> anyhow::Result<SchemaPoolFeesV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaPoolFeesV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_pool_fees(v: &SchemaPoolFeesV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_trader_stats(bytes: &[u8]) -> anyhow::Result<SchemaTraderStatsV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaTraderStatsV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_trader_stats(v: &SchemaTraderStatsV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_alert(bytes: &[u8]) -> anyhow::Result<SchemaAlertV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaAlertV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_alert(v: &SchemaAlertV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_alert_watcher(bytes: &[u8]) -> anyhow::Result<SchemaAlertWatcherV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaAlertWatcherV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_alert_watcher(v: &SchemaAlertWatcherV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
+pub fn decode_alert_event(bytes: &[u8]) -> anyhow::Result<SchemaAlertEventV1> {
+    use borsh::BorshDeserialize;
+    Ok(SchemaAlertEventV1::try_from_slice(bytes)?)
+}
+
+pub fn encode_alert_event(v: &SchemaAlertEventV1) -> anyhow::Result<Vec<u8>> {
+    Ok(borsh::to_vec(v)?)
+}
+
 pub fn decode_u128_value(bytes: &[u8]) -> anyhow::Result<u128> {
     if bytes.len() != 16 {
         return Err(anyhow!("invalid u128 length {}", bytes.len()));
@@ -4464,6 +7614,57 @@ fn now_ts() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
 }
 
+fn address_spk_from_str(s: &str) -> Option<Vec<u8>> {
+    Address::from_str(s.trim())
+        .ok()
+        .and_then(|a| a.require_network(get_network()).ok())
+        .map(|a| a.script_pubkey().into_bytes())
+}
+
+/// Address for a script pubkey on the active network, or its hex when it has none.
+pub fn address_str_from_spk(spk: &[u8]) -> String {
+    Address::from_script(Script::from_bytes(spk), get_network())
+        .map(|a| a.to_string())
+        .unwrap_or_else(|_| hex::encode(spk))
+}
+
+fn arbitrage_json(height: u32, rec: &SchemaArbitrageV1) -> Value {
+    json!({
+        "height": height,
+        "timestamp": rec.timestamp,
+        "txid": hex::encode(rec.txid.iter().rev().copied().collect::<Vec<u8>>()),
+        "address": address_str_from_spk(&rec.actor_spk),
+        "scope": if rec.single_tx { "tx" } else { "block" },
+        "token": id_str(&rec.token),
+        "amount_in": rec.amount_in.to_string(),
+        "amount_out": rec.amount_out.to_string(),
+        "profit": (rec.amount_out as i128 - rec.amount_in as i128).to_string(),
+        "profit_usd": rec.profit_usd.to_string(),
+        "pools": rec.pools.iter().map(id_str).collect::<Vec<String>>(),
+        "path": rec.path.iter().map(id_str).collect::<Vec<String>>(),
+    })
+}
+
+fn arbitrage_totals_json(totals: &SchemaArbitrageTotalsV1) -> Value {
+    json!({
+        "count": totals.count,
+        "profit_usd": totals.profit_usd.to_string(),
+        "last_height": totals.last_height,
+    })
+}
+
+fn trader_stats_json(stats: &SchemaTraderStatsV1) -> Value {
+    json!({
+        "volume_usd": stats.volume_usd.to_string(),
+        "trades": stats.trades,
+        "bought_usd": stats.bought_usd.to_string(),
+        "sold_usd": stats.sold_usd.to_string(),
+        "net_buy_usd": net_buy_usd(stats).to_string(),
+        "lp_added_usd": stats.lp_added_usd.to_string(),
+        "lp_removed_usd": stats.lp_removed_usd.to_string(),
+    })
+}
+
 fn parse_id_from_str(s: &str) -> Option<SchemaAlkaneId> {
     let parts: Vec<&str> = s.split(':').collect();
     if parts.len() != 2 {
@@ -4486,7 +7687,7 @@ fn parse_id_from_str(s: &str) -> Option<SchemaAlkaneId> {
     Some(SchemaAlkaneId { block: parse_u32(parts[0])?, tx: parse_u64(parts[1])? })
 }
 
-fn decode_alkane_id_be(bytes: &[u8]) -> Option<SchemaAlkaneId> {
+pub fn decode_alkane_id_be(bytes: &[u8]) -> Option<SchemaAlkaneId> {
     if bytes.len() != 12 {
         return None;
     }
@@ -4995,10 +8196,16 @@ fn read_btc_usd_line_v1(
 
 fn parse_timeframe(s: &str) -> Option<Timeframe> {
     match s {
+        "1m" => Some(Timeframe::Min1),
+        "5m" | "m5" => Some(Timeframe::Min5),
+        "15m" | "m15" => Some(Timeframe::Min15),
+        "30m" | "m30" => Some(Timeframe::Min30),
         "10m" | "m10" => Some(Timeframe::M10),
         "1h" | "h1" => Some(Timeframe::H1),
         "4h" | "h4" => Some(Timeframe::H4),
+        "12h" | "h12" => Some(Timeframe::H12),
         "1d" | "d1" => Some(Timeframe::D1),
+        "3d" | "d3" => Some(Timeframe::D3),
         "1w" | "w1" => Some(Timeframe::W1),
         "1M" | "m1" => Some(Timeframe::M1),
         _ => None,
@@ -5087,6 +8294,55 @@ fn parse_u128_arg(v: Option<&Value>) -> Option<u128> {
     }
 }
 
+fn hops_json(hops: &[Hop]) -> Vec<Value> {
+    hops.iter()
+        .map(|h| {
+            json!({
+                "pool":       id_str(&h.pool),
+                "token_in":   id_str(&h.token_in),
+                "token_out":  id_str(&h.token_out),
+                "amount_in":  h.amount_in.to_string(),
+                "amount_out": h.amount_out.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// `numerator / denominator` at `PRICE_SCALE`, as a string; `None` when undefined.
+fn scaled_price(numerator: u128, denominator: u128) -> Option<String> {
+    if denominator == 0 {
+        return None;
+    }
+    numerator.checked_mul(PRICE_SCALE).map(|v| (v / denominator).to_string())
+}
+
+fn ratio(part: u128, total: u128) -> f64 {
+    if total == 0 { 0.0 } else { part as f64 / total as f64 }
+}
+
+fn screener_item_json(
+    id: &SchemaAlkaneId,
+    row: &ScreenerRow,
+    record: Option<&AlkaneCreationRecord>,
+) -> Value {
+    let m = &row.metrics;
+    json!({
+        "id": id_str(id),
+        "name": record.and_then(|r| r.names.first()),
+        "symbol": record.and_then(|r| r.symbols.first()),
+        "creation_height": row.creation_height,
+        "holders": row.holders,
+        "has_pool": row.has_pool,
+        "price_usd": m.price_usd.to_string(),
+        "marketcap_usd": m.marketcap_usd.to_string(),
+        "fdv_usd": m.fdv_usd.to_string(),
+        "volume_1d": m.volume_1d.to_string(),
+        "volume_7d": m.volume_7d.to_string(),
+        "change_1d": m.change_1d,
+        "change_7d": m.change_7d,
+    })
+}
+
 fn id_str(id: &SchemaAlkaneId) -> String {
     format!("{}:{}", id.block, id.tx)
 }
@@ -463,7 +463,7 @@ pub struct ActivityPage {
 /* -------- legacy reader: newest→oldest by timestamp (primary) ------ */
 
 #[inline]
-fn parse_ts_from_key_tail(k: &[u8]) -> Option<u64> {
+pub(crate) fn parse_ts_from_key_tail(k: &[u8]) -> Option<u64> {
     // key ends with "...:<ts>:<seq>"
     let mut parts = k.rsplit(|&b| b == b':');
     let _seq_b = parts.next();
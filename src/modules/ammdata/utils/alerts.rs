@@ -0,0 +1,247 @@
+use crate::modules::ammdata::schemas::SchemaAlertConditionV1;
+use crate::schemas::SchemaAlkaneId;
+use crate::utils::parse_alkane_id;
+use anyhow::{Result, anyhow};
+use bitcoin::hashes::{Hash as _, sha256};
+use serde_json::{Value, json};
+
+pub const ALERT_WATCHER_MAX_LEN: usize = 64;
+
+/// Alerts one watcher may have registered at once.
+pub const ALERT_MAX_PER_WATCHER: u32 = 100;
+
+/// What an alert is checked against, so a block only reads the alerts of the tokens and
+/// pools it touched. Swap alerts without a pool watch every pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertSubject {
+    Price(SchemaAlkaneId),
+    Tvl(SchemaAlkaneId),
+    Swaps(Option<SchemaAlkaneId>),
+}
+
+impl AlertSubject {
+    pub fn of(condition: &SchemaAlertConditionV1) -> Self {
+        match condition {
+            SchemaAlertConditionV1::PriceAbove { token, .. }
+            | SchemaAlertConditionV1::PriceBelow { token, .. } => AlertSubject::Price(*token),
+            SchemaAlertConditionV1::TvlChange { pool, .. } => AlertSubject::Tvl(*pool),
+            SchemaAlertConditionV1::LargeSwap { pool, .. } => AlertSubject::Swaps(*pool),
+        }
+    }
+
+    pub fn tag(&self) -> u8 {
+        match self {
+            AlertSubject::Price(_) => b'p',
+            AlertSubject::Tvl(_) => b'v',
+            AlertSubject::Swaps(Some(_)) => b's',
+            AlertSubject::Swaps(None) => b'*',
+        }
+    }
+
+    pub fn id(&self) -> Option<SchemaAlkaneId> {
+        match self {
+            AlertSubject::Price(id) | AlertSubject::Tvl(id) => Some(*id),
+            AlertSubject::Swaps(pool) => *pool,
+        }
+    }
+}
+
+/// A fresh secret for a new watcher, as hex. Only its hash is stored.
+pub fn new_watcher_token() -> Result<String> {
+    let mut buf = [0u8; 32];
+    getrandom::getrandom(&mut buf).map_err(|e| anyhow!("getrandom failed: {e}"))?;
+    Ok(hex::encode(buf))
+}
+
+pub fn watcher_token_hash(token: &str) -> [u8; 32] {
+    sha256::Hash::hash(token.as_bytes()).to_byte_array()
+}
+
+/// Watchers name a client's queue: 1-64 ASCII letters, digits, `-`, `_`, `.` or `:`.
+pub fn valid_watcher(watcher: &str) -> bool {
+    !watcher.is_empty()
+        && watcher.len() <= ALERT_WATCHER_MAX_LEN
+        && watcher.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_.:".contains(&b))
+}
+
+/// Stable id of a watcher's condition, so registering the same condition twice is a no-op.
+pub fn alert_id(watcher: &str, condition: &SchemaAlertConditionV1) -> u64 {
+    let mut buf = watcher.as_bytes().to_vec();
+    buf.push(0);
+    buf.extend(borsh::to_vec(condition).unwrap_or_default());
+    let hash = sha256::Hash::hash(&buf).to_byte_array();
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&hash[..8]);
+    u64::from_be_bytes(id)
+}
+
+pub fn alert_id_str(id: u64) -> String {
+    format!("{id:016x}")
+}
+
+pub fn parse_alert_id(raw: &str) -> Option<u64> {
+    if raw.len() != 16 {
+        return None;
+    }
+    u64::from_str_radix(raw, 16).ok()
+}
+
+/// Parses `{"kind": "price_above" | "price_below", "token": "2:0", "price_usd": "<scaled>"}`,
+/// `{"kind": "tvl_change", "pool": "2:1", "change_bps": 500}` or
+/// `{"kind": "large_swap", "pool"?: "2:1", "min_sats": "100000000"}`.
+pub fn parse_condition(value: &Value) -> Result<SchemaAlertConditionV1, String> {
+    let obj = value.as_object().ok_or("invalid_condition")?;
+    let id = |name: &str| -> Result<SchemaAlkaneId, String> {
+        obj.get(name)
+            .and_then(|v| v.as_str())
+            .and_then(parse_alkane_id)
+            .ok_or_else(|| format!("invalid_condition_{name}"))
+    };
+    let amount = |name: &str| -> Result<u128, String> {
+        let parsed = match obj.get(name) {
+            Some(Value::String(s)) => s.parse::<u128>().ok(),
+            Some(Value::Number(n)) => n.as_u64().map(u128::from),
+            _ => None,
+        };
+        parsed.filter(|v| *v > 0).ok_or_else(|| format!("invalid_condition_{name}"))
+    };
+    match obj.get("kind").and_then(|v| v.as_str()) {
+        Some("price_above") => Ok(SchemaAlertConditionV1::PriceAbove {
+            token: id("token")?,
+            price_usd: amount("price_usd")?,
+        }),
+        Some("price_below") => Ok(SchemaAlertConditionV1::PriceBelow {
+            token: id("token")?,
+            price_usd: amount("price_usd")?,
+        }),
+        Some("tvl_change") => {
+            let change_bps = obj
+                .get("change_bps")
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u32::try_from(v).ok())
+                .filter(|v| *v > 0)
+                .ok_or("invalid_condition_change_bps")?;
+            Ok(SchemaAlertConditionV1::TvlChange { pool: id("pool")?, change_bps })
+        }
+        Some("large_swap") => {
+            let pool = match obj.get("pool") {
+                None | Some(Value::Null) => None,
+                Some(_) => Some(id("pool")?),
+            };
+            Ok(SchemaAlertConditionV1::LargeSwap { pool, min_sats: amount("min_sats")? })
+        }
+        _ => Err("invalid_condition_kind".to_string()),
+    }
+}
+
+pub fn condition_json(condition: &SchemaAlertConditionV1) -> Value {
+    let id = |id: &SchemaAlkaneId| format!("{}:{}", id.block, id.tx);
+    match condition {
+        SchemaAlertConditionV1::PriceAbove { token, price_usd } => {
+            json!({ "kind": "price_above", "token": id(token), "price_usd": price_usd.to_string() })
+        }
+        SchemaAlertConditionV1::PriceBelow { token, price_usd } => {
+            json!({ "kind": "price_below", "token": id(token), "price_usd": price_usd.to_string() })
+        }
+        SchemaAlertConditionV1::TvlChange { pool, change_bps } => {
+            json!({ "kind": "tvl_change", "pool": id(pool), "change_bps": change_bps })
+        }
+        SchemaAlertConditionV1::LargeSwap { pool, min_sats } => json!({
+            "kind": "large_swap",
+            "pool": pool.as_ref().map(id),
+            "min_sats": min_sats.to_string(),
+        }),
+    }
+}
+
+/// Whether a price condition holds at `price_usd`; unpriced tokens (0) hold neither side.
+pub fn price_condition_holds(condition: &SchemaAlertConditionV1, price_usd: u128) -> bool {
+    match condition {
+        SchemaAlertConditionV1::PriceAbove { price_usd: target, .. } => {
+            price_usd > 0 && price_usd >= *target
+        }
+        SchemaAlertConditionV1::PriceBelow { price_usd: target, .. } => {
+            price_usd > 0 && price_usd <= *target
+        }
+        _ => false,
+    }
+}
+
+/// Absolute change from `prev` to `now` in basis points; None when there was no prior TVL.
+pub fn tvl_change_bps(prev: u128, now: u128) -> Option<u128> {
+    if prev == 0 {
+        return None;
+    }
+    Some(prev.abs_diff(now).saturating_mul(10_000) / prev)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions_round_trip() {
+        let raw = json!({ "kind": "large_swap", "min_sats": "100000000" });
+        let cond = parse_condition(&raw).unwrap();
+        assert_eq!(cond, SchemaAlertConditionV1::LargeSwap { pool: None, min_sats: 100_000_000 });
+        assert_eq!(condition_json(&cond)["min_sats"], "100000000");
+
+        let raw = json!({ "kind": "price_above", "token": "2:16", "price_usd": 5 });
+        let cond = parse_condition(&raw).unwrap();
+        assert_eq!(parse_condition(&condition_json(&cond)).unwrap(), cond);
+
+        let err = |v: Value| parse_condition(&v).unwrap_err();
+        assert_eq!(err(json!({ "kind": "price_at" })), "invalid_condition_kind");
+        assert_eq!(err(json!({ "kind": "price_below", "token": "x" })), "invalid_condition_token");
+        assert_eq!(
+            err(json!({ "kind": "tvl_change", "pool": "2:1", "change_bps": 0 })),
+            "invalid_condition_change_bps"
+        );
+    }
+
+    #[test]
+    fn ids_are_stable_per_watcher() {
+        let cond = SchemaAlertConditionV1::TvlChange {
+            pool: SchemaAlkaneId { block: 2, tx: 1 },
+            change_bps: 500,
+        };
+        assert_eq!(alert_id("bot", &cond), alert_id("bot", &cond));
+        assert_ne!(alert_id("bot", &cond), alert_id("bot2", &cond));
+        let id = alert_id("bot", &cond);
+        assert_eq!(parse_alert_id(&alert_id_str(id)), Some(id));
+        assert!(valid_watcher("desk-1:eu"));
+        assert!(!valid_watcher("no spaces"));
+        assert!(!valid_watcher(""));
+    }
+
+    #[test]
+    fn thresholds() {
+        let token = SchemaAlkaneId { block: 2, tx: 16 };
+        let above = SchemaAlertConditionV1::PriceAbove { token, price_usd: 100 };
+        let below = SchemaAlertConditionV1::PriceBelow { token, price_usd: 100 };
+        assert!(price_condition_holds(&above, 100));
+        assert!(!price_condition_holds(&above, 99));
+        assert!(price_condition_holds(&below, 99));
+        assert!(!price_condition_holds(&below, 0));
+
+        assert_eq!(tvl_change_bps(1_000, 1_050), Some(500));
+        assert_eq!(tvl_change_bps(1_000, 900), Some(1_000));
+        assert_eq!(tvl_change_bps(0, 900), None);
+    }
+
+    #[test]
+    fn subjects_and_tokens() {
+        let pool = SchemaAlkaneId { block: 2, tx: 1 };
+        let any_pool = SchemaAlertConditionV1::LargeSwap { pool: None, min_sats: 1 };
+        let one_pool = SchemaAlertConditionV1::LargeSwap { pool: Some(pool), min_sats: 1 };
+        let tvl = SchemaAlertConditionV1::TvlChange { pool, change_bps: 1 };
+        assert_eq!(AlertSubject::of(&any_pool).id(), None);
+        assert_eq!(AlertSubject::of(&one_pool), AlertSubject::Swaps(Some(pool)));
+        assert_ne!(AlertSubject::of(&one_pool).tag(), AlertSubject::of(&tvl).tag());
+
+        let token = new_watcher_token().unwrap();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, new_watcher_token().unwrap());
+        assert_ne!(watcher_token_hash(&token), watcher_token_hash("other"));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::schemas::SchemaAlkaneId;
+use std::collections::HashMap;
+
+/// Longest swap cycle searched for. Keeps the search cheap for addresses that swap a lot
+/// in one block.
+pub const MAX_CYCLE_HOPS: usize = 6;
+
+/// Search steps (legs tried) allowed per block. The search is exponential in the number of
+/// swaps an address makes, so a block that exhausts it keeps the cycles found so far.
+pub const CYCLE_SEARCH_BUDGET: usize = 20_000;
+
+/// One swap by an address: it paid `amount_in` of `token_in` to `pool` and got
+/// `amount_out` of `token_out`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapLeg {
+    pub pool: SchemaAlkaneId,
+    pub token_in: SchemaAlkaneId,
+    pub amount_in: u128,
+    pub token_out: SchemaAlkaneId,
+    pub amount_out: u128,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cycle {
+    pub token: SchemaAlkaneId,
+    pub amount_in: u128,
+    pub amount_out: u128,
+    pub profit_usd: i128,
+    /// Indices into the legs, in path order.
+    pub legs: Vec<usize>,
+}
+
+struct Search<'a> {
+    legs: &'a [SwapLeg],
+    /// Leg indices by `token_in`, so each step only tries legs that can follow.
+    by_token_in: HashMap<SchemaAlkaneId, Vec<usize>>,
+}
+
+impl Search<'_> {
+    fn close_cycle(
+        &self,
+        budget: &mut usize,
+        taken: &mut [bool],
+        start: SchemaAlkaneId,
+        current: SchemaAlkaneId,
+        path: &mut Vec<usize>,
+    ) -> bool {
+        if path.len() >= MAX_CYCLE_HOPS {
+            return false;
+        }
+        for &i in self.by_token_in.get(&current).into_iter().flatten() {
+            if taken[i] {
+                continue;
+            }
+            if *budget == 0 {
+                return false;
+            }
+            *budget -= 1;
+            taken[i] = true;
+            path.push(i);
+            let token_out = self.legs[i].token_out;
+            if token_out == start || self.close_cycle(budget, taken, start, token_out, path) {
+                return true;
+            }
+            taken[i] = false;
+            path.pop();
+        }
+        false
+    }
+}
+
+/// Disjoint swap cycles in `legs`, whatever order the legs were observed in. A cycle can be
+/// read from any of its tokens, so each one is reported in the token where it made the most
+/// (by `value_usd`), which is the token the arbitrage was denominated in. Every leg tried
+/// spends one unit of `budget`; the search stops when it runs out.
+pub fn find_cycles(
+    legs: &[SwapLeg],
+    value_usd: impl Fn(SchemaAlkaneId, u128) -> u128,
+    budget: &mut usize,
+) -> Vec<Cycle> {
+    let mut by_token_in: HashMap<SchemaAlkaneId, Vec<usize>> = HashMap::new();
+    for (i, leg) in legs.iter().enumerate() {
+        by_token_in.entry(leg.token_in).or_default().push(i);
+    }
+    let search = Search { legs, by_token_in };
+    let mut taken = vec![false; legs.len()];
+    let mut out = Vec::new();
+    while *budget > 0 {
+        let mut starts: Vec<SchemaAlkaneId> =
+            legs.iter().zip(&taken).filter(|(_, t)| !**t).map(|(l, _)| l.token_in).collect();
+        starts.sort();
+        starts.dedup();
+
+        let mut best: Option<Cycle> = None;
+        for start in starts {
+            let mut trial = taken.clone();
+            let mut path = Vec::new();
+            if !search.close_cycle(budget, &mut trial, start, start, &mut path) {
+                continue;
+            }
+            let amount_in = legs[path[0]].amount_in;
+            let amount_out = legs[path[path.len() - 1]].amount_out;
+            let profit_usd =
+                value_usd(start, amount_out) as i128 - value_usd(start, amount_in) as i128;
+            if best.as_ref().is_none_or(|b| profit_usd > b.profit_usd) {
+                best = Some(Cycle { token: start, amount_in, amount_out, profit_usd, legs: path });
+            }
+        }
+        let Some(cycle) = best else { break };
+        for &i in &cycle.legs {
+            taken[i] = true;
+        }
+        out.push(cycle);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(tx: u64) -> SchemaAlkaneId {
+        SchemaAlkaneId { block: 2, tx }
+    }
+
+    fn leg(pool: u64, token_in: u64, amount_in: u128, token_out: u64, amount_out: u128) -> SwapLeg {
+        SwapLeg {
+            pool: id(pool),
+            token_in: id(token_in),
+            amount_in,
+            token_out: id(token_out),
+            amount_out,
+        }
+    }
+
+    #[test]
+    fn finds_triangle_in_any_order_and_reports_it_in_the_profitable_token() {
+        // 1 -> 2 -> 3 -> 1 seen out of order, plus a swap that is not part of the cycle.
+        let legs = vec![
+            leg(101, 3, 500, 1, 1_100),
+            leg(100, 1, 1_000, 2, 2_000),
+            leg(104, 4, 10, 1, 20),
+            leg(102, 2, 2_000, 3, 500),
+        ];
+        // Token 1 is worth 1 USD per unit; everything else is unpriced.
+        let mut budget = CYCLE_SEARCH_BUDGET;
+        let cycles = find_cycles(&legs, |t, a| if t == id(1) { a } else { 0 }, &mut budget);
+        assert_eq!(cycles.len(), 1);
+        let c = &cycles[0];
+        assert_eq!(c.token, id(1));
+        assert_eq!((c.amount_in, c.amount_out, c.profit_usd), (1_000, 1_100, 100));
+        assert_eq!(c.legs, vec![1, 3, 0]);
+    }
+
+    #[test]
+    fn one_way_swaps_are_not_cycles() {
+        let legs = vec![leg(100, 1, 1_000, 2, 2_000), leg(102, 2, 2_000, 3, 500)];
+        let mut budget = CYCLE_SEARCH_BUDGET;
+        assert!(find_cycles(&legs, |_, a| a, &mut budget).is_empty());
+    }
+
+    #[test]
+    fn the_search_stops_when_the_budget_runs_out() {
+        // Many parallel 1 <-> 2 swaps: every pair is a cycle, and there are far more paths
+        // than the budget allows.
+        let legs: Vec<SwapLeg> = (0..200)
+            .map(|i| if i % 2 == 0 { leg(100, 1, 10, 2, 10) } else { leg(101, 2, 10, 1, 10) })
+            .collect();
+        let mut budget = 50;
+        let cycles = find_cycles(&legs, |_, a| a, &mut budget);
+        assert_eq!(budget, 0);
+        assert!(!cycles.is_empty() && cycles.len() < 100);
+    }
+}
@@ -1,11 +1,10 @@
 use crate::modules::ammdata::consts::{AMOUNT_SCALE, PRICE_SCALE};
 use crate::modules::ammdata::schemas::{SchemaCandleV1, SchemaFullCandleV1, Timeframe};
 use crate::runtime::state_at::StateAt;
+use crate::runtime::tree_db::prefix_end_exclusive;
 use crate::schemas::SchemaAlkaneId;
 
-use crate::modules::ammdata::storage::{
-    AmmDataProvider, GetListEntriesDescParams, GetRawValueParams,
-};
+use crate::modules::ammdata::storage::{AmmDataProvider, GetRangeEntriesParams, GetRawValueParams};
 use crate::modules::ammdata::storage::{decode_full_candle_v1, encode_full_candle_v1};
 use anyhow::Result;
 use std::collections::BTreeMap;
@@ -31,6 +30,18 @@ pub fn price_base_per_quote(base_res: u128, quote_res: u128) -> u128 {
 
 /* ---------- time bucketing ---------- */
 
+/// Frames materialized for pool candles. `Min1` is written per trade alongside `M10`;
+/// the higher frames are canonicalized from `M10` in `derive_token_data`.
+pub const POOL_CANDLE_FRAMES: [Timeframe; 7] = [
+    Timeframe::Min1,
+    Timeframe::M10,
+    Timeframe::H1,
+    Timeframe::H4,
+    Timeframe::D1,
+    Timeframe::W1,
+    Timeframe::M1,
+];
+
 #[inline]
 fn bucket_start(ts: u64, frame: Timeframe) -> u64 {
     let d = frame.duration_secs();
@@ -93,11 +104,33 @@ impl DualCandle {
 
 pub struct CandleCache {
     map: BTreeMap<CandleKey, DualCandle>,
+    by_height: BTreeMap<(SchemaAlkaneId, u32), DualCandle>,
 }
 
 impl CandleCache {
     pub fn new() -> Self {
-        Self { map: BTreeMap::new() }
+        Self { map: BTreeMap::new(), by_height: BTreeMap::new() }
+    }
+
+    /// Apply one trade to the pool's per-block candle. Block candles are bucketed by height,
+    /// so they are immune to miner timestamp skew.
+    pub fn apply_trade_for_height(
+        &mut self,
+        height: u32,
+        pool: SchemaAlkaneId,
+        p_b_per_q: u128,
+        p_q_per_b: u128,
+        base_volume: u128,
+        quote_volume: u128,
+    ) {
+        self.by_height
+            .entry((pool, height))
+            .and_modify(|dc| dc.update(p_q_per_b, p_b_per_q, base_volume, quote_volume))
+            .or_insert_with(|| {
+                let mut dc = DualCandle::new(p_q_per_b, p_b_per_q);
+                dc.update(p_q_per_b, p_b_per_q, base_volume, quote_volume);
+                dc
+            });
     }
 
     /// Apply one trade to all specified frames.
@@ -143,10 +176,16 @@ impl CandleCache {
         provider: &AmmDataProvider,
     ) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, Vec<(SchemaAlkaneId, Timeframe, u64, SchemaFullCandleV1)>)>
     {
-        let mut writes = Vec::with_capacity(self.map.len());
+        let mut writes = Vec::with_capacity(self.map.len() + self.by_height.len());
         let mut entries = Vec::with_capacity(self.map.len());
         let table = provider.table();
 
+        // A block is indexed in one pass, so its candle replaces any previous write.
+        for ((pool, height), dc) in self.by_height.into_iter() {
+            let full = SchemaFullCandleV1 { base_candle: dc.base, quote_candle: dc.quote };
+            writes.push((table.height_candle_key(&pool, height), encode_full_candle_v1(&full)?));
+        }
+
         for (ck, dc_new) in self.map.into_iter() {
             let k = table.candle_key(&ck.pool, ck.tf, ck.bucket_ts);
 
@@ -203,105 +242,317 @@ pub struct CandleSlice {
     pub candles_newest_first: Vec<SchemaCandleV1>,
     pub newest_ts: u64, // bucket start of the newest candle that actually exists
 }
+
+/// Aggregates a contiguous slice of `src_dur` candles into `dst_dur` buckets, where
+/// `dst_dur` is a multiple of `src_dur`. The oldest output bucket may be partial.
+pub fn resample_slice(slice: CandleSlice, src_dur: u64, dst_dur: u64) -> CandleSlice {
+    if slice.candles_newest_first.is_empty() || src_dur == 0 || dst_dur <= src_dur {
+        return slice;
+    }
+    let mut buckets: BTreeMap<u64, SchemaCandleV1> = BTreeMap::new();
+    for (i, c) in slice.candles_newest_first.iter().enumerate().rev() {
+        let ts = slice.newest_ts.saturating_sub(i as u64 * src_dur);
+        buckets
+            .entry(ts / dst_dur * dst_dur)
+            .and_modify(|acc| {
+                acc.high = acc.high.max(c.high);
+                acc.low = acc.low.min(c.low);
+                acc.close = c.close;
+                acc.volume = acc.volume.saturating_add(c.volume);
+            })
+            .or_insert(*c);
+    }
+    CandleSlice {
+        candles_newest_first: buckets.into_values().rev().collect(),
+        newest_ts: slice.newest_ts / dst_dur * dst_dur,
+    }
+}
+
+/// Serves `tf` from the `stored` frames: reads it directly when stored, otherwise reads
+/// the coarsest stored frame dividing it and resamples. Unservable frames yield no candles.
+pub fn read_resampled(
+    tf: Timeframe,
+    stored: &[Timeframe],
+    read: impl FnOnce(Timeframe) -> Result<CandleSlice>,
+) -> Result<CandleSlice> {
+    let Some(src) = tf.resample_source(stored) else {
+        return Ok(CandleSlice { candles_newest_first: vec![], newest_ts: 0 });
+    };
+    let slice = read(src)?;
+    if src == tf {
+        return Ok(slice);
+    }
+    Ok(resample_slice(slice, src.duration_secs(), tf.duration_secs()))
+}
+
+/// The newest `window` candles of `tf` for a pool, gap-filled up to `now_ts`.
 pub fn read_candles_v1(
     provider: &AmmDataProvider,
     pool: SchemaAlkaneId,
     tf: Timeframe,
-    _limit_unused: usize,
+    window: usize,
     now_ts: u64,
     side: PriceSide,
 ) -> Result<CandleSlice> {
-    let dur = tf.duration_secs();
+    let mut slice = read_resampled(tf, &POOL_CANDLE_FRAMES, |src| {
+        // Enough source candles to fill `window` whole `tf` buckets.
+        let ratio = (tf.duration_secs() / src.duration_secs()).max(1) as usize;
+        read_stored_candles_v1(provider, pool, src, window.saturating_mul(ratio), now_ts, side)
+    })?;
+    slice.candles_newest_first.truncate(window);
+    Ok(slice)
+}
 
-    let table = provider.table();
-    let logical = table.candle_ns_prefix(&pool, tf);
-    let mut per_bucket: BTreeMap<u64, SchemaFullCandleV1> = BTreeMap::new();
-    for (k, v) in provider
-        .get_list_entries_desc(GetListEntriesDescParams {
+/// Number of `tf` candles `read_candles_v1` would return without a window: one per bucket
+/// from the pool's first stored candle up to `now_ts`.
+pub fn pool_candle_total(
+    provider: &AmmDataProvider,
+    pool: SchemaAlkaneId,
+    tf: Timeframe,
+    now_ts: u64,
+) -> Result<usize> {
+    let Some(src) = tf.resample_source(&POOL_CANDLE_FRAMES) else { return Ok(0) };
+    let prefix = provider.table().candle_ns_prefix(&pool, src);
+    let first = provider
+        .get_range_entries(GetRangeEntriesParams {
             blockhash: StateAt::Latest,
-            prefix: logical,
+            start: prefix.clone(),
+            end: prefix_end_exclusive(&prefix),
+            limit: 1,
+            reverse: false,
         })?
         .entries
-    {
-        if let Some(ts_bytes) = k.rsplit(|&b| b == b':').next() {
-            if let Ok(ts_str) = std::str::from_utf8(ts_bytes) {
-                if let Ok(ts) = ts_str.parse::<u64>() {
-                    let fc = decode_full_candle_v1(&v)?;
-                    if !per_bucket.contains_key(&ts) {
-                        per_bucket.insert(ts, fc);
-                    }
-                }
-            }
-        }
-    }
+        .into_iter()
+        .find_map(|(k, _)| candle_key_ts(&k));
+    let Some(first) = first else { return Ok(0) };
+    let dur = tf.duration_secs();
+    let newest = (now_ts / dur * dur).max(first / dur * dur);
+    Ok(((newest - first / dur * dur) / dur) as usize + 1)
+}
 
-    if per_bucket.is_empty() {
-        return Ok(CandleSlice { candles_newest_first: vec![], newest_ts: 0 });
-    }
+#[inline]
+fn candle_key_ts(key: &[u8]) -> Option<u64> {
+    key.rsplit(|&b| b == b':')
+        .next()
+        .and_then(|raw| std::str::from_utf8(raw).ok())
+        .and_then(|raw| raw.parse::<u64>().ok())
+}
 
-    let start_bucket = *per_bucket.keys().next().unwrap();
-    let newest_bucket_with_data = *per_bucket.keys().last().unwrap();
+/// Reads the newest `window` buckets of a stored frame, plus the last candle before them to
+/// seed the gap fill. Bucket timestamps are unpadded decimals (see `candle_key`); they sort
+/// by time because every bucket from 2001 to 2286 has ten digits.
+fn read_stored_candles_v1(
+    provider: &AmmDataProvider,
+    pool: SchemaAlkaneId,
+    tf: Timeframe,
+    window: usize,
+    now_ts: u64,
+    side: PriceSide,
+) -> Result<CandleSlice> {
+    let window = window.max(1);
+    let dur = tf.duration_secs();
     let newest_bucket_now = (now_ts / dur) * dur;
+    let oldest_bucket = newest_bucket_now.saturating_sub((window as u64 - 1).saturating_mul(dur));
 
-    let mut last_close: u128 = 0;
-    let mut have_prev: bool = false;
-    let mut forward: BTreeMap<u64, SchemaCandleV1> = BTreeMap::new();
-    let mut bts = start_bucket;
+    let table = provider.table();
+    let prefix = table.candle_ns_prefix(&pool, tf);
+    let pick = |fc: SchemaFullCandleV1| match side {
+        PriceSide::Base => fc.base_candle,
+        PriceSide::Quote => fc.quote_candle,
+    };
+    let read = |start: Vec<u8>, end: Option<Vec<u8>>, limit: usize, reverse: bool| {
+        provider
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: StateAt::Latest,
+                start,
+                end,
+                limit,
+                reverse,
+            })
+            .map(|res| res.entries)
+    };
+
+    let mut per_bucket: BTreeMap<u64, SchemaCandleV1> = BTreeMap::new();
+    let window_start = table.candle_key(&pool, tf, oldest_bucket);
+    let window_end = table.candle_key(&pool, tf, newest_bucket_now.saturating_add(1));
+    for (k, v) in read(window_start.clone(), Some(window_end), window, false)? {
+        let Some(ts) = candle_key_ts(&k) else { continue };
+        per_bucket.entry(ts).or_insert(pick(decode_full_candle_v1(&v)?));
+    }
+    let mut seed: Option<u128> = None;
+    for (k, v) in read(prefix, Some(window_start), 1, true)? {
+        if candle_key_ts(&k).is_some() {
+            seed = Some(pick(decode_full_candle_v1(&v)?).close);
+        }
+    }
 
-    while bts <= newest_bucket_with_data {
-        if let Some(fc) = per_bucket.get(&bts) {
-            let mut c = match side {
-                PriceSide::Base => fc.base_candle,
-                PriceSide::Quote => fc.quote_candle,
-            };
-            if have_prev {
-                c.open = last_close;
-                if c.open > c.high {
-                    c.high = c.open;
-                }
-                if c.open < c.low {
-                    c.low = c.open;
+    let start_bucket = match (seed, per_bucket.keys().next()) {
+        (Some(_), _) => oldest_bucket,
+        (None, Some(&first)) => first,
+        (None, None) => return Ok(CandleSlice { candles_newest_first: vec![], newest_ts: 0 }),
+    };
+
+    let mut last_close: u128 = seed.unwrap_or(0);
+    let mut have_prev: bool = seed.is_some();
+    let mut newest_first: Vec<SchemaCandleV1> = Vec::new();
+    let mut bts = start_bucket;
+    while bts <= newest_bucket_now {
+        let c = match per_bucket.get(&bts) {
+            Some(stored) => {
+                let mut c = *stored;
+                if have_prev {
+                    c.open = last_close;
+                    c.high = c.high.max(c.open);
+                    c.low = c.low.min(c.open);
                 }
+                c
             }
-            last_close = c.close;
-            have_prev = true;
-            forward.insert(bts, c);
-        } else {
-            let c = SchemaCandleV1 {
+            None => SchemaCandleV1 {
                 open: last_close,
                 high: last_close,
                 low: last_close,
                 close: last_close,
                 volume: 0,
-            };
-            have_prev = true;
-            forward.insert(bts, c);
-        }
+            },
+        };
+        last_close = c.close;
+        have_prev = true;
+        newest_first.push(c);
         bts = match bts.checked_add(dur) {
             Some(n) => n,
             None => break,
         };
     }
+    newest_first.reverse();
 
-    if newest_bucket_now > newest_bucket_with_data {
-        let mut t = newest_bucket_with_data.saturating_add(dur);
-        while t <= newest_bucket_now {
-            let c = SchemaCandleV1 {
-                open: last_close,
-                high: last_close,
-                low: last_close,
-                close: last_close,
-                volume: 0,
-            };
-            forward.insert(t, c);
-            t = match t.checked_add(dur) {
-                Some(n) => n,
-                None => break,
-            };
+    Ok(CandleSlice { candles_newest_first: newest_first, newest_ts: newest_bucket_now })
+}
+
+/// Block-height candles, newest first. `newest_height` is the start height of the first one;
+/// `total` counts every candle from the pool's first block candle up to the tip.
+pub struct HeightCandleSlice {
+    pub candles_newest_first: Vec<SchemaCandleV1>,
+    pub newest_height: u32,
+    pub total: usize,
+}
+
+/// The newest `window` candles covering `blocks` blocks each, built from the per-block
+/// candles and gap-filled with the previous close up to `tip_height`. Only the blocks in the
+/// window and the last candle before it are read.
+pub fn read_height_candles_v1(
+    provider: &AmmDataProvider,
+    pool: SchemaAlkaneId,
+    blocks: u32,
+    window: usize,
+    tip_height: u32,
+    side: PriceSide,
+) -> Result<HeightCandleSlice> {
+    let blocks = blocks.max(1);
+    let table = provider.table();
+    let prefix = table.height_candle_ns_prefix(&pool);
+    let pick = |fc: SchemaFullCandleV1| match side {
+        PriceSide::Base => fc.base_candle,
+        PriceSide::Quote => fc.quote_candle,
+    };
+    let read = |start: Vec<u8>, end: Option<Vec<u8>>, limit: usize, reverse: bool| {
+        provider
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: StateAt::Latest,
+                start,
+                end,
+                limit,
+                reverse,
+            })
+            .map(|res| res.entries)
+    };
+    let height_of = |k: &[u8]| -> Option<u32> {
+        k.strip_prefix(prefix.as_slice())
+            .and_then(|raw| std::str::from_utf8(raw).ok())
+            .and_then(|raw| raw.parse::<u32>().ok())
+    };
+
+    let Some(first) = read(prefix.clone(), prefix_end_exclusive(&prefix), 1, false)?
+        .first()
+        .and_then(|(k, _)| height_of(k))
+    else {
+        return Ok(HeightCandleSlice { candles_newest_first: vec![], newest_height: 0, total: 0 });
+    };
+    let first_start = first / blocks * blocks;
+    let newest_start = tip_height.max(first) / blocks * blocks;
+    let total = ((newest_start - first_start) / blocks) as usize + 1;
+    let window = window.clamp(1, total);
+    let oldest_start = newest_start - (window as u32 - 1) * blocks;
+
+    let window_start = table.height_candle_key(&pool, oldest_start);
+    let window_end = newest_start
+        .checked_add(blocks)
+        .map(|end| table.height_candle_key(&pool, end))
+        .or_else(|| prefix_end_exclusive(&prefix));
+    let mut per_block: BTreeMap<u32, SchemaCandleV1> = BTreeMap::new();
+    for (k, v) in read(window_start.clone(), window_end, usize::MAX, false)? {
+        let Some(height) = height_of(&k) else { continue };
+        per_block.entry(height).or_insert(pick(decode_full_candle_v1(&v)?));
+    }
+    let mut last_close: Option<u128> = None;
+    for (_, v) in read(prefix.clone(), Some(window_start), 1, true)? {
+        last_close = Some(pick(decode_full_candle_v1(&v)?).close);
+    }
+
+    let mut out: Vec<SchemaCandleV1> = Vec::with_capacity(window);
+    let mut start = oldest_start;
+    while start <= newest_start {
+        let end = start.saturating_add(blocks);
+        let prev = last_close.unwrap_or_else(|| {
+            per_block.range(start..end).next().map(|(_, c)| c.open).unwrap_or(0)
+        });
+        let mut acc = SchemaCandleV1 { open: prev, high: prev, low: prev, close: prev, volume: 0 };
+        for (_, c) in per_block.range(start..end) {
+            acc.high = acc.high.max(c.high);
+            acc.low = acc.low.min(c.low);
+            acc.close = c.close;
+            acc.volume = acc.volume.saturating_add(c.volume);
         }
+        last_close = Some(acc.close);
+        out.push(acc);
+        start = match start.checked_add(blocks) {
+            Some(n) => n,
+            None => break,
+        };
     }
+    out.reverse();
+    Ok(HeightCandleSlice { candles_newest_first: out, newest_height: newest_start, total })
+}
 
-    let newest_first: Vec<SchemaCandleV1> = forward.into_iter().rev().map(|(_ts, c)| c).collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(CandleSlice { candles_newest_first: newest_first, newest_ts: newest_bucket_now })
+    fn candle(open: u128, high: u128, low: u128, close: u128, volume: u128) -> SchemaCandleV1 {
+        SchemaCandleV1 { open, high, low, close, volume }
+    }
+
+    #[test]
+    fn resamples_contiguous_slices_into_aligned_buckets() {
+        // 10m candles at 00:20, 00:30 .. 01:10, newest first; resampled to 30m.
+        let slice = CandleSlice {
+            candles_newest_first: vec![
+                candle(14, 15, 13, 15, 6),
+                candle(12, 14, 11, 14, 5),
+                candle(11, 12, 10, 12, 4),
+                candle(10, 11, 9, 11, 3),
+                candle(9, 10, 8, 10, 2),
+                candle(8, 9, 7, 9, 1),
+            ],
+            newest_ts: 70 * 60,
+        };
+        let out = resample_slice(slice, 600, 1_800);
+        assert_eq!(out.newest_ts, 60 * 60);
+        assert_eq!(
+            out.candles_newest_first,
+            vec![candle(12, 15, 11, 15, 11), candle(9, 12, 8, 12, 9), candle(8, 9, 7, 9, 1)]
+        );
+        assert_eq!(Timeframe::Min30.resample_source(&POOL_CANDLE_FRAMES), Some(Timeframe::M10));
+        assert_eq!(Timeframe::Min15.resample_source(&POOL_CANDLE_FRAMES), Some(Timeframe::Min1));
+        assert_eq!(Timeframe::D3.resample_source(&POOL_CANDLE_FRAMES), Some(Timeframe::D1));
+    }
 }
@@ -1,16 +1,49 @@
+use crate::modules::ammdata::adapters::{PoolAdapter, default_pool_adapter, pool_adapter_by_name};
 use crate::modules::ammdata::consts::CanonicalQuoteUnit;
-use crate::modules::ammdata::schemas::{ActivityDirection, ActivityKind, Timeframe};
-use crate::modules::ammdata::storage::AmmDataProvider;
-use crate::modules::ammdata::utils::candles::{
-    bucket_start_for, price_base_per_quote, price_quote_per_base,
+use crate::modules::ammdata::schemas::{ActivityKind, Timeframe, active_timeframes};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetPoolLpSupplyLatestParams, GetPoolProtocolParams, GetRangeEntriesParams,
+    GetRawValueParams,
 };
+use crate::modules::ammdata::utils::activity::decode_activity_v1;
+use crate::modules::ammdata::utils::candles::bucket_start_for;
+use crate::modules::ammdata::utils::index_pnl::SwapEvent;
+use crate::modules::ammdata::utils::index_pools::PoolDiscoveryResult;
+use crate::modules::ammdata::utils::index_positions::LpEvent;
 use crate::modules::ammdata::utils::index_state::IndexState;
-use crate::modules::essentials::storage::EssentialsProvider;
+use crate::modules::ammdata::utils::lp_positions::sqrt_k_per_lp;
+use crate::modules::ammdata::utils::pool_fees::swap_fee;
+use crate::modules::essentials::storage::{EssentialsProvider, load_tx_packed_outflow_v2};
+use crate::runtime::state_at::StateAt;
+use crate::runtime::tree_db::{next_key, prefix_end_exclusive};
 use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
 use bitcoin::Txid;
 use bitcoin::hashes::Hash;
 use std::collections::HashMap;
 
+/// Activity rows walked per block by `backfill_pool_swaps_by_height`.
+const POOL_SWAPS_BACKFILL_CHUNK: usize = 2_000;
+
+/// Adapter that indexed `pool`; pools without a recorded protocol predate adapters and
+/// use the default.
+fn resolve_pool_adapter(
+    provider: &AmmDataProvider,
+    cache: &mut HashMap<SchemaAlkaneId, &'static dyn PoolAdapter>,
+    pool: SchemaAlkaneId,
+) -> Result<&'static dyn PoolAdapter> {
+    if let Some(adapter) = cache.get(&pool) {
+        return Ok(*adapter);
+    }
+    let adapter = provider
+        .get_pool_protocol(GetPoolProtocolParams { blockhash: StateAt::Latest, pool })?
+        .protocol
+        .map(|name| pool_adapter_by_name(&name))
+        .unwrap_or_else(default_pool_adapter);
+    cache.insert(pool, adapter);
+    Ok(adapter)
+}
+
 pub fn process_balance_deltas(
     block_ts: u64,
     height: u32,
@@ -18,9 +51,9 @@ pub fn process_balance_deltas(
     essentials: &EssentialsProvider,
     canonical_quote_units: &HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
     frames: &[Timeframe],
-    tx_meta: &HashMap<Txid, (Vec<u8>, bool)>,
+    discovery: &PoolDiscoveryResult,
     state: &mut IndexState,
-) {
+) -> Result<()> {
     let table = provider.table();
     let balance_txs = match crate::modules::ammdata::load_balance_txs_by_height(essentials, height)
     {
@@ -31,8 +64,19 @@ pub fn process_balance_deltas(
         }
     };
 
+    // Token charts are derived from the frames they store; finer pool-only frames are skipped.
+    let token_frames: Vec<Timeframe> =
+        frames.iter().copied().filter(|tf| active_timeframes().contains(tf)).collect();
+
+    // Running LP supply per pool within this block, seeded from the last stored supply.
+    let mut block_lp_supply: HashMap<SchemaAlkaneId, u128> = HashMap::new();
+
     // Apply balance deltas per pool and emit activity + candles.
     for (owner, entries) in balance_txs {
+        if !state.pools_map.contains_key(&owner) {
+            continue;
+        }
+        let adapter = resolve_pool_adapter(provider, &mut state.pool_adapters, owner)?;
         let Some(defs) = state.pools_map.get(&owner) else { continue };
         let Some(snapshot) = state.reserves_snapshot.get_mut(&owner) else { continue };
 
@@ -46,25 +90,25 @@ pub fn process_balance_deltas(
                 continue;
             }
 
+            let txid = Txid::from_byte_array(entry.txid);
+            if let Some((base, quote)) = discovery.reported_reserves.get(&(txid, owner)) {
+                snapshot.base_reserve = *base;
+                snapshot.quote_reserve = *quote;
+            }
             let prev_base = snapshot.base_reserve;
             let prev_quote = snapshot.quote_reserve;
-            let new_base = crate::modules::ammdata::apply_delta_u128(prev_base, base_delta);
-            let new_quote = crate::modules::ammdata::apply_delta_u128(prev_quote, quote_delta);
+            let (new_base, new_quote) =
+                adapter.apply_delta((prev_base, prev_quote), base_delta, quote_delta);
             snapshot.base_reserve = new_base;
             snapshot.quote_reserve = new_quote;
 
-            let (kind, direction) = match (base_delta.signum(), quote_delta.signum()) {
-                (1, -1) => (ActivityKind::TradeSell, Some(ActivityDirection::BaseIn)),
-                (-1, 1) => (ActivityKind::TradeBuy, Some(ActivityDirection::QuoteIn)),
-                (1, 1) => (ActivityKind::LiquidityAdd, None),
-                (-1, -1) => (ActivityKind::LiquidityRemove, None),
-                _ => continue,
+            let Some((kind, direction)) = adapter.classify_delta(base_delta, quote_delta) else {
+                continue;
             };
             state.pools_touched.insert(owner);
 
-            let txid = Txid::from_byte_array(entry.txid);
             let (address_spk, success) =
-                tx_meta.get(&txid).cloned().unwrap_or_else(|| (Vec::new(), true));
+                discovery.tx_meta.get(&txid).cloned().unwrap_or_else(|| (Vec::new(), true));
             let address_spk = address_spk.clone();
 
             let activity = crate::modules::ammdata::schemas::SchemaActivityV1 {
@@ -81,6 +125,10 @@ pub fn process_balance_deltas(
             if let Ok(seq) = state.activity_acc.push(owner, block_ts, activity.clone()) {
                 state.index_acc.add(&owner, block_ts, seq, &activity);
                 if matches!(kind, ActivityKind::TradeBuy | ActivityKind::TradeSell) {
+                    state.pool_swaps_by_height_writes.push((
+                        table.pool_swaps_by_height_key(&owner, height, block_ts, seq),
+                        Vec::new(),
+                    ));
                     state.token_swaps_writes.push((
                         table.token_swaps_key(&defs.base_alkane_id, block_ts, seq, &owner),
                         Vec::new(),
@@ -144,15 +192,89 @@ pub fn process_balance_deltas(
                 }
             }
 
+            if matches!(kind, ActivityKind::LiquidityAdd | ActivityKind::LiquidityRemove) {
+                // LP amounts and holders come from the LP tokens the pool actually minted or
+                // burned; the supply is only tracked here to price the lot, and the stored
+                // supply is refreshed from the circulating supply in `derive_pool_metrics`.
+                let supply = match block_lp_supply.get(&owner) {
+                    Some(supply) => *supply,
+                    None => {
+                        provider
+                            .get_pool_lp_supply_latest(GetPoolLpSupplyLatestParams {
+                                blockhash: StateAt::Latest,
+                                pool: owner,
+                            })?
+                            .supply
+                    }
+                };
+                let movement = discovery.lp_movements.get(&(txid, owner));
+                let lp_tokens = movement
+                    .map(|m| {
+                        if kind == ActivityKind::LiquidityAdd {
+                            m.transfer.minted
+                        } else {
+                            m.transfer.burned
+                        }
+                    })
+                    .unwrap_or(0);
+                let supply = if kind == ActivityKind::LiquidityAdd {
+                    supply.saturating_add(lp_tokens)
+                } else {
+                    supply.saturating_sub(lp_tokens)
+                };
+                block_lp_supply.insert(owner, supply);
+                let holder_spk = movement.map(|m| m.holder_spk.clone()).unwrap_or_default();
+                if success && lp_tokens > 0 && !holder_spk.is_empty() {
+                    state.lp_events.push(LpEvent {
+                        pool: owner,
+                        address_spk: holder_spk,
+                        txid: entry.txid,
+                        kind,
+                        token0: crate::modules::ammdata::abs_i128(base_delta),
+                        token1: crate::modules::ammdata::abs_i128(quote_delta),
+                        lp_tokens,
+                        sqrt_k_per_lp: sqrt_k_per_lp(new_base, new_quote, supply),
+                    });
+                }
+            }
+
             if matches!(kind, ActivityKind::TradeBuy | ActivityKind::TradeSell) {
                 state.has_trades = true;
                 let base_abs = crate::modules::ammdata::abs_i128(base_delta);
                 let quote_abs = crate::modules::ammdata::abs_i128(quote_delta);
+                if success && !address_spk.is_empty() {
+                    // The trader pays whichever token the pool received.
+                    let ((token_in, amount_in), (token_out, amount_out)) = if base_delta > 0 {
+                        ((defs.base_alkane_id, base_abs), (defs.quote_alkane_id, quote_abs))
+                    } else {
+                        ((defs.quote_alkane_id, quote_abs), (defs.base_alkane_id, base_abs))
+                    };
+                    state.swap_events.push(SwapEvent {
+                        pool: owner,
+                        address_spk: address_spk.clone(),
+                        txid: entry.txid,
+                        token_in,
+                        amount_in,
+                        token_out,
+                        amount_out,
+                    });
+                }
+                if success {
+                    // The pool keeps its fee out of whichever token it received.
+                    let fee_bps = adapter.fee_bps();
+                    let fees = state.pool_fees.entry(owner).or_default();
+                    if base_delta > 0 {
+                        fees.base_fees = fees.base_fees.saturating_add(swap_fee(base_abs, fee_bps));
+                    } else {
+                        fees.quote_fees =
+                            fees.quote_fees.saturating_add(swap_fee(quote_abs, fee_bps));
+                    }
+                    fees.trades = fees.trades.saturating_add(1);
+                }
                 let entry = state.in_block_trade_volumes.entry(owner).or_insert((0, 0));
                 entry.0 = entry.0.saturating_add(base_abs);
                 entry.1 = entry.1.saturating_add(quote_abs);
-                let p_q_per_b = price_quote_per_base(new_base, new_quote);
-                let p_b_per_q = price_base_per_quote(new_base, new_quote);
+                let (p_b_per_q, p_q_per_b) = adapter.spot_prices(new_base, new_quote);
                 let base_volume = base_abs;
                 let quote_volume = quote_abs;
 
@@ -165,22 +287,103 @@ pub fn process_balance_deltas(
                     base_volume,
                     quote_volume,
                 );
+                state.candle_cache.apply_trade_for_height(
+                    height,
+                    owner,
+                    p_b_per_q,
+                    p_q_per_b,
+                    base_volume,
+                    quote_volume,
+                );
 
                 if canonical_quote_units.contains_key(&defs.quote_alkane_id) {
                     let entry =
                         state.canonical_trade_buckets.entry(defs.base_alkane_id).or_default();
-                    for tf in frames {
+                    for tf in &token_frames {
                         entry.insert((*tf, bucket_start_for(block_ts, *tf)));
                     }
                 }
                 if canonical_quote_units.contains_key(&defs.base_alkane_id) {
                     let entry =
                         state.canonical_trade_buckets.entry(defs.quote_alkane_id).or_default();
-                    for tf in frames {
+                    for tf in &token_frames {
                         entry.insert((*tf, bucket_start_for(block_ts, *tf)));
                     }
                 }
             }
         }
     }
+    Ok(())
+}
+
+/// Adds swaps indexed before the per-pool height index existed to it, a chunk of activity
+/// per block, resuming from the key stored under `POOL_SWAPS_BY_HEIGHT_BACKFILL`. Activity
+/// keys carry no height, so each swap's height comes from its essentials tx row. Returns
+/// the number of swaps added, or `None` once the backfill has finished.
+pub fn backfill_pool_swaps_by_height(
+    provider: &AmmDataProvider,
+    essentials: &EssentialsProvider,
+    state: &mut IndexState,
+) -> Result<Option<usize>> {
+    let table = provider.table();
+    let marker = table.POOL_SWAPS_BY_HEIGHT_BACKFILL.key().to_vec();
+    let activity_root = table.ACTIVITY.key().to_vec();
+    let start = match provider
+        .get_raw_value(GetRawValueParams { blockhash: StateAt::Latest, key: marker.clone() })?
+        .value
+    {
+        Some(resume) if resume.is_empty() => return Ok(None),
+        Some(resume) => resume,
+        None => activity_root.clone(),
+    };
+    let chunk = provider
+        .get_range_entries(GetRangeEntriesParams {
+            blockhash: StateAt::Latest,
+            start,
+            end: prefix_end_exclusive(&activity_root),
+            limit: POOL_SWAPS_BACKFILL_CHUNK,
+            reverse: false,
+        })?
+        .entries;
+
+    let mut added = 0usize;
+    for (key, raw) in &chunk {
+        let Some((pool, ts, seq)) = key
+            .get(activity_root.len()..)
+            .and_then(|tail| std::str::from_utf8(tail).ok())
+            .and_then(parse_activity_key_tail)
+        else {
+            continue;
+        };
+        let activity = decode_activity_v1(raw)?;
+        if !matches!(activity.kind, ActivityKind::TradeBuy | ActivityKind::TradeSell) {
+            continue;
+        }
+        let txid = Txid::from_byte_array(activity.txid);
+        let Some(row) = load_tx_packed_outflow_v2(essentials, &txid) else { continue };
+        state
+            .pool_swaps_by_height_writes
+            .push((table.pool_swaps_by_height_key(&pool, row.height, ts, seq), Vec::new()));
+        added += 1;
+    }
+
+    let resume = match chunk.last() {
+        Some((last, _)) if chunk.len() == POOL_SWAPS_BACKFILL_CHUNK => next_key(last),
+        _ => Vec::new(),
+    };
+    state.pool_swaps_by_height_writes.push((marker, resume));
+    Ok(Some(added))
+}
+
+/// `<block>:<tx>:<ts>:<seq>`, the part of an activity key after the namespace.
+fn parse_activity_key_tail(tail: &str) -> Option<(SchemaAlkaneId, u64, u32)> {
+    let mut parts = tail.split(':');
+    let block = parts.next()?.parse().ok()?;
+    let tx = parts.next()?.parse().ok()?;
+    let ts = parts.next()?.parse().ok()?;
+    let seq = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((SchemaAlkaneId { block, tx }, ts, seq))
 }
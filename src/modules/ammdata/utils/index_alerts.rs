@@ -0,0 +1,125 @@
+use crate::modules::ammdata::schemas::{SchemaAlertConditionV1, SchemaAlertEventV1};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetSubjectAlertsParams, GetTokenMetricsParams,
+    GetTvlVersionedAtOrBeforeParams, encode_alert_event,
+};
+use crate::modules::ammdata::utils::alerts::{AlertSubject, price_condition_holds, tvl_change_bps};
+use crate::modules::ammdata::utils::index_finalize::FinalizeResult;
+use crate::modules::ammdata::utils::index_state::IndexState;
+use crate::modules::ammdata::utils::pool_fees::usd_to_sats;
+use crate::runtime::state_at::StateAt;
+use anyhow::Result;
+use std::collections::{BTreeSet, HashMap};
+
+/// Checks the alerts of what the block touched: price alerts of the tokens whose metrics were
+/// written this block, TVL alerts of the pools whose TVL was recomputed, and swap alerts of
+/// the pools that were swapped in (plus those watching every pool) against each swap's value
+/// in sats. A price alert fires when its condition holds at the new price but not at the last
+/// committed one, so it fires once per crossing. Alerts are registered and removed by RPC;
+/// only the events are written here. Runs after `prepare_batch` and adds its writes to the
+/// batch; returns the events fired.
+pub fn evaluate_alerts(
+    height: u32,
+    block_ts: u64,
+    provider: &AmmDataProvider,
+    state: &IndexState,
+    finalize: &mut FinalizeResult,
+) -> Result<usize> {
+    let table = provider.table();
+    let subject_alerts = |subject: AlertSubject| {
+        provider
+            .get_subject_alerts(GetSubjectAlertsParams { subject })
+            .map(|res| res.alerts)
+    };
+    let event = |alert_id: u64, value: u128, txid: Option<[u8; 32]>| SchemaAlertEventV1 {
+        alert_id,
+        height,
+        timestamp: block_ts,
+        value,
+        txid,
+    };
+    let mut fired: Vec<(String, SchemaAlertEventV1)> = Vec::new();
+
+    // Tokens and pools in id order, so events get the same sequence numbers on every run.
+    let mut tokens: Vec<_> = state.token_metrics_cache.iter().collect();
+    tokens.sort_by_key(|(token, _)| **token);
+    for (token, metrics) in tokens {
+        let alerts = subject_alerts(AlertSubject::Price(*token))?;
+        if alerts.is_empty() {
+            continue;
+        }
+        // The block's metrics are not written yet, so this is the price before the block.
+        let prev = provider
+            .get_token_metrics(GetTokenMetricsParams { blockhash: StateAt::Latest, token: *token })?
+            .metrics
+            .price_usd;
+        for (id, alert) in alerts {
+            let holds = price_condition_holds(&alert.condition, metrics.price_usd);
+            if holds && !price_condition_holds(&alert.condition, prev) {
+                fired.push((alert.watcher.clone(), event(id, metrics.price_usd, None)));
+            }
+        }
+    }
+
+    let mut pools: Vec<_> = state.pool_tvl_usd.iter().collect();
+    pools.sort_by_key(|(pool, _)| **pool);
+    for (pool, now) in pools {
+        let alerts = subject_alerts(AlertSubject::Tvl(*pool))?;
+        if alerts.is_empty() {
+            continue;
+        }
+        let prev = provider
+            .get_tvl_versioned_at_or_before(GetTvlVersionedAtOrBeforeParams {
+                blockhash: StateAt::Latest,
+                pool: *pool,
+                height: height.saturating_sub(1),
+            })?
+            .value
+            .unwrap_or(0);
+        let Some(bps) = tvl_change_bps(prev, *now) else { continue };
+        for (id, alert) in alerts {
+            let SchemaAlertConditionV1::TvlChange { change_bps, .. } = alert.condition else {
+                continue;
+            };
+            if bps >= u128::from(change_bps) {
+                fired.push((alert.watcher.clone(), event(id, bps, None)));
+            }
+        }
+    }
+
+    if !state.swap_values.is_empty() {
+        let pools: BTreeSet<_> = state.swap_values.iter().map(|(pool, _, _)| *pool).collect();
+        let mut subjects: Vec<AlertSubject> =
+            pools.into_iter().map(|pool| AlertSubject::Swaps(Some(pool))).collect();
+        subjects.push(AlertSubject::Swaps(None));
+        for subject in subjects {
+            for (id, alert) in subject_alerts(subject)? {
+                let SchemaAlertConditionV1::LargeSwap { pool, min_sats } = alert.condition else {
+                    continue;
+                };
+                for (swap_pool, txid, value_usd) in &state.swap_values {
+                    if pool.is_some_and(|p| p != *swap_pool) {
+                        continue;
+                    }
+                    let sats = usd_to_sats(*value_usd, state.btc_usd_price);
+                    if sats >= min_sats {
+                        fired.push((alert.watcher.clone(), event(id, sats, Some(*txid))));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut seqs: HashMap<String, u32> = HashMap::new();
+    for (watcher, event) in &fired {
+        let seq = seqs.entry(watcher.clone()).or_insert(0);
+        finalize
+            .puts
+            .push((table.alert_event_key(watcher, height, *seq), encode_alert_event(event)?));
+        *seq += 1;
+    }
+    if !finalize.puts.is_empty() || !finalize.deletes.is_empty() {
+        finalize.should_write = true;
+    }
+    Ok(fired.len())
+}
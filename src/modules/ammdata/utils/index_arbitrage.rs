@@ -0,0 +1,170 @@
+use crate::modules::ammdata::consts::CanonicalQuoteUnit;
+use crate::modules::ammdata::schemas::{SchemaArbitrageTotalsV1, SchemaArbitrageV1};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetArbitrageActorTotalsParams, encode_arbitrage, encode_arbitrage_totals,
+};
+use crate::modules::ammdata::utils::arbitrage::{CYCLE_SEARCH_BUDGET, Cycle, SwapLeg, find_cycles};
+use crate::modules::ammdata::utils::index_pnl::SwapEvent;
+use crate::modules::ammdata::utils::index_positions::BlockPrices;
+use crate::modules::ammdata::utils::index_state::IndexState;
+use crate::modules::ammdata::utils::lp_positions::amount_value_usd;
+use crate::runtime::state_at::StateAt;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+
+/// Cycles among `group` (indices into `events`), with cycle legs mapped back to event
+/// indices.
+fn cycles_in(
+    events: &[SwapEvent],
+    group: &[usize],
+    value_usd: &impl Fn(SchemaAlkaneId, u128) -> u128,
+    budget: &mut usize,
+) -> Vec<(Vec<usize>, Cycle)> {
+    if group.len() < 2 {
+        return Vec::new();
+    }
+    let legs: Vec<SwapLeg> = group
+        .iter()
+        .map(|&i| SwapLeg {
+            pool: events[i].pool,
+            token_in: events[i].token_in,
+            amount_in: events[i].amount_in,
+            token_out: events[i].token_out,
+            amount_out: events[i].amount_out,
+        })
+        .collect();
+    find_cycles(&legs, value_usd, budget)
+        .into_iter()
+        .map(|cycle| (cycle.legs.iter().map(|&l| group[l]).collect(), cycle))
+        .collect()
+}
+
+fn add_to_totals(totals: &mut SchemaArbitrageTotalsV1, height: u32, profit_usd: i128) {
+    totals.count = totals.count.saturating_add(1);
+    totals.profit_usd = totals.profit_usd.saturating_add(profit_usd);
+    totals.last_height = height;
+}
+
+/// Records swap cycles in the block: first within each transaction, then across the
+/// remaining swaps of each address (multi-transaction arbitrage and sandwiches). The whole
+/// block shares one `CYCLE_SEARCH_BUDGET`. Runs before `apply_swap_events`, which consumes
+/// the swap events.
+pub fn detect_arbitrage(
+    height: u32,
+    block_ts: u64,
+    provider: &AmmDataProvider,
+    canonical_quote_units: &HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
+    state: &mut IndexState,
+) -> Result<()> {
+    if state.swap_events.len() < 2 {
+        return Ok(());
+    }
+    let table = provider.table();
+    let events = state.swap_events.clone();
+
+    let mut prices = BlockPrices::new(provider, canonical_quote_units);
+    let price_usd: HashMap<SchemaAlkaneId, u128> = events
+        .iter()
+        .flat_map(|ev| [ev.token_in, ev.token_out])
+        .map(|token| (token, prices.price_usd(token, state)))
+        .collect();
+    let value_usd = |token: SchemaAlkaneId, amount: u128| {
+        amount_value_usd(amount, price_usd.get(&token).copied().unwrap_or(0))
+    };
+
+    let mut by_tx: BTreeMap<[u8; 32], Vec<usize>> = BTreeMap::new();
+    for (i, ev) in events.iter().enumerate() {
+        by_tx.entry(ev.txid).or_default().push(i);
+    }
+    let mut found: Vec<(bool, Vec<usize>, Cycle)> = Vec::new();
+    let mut used = vec![false; events.len()];
+    let mut budget = CYCLE_SEARCH_BUDGET;
+    for group in by_tx.values() {
+        for (legs, cycle) in cycles_in(&events, group, &value_usd, &mut budget) {
+            legs.iter().for_each(|&i| used[i] = true);
+            found.push((true, legs, cycle));
+        }
+    }
+
+    let mut by_actor: BTreeMap<&[u8], Vec<usize>> = BTreeMap::new();
+    for (i, ev) in events.iter().enumerate() {
+        if !used[i] {
+            by_actor.entry(ev.address_spk.as_slice()).or_default().push(i);
+        }
+    }
+    for group in by_actor.values() {
+        for (legs, cycle) in cycles_in(&events, group, &value_usd, &mut budget) {
+            found.push((false, legs, cycle));
+        }
+    }
+    if found.is_empty() {
+        return Ok(());
+    }
+
+    let mut block_totals = SchemaArbitrageTotalsV1::default();
+    // (totals before the block, running totals) per actor.
+    let mut actor_totals: HashMap<
+        Vec<u8>,
+        (Option<SchemaArbitrageTotalsV1>, SchemaArbitrageTotalsV1),
+    > = HashMap::new();
+    for (seq, (single_tx, legs, cycle)) in found.into_iter().enumerate() {
+        let first = &events[legs[0]];
+        let mut path = vec![cycle.token];
+        path.extend(legs.iter().map(|&i| events[i].token_out));
+        let record = SchemaArbitrageV1 {
+            txid: first.txid,
+            timestamp: block_ts,
+            actor_spk: first.address_spk.clone(),
+            single_tx,
+            token: cycle.token,
+            amount_in: cycle.amount_in,
+            amount_out: cycle.amount_out,
+            profit_usd: cycle.profit_usd,
+            pools: legs.iter().map(|&i| events[i].pool).collect(),
+            path,
+        };
+
+        let seq = seq as u32;
+        let encoded = encode_arbitrage(&record)?;
+        state.arbitrage_writes.push((table.arbitrage_key(height, seq), encoded.clone()));
+        state
+            .arbitrage_writes
+            .push((table.actor_arbitrage_key(&record.actor_spk, height, seq), encoded));
+
+        add_to_totals(&mut block_totals, height, record.profit_usd);
+        let (_, totals) = match actor_totals.entry(record.actor_spk.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let prev = provider
+                    .get_arbitrage_actor_totals(GetArbitrageActorTotalsParams {
+                        blockhash: StateAt::Latest,
+                        actor_spk: record.actor_spk.clone(),
+                    })?
+                    .totals;
+                entry.insert((prev.clone(), prev.unwrap_or_default()))
+            }
+        };
+        add_to_totals(totals, height, record.profit_usd);
+    }
+
+    state
+        .arbitrage_writes
+        .push((table.arbitrage_block_totals_key(height), encode_arbitrage_totals(&block_totals)?));
+    for (spk, (prev, totals)) in actor_totals {
+        let encoded = encode_arbitrage_totals(&totals)?;
+        if let Some(prev) = prev {
+            state
+                .arbitrage_deletes
+                .push(table.arbitrage_actor_rank_key(prev.profit_usd, &spk));
+        }
+        state
+            .arbitrage_writes
+            .push((table.arbitrage_actor_totals_key(&spk), encoded.clone()));
+        state
+            .arbitrage_writes
+            .push((table.arbitrage_actor_rank_key(totals.profit_usd, &spk), encoded));
+    }
+    Ok(())
+}
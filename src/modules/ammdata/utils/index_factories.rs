@@ -1,4 +1,7 @@
 use crate::alkanes::trace::EspoBlock;
+use crate::config::get_network;
+use crate::modules::ammdata::adapters::pool_adapters;
+use crate::modules::ammdata::consts::configured_amm_factories;
 use crate::modules::ammdata::storage::{AmmDataProvider, GetAmmFactoriesParams};
 use crate::modules::essentials::storage::{
     EssentialsProvider, GetCreationIdsInBlockParams, GetCreationRecordParams,
@@ -34,6 +37,11 @@ pub fn prepare_factories(
         .map(|res| res.factories.into_iter().collect())
         .unwrap_or_default();
     let mut amm_factory_writes: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+    for factory in configured_amm_factories(get_network()) {
+        if amm_factories.insert(factory) {
+            amm_factory_writes.push((table.amm_factory_key(&factory), Vec::new()));
+        }
+    }
     let mut proxy_target_cache: HashMap<SchemaAlkaneId, Option<SchemaAlkaneId>> = HashMap::new();
     let created_alkanes = essentials
         .get_creation_ids_in_block(GetCreationIdsInBlockParams {
@@ -54,7 +62,7 @@ pub fn prepare_factories(
         }) {
             if let Some(rec) = resp.record {
                 if let Some(inspection) = rec.inspection.as_ref() {
-                    if crate::modules::ammdata::inspection_is_amm_factory(inspection) {
+                    if pool_adapters().iter().any(|a| a.is_factory(inspection)) {
                         is_factory = true;
                     }
                     if let Some(factory_id) = inspection.factory_alkane {
@@ -80,7 +88,7 @@ pub fn prepare_factories(
                 }) {
                     if let Some(rec) = resp.record {
                         if let Some(inspection) = rec.inspection.as_ref() {
-                            if crate::modules::ammdata::inspection_is_amm_factory(inspection) {
+                            if pool_adapters().iter().any(|a| a.is_factory(inspection)) {
                                 is_factory = true;
                             }
                         }
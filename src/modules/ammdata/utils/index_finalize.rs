@@ -27,6 +27,13 @@ pub struct FinalizeStats {
     pub amm_factories: usize,
     pub factory_pools: usize,
     pub pool_factory: usize,
+    pub pool_protocol: usize,
+    pub lp_positions: usize,
+    pub address_pnl: usize,
+    pub pool_state: usize,
+    pub arbitrage: usize,
+    pub pool_fees: usize,
+    pub trader_stats: usize,
     pub pool_creation_info: usize,
     pub pool_creations: usize,
     pub token_pools: usize,
@@ -36,6 +43,7 @@ pub struct FinalizeStats {
     pub pool_lp_supply: usize,
     pub pool_details_snapshot: usize,
     pub tvl_versioned: usize,
+    pub timeseries: usize,
     pub token_swaps: usize,
     pub address_pool_swaps: usize,
     pub address_token_swaps: usize,
@@ -200,6 +208,13 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
     let af_cnt = state.amm_factory_writes.len();
     let fp_cnt = state.factory_pools_writes.len();
     let pf_cnt = state.pool_factory_writes.len();
+    let pp_cnt = state.pool_protocol_writes.len();
+    let lpp_cnt = state.lp_position_writes.len();
+    let pnl_cnt = state.address_pnl_writes.len();
+    let pst_cnt = state.pool_state_writes.len();
+    let arb_cnt = state.arbitrage_writes.len();
+    let pfee_cnt = state.pool_fee_writes.len();
+    let trs_cnt = state.trader_stats_writes.len();
     let pc_cnt = state.pool_creation_info_writes.len();
     let pcg_cnt = state.pool_creations_writes.len();
     let aps_cnt = state.address_pool_swaps_writes.len();
@@ -216,6 +231,7 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
     let pls_cnt = state.pool_lp_supply_writes.len();
     let pds_cnt = state.pool_details_snapshot_writes.len();
     let tvl_cnt = state.tvl_versioned_writes.len();
+    let tsr_cnt = state.timeseries_writes.len();
     let ts_cnt = state.token_swaps_writes.len();
     let a_cnt = activity_writes.len();
     let i_cnt = index_writes.len();
@@ -245,6 +261,15 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
         || !state.amm_factory_writes.is_empty()
         || !state.factory_pools_writes.is_empty()
         || !state.pool_factory_writes.is_empty()
+        || !state.pool_protocol_writes.is_empty()
+        || !state.lp_position_writes.is_empty()
+        || !state.address_pnl_writes.is_empty()
+        || !state.pool_state_writes.is_empty()
+        || !state.arbitrage_writes.is_empty()
+        || !state.arbitrage_deletes.is_empty()
+        || !state.pool_fee_writes.is_empty()
+        || !state.trader_stats_writes.is_empty()
+        || !state.trader_stats_deletes.is_empty()
         || !state.pool_creation_info_writes.is_empty()
         || !state.pool_creations_writes.is_empty()
         || !state.pool_defs_writes.is_empty()
@@ -253,7 +278,9 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
         || !state.pool_lp_supply_writes.is_empty()
         || !state.pool_details_snapshot_writes.is_empty()
         || !state.tvl_versioned_writes.is_empty()
+        || !state.timeseries_writes.is_empty()
         || !state.token_swaps_writes.is_empty()
+        || !state.pool_swaps_by_height_writes.is_empty()
         || !state.address_pool_swaps_writes.is_empty()
         || !state.address_token_swaps_writes.is_empty()
         || !state.address_pool_creations_writes.is_empty()
@@ -285,6 +312,13 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
     puts.extend(std::mem::take(&mut state.amm_factory_writes));
     puts.extend(std::mem::take(&mut state.factory_pools_writes));
     puts.extend(std::mem::take(&mut state.pool_factory_writes));
+    puts.extend(std::mem::take(&mut state.pool_protocol_writes));
+    puts.extend(std::mem::take(&mut state.lp_position_writes));
+    puts.extend(std::mem::take(&mut state.address_pnl_writes));
+    puts.extend(std::mem::take(&mut state.pool_state_writes));
+    puts.extend(std::mem::take(&mut state.arbitrage_writes));
+    puts.extend(std::mem::take(&mut state.pool_fee_writes));
+    puts.extend(std::mem::take(&mut state.trader_stats_writes));
     puts.extend(std::mem::take(&mut state.pool_creation_info_writes));
     puts.extend(std::mem::take(&mut state.pool_creations_writes));
     puts.extend(std::mem::take(&mut state.pool_defs_writes));
@@ -294,7 +328,9 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
     puts.extend(std::mem::take(&mut state.pool_lp_supply_writes));
     puts.extend(std::mem::take(&mut state.pool_details_snapshot_writes));
     puts.extend(std::mem::take(&mut state.tvl_versioned_writes));
+    puts.extend(std::mem::take(&mut state.timeseries_writes));
     puts.extend(std::mem::take(&mut state.token_swaps_writes));
+    puts.extend(std::mem::take(&mut state.pool_swaps_by_height_writes));
     puts.extend(std::mem::take(&mut state.address_pool_swaps_writes));
     puts.extend(std::mem::take(&mut state.address_token_swaps_writes));
     puts.extend(std::mem::take(&mut state.address_pool_creations_writes));
@@ -316,6 +352,8 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
     deletes.extend(std::mem::take(&mut state.derived_metrics_index_deletes));
     deletes.extend(std::mem::take(&mut state.derived_search_index_deletes));
     deletes.extend(std::mem::take(&mut state.pool_metrics_index_deletes));
+    deletes.extend(std::mem::take(&mut state.arbitrage_deletes));
+    deletes.extend(std::mem::take(&mut state.trader_stats_deletes));
 
     let stats = FinalizeStats {
         candle_writes: c_cnt,
@@ -337,6 +375,13 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
         amm_factories: af_cnt,
         factory_pools: fp_cnt,
         pool_factory: pf_cnt,
+        pool_protocol: pp_cnt,
+        lp_positions: lpp_cnt,
+        address_pnl: pnl_cnt,
+        pool_state: pst_cnt,
+        arbitrage: arb_cnt,
+        pool_fees: pfee_cnt,
+        trader_stats: trs_cnt,
         pool_creation_info: pc_cnt,
         pool_creations: pcg_cnt,
         token_pools: tp_cnt,
@@ -346,6 +391,7 @@ pub fn prepare_batch(provider: &AmmDataProvider, state: &mut IndexState) -> Resu
         pool_lp_supply: pls_cnt,
         pool_details_snapshot: pds_cnt,
         tvl_versioned: tvl_cnt,
+        timeseries: tsr_cnt,
         token_swaps: ts_cnt,
         address_pool_swaps: aps_cnt,
         address_token_swaps: ats_cnt,
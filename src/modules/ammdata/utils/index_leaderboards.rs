@@ -0,0 +1,278 @@
+use crate::modules::ammdata::consts::CanonicalQuoteUnit;
+use crate::modules::ammdata::schemas::{ActivityKind, SchemaTraderStatsV1, Timeframe};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetRangeEntriesParams, GetRawValueParams, GetTraderStatsParams,
+    decode_trader_stats, encode_trader_stats,
+};
+use crate::modules::ammdata::utils::candles::bucket_start_for;
+use crate::modules::ammdata::utils::index_positions::BlockPrices;
+use crate::modules::ammdata::utils::index_state::IndexState;
+use crate::modules::ammdata::utils::leaderboards::{
+    LEADERBOARD_METRICS, LEADERBOARD_WINDOWS, LONGEST_WINDOW_DAYS, LeaderboardScope, add_stats,
+    sub_stats, window_start,
+};
+use crate::modules::ammdata::utils::lp_positions::amount_value_usd;
+use crate::runtime::state_at::StateAt;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+
+type StatsKey = (LeaderboardScope, SchemaAlkaneId, Vec<u8>);
+/// (window days, scope, id, spk); 0 days is all-time.
+type BoardKey = (u32, LeaderboardScope, SchemaAlkaneId, Vec<u8>);
+
+/// Where a board total is stored: all-time totals are the all-time stats rows.
+fn board_stats_key(provider: &AmmDataProvider, (days, scope, id, spk): &BoardKey) -> Vec<u8> {
+    let table = provider.table();
+    if *days == 0 {
+        table.trader_stats_all_key(*scope, id, spk)
+    } else {
+        table.trader_stats_window_key(*scope, id, *days, spk)
+    }
+}
+
+/// Board totals changed this block, as (stored, updated), so the stale rank keys of the
+/// stored totals can be dropped.
+struct Boards<'a> {
+    provider: &'a AmmDataProvider,
+    totals: BTreeMap<BoardKey, (SchemaTraderStatsV1, SchemaTraderStatsV1)>,
+}
+
+impl<'a> Boards<'a> {
+    fn new(provider: &'a AmmDataProvider) -> Self {
+        Self { provider, totals: BTreeMap::new() }
+    }
+
+    fn entry(&mut self, board: BoardKey) -> Result<&mut SchemaTraderStatsV1> {
+        let provider = self.provider;
+        match self.totals.entry(board) {
+            Entry::Occupied(e) => Ok(&mut e.into_mut().1),
+            Entry::Vacant(e) => {
+                let key = board_stats_key(provider, e.key());
+                let stored = provider
+                    .get_raw_value(GetRawValueParams { blockhash: StateAt::Latest, key })?
+                    .value
+                    .and_then(|raw| decode_trader_stats(&raw).ok())
+                    .unwrap_or_default();
+                Ok(&mut e.insert((stored.clone(), stored)).1)
+            }
+        }
+    }
+
+    /// Writes the changed totals and moves their rank keys. Addresses scoring 0 or less on a
+    /// metric are left off that board.
+    fn flush(self, state: &mut IndexState) -> Result<()> {
+        let table = self.provider.table();
+        for (board, (stored, totals)) in &self.totals {
+            if stored == totals {
+                continue;
+            }
+            let (days, scope, id, spk) = board;
+            for metric in LEADERBOARD_METRICS {
+                let score = metric.score(stored);
+                if score > 0 {
+                    state
+                        .trader_stats_deletes
+                        .push(table.trader_rank_key(*scope, id, *days, metric, score, spk));
+                }
+            }
+            let key = board_stats_key(self.provider, board);
+            if *days != 0 && *totals == SchemaTraderStatsV1::default() {
+                state.trader_stats_deletes.push(key);
+                continue;
+            }
+            let encoded = encode_trader_stats(totals)?;
+            for metric in LEADERBOARD_METRICS {
+                let score = metric.score(totals);
+                if score > 0 {
+                    state.trader_stats_writes.push((
+                        table.trader_rank_key(*scope, id, *days, metric, score, spk),
+                        encoded.clone(),
+                    ));
+                }
+            }
+            state.trader_stats_writes.push((key, encoded));
+        }
+        Ok(())
+    }
+}
+
+/// Splits `<scope><id><spk>` off a trader stats key.
+fn parse_subject(raw: &[u8]) -> Option<(LeaderboardScope, SchemaAlkaneId, Vec<u8>)> {
+    let (&tag, rest) = raw.split_first()?;
+    let scope = LeaderboardScope::from_tag(tag)?;
+    if rest.len() < 12 {
+        return None;
+    }
+    let block = u32::from_be_bytes(rest[..4].try_into().ok()?);
+    let tx = u64::from_be_bytes(rest[4..12].try_into().ok()?);
+    Some((scope, SchemaAlkaneId { block, tx }, rest[12..].to_vec()))
+}
+
+fn range_entries(
+    provider: &AmmDataProvider,
+    start: Vec<u8>,
+    end: Option<Vec<u8>>,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    Ok(provider
+        .get_range_entries(GetRangeEntriesParams {
+            blockhash: StateAt::Latest,
+            start,
+            end,
+            limit: usize::MAX,
+            reverse: false,
+        })?
+        .entries)
+}
+
+/// Takes the days that fall out of each window when the tip moves from `prev_day` to
+/// `tip_day` back out of the window totals, and drops their expiry markers once they leave
+/// the longest window.
+fn expire_days(
+    provider: &AmmDataProvider,
+    prev_day: u64,
+    tip_day: u64,
+    boards: &mut Boards<'_>,
+    state: &mut IndexState,
+) -> Result<()> {
+    let table = provider.table();
+    let marker_prefix = table.TRADER_STATS_BY_DAY.key().to_vec();
+    for (_, days) in LEADERBOARD_WINDOWS {
+        if days == 0 {
+            continue;
+        }
+        let start = table.trader_stats_by_day_prefix(window_start(prev_day, days));
+        let end = table.trader_stats_by_day_prefix(window_start(tip_day, days));
+        for (key, _) in range_entries(provider, start, Some(end))? {
+            let Some(rest) = key.strip_prefix(marker_prefix.as_slice()) else { continue };
+            if rest.len() < 8 {
+                continue;
+            }
+            let day_ts = u64::from_be_bytes(rest[..8].try_into()?);
+            let Some((scope, id, spk)) = parse_subject(&rest[8..]) else { continue };
+            let stats = provider
+                .get_trader_stats(GetTraderStatsParams {
+                    blockhash: StateAt::Latest,
+                    scope,
+                    id,
+                    day_ts: Some(day_ts),
+                    address_spk: spk.clone(),
+                })?
+                .stats
+                .unwrap_or_default();
+            sub_stats(boards.entry((days, scope, id, spk))?, &stats);
+            if days == LONGEST_WINDOW_DAYS {
+                state.trader_stats_deletes.push(key);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Books the block's swaps and liquidity events into per-address stats for each pool and
+/// token, per UTC day, over the 1d/7d/30d windows ending on the tip's day and all-time, and
+/// keeps the rank index of every board in step. Runs after `derive_token_data` for prices
+/// and before `apply_lp_events` and `apply_swap_events` take the events.
+pub fn record_trader_stats(
+    block_ts: u64,
+    provider: &AmmDataProvider,
+    canonical_quote_units: &HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
+    state: &mut IndexState,
+) -> Result<()> {
+    let table = provider.table();
+    let day_ts = bucket_start_for(block_ts, Timeframe::D1);
+    let tip_key = table.TRADER_STATS_TIP_DAY.key().to_vec();
+    let prev_day = provider
+        .get_raw_value(GetRawValueParams { blockhash: StateAt::Latest, key: tip_key.clone() })?
+        .value
+        .and_then(|raw| raw.try_into().ok())
+        .map(u64::from_be_bytes);
+    // Block times can step back a little; windows never move backwards.
+    let tip_day = prev_day.map_or(day_ts, |prev| prev.max(day_ts));
+    let mut boards = Boards::new(provider);
+    if let Some(prev) = prev_day.filter(|prev| *prev < tip_day) {
+        expire_days(provider, prev, tip_day, &mut boards, state)?;
+    }
+    if prev_day != Some(tip_day) {
+        state.trader_stats_writes.push((tip_key, tip_day.to_be_bytes().to_vec()));
+    }
+    if state.swap_events.is_empty() && state.lp_events.is_empty() {
+        return boards.flush(state);
+    }
+
+    let mut prices = BlockPrices::new(provider, canonical_quote_units);
+    let mut deltas: BTreeMap<StatsKey, SchemaTraderStatsV1> = BTreeMap::new();
+    let mut book = |scope: LeaderboardScope, id: SchemaAlkaneId, spk: &[u8], delta| {
+        add_stats(deltas.entry((scope, id, spk.to_vec())).or_default(), &delta);
+    };
+
+    for ev in &state.swap_events {
+        let Some(defs) = state.pools_map.get(&ev.pool).copied() else { continue };
+        // Valued like the PnL books: by the leg paid in, else the leg received.
+        let paid = amount_value_usd(ev.amount_in, prices.price_usd(ev.token_in, state));
+        let value_usd = if paid > 0 {
+            paid
+        } else {
+            amount_value_usd(ev.amount_out, prices.price_usd(ev.token_out, state))
+        };
+        let trade = SchemaTraderStatsV1 { volume_usd: value_usd, trades: 1, ..Default::default() };
+        let bought = SchemaTraderStatsV1 { bought_usd: value_usd, ..trade.clone() };
+        let sold = SchemaTraderStatsV1 { sold_usd: value_usd, ..trade };
+        let pool_side =
+            if ev.token_out == defs.base_alkane_id { bought.clone() } else { sold.clone() };
+        book(LeaderboardScope::Pool, ev.pool, &ev.address_spk, pool_side);
+        book(LeaderboardScope::Token, ev.token_out, &ev.address_spk, bought);
+        book(LeaderboardScope::Token, ev.token_in, &ev.address_spk, sold);
+    }
+
+    for ev in &state.lp_events {
+        let Some(defs) = state.pools_map.get(&ev.pool).copied() else { continue };
+        let base_usd = amount_value_usd(ev.token0, prices.price_usd(defs.base_alkane_id, state));
+        let quote_usd = amount_value_usd(ev.token1, prices.price_usd(defs.quote_alkane_id, state));
+        let lp = |value_usd: u128| match ev.kind {
+            ActivityKind::LiquidityAdd => {
+                SchemaTraderStatsV1 { lp_added_usd: value_usd, ..Default::default() }
+            }
+            _ => SchemaTraderStatsV1 { lp_removed_usd: value_usd, ..Default::default() },
+        };
+        book(
+            LeaderboardScope::Pool,
+            ev.pool,
+            &ev.address_spk,
+            lp(base_usd.saturating_add(quote_usd)),
+        );
+        book(LeaderboardScope::Token, defs.base_alkane_id, &ev.address_spk, lp(base_usd));
+        book(LeaderboardScope::Token, defs.quote_alkane_id, &ev.address_spk, lp(quote_usd));
+    }
+
+    for ((scope, id, spk), delta) in deltas {
+        let mut stats = provider
+            .get_trader_stats(GetTraderStatsParams {
+                blockhash: StateAt::Latest,
+                scope,
+                id,
+                day_ts: Some(day_ts),
+                address_spk: spk.clone(),
+            })?
+            .stats
+            .unwrap_or_default();
+        add_stats(&mut stats, &delta);
+        state.trader_stats_writes.push((
+            table.trader_stats_day_key(scope, &id, day_ts, &spk),
+            encode_trader_stats(&stats)?,
+        ));
+        if day_ts >= window_start(tip_day, LONGEST_WINDOW_DAYS) {
+            state
+                .trader_stats_writes
+                .push((table.trader_stats_by_day_key(day_ts, scope, &id, &spk), Vec::new()));
+        }
+        for (_, days) in LEADERBOARD_WINDOWS {
+            // A block stamped before the tip's day only counts in windows still covering it.
+            if days == 0 || day_ts >= window_start(tip_day, days) {
+                add_stats(boards.entry((days, scope, id, spk.clone()))?, &delta);
+            }
+        }
+    }
+    boards.flush(state)
+}
@@ -0,0 +1,117 @@
+use crate::modules::ammdata::consts::CanonicalQuoteUnit;
+use crate::modules::ammdata::schemas::{SchemaCostBasisV1, SchemaPnlTradeV1};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetCostBasisParams, encode_cost_basis, encode_pnl_trade,
+};
+use crate::modules::ammdata::utils::index_positions::BlockPrices;
+use crate::modules::ammdata::utils::index_state::IndexState;
+use crate::modules::ammdata::utils::lp_positions::amount_value_usd;
+use crate::modules::ammdata::utils::pnl::{apply_buy, apply_sell};
+use crate::runtime::state_at::StateAt;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// A successful swap attributed to an address: it paid `amount_in` of `token_in` and got
+/// `amount_out` of `token_out`.
+#[derive(Clone, Debug)]
+pub struct SwapEvent {
+    pub pool: SchemaAlkaneId,
+    pub address_spk: Vec<u8>,
+    pub txid: [u8; 32],
+    pub token_in: SchemaAlkaneId,
+    pub amount_in: u128,
+    pub token_out: SchemaAlkaneId,
+    pub amount_out: u128,
+}
+
+/// Books the block's swaps into per-address cost basis and per-trade realized PnL. Each
+/// swap is valued once (by the leg paid in, or the leg received when the paid token has no
+/// price) and that value is the proceeds of the sold token and the cost of the bought one.
+/// A swap with no priced leg is booked as unpriced on both sides.
+pub fn apply_swap_events(
+    height: u32,
+    block_ts: u64,
+    provider: &AmmDataProvider,
+    canonical_quote_units: &HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
+    state: &mut IndexState,
+) -> Result<()> {
+    let events = std::mem::take(&mut state.swap_events);
+    if events.is_empty() {
+        return Ok(());
+    }
+    let table = provider.table();
+    let mut prices = BlockPrices::new(provider, canonical_quote_units);
+
+    let mut bases: HashMap<(Vec<u8>, SchemaAlkaneId), SchemaCostBasisV1> = HashMap::new();
+    let load = |spk: &[u8], token: SchemaAlkaneId| -> Result<SchemaCostBasisV1> {
+        Ok(provider
+            .get_cost_basis(GetCostBasisParams {
+                blockhash: StateAt::Latest,
+                address_spk: spk.to_vec(),
+                token,
+            })?
+            .basis
+            .unwrap_or_default())
+    };
+
+    for (seq, ev) in events.into_iter().enumerate() {
+        let price_in = prices.price_usd(ev.token_in, state);
+        let price_out = prices.price_usd(ev.token_out, state);
+        let value_usd = if price_in > 0 {
+            Some(amount_value_usd(ev.amount_in, price_in))
+        } else if price_out > 0 {
+            Some(amount_value_usd(ev.amount_out, price_out))
+        } else {
+            None
+        };
+        state.swap_values.push((ev.pool, ev.txid, value_usd.unwrap_or(0)));
+
+        let sold = match bases.entry((ev.address_spk.clone(), ev.token_in)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(load(&ev.address_spk, ev.token_in)?),
+        };
+        let pnl = apply_sell(sold, ev.amount_in, value_usd);
+        let sell = SchemaPnlTradeV1 {
+            txid: ev.txid,
+            pool: ev.pool,
+            timestamp: block_ts,
+            is_buy: false,
+            amount: ev.amount_in,
+            value_usd,
+            realized_fifo_usd: pnl.fifo_usd,
+            realized_avg_usd: pnl.avg_usd,
+        };
+
+        let bought = match bases.entry((ev.address_spk.clone(), ev.token_out)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(load(&ev.address_spk, ev.token_out)?),
+        };
+        apply_buy(bought, height, block_ts, ev.amount_out, value_usd);
+        let buy = SchemaPnlTradeV1 {
+            is_buy: true,
+            amount: ev.amount_out,
+            realized_fifo_usd: 0,
+            realized_avg_usd: 0,
+            ..sell.clone()
+        };
+
+        let seq = seq as u32;
+        state.address_pnl_writes.push((
+            table.address_pnl_trade_key(&ev.address_spk, &ev.token_in, height, seq),
+            encode_pnl_trade(&sell)?,
+        ));
+        state.address_pnl_writes.push((
+            table.address_pnl_trade_key(&ev.address_spk, &ev.token_out, height, seq),
+            encode_pnl_trade(&buy)?,
+        ));
+    }
+
+    for ((spk, token), basis) in bases {
+        state
+            .address_pnl_writes
+            .push((table.cost_basis_key(&spk, &token), encode_cost_basis(&basis)?));
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::modules::ammdata::consts::CanonicalQuoteUnit;
+use crate::modules::ammdata::schemas::Timeframe;
+use crate::modules::ammdata::storage::{AmmDataProvider, GetPoolFeesDayParams, encode_pool_fees};
+use crate::modules::ammdata::utils::candles::bucket_start_for;
+use crate::modules::ammdata::utils::index_positions::BlockPrices;
+use crate::modules::ammdata::utils::index_state::IndexState;
+use crate::modules::ammdata::utils::lp_positions::amount_value_usd;
+use crate::modules::ammdata::utils::pool_fees::{add_fees, usd_to_sats};
+use crate::runtime::state_at::StateAt;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Values the swap fees collected in `process_balance_deltas` and appends them to each
+/// pool's per-block and per-day fee series. Runs after `derive_token_data` so fees are
+/// valued at this block's prices.
+pub fn record_pool_fees(
+    height: u32,
+    block_ts: u64,
+    provider: &AmmDataProvider,
+    canonical_quote_units: &HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
+    state: &mut IndexState,
+) -> Result<()> {
+    if state.pool_fees.is_empty() {
+        return Ok(());
+    }
+    let table = provider.table();
+    let day_ts = bucket_start_for(block_ts, Timeframe::D1);
+    let mut prices = BlockPrices::new(provider, canonical_quote_units);
+
+    let mut pools: Vec<_> = std::mem::take(&mut state.pool_fees).into_iter().collect();
+    pools.sort_by_key(|(pool, _)| *pool);
+    for (pool, mut fees) in pools {
+        let Some(defs) = state.pools_map.get(&pool).copied() else { continue };
+        let base_usd =
+            amount_value_usd(fees.base_fees, prices.price_usd(defs.base_alkane_id, state));
+        let quote_usd =
+            amount_value_usd(fees.quote_fees, prices.price_usd(defs.quote_alkane_id, state));
+        fees.fees_usd = base_usd.saturating_add(quote_usd);
+        fees.fees_sats = usd_to_sats(fees.fees_usd, state.btc_usd_price);
+        state
+            .pool_fee_writes
+            .push((table.pool_fees_block_key(&pool, height), encode_pool_fees(&fees)?));
+
+        let mut day = provider
+            .get_pool_fees_day(GetPoolFeesDayParams { blockhash: StateAt::Latest, pool, day_ts })?
+            .fees
+            .unwrap_or_default();
+        add_fees(&mut day, &fees);
+        state
+            .pool_fee_writes
+            .push((table.pool_fees_day_key(&pool, day_ts), encode_pool_fees(&day)?));
+    }
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use crate::modules::ammdata::consts::{AMOUNT_SCALE, CanonicalQuoteUnit};
+use crate::modules::ammdata::consts::{
+    AMOUNT_SCALE, CanonicalQuoteUnit, SERIES_POOL_TVL_SATS, SERIES_POOL_TVL_USD,
+};
 use crate::modules::ammdata::schemas::{SchemaPoolMetricsV1, SchemaPoolMetricsV2, Timeframe};
 use crate::modules::ammdata::storage::{
     AmmDataProvider, GetListEntriesDescParams, GetPoolCreationInfoParams, GetPoolMetricsV2Params,
@@ -10,6 +12,7 @@ use crate::modules::ammdata::utils::candles::bucket_start_for;
 use crate::modules::ammdata::utils::index_state::IndexState;
 use crate::modules::essentials::storage::{EssentialsProvider, GetLatestCirculatingSupplyParams};
 use crate::runtime::state_at::StateAt;
+use crate::runtime::timeseries::series_point;
 use crate::schemas::SchemaAlkaneId;
 use anyhow::Result;
 use bitcoin::Network;
@@ -484,6 +487,7 @@ pub fn derive_pool_metrics(
             })
             .map(|res| res.supply)
             .unwrap_or(0);
+        state.lp_supply.insert(*pool, lp_supply);
         state
             .pool_lp_supply_writes
             .push((table.pool_lp_supply_latest_key(pool), encode_u128_value(lp_supply)?));
@@ -618,6 +622,20 @@ pub fn derive_pool_metrics(
         state
             .tvl_versioned_writes
             .push((table.tvl_versioned_key(pool, height), encode_u128_value(pool_tvl_usd)?));
+        state.pool_tvl_usd.insert(*pool, pool_tvl_usd);
+        let series_key = format!("{}:{}", pool.block, pool.tx);
+        state.timeseries_writes.push(series_point(
+            SERIES_POOL_TVL_USD,
+            &series_key,
+            height,
+            i128::try_from(pool_tvl_usd).unwrap_or(i128::MAX),
+        ));
+        state.timeseries_writes.push(series_point(
+            SERIES_POOL_TVL_SATS,
+            &series_key,
+            height,
+            i128::try_from(pool_tvl_sats).unwrap_or(i128::MAX),
+        ));
     }
 
     Ok(())
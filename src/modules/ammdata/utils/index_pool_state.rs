@@ -0,0 +1,60 @@
+use crate::modules::ammdata::adapters::default_pool_adapter;
+use crate::modules::ammdata::schemas::SchemaPoolStateV1;
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetPoolStateAtParams, encode_latest_pool_state, encode_pool_state,
+};
+use crate::modules::ammdata::utils::index_state::IndexState;
+use crate::modules::ammdata::utils::twap::next_state;
+use crate::runtime::state_at::StateAt;
+use anyhow::Result;
+
+/// Appends a pool state record for every pool the block touched. Runs after
+/// `derive_pool_metrics`, which leaves the block's LP supply in `state.lp_supply`.
+pub fn record_pool_states(
+    height: u32,
+    block_ts: u64,
+    provider: &AmmDataProvider,
+    state: &mut IndexState,
+) -> Result<()> {
+    if state.pools_touched.is_empty() {
+        return Ok(());
+    }
+    let table = provider.table();
+
+    let mut pools: Vec<_> = state.pools_touched.iter().copied().collect();
+    pools.sort();
+    for pool in pools {
+        let Some(snap) = state.reserves_snapshot.get(&pool) else { continue };
+        let adapter = state.pool_adapters.get(&pool).copied().unwrap_or_else(default_pool_adapter);
+        let (p_b_per_q, p_q_per_b) = adapter.spot_prices(snap.base_reserve, snap.quote_reserve);
+        // The block's own record is not written yet, so the latest record is the one
+        // before it.
+        let prev = provider
+            .get_pool_state_at(GetPoolStateAtParams {
+                blockhash: StateAt::Latest,
+                pool,
+                height: None,
+            })?
+            .state;
+        let record = next_state(
+            prev.as_ref().map(|(h, s)| (*h, s)),
+            height,
+            SchemaPoolStateV1 {
+                timestamp: block_ts,
+                base_reserve: snap.base_reserve,
+                quote_reserve: snap.quote_reserve,
+                lp_supply: state.lp_supply.get(&pool).copied().unwrap_or(0),
+                price_quote_per_base: p_q_per_b,
+                price_base_per_quote: p_b_per_q,
+                ..Default::default()
+            },
+        );
+        state
+            .pool_state_writes
+            .push((table.pool_state_key(&pool, height), encode_pool_state(&record)?));
+        state
+            .pool_state_writes
+            .push((table.pool_state_latest_key(&pool), encode_latest_pool_state(height, &record)?));
+    }
+    Ok(())
+}
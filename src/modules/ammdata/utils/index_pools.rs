@@ -1,24 +1,23 @@
 use crate::alkanes::trace::{
-    EspoBlock, EspoSandshrewLikeTraceEvent, EspoSandshrewLikeTraceShortId,
+    EspoAlkanesTransaction, EspoBlock, EspoHostFunctionValues, EspoSandshrewLikeTraceEvent,
 };
 use crate::config::{debug_enabled, get_electrum_like};
+use crate::modules::ammdata::adapters::{PoolAdapter, pool_adapters};
 use crate::modules::ammdata::consts::CanonicalQuoteUnit;
 use crate::modules::ammdata::schemas::{
     ActivityKind, SchemaActivityV1, SchemaCanonicalPoolEntry, SchemaPoolCreationInfoV1,
 };
 use crate::modules::ammdata::storage::{
-    AmmDataProvider, encode_pool_creation_info, encode_u128_value,
+    AmmDataProvider, AmmDataTable, encode_pool_creation_info, encode_u128_value,
 };
 use crate::modules::ammdata::utils::index_state::IndexState;
-use crate::modules::ammdata::utils::reserves::{
-    NewPoolInfo, extract_new_pools_from_espo_transaction,
-};
+use crate::modules::ammdata::utils::reserves::{LpTransfer, NewPoolInfo};
 use crate::modules::essentials::storage::{
     EssentialsProvider, GetCreationIdsInBlockParams, GetCreationRecordParams,
     GetLatestCirculatingSupplyParams, load_tx_summary_v2,
 };
 use crate::modules::essentials::utils::balances::{
-    clean_espo_sandshrew_like_trace, get_alkane_balances,
+    clean_espo_sandshrew_like_trace, get_alkane_balances, get_outpoint_balances_with_spent,
 };
 use crate::runtime::state_at::StateAt;
 use crate::schemas::SchemaAlkaneId;
@@ -30,71 +29,100 @@ use std::collections::{HashMap, HashSet};
 
 pub struct PoolDiscoveryResult {
     pub tx_meta: HashMap<Txid, (Vec<u8>, bool)>,
+    /// Reserves each pool reported before the first trade it handled in a transaction.
+    pub reported_reserves: HashMap<(Txid, SchemaAlkaneId), (u128, u128)>,
+    /// LP tokens each pool minted or burned in a transaction.
+    pub lp_movements: HashMap<(Txid, SchemaAlkaneId), LpMovement>,
 }
 
-fn decode_storage_id_hex_word(raw_hex: &str) -> Option<SchemaAlkaneId> {
-    let hex = raw_hex.strip_prefix("0x").unwrap_or(raw_hex);
-    if hex.len() != 64 {
-        return None;
-    }
-    let raw = hex::decode(hex).ok()?;
-    let block_bytes: [u8; 16] = raw[0..16].try_into().ok()?;
-    let tx_bytes: [u8; 16] = raw[16..32].try_into().ok()?;
-    let block = u128::from_le_bytes(block_bytes);
-    let tx = u128::from_le_bytes(tx_bytes);
-    if block > u32::MAX as u128 || tx > u64::MAX as u128 {
-        return None;
-    }
-    Some(SchemaAlkaneId { block: block as u32, tx: tx as u64 })
+/// Real LP movement of a pool in one transaction, with the script that received the minted
+/// LP (an output of the transaction) or gave up the burned LP (a spent input). The script is
+/// empty when no such outpoint held the pool's LP token.
+#[derive(Clone, Debug, Default)]
+pub struct LpMovement {
+    pub transfer: LpTransfer,
+    pub holder_spk: Vec<u8>,
 }
 
-fn short_to_schema_id(id: &EspoSandshrewLikeTraceShortId) -> Option<SchemaAlkaneId> {
-    Some(SchemaAlkaneId {
-        block: crate::modules::ammdata::parse_hex_u32(&id.block)?,
-        tx: crate::modules::ammdata::parse_hex_u64(&id.tx)?,
-    })
+/// Script of the first outpoint in `outpoints` holding `lp_token`, or empty.
+fn lp_holder_spk(
+    essentials: &EssentialsProvider,
+    outpoints: impl IntoIterator<Item = (Txid, u32)>,
+    lp_token: SchemaAlkaneId,
+) -> Result<Vec<u8>> {
+    for (txid, vout) in outpoints {
+        let lookup = get_outpoint_balances_with_spent(StateAt::Latest, essentials, &txid, vout)?;
+        if lookup.balances.iter().any(|b| b.alkane == lp_token && b.amount > 0) {
+            return Ok(lookup.spk.map(|spk| spk.into_bytes()).unwrap_or_default());
+        }
+    }
+    Ok(Vec::new())
 }
 
-fn parse_pool_defs_from_creation_summary(
-    summary: &crate::modules::essentials::storage::AlkaneTxSummary,
+fn record_pool_protocol(
+    table: &AmmDataTable<'_>,
+    state: &mut IndexState,
     pool_id: SchemaAlkaneId,
-) -> Option<(SchemaAlkaneId, SchemaAlkaneId, Option<SchemaAlkaneId>)> {
-    let mut stack: Vec<EspoSandshrewLikeTraceShortId> = Vec::new();
-    for trace in &summary.traces {
-        for ev in &trace.events {
-            match ev {
-                EspoSandshrewLikeTraceEvent::Invoke(inv) => {
-                    stack.push(inv.context.myself.clone());
-                }
-                EspoSandshrewLikeTraceEvent::Return(ret) => {
-                    let Some(leaving) = stack.pop() else { continue };
-                    let Some(leaving_id) = short_to_schema_id(&leaving) else {
-                        continue;
-                    };
-                    if leaving_id != pool_id {
-                        continue;
-                    }
+    adapter: &'static dyn PoolAdapter,
+) {
+    state.pool_adapters.insert(pool_id, adapter);
+    state
+        .pool_protocol_writes
+        .push((table.pool_protocol_key(&pool_id), adapter.name().as_bytes().to_vec()));
+}
 
-                    let mut alk0: Option<SchemaAlkaneId> = None;
-                    let mut alk1: Option<SchemaAlkaneId> = None;
-                    let mut factory: Option<SchemaAlkaneId> = None;
-                    for kv in &ret.response.storage {
-                        match kv.key.as_str() {
-                            "/alkane/0" => alk0 = decode_storage_id_hex_word(&kv.value),
-                            "/alkane/1" => alk1 = decode_storage_id_hex_word(&kv.value),
-                            "/factory_id" => factory = decode_storage_id_hex_word(&kv.value),
-                            _ => {}
+/// Pools created by `transaction` according to `adapter`, with the factory resolved from
+/// the creating call when the trace shows one.
+fn adapter_new_pools(
+    adapter: &dyn PoolAdapter,
+    transaction: &EspoAlkanesTransaction,
+    host_function_values: &EspoHostFunctionValues,
+    amm_factories: &HashSet<SchemaAlkaneId>,
+) -> Vec<NewPoolInfo> {
+    let mut pool_factory_by_id: HashMap<SchemaAlkaneId, SchemaAlkaneId> = HashMap::new();
+    if let Some(traces) = &transaction.traces {
+        for trace in traces {
+            let Some(cleaned) =
+                clean_espo_sandshrew_like_trace(&trace.sandshrew_trace, host_function_values)
+            else {
+                continue;
+            };
+            let mut pending_factory: Option<SchemaAlkaneId> = None;
+            for ev in &cleaned.events {
+                match ev {
+                    EspoSandshrewLikeTraceEvent::Invoke(inv) => {
+                        if let Some(factory) = adapter.factory_for_create_call(inv, amm_factories) {
+                            pending_factory = Some(factory);
                         }
                     }
-                    if let (Some(base), Some(quote)) = (alk0, alk1) {
-                        return Some((base, quote, factory));
+                    EspoSandshrewLikeTraceEvent::Create(c) => {
+                        if let Some(factory) = pending_factory.take() {
+                            if let (Some(block), Some(tx)) = (
+                                crate::modules::ammdata::parse_hex_u32(&c.block),
+                                crate::modules::ammdata::parse_hex_u64(&c.tx),
+                            ) {
+                                pool_factory_by_id.insert(SchemaAlkaneId { block, tx }, factory);
+                            }
+                        }
                     }
+                    _ => {}
                 }
-                _ => {}
             }
         }
     }
-    None
+
+    let Ok(new_pools) = adapter.extract_new_pools(transaction, host_function_values) else {
+        return Vec::new();
+    };
+    new_pools
+        .into_iter()
+        .map(|mut info| {
+            if let Some(factory) = pool_factory_by_id.get(&info.pool_id) {
+                info.factory_id = Some(*factory);
+            }
+            info
+        })
+        .collect()
 }
 
 pub fn bootstrap_pools_from_creation_records(
@@ -146,8 +174,10 @@ pub fn bootstrap_pools_from_creation_records(
         let Some(summary) = load_tx_summary_v2(essentials_meta, &txid) else {
             continue;
         };
-        let Some((base_alkane_id, quote_alkane_id, factory_from_trace)) =
-            parse_pool_defs_from_creation_summary(&summary, pool_id)
+        let Some((adapter, (base_alkane_id, quote_alkane_id, factory_from_trace))) =
+            pool_adapters()
+                .iter()
+                .find_map(|a| a.pool_defs_from_summary(&summary, pool_id).map(|d| (*a, d)))
         else {
             continue;
         };
@@ -187,6 +217,7 @@ pub fn bootstrap_pools_from_creation_records(
         state
             .pool_factory_writes
             .push((table.pool_factory_key(&pool_id), factory_bytes));
+        record_pool_protocol(&table, state, pool_id, adapter);
 
         let mut pool_balances =
             get_alkane_balances(blockhash.clone(), essentials_balances, &pool_id)
@@ -291,263 +322,279 @@ pub fn discover_new_pools(
             continue;
         }
 
-        let mut pool_factory_by_id: HashMap<SchemaAlkaneId, SchemaAlkaneId> = HashMap::new();
-        if let Some(traces) = &transaction.traces {
-            for trace in traces {
-                let Some(cleaned) = clean_espo_sandshrew_like_trace(
-                    &trace.sandshrew_trace,
-                    &block.host_function_values,
-                ) else {
-                    continue;
-                };
-                let mut pending_factory: Option<SchemaAlkaneId> = None;
-                for ev in &cleaned.events {
-                    match ev {
-                        EspoSandshrewLikeTraceEvent::Invoke(inv) => {
-                            if let Some(factory) =
-                                crate::modules::ammdata::parse_factory_create_call(
-                                    inv,
-                                    amm_factories,
-                                )
-                            {
-                                pending_factory = Some(factory);
-                            }
-                        }
-                        EspoSandshrewLikeTraceEvent::Create(c) => {
-                            if let Some(factory) = pending_factory.take() {
-                                if let (Some(block), Some(tx)) = (
-                                    crate::modules::ammdata::parse_hex_u32(&c.block),
-                                    crate::modules::ammdata::parse_hex_u64(&c.tx),
-                                ) {
-                                    pool_factory_by_id
-                                        .insert(SchemaAlkaneId { block, tx }, factory);
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
+        // Adapters are tried in registration order; the first to decode a pool claims it.
+        let new_pools: Vec<(&'static dyn PoolAdapter, NewPoolInfo)> = pool_adapters()
+            .iter()
+            .flat_map(|adapter| {
+                adapter_new_pools(*adapter, transaction, &block.host_function_values, amm_factories)
+                    .into_iter()
+                    .map(move |info| (*adapter, info))
+            })
+            .collect();
+        for (adapter, NewPoolInfo { pool_id, defs, factory_id }) in new_pools {
+            if !seen_new_pools.insert((pool_id.block, pool_id.tx)) {
+                continue;
+            }
+            if state.pools_map.contains_key(&pool_id) {
+                continue;
+            }
+            let factory_ok = factory_id.map(|id| amm_factories.contains(&id)).unwrap_or(false);
+            if !factory_ok {
+                continue;
             }
-        }
-
-        if let Ok(new_pools) =
-            extract_new_pools_from_espo_transaction(transaction, &block.host_function_values)
-        {
-            for NewPoolInfo { pool_id, defs, factory_id } in new_pools {
-                if !seen_new_pools.insert((pool_id.block, pool_id.tx)) {
-                    continue;
-                }
-                if state.pools_map.contains_key(&pool_id) {
-                    continue;
-                }
-                let factory_from_call = pool_factory_by_id.get(&pool_id).copied();
-                let factory_id = factory_from_call.or(factory_id);
-                let factory_ok = factory_id.map(|id| amm_factories.contains(&id)).unwrap_or(false);
-                if !factory_ok {
-                    continue;
-                }
 
-                state.pools_map.insert(pool_id, defs);
-                if let Ok(encoded_defs) = borsh::to_vec(&defs) {
-                    state.pool_defs_writes.push((table.pools_key(&pool_id), encoded_defs));
-                }
+            state.pools_map.insert(pool_id, defs);
+            if let Ok(encoded_defs) = borsh::to_vec(&defs) {
+                state.pool_defs_writes.push((table.pools_key(&pool_id), encoded_defs));
+            }
+            state
+                .token_pools_writes
+                .push((table.token_pools_key(&defs.base_alkane_id, &pool_id), Vec::new()));
+            state
+                .token_pools_writes
+                .push((table.token_pools_key(&defs.quote_alkane_id, &pool_id), Vec::new()));
+            state.reserves_snapshot.entry(pool_id).or_insert(
+                crate::modules::ammdata::schemas::SchemaPoolSnapshot {
+                    base_reserve: 0,
+                    quote_reserve: 0,
+                    base_id: defs.base_alkane_id,
+                    quote_id: defs.quote_alkane_id,
+                },
+            );
+            if canonical_quote_units.contains_key(&defs.quote_alkane_id) {
                 state
-                    .token_pools_writes
-                    .push((table.token_pools_key(&defs.base_alkane_id, &pool_id), Vec::new()));
+                    .canonical_pool_updates
+                    .entry(defs.base_alkane_id)
+                    .or_default()
+                    .push(SchemaCanonicalPoolEntry { pool_id, quote_id: defs.quote_alkane_id });
+            }
+            if canonical_quote_units.contains_key(&defs.base_alkane_id) {
                 state
-                    .token_pools_writes
-                    .push((table.token_pools_key(&defs.quote_alkane_id, &pool_id), Vec::new()));
-                state.reserves_snapshot.entry(pool_id).or_insert(
-                    crate::modules::ammdata::schemas::SchemaPoolSnapshot {
-                        base_reserve: 0,
-                        quote_reserve: 0,
-                        base_id: defs.base_alkane_id,
-                        quote_id: defs.quote_alkane_id,
-                    },
-                );
-                if canonical_quote_units.contains_key(&defs.quote_alkane_id) {
-                    state
-                        .canonical_pool_updates
-                        .entry(defs.base_alkane_id)
-                        .or_default()
-                        .push(SchemaCanonicalPoolEntry { pool_id, quote_id: defs.quote_alkane_id });
-                }
-                if canonical_quote_units.contains_key(&defs.base_alkane_id) {
-                    state
-                        .canonical_pool_updates
-                        .entry(defs.quote_alkane_id)
-                        .or_default()
-                        .push(SchemaCanonicalPoolEntry { pool_id, quote_id: defs.base_alkane_id });
-                }
-
-                let pool_label = get_alkane_label(
-                    blockhash.clone(),
-                    essentials,
-                    &mut state.alkane_label_cache,
-                    &pool_id,
-                );
-                let pool_name = crate::modules::ammdata::strip_lp_suffix(&pool_label);
-                let pool_name_norm = pool_name.trim().to_ascii_lowercase();
-                if !pool_name_norm.is_empty() {
-                    state
-                        .pool_name_index_writes
-                        .push((table.pool_name_index_key(&pool_name_norm, &pool_id), Vec::new()));
-                }
+                    .canonical_pool_updates
+                    .entry(defs.quote_alkane_id)
+                    .or_default()
+                    .push(SchemaCanonicalPoolEntry { pool_id, quote_id: defs.base_alkane_id });
+            }
 
-                if let Some(factory_id) = factory_id {
-                    state
-                        .factory_pools_writes
-                        .push((table.factory_pools_key(&factory_id, &pool_id), Vec::new()));
-                    let mut factory_bytes = Vec::with_capacity(12);
-                    factory_bytes.extend_from_slice(&factory_id.block.to_be_bytes());
-                    factory_bytes.extend_from_slice(&factory_id.tx.to_be_bytes());
-                    state
-                        .pool_factory_writes
-                        .push((table.pool_factory_key(&pool_id), factory_bytes));
-                }
+            let pool_label = get_alkane_label(
+                blockhash.clone(),
+                essentials,
+                &mut state.alkane_label_cache,
+                &pool_id,
+            );
+            let pool_name = crate::modules::ammdata::strip_lp_suffix(&pool_label);
+            let pool_name_norm = pool_name.trim().to_ascii_lowercase();
+            if !pool_name_norm.is_empty() {
+                state
+                    .pool_name_index_writes
+                    .push((table.pool_name_index_key(&pool_name_norm, &pool_id), Vec::new()));
+            }
 
-                // Pool creation info
-                let mut creator_spk = crate::modules::ammdata::pool_creator_spk_from_protostone(
-                    &transaction.transaction,
-                );
-                if creator_spk.is_none() {
-                    let mut lowest_spk: Option<ScriptBuf> = None;
-                    let mut lowest_value: Option<u64> = None;
-                    for vin in &transaction.transaction.input {
-                        if vin.previous_output.is_null() {
-                            continue;
-                        }
-                        let prev_txid = vin.previous_output.txid;
-                        let prev_tx = if let Some(tx) = block_tx_map.get(&prev_txid) {
-                            Some((*tx).clone())
-                        } else if let Some(tx) = prev_tx_cache.get(&prev_txid) {
-                            Some(tx.clone())
+            if let Some(factory_id) = factory_id {
+                state
+                    .factory_pools_writes
+                    .push((table.factory_pools_key(&factory_id, &pool_id), Vec::new()));
+                let mut factory_bytes = Vec::with_capacity(12);
+                factory_bytes.extend_from_slice(&factory_id.block.to_be_bytes());
+                factory_bytes.extend_from_slice(&factory_id.tx.to_be_bytes());
+                state
+                    .pool_factory_writes
+                    .push((table.pool_factory_key(&pool_id), factory_bytes));
+            }
+            record_pool_protocol(&table, state, pool_id, adapter);
+
+            // Pool creation info
+            let mut creator_spk =
+                crate::modules::ammdata::pool_creator_spk_from_protostone(&transaction.transaction);
+            if creator_spk.is_none() {
+                let mut lowest_spk: Option<ScriptBuf> = None;
+                let mut lowest_value: Option<u64> = None;
+                for vin in &transaction.transaction.input {
+                    if vin.previous_output.is_null() {
+                        continue;
+                    }
+                    let prev_txid = vin.previous_output.txid;
+                    let prev_tx = if let Some(tx) = block_tx_map.get(&prev_txid) {
+                        Some((*tx).clone())
+                    } else if let Some(tx) = prev_tx_cache.get(&prev_txid) {
+                        Some(tx.clone())
+                    } else {
+                        let raw = get_electrum_like()
+                            .batch_transaction_get_raw(&[prev_txid])
+                            .unwrap_or_default()
+                            .into_iter()
+                            .next()
+                            .unwrap_or_default();
+                        if raw.is_empty() {
+                            None
                         } else {
-                            let raw = get_electrum_like()
-                                .batch_transaction_get_raw(&[prev_txid])
-                                .unwrap_or_default()
-                                .into_iter()
-                                .next()
-                                .unwrap_or_default();
-                            if raw.is_empty() {
-                                None
-                            } else {
-                                deserialize::<Transaction>(&raw).ok().map(|tx| {
-                                    prev_tx_cache.insert(prev_txid, tx.clone());
-                                    tx
-                                })
-                            }
-                        };
-                        let Some(prev_tx) = prev_tx else { continue };
-                        let idx = vin.previous_output.vout as usize;
-                        let Some(prev_out) = prev_tx.output.get(idx) else { continue };
-                        let value = prev_out.value.to_sat();
-                        if lowest_value.map_or(true, |v| value < v) {
-                            lowest_value = Some(value);
-                            lowest_spk = Some(prev_out.script_pubkey.clone());
+                            deserialize::<Transaction>(&raw).ok().map(|tx| {
+                                prev_tx_cache.insert(prev_txid, tx.clone());
+                                tx
+                            })
                         }
+                    };
+                    let Some(prev_tx) = prev_tx else { continue };
+                    let idx = vin.previous_output.vout as usize;
+                    let Some(prev_out) = prev_tx.output.get(idx) else { continue };
+                    let value = prev_out.value.to_sat();
+                    if lowest_value.map_or(true, |v| value < v) {
+                        lowest_value = Some(value);
+                        lowest_spk = Some(prev_out.script_pubkey.clone());
                     }
-                    creator_spk = lowest_spk;
                 }
+                creator_spk = lowest_spk;
+            }
 
-                let mut pool_balances =
-                    get_alkane_balances(StateAt::Latest, essentials, &pool_id).unwrap_or_default();
-                let initial_token0_amount = pool_balances.remove(&defs.base_alkane_id).unwrap_or(0);
-                let initial_token1_amount =
-                    pool_balances.remove(&defs.quote_alkane_id).unwrap_or(0);
-                let initial_lp_supply = essentials
-                    .get_latest_circulating_supply(GetLatestCirculatingSupplyParams {
-                        blockhash: StateAt::Latest,
-                        alkane: pool_id,
-                    })
-                    .map(|res| res.supply)
-                    .unwrap_or(0);
-
-                state.pool_lp_supply_writes.push((
-                    table.pool_lp_supply_latest_key(&pool_id),
-                    encode_u128_value(initial_lp_supply)?,
-                ));
-
-                let creation_info = SchemaPoolCreationInfoV1 {
-                    creator_spk: creator_spk.map(|s| s.as_bytes().to_vec()).unwrap_or_default(),
-                    creation_height: height,
-                    initial_token0_amount,
-                    initial_token1_amount,
-                    initial_lp_supply,
-                };
-                state.pool_creation_info_cache.insert(pool_id, creation_info.clone());
-                state.pool_creation_info_writes.push((
-                    table.pool_creation_info_key(&pool_id),
-                    encode_pool_creation_info(&creation_info)?,
-                ));
-
-                let txid = transaction.transaction.compute_txid();
-                let txid_bytes = txid.to_byte_array();
-                let (address_spk, success) =
-                    tx_meta.get(&txid).cloned().unwrap_or_else(|| (Vec::new(), true));
-
-                let activity = SchemaActivityV1 {
-                    timestamp: block_ts,
-                    txid: txid_bytes,
-                    kind: ActivityKind::PoolCreate,
-                    direction: None,
-                    base_delta: 0,
-                    quote_delta: 0,
-                    address_spk,
-                    success,
-                };
-
-                if let Ok(seq) = state.activity_acc.push(pool_id, block_ts, activity.clone()) {
-                    state.index_acc.add(&pool_id, block_ts, seq, &activity);
-                    state
-                        .pool_creations_writes
-                        .push((table.pool_creations_key(block_ts, seq, &pool_id), Vec::new()));
-                    if !activity.address_spk.is_empty() {
-                        state.address_pool_creations_writes.push((
-                            table.address_pool_creations_key(
-                                &activity.address_spk,
-                                block_ts,
-                                seq,
-                                &pool_id,
-                            ),
-                            Vec::new(),
-                        ));
-                        state.address_amm_history_writes.push((
-                            table.address_amm_history_key(
-                                &activity.address_spk,
-                                block_ts,
-                                seq,
-                                activity.kind,
-                                &pool_id,
-                            ),
-                            Vec::new(),
-                        ));
-                    }
-                    state.amm_history_all_writes.push((
-                        table.amm_history_all_key(block_ts, seq, activity.kind, &pool_id),
+            let mut pool_balances =
+                get_alkane_balances(StateAt::Latest, essentials, &pool_id).unwrap_or_default();
+            let initial_token0_amount = pool_balances.remove(&defs.base_alkane_id).unwrap_or(0);
+            let initial_token1_amount = pool_balances.remove(&defs.quote_alkane_id).unwrap_or(0);
+            let initial_lp_supply = essentials
+                .get_latest_circulating_supply(GetLatestCirculatingSupplyParams {
+                    blockhash: StateAt::Latest,
+                    alkane: pool_id,
+                })
+                .map(|res| res.supply)
+                .unwrap_or(0);
+
+            state.pool_lp_supply_writes.push((
+                table.pool_lp_supply_latest_key(&pool_id),
+                encode_u128_value(initial_lp_supply)?,
+            ));
+
+            let creation_info = SchemaPoolCreationInfoV1 {
+                creator_spk: creator_spk.map(|s| s.as_bytes().to_vec()).unwrap_or_default(),
+                creation_height: height,
+                initial_token0_amount,
+                initial_token1_amount,
+                initial_lp_supply,
+            };
+            state.pool_creation_info_cache.insert(pool_id, creation_info.clone());
+            state.pool_creation_info_writes.push((
+                table.pool_creation_info_key(&pool_id),
+                encode_pool_creation_info(&creation_info)?,
+            ));
+
+            let txid = transaction.transaction.compute_txid();
+            let txid_bytes = txid.to_byte_array();
+            let (address_spk, success) =
+                tx_meta.get(&txid).cloned().unwrap_or_else(|| (Vec::new(), true));
+
+            let activity = SchemaActivityV1 {
+                timestamp: block_ts,
+                txid: txid_bytes,
+                kind: ActivityKind::PoolCreate,
+                direction: None,
+                base_delta: 0,
+                quote_delta: 0,
+                address_spk,
+                success,
+            };
+
+            if let Ok(seq) = state.activity_acc.push(pool_id, block_ts, activity.clone()) {
+                state.index_acc.add(&pool_id, block_ts, seq, &activity);
+                state
+                    .pool_creations_writes
+                    .push((table.pool_creations_key(block_ts, seq, &pool_id), Vec::new()));
+                if !activity.address_spk.is_empty() {
+                    state.address_pool_creations_writes.push((
+                        table.address_pool_creations_key(
+                            &activity.address_spk,
+                            block_ts,
+                            seq,
+                            &pool_id,
+                        ),
+                        Vec::new(),
+                    ));
+                    state.address_amm_history_writes.push((
+                        table.address_amm_history_key(
+                            &activity.address_spk,
+                            block_ts,
+                            seq,
+                            activity.kind,
+                            &pool_id,
+                        ),
                         Vec::new(),
                     ));
                 }
+                state.amm_history_all_writes.push((
+                    table.amm_history_all_key(block_ts, seq, activity.kind, &pool_id),
+                    Vec::new(),
+                ));
+            }
 
-                println!(
-                    "[AMMDATA] New pool created @ block #{blk}, ts={ts}\n\
+            println!(
+                "[AMMDATA] New pool created @ block #{blk}, ts={ts}\n\
                      [AMMDATA]   Pool:  {pb}:{pt}\n\
                      [AMMDATA]   Base:  {bb}:{bt}\n\
                      [AMMDATA]   Quote: {qb}:{qt}",
-                    blk = height,
-                    ts = block_ts,
-                    pb = pool_id.block,
-                    pt = pool_id.tx,
-                    bb = defs.base_alkane_id.block,
-                    bt = defs.base_alkane_id.tx,
-                    qb = defs.quote_alkane_id.block,
-                    qt = defs.quote_alkane_id.tx
-                );
+                blk = height,
+                ts = block_ts,
+                pb = pool_id.block,
+                pt = pool_id.tx,
+                bb = defs.base_alkane_id.block,
+                bt = defs.base_alkane_id.tx,
+                qb = defs.quote_alkane_id.block,
+                qt = defs.quote_alkane_id.tx
+            );
+        }
+    }
+
+    // Reserves are read after discovery so pools created in this block are known.
+    let mut reported_reserves: HashMap<(Txid, SchemaAlkaneId), (u128, u128)> = HashMap::new();
+    let mut lp_movements: HashMap<(Txid, SchemaAlkaneId), LpMovement> = HashMap::new();
+    for transaction in block.transactions.iter() {
+        if transaction.traces.is_none() {
+            continue;
+        }
+        let txid = transaction.transaction.compute_txid();
+        for adapter in pool_adapters() {
+            for (pool, transfer) in adapter.lp_transfers(transaction, &state.pools_map)? {
+                let claimed =
+                    state.pool_adapters.get(&pool).is_none_or(|a| a.name() == adapter.name());
+                if !claimed || lp_movements.contains_key(&(txid, pool)) {
+                    continue;
+                }
+                let tx = &transaction.transaction;
+                // Minted LP lands on an output; burned LP was held by a spent input.
+                let holder_spk = if transfer.minted >= transfer.burned {
+                    lp_holder_spk(essentials, (0..tx.output.len() as u32).map(|v| (txid, v)), pool)?
+                } else {
+                    lp_holder_spk(
+                        essentials,
+                        tx.input
+                            .iter()
+                            .filter(|vin| !vin.previous_output.is_null())
+                            .map(|vin| (vin.previous_output.txid, vin.previous_output.vout)),
+                        pool,
+                    )?
+                };
+                lp_movements.insert((txid, pool), LpMovement { transfer, holder_spk });
+            }
+        }
+        for adapter in pool_adapters() {
+            let reported = match adapter.reported_reserves(transaction, &state.pools_map) {
+                Ok(reported) => reported,
+                Err(e) => {
+                    eprintln!(
+                        "[AMMDATA] {} reserves unreadable in tx {txid}: {e:?}",
+                        adapter.name()
+                    );
+                    continue;
+                }
+            };
+            for (pool, reserves) in reported {
+                let claimed =
+                    state.pool_adapters.get(&pool).is_none_or(|a| a.name() == adapter.name());
+                if claimed {
+                    reported_reserves.entry((txid, pool)).or_insert(reserves);
+                }
             }
         }
     }
 
-    Ok(PoolDiscoveryResult { tx_meta })
+    Ok(PoolDiscoveryResult { tx_meta, reported_reserves, lp_movements })
 }
 
 pub(crate) fn get_alkane_label(
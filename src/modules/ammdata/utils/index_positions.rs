@@ -0,0 +1,126 @@
+use crate::modules::ammdata::consts::CanonicalQuoteUnit;
+use crate::modules::ammdata::schemas::{ActivityKind, SchemaLpLotV1, SchemaLpPositionV1};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetLpPositionParams, GetTokenMetricsParams, encode_lp_position,
+};
+use crate::modules::ammdata::utils::index_state::IndexState;
+use crate::modules::ammdata::utils::lp_positions::{amount_value_usd, apply_burn, token_price_usd};
+use crate::runtime::state_at::StateAt;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// A liquidity add or remove attributed to an address, in pool (base, quote) order.
+#[derive(Clone, Debug)]
+pub struct LpEvent {
+    pub pool: SchemaAlkaneId,
+    pub address_spk: Vec<u8>,
+    pub txid: [u8; 32],
+    pub kind: ActivityKind,
+    pub token0: u128,
+    pub token1: u128,
+    pub lp_tokens: u128,
+    /// sqrt(k) per LP token right after the event.
+    pub sqrt_k_per_lp: u128,
+}
+
+/// USD prices at the block being indexed, memoized per token. Reads `token_metrics_cache`,
+/// so it is only meaningful after `derive_token_data`.
+pub struct BlockPrices<'a> {
+    provider: &'a AmmDataProvider,
+    canonical_quote_units: &'a HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
+    cache: HashMap<SchemaAlkaneId, u128>,
+}
+
+impl<'a> BlockPrices<'a> {
+    pub fn new(
+        provider: &'a AmmDataProvider,
+        canonical_quote_units: &'a HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
+    ) -> Self {
+        Self { provider, canonical_quote_units, cache: HashMap::new() }
+    }
+
+    pub fn price_usd(&mut self, token: SchemaAlkaneId, state: &IndexState) -> u128 {
+        let provider = self.provider;
+        let units = self.canonical_quote_units;
+        *self.cache.entry(token).or_insert_with(|| {
+            token_price_usd(&token, units, state.btc_usd_price, || {
+                state.token_metrics_cache.get(&token).map(|m| m.price_usd).unwrap_or_else(|| {
+                    provider
+                        .get_token_metrics(GetTokenMetricsParams {
+                            blockhash: StateAt::Latest,
+                            token,
+                        })
+                        .map(|res| res.metrics.price_usd)
+                        .unwrap_or(0)
+                })
+            })
+        })
+    }
+}
+
+/// Folds the block's liquidity events into per-address LP positions. Runs after
+/// `derive_token_data` so deposits and withdrawals are valued at this block's prices.
+pub fn apply_lp_events(
+    height: u32,
+    block_ts: u64,
+    provider: &AmmDataProvider,
+    canonical_quote_units: &HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
+    state: &mut IndexState,
+) -> Result<()> {
+    let events = std::mem::take(&mut state.lp_events);
+    if events.is_empty() {
+        return Ok(());
+    }
+    let table = provider.table();
+
+    let mut prices = BlockPrices::new(provider, canonical_quote_units);
+
+    let mut positions: HashMap<(Vec<u8>, SchemaAlkaneId), SchemaLpPositionV1> = HashMap::new();
+    for ev in events {
+        let Some(defs) = state.pools_map.get(&ev.pool).copied() else { continue };
+        let value_usd = amount_value_usd(ev.token0, prices.price_usd(defs.base_alkane_id, state))
+            .saturating_add(amount_value_usd(
+                ev.token1,
+                prices.price_usd(defs.quote_alkane_id, state),
+            ));
+
+        let position = match positions.entry((ev.address_spk.clone(), ev.pool)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let stored = provider
+                    .get_lp_position(GetLpPositionParams {
+                        blockhash: StateAt::Latest,
+                        address_spk: ev.address_spk.clone(),
+                        pool: ev.pool,
+                    })?
+                    .position;
+                entry.insert(stored.unwrap_or_default())
+            }
+        };
+        match ev.kind {
+            ActivityKind::LiquidityAdd => position.lots.push(SchemaLpLotV1 {
+                txid: ev.txid,
+                mint_height: height,
+                mint_ts: block_ts,
+                lp_tokens: ev.lp_tokens,
+                token0_deposited: ev.token0,
+                token1_deposited: ev.token1,
+                entry_value_usd: value_usd,
+                entry_sqrt_k_per_lp: ev.sqrt_k_per_lp,
+            }),
+            ActivityKind::LiquidityRemove => {
+                apply_burn(position, ev.lp_tokens, ev.token0, ev.token1, value_usd)
+            }
+            _ => {}
+        }
+    }
+
+    for ((spk, pool), position) in positions {
+        state
+            .lp_position_writes
+            .push((table.lp_position_key(&spk, &pool), encode_lp_position(&position)?));
+    }
+    Ok(())
+}
@@ -1,20 +1,45 @@
+use crate::modules::ammdata::adapters::{PoolAdapter, default_pool_adapter, pool_adapter_by_name};
 use crate::modules::ammdata::schemas::{SchemaMarketDefs, SchemaPoolSnapshot};
-use crate::modules::ammdata::storage::{AmmDataProvider, GetReservesSnapshotParams};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetPoolProtocolParams, GetReservesSnapshotParams,
+};
 use crate::runtime::state_at::StateAt;
 use crate::schemas::SchemaAlkaneId;
 use anyhow::Result;
 use std::collections::HashMap;
 
+/// Pool reserves as of `blockhash`: live for `StateAt::Latest`, the indexed snapshot for a
+/// past block.
 pub fn load_reserves_snapshot(
     provider: &AmmDataProvider,
+    blockhash: StateAt,
 ) -> Result<HashMap<SchemaAlkaneId, SchemaPoolSnapshot>> {
     let snapshot = provider
-        .get_reserves_snapshot(GetReservesSnapshotParams { blockhash: StateAt::Latest })?
+        .get_reserves_snapshot(GetReservesSnapshotParams { blockhash })?
         .snapshot
         .unwrap_or_default();
     Ok(snapshot)
 }
 
+/// Adapter for every pool in `snapshot`, from the protocol recorded when it was indexed.
+/// Pools indexed before protocols were recorded get the default adapter.
+pub fn load_pool_adapters(
+    provider: &AmmDataProvider,
+    blockhash: StateAt,
+    snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+) -> Result<HashMap<SchemaAlkaneId, &'static dyn PoolAdapter>> {
+    let mut adapters = HashMap::with_capacity(snapshot.len());
+    for pool in snapshot.keys() {
+        let adapter = provider
+            .get_pool_protocol(GetPoolProtocolParams { blockhash: blockhash.clone(), pool: *pool })?
+            .protocol
+            .map(|name| pool_adapter_by_name(&name))
+            .unwrap_or_else(default_pool_adapter);
+        adapters.insert(*pool, adapter);
+    }
+    Ok(adapters)
+}
+
 pub fn pools_map_from_snapshot(
     reserves_snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
 ) -> HashMap<SchemaAlkaneId, SchemaMarketDefs> {
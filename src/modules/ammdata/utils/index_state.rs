@@ -1,15 +1,19 @@
+use crate::modules::ammdata::adapters::PoolAdapter;
 use crate::modules::ammdata::schemas::{
     SchemaCanonicalPoolEntry, SchemaFullCandleV1, SchemaMarketDefs, SchemaPoolCreationInfoV1,
-    SchemaPoolSnapshot, SchemaTokenMetricsV1, Timeframe,
+    SchemaPoolFeesV1, SchemaPoolSnapshot, SchemaTokenMetricsV1, Timeframe,
 };
 use crate::modules::ammdata::utils::activity::{ActivityIndexAcc, ActivityWriteAcc};
 use crate::modules::ammdata::utils::candles::CandleCache;
+use crate::modules::ammdata::utils::index_pnl::SwapEvent;
+use crate::modules::ammdata::utils::index_positions::LpEvent;
 use crate::schemas::SchemaAlkaneId;
 use std::collections::{HashMap, HashSet};
 
 pub struct IndexState {
     pub reserves_snapshot: HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
     pub pools_map: HashMap<SchemaAlkaneId, SchemaMarketDefs>,
+    pub pool_adapters: HashMap<SchemaAlkaneId, &'static dyn PoolAdapter>,
 
     pub amm_factory_writes: Vec<(Vec<u8>, Vec<u8>)>,
 
@@ -22,10 +26,30 @@ pub struct IndexState {
     pub in_block_trade_volumes: HashMap<SchemaAlkaneId, (u128, u128)>,
     pub pools_touched: HashSet<SchemaAlkaneId>,
     pub has_trades: bool,
+    pub lp_supply: HashMap<SchemaAlkaneId, u128>,
+    pub lp_events: Vec<LpEvent>,
+    pub lp_position_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    pub swap_events: Vec<SwapEvent>,
+    pub address_pnl_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    pub pool_state_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    pub arbitrage_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Stale arbitrage rank entries of actors whose totals moved this block.
+    pub arbitrage_deletes: Vec<Vec<u8>>,
+    /// Swap fees per pool in raw token amounts, valued by `record_pool_fees`.
+    pub pool_fees: HashMap<SchemaAlkaneId, SchemaPoolFeesV1>,
+    pub pool_fee_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    pub trader_stats_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Stale trader rank entries, emptied window totals and expired day markers.
+    pub trader_stats_deletes: Vec<Vec<u8>>,
+    /// (pool, txid, USD value) of each swap as valued by `apply_swap_events`, and each touched
+    /// pool's TVL from `derive_pool_metrics`. Kept past `prepare_batch` for alerts.
+    pub swap_values: Vec<(SchemaAlkaneId, [u8; 32], u128)>,
+    pub pool_tvl_usd: HashMap<SchemaAlkaneId, u128>,
 
     pub pool_name_index_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub factory_pools_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub pool_factory_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    pub pool_protocol_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub pool_creation_info_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub pool_defs_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub pool_metrics_writes: Vec<(Vec<u8>, Vec<u8>)>,
@@ -35,7 +59,9 @@ pub struct IndexState {
     pub pool_lp_supply_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub pool_details_snapshot_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub tvl_versioned_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    pub timeseries_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub token_swaps_writes: Vec<(Vec<u8>, Vec<u8>)>,
+    pub pool_swaps_by_height_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub pool_creations_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub address_pool_swaps_writes: Vec<(Vec<u8>, Vec<u8>)>,
     pub address_token_swaps_writes: Vec<(Vec<u8>, Vec<u8>)>,
@@ -86,6 +112,7 @@ impl IndexState {
         Self {
             reserves_snapshot,
             pools_map,
+            pool_adapters: HashMap::new(),
             amm_factory_writes: Vec::new(),
             candle_cache: CandleCache::new(),
             activity_acc: ActivityWriteAcc::new(),
@@ -95,9 +122,24 @@ impl IndexState {
             in_block_trade_volumes: HashMap::new(),
             pools_touched: HashSet::new(),
             has_trades: false,
+            lp_supply: HashMap::new(),
+            lp_events: Vec::new(),
+            lp_position_writes: Vec::new(),
+            swap_events: Vec::new(),
+            address_pnl_writes: Vec::new(),
+            pool_state_writes: Vec::new(),
+            arbitrage_writes: Vec::new(),
+            arbitrage_deletes: Vec::new(),
+            pool_fees: HashMap::new(),
+            pool_fee_writes: Vec::new(),
+            trader_stats_writes: Vec::new(),
+            trader_stats_deletes: Vec::new(),
+            swap_values: Vec::new(),
+            pool_tvl_usd: HashMap::new(),
             pool_name_index_writes: Vec::new(),
             factory_pools_writes: Vec::new(),
             pool_factory_writes: Vec::new(),
+            pool_protocol_writes: Vec::new(),
             pool_creation_info_writes: Vec::new(),
             pool_defs_writes: Vec::new(),
             pool_metrics_writes: Vec::new(),
@@ -107,7 +149,9 @@ impl IndexState {
             pool_lp_supply_writes: Vec::new(),
             pool_details_snapshot_writes: Vec::new(),
             tvl_versioned_writes: Vec::new(),
+            timeseries_writes: Vec::new(),
             token_swaps_writes: Vec::new(),
+            pool_swaps_by_height_writes: Vec::new(),
             pool_creations_writes: Vec::new(),
             address_pool_swaps_writes: Vec::new(),
             address_token_swaps_writes: Vec::new(),
@@ -1,18 +1,16 @@
 use crate::modules::ammdata::config::{DerivedMergeStrategy, DerivedQuoteConfig};
 use crate::modules::ammdata::consts::{AMOUNT_SCALE, CanonicalQuoteUnit, PRICE_SCALE};
-use crate::modules::ammdata::price_feeds::{
-    PriceFeed, UniswapPriceFeed, get_historical_btc_usd_price,
-};
+use crate::modules::ammdata::price_feeds::{FeedContext, PriceFeedSpec, fetch_price};
 use crate::modules::ammdata::schemas::{
     SchemaCandleV1, SchemaCanonicalPoolEntry, SchemaFullCandleV1, SchemaTokenMetricsV1, Timeframe,
     active_timeframes,
 };
 use crate::modules::ammdata::storage::{
-    AmmDataProvider, GetListEntriesDescParams, GetRawValueParams, SchemaChartChangeSetV1,
-    SchemaChartChangeValueV1, SearchIndexField, TokenMetricsIndexField, decode_candle_v1,
-    decode_chart_change_set_v1, decode_full_candle_v1, decode_token_metrics, decode_u128_value,
-    encode_alkane_id_be, encode_candle_v1, encode_chart_change_set_v1, encode_token_metrics,
-    encode_u128_value, parse_change_basis_points,
+    AmmDataProvider, GetListEntriesDescParams, GetRangeEntriesParams, GetRawValueParams,
+    SchemaChartChangeSetV1, SchemaChartChangeValueV1, SearchIndexField, TokenMetricsIndexField,
+    decode_alkane_id_be, decode_candle_v1, decode_chart_change_set_v1, decode_full_candle_v1,
+    decode_token_metrics, decode_u128_value, encode_alkane_id_be, encode_candle_v1,
+    encode_chart_change_set_v1, encode_token_metrics, encode_u128_value, parse_change_basis_points,
 };
 use crate::modules::ammdata::utils::candles::bucket_start_for;
 use crate::modules::ammdata::utils::index_state::IndexState;
@@ -21,6 +19,7 @@ use crate::modules::essentials::storage::{
     EssentialsProvider, GetCreationRecordParams, GetRawValueParams as EssentialsGetRawValueParams,
 };
 use crate::runtime::state_at::StateAt;
+use crate::runtime::tree_db::prefix_end_exclusive;
 use crate::schemas::SchemaAlkaneId;
 use anyhow::Result;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -224,7 +223,7 @@ pub fn derive_token_data(
     essentials: &EssentialsProvider,
     canonical_quote_units: &HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
     derived_quotes: &[DerivedQuoteConfig],
-    use_historical_backfill: bool,
+    price_feed: &PriceFeedSpec,
     search_index_enabled: bool,
     search_prefix_min: usize,
     search_prefix_max: usize,
@@ -240,30 +239,16 @@ pub fn derive_token_data(
     // ---------- btc/usd price ----------
     if state.has_trades {
         let mut price: Option<u128> = None;
-        if use_historical_backfill {
-            match get_historical_btc_usd_price(height as u64) {
-                Ok(Some(v)) => price = Some(v),
-                Ok(None) => {}
-                Err(e) => {
-                    eprintln!(
-                        "[AMMDATA] btc/usd historical backfill failed at height {height}: {e:?}"
-                    );
-                }
+        let fetched = {
+            let feed = price_feed.build(&FeedContext { reserves: &state.reserves_snapshot });
+            fetch_price(feed.as_ref(), height as u64)
+        };
+        match fetched {
+            Ok(v) => price = Some(v),
+            Err(e) => {
+                eprintln!("[AMMDATA] btc/usd price_feed failed at height {height}: {e:?}");
             }
         }
-        if price.is_none() {
-            match UniswapPriceFeed::from_global_config() {
-                Ok(feed) => match feed.get_bitcoin_price_usd_at_block_height(height as u64) {
-                    Ok(v) => price = Some(v),
-                    Err(e) => {
-                        eprintln!("[AMMDATA] btc/usd price_feed failed at height {height}: {e:?}");
-                    }
-                },
-                Err(e) => {
-                    eprintln!("[AMMDATA] btc/usd price_feed init failed at height {height}: {e:?}")
-                }
-            };
-        }
 
         if price.is_none() {
             let key = table.btc_usd_price_key(height as u64);
@@ -1468,6 +1453,14 @@ pub fn derive_token_data(
                                 token,
                             ),
                         ),
+                        (
+                            TokenMetricsIndexField::FdvUsd,
+                            table.token_metrics_index_key_u128(
+                                TokenMetricsIndexField::FdvUsd,
+                                m.fdv_usd,
+                                token,
+                            ),
+                        ),
                         (
                             TokenMetricsIndexField::Volume1d,
                             table.token_metrics_index_key_u128(
@@ -2063,3 +2056,48 @@ pub fn derive_token_data(
 
     Ok(())
 }
+
+/// Adds every token with stored metrics to the `fdv` sort index, once. Tokens whose metrics
+/// were written before that index existed are otherwise missing from it until they trade
+/// again. Runs after `derive_token_data` so an fdv key this block retires is not re-added.
+pub fn backfill_fdv_index(provider: &AmmDataProvider, state: &mut IndexState) -> Result<usize> {
+    let table = provider.table();
+    let marker = table.TOKEN_METRICS_FDV_BACKFILLED.key().to_vec();
+    let done = provider
+        .get_raw_value(GetRawValueParams { blockhash: StateAt::Latest, key: marker.clone() })?
+        .value
+        .is_some();
+    if done {
+        return Ok(0);
+    }
+    let prefix = table.TOKEN_METRICS.key().to_vec();
+    let entries = provider
+        .get_range_entries(GetRangeEntriesParams {
+            blockhash: StateAt::Latest,
+            start: prefix.clone(),
+            end: prefix_end_exclusive(&prefix),
+            limit: usize::MAX,
+            reverse: false,
+        })?
+        .entries;
+    let retired: HashSet<Vec<u8>> = state.token_metrics_index_deletes.iter().cloned().collect();
+    let mut added = 0usize;
+    for (key, raw) in entries {
+        let Some(token) = key.get(prefix.len()..).and_then(decode_alkane_id_be) else {
+            continue;
+        };
+        let Ok(metrics) = decode_token_metrics(&raw) else { continue };
+        let index_key = table.token_metrics_index_key_u128(
+            TokenMetricsIndexField::FdvUsd,
+            metrics.fdv_usd,
+            &token,
+        );
+        if retired.contains(&index_key) {
+            continue;
+        }
+        state.token_metrics_index_writes.push((index_key, Vec::new()));
+        added += 1;
+    }
+    state.token_metrics_index_writes.push((marker, vec![1]));
+    Ok(added)
+}
@@ -0,0 +1,190 @@
+use crate::modules::ammdata::schemas::SchemaTraderStatsV1;
+
+/// Leaderboard windows as (label, UTC days); 0 days is all-time.
+pub const LEADERBOARD_WINDOWS: [(&str, u32); 4] = [("1d", 1), ("7d", 7), ("30d", 30), ("all", 0)];
+
+/// Day stats stay marked for expiry until they leave the longest window.
+pub const LONGEST_WINDOW_DAYS: u32 = 30;
+
+pub fn window_days(label: &str) -> Option<u32> {
+    LEADERBOARD_WINDOWS.iter().find(|(l, _)| *l == label).map(|(_, days)| *days)
+}
+
+/// First UTC day of a `days`-day window ending on `last_day` (inclusive).
+pub fn window_start(last_day: u64, days: u32) -> u64 {
+    last_day.saturating_sub(u64::from(days.saturating_sub(1)) * 86_400)
+}
+
+/// Whose traders a leaderboard covers. Pools and tokens are both alkanes (an LP token can
+/// trade in another pool), so the scope is part of the key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LeaderboardScope {
+    Pool,
+    Token,
+}
+
+impl LeaderboardScope {
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            b'p' => Some(LeaderboardScope::Pool),
+            b't' => Some(LeaderboardScope::Token),
+            _ => None,
+        }
+    }
+
+    pub fn tag(&self) -> u8 {
+        match self {
+            LeaderboardScope::Pool => b'p',
+            LeaderboardScope::Token => b't',
+        }
+    }
+}
+
+/// What a leaderboard ranks addresses by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaderboardMetric {
+    Volume,
+    Trades,
+    NetBuy,
+    NetSell,
+    /// Liquidity added minus removed (USD). All-time pool boards rank by LP share instead.
+    Liquidity,
+}
+
+pub const LEADERBOARD_METRICS: [LeaderboardMetric; 5] = [
+    LeaderboardMetric::Volume,
+    LeaderboardMetric::Trades,
+    LeaderboardMetric::NetBuy,
+    LeaderboardMetric::NetSell,
+    LeaderboardMetric::Liquidity,
+];
+
+impl LeaderboardMetric {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "volume" => Some(LeaderboardMetric::Volume),
+            "trades" => Some(LeaderboardMetric::Trades),
+            "net_buy" => Some(LeaderboardMetric::NetBuy),
+            "net_sell" => Some(LeaderboardMetric::NetSell),
+            "lp" => Some(LeaderboardMetric::Liquidity),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardMetric::Volume => "volume",
+            LeaderboardMetric::Trades => "trades",
+            LeaderboardMetric::NetBuy => "net_buy",
+            LeaderboardMetric::NetSell => "net_sell",
+            LeaderboardMetric::Liquidity => "lp",
+        }
+    }
+
+    /// Key tag of the metric's rank index.
+    pub fn code(&self) -> u8 {
+        match self {
+            LeaderboardMetric::Volume => 0,
+            LeaderboardMetric::Trades => 1,
+            LeaderboardMetric::NetBuy => 2,
+            LeaderboardMetric::NetSell => 3,
+            LeaderboardMetric::Liquidity => 4,
+        }
+    }
+
+    /// Ranking score; addresses scoring 0 or less are left off the board.
+    pub fn score(&self, stats: &SchemaTraderStatsV1) -> i128 {
+        match self {
+            LeaderboardMetric::Volume => to_i128(stats.volume_usd),
+            LeaderboardMetric::Trades => stats.trades as i128,
+            LeaderboardMetric::NetBuy => net_buy_usd(stats),
+            LeaderboardMetric::NetSell => net_buy_usd(stats).saturating_neg(),
+            LeaderboardMetric::Liquidity => {
+                to_i128(stats.lp_added_usd).saturating_sub(to_i128(stats.lp_removed_usd))
+            }
+        }
+    }
+}
+
+/// Bought minus sold, in USD.
+pub fn net_buy_usd(stats: &SchemaTraderStatsV1) -> i128 {
+    to_i128(stats.bought_usd).saturating_sub(to_i128(stats.sold_usd))
+}
+
+pub fn add_stats(total: &mut SchemaTraderStatsV1, stats: &SchemaTraderStatsV1) {
+    total.volume_usd = total.volume_usd.saturating_add(stats.volume_usd);
+    total.trades = total.trades.saturating_add(stats.trades);
+    total.bought_usd = total.bought_usd.saturating_add(stats.bought_usd);
+    total.sold_usd = total.sold_usd.saturating_add(stats.sold_usd);
+    total.lp_added_usd = total.lp_added_usd.saturating_add(stats.lp_added_usd);
+    total.lp_removed_usd = total.lp_removed_usd.saturating_add(stats.lp_removed_usd);
+}
+
+/// Takes a day's stats back out of a window total.
+pub fn sub_stats(total: &mut SchemaTraderStatsV1, stats: &SchemaTraderStatsV1) {
+    total.volume_usd = total.volume_usd.saturating_sub(stats.volume_usd);
+    total.trades = total.trades.saturating_sub(stats.trades);
+    total.bought_usd = total.bought_usd.saturating_sub(stats.bought_usd);
+    total.sold_usd = total.sold_usd.saturating_sub(stats.sold_usd);
+    total.lp_added_usd = total.lp_added_usd.saturating_sub(stats.lp_added_usd);
+    total.lp_removed_usd = total.lp_removed_usd.saturating_sub(stats.lp_removed_usd);
+}
+
+fn to_i128(v: u128) -> i128 {
+    i128::try_from(v).unwrap_or(i128::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trader(
+        volume_usd: u128,
+        trades: u64,
+        bought_usd: u128,
+        sold_usd: u128,
+    ) -> SchemaTraderStatsV1 {
+        SchemaTraderStatsV1 { volume_usd, trades, bought_usd, sold_usd, ..Default::default() }
+    }
+
+    #[test]
+    fn scores_by_each_metric() {
+        let alice = trader(500, 2, 400, 100);
+        let bob = trader(900, 9, 100, 800);
+        let carol = trader(500, 1, 250, 250);
+        assert_eq!(LeaderboardMetric::Volume.score(&bob), 900);
+        assert_eq!(LeaderboardMetric::Trades.score(&alice), 2);
+        assert_eq!(LeaderboardMetric::NetBuy.score(&alice), 300);
+        assert_eq!(LeaderboardMetric::NetSell.score(&bob), 700);
+        // Carol broke even, so she is on neither side.
+        assert_eq!(LeaderboardMetric::NetBuy.score(&carol), 0);
+        assert_eq!(LeaderboardMetric::NetSell.score(&carol), 0);
+        assert_eq!(LeaderboardMetric::Liquidity.score(&carol), 0);
+        let codes: Vec<u8> = LEADERBOARD_METRICS.iter().map(|m| m.code()).collect();
+        assert_eq!(codes, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn stats_accumulate() {
+        let mut total = trader(1, 1, 1, 0);
+        add_stats(&mut total, &SchemaTraderStatsV1 { lp_added_usd: 7, ..trader(2, 1, 0, 2) });
+        assert_eq!(total.volume_usd, 3);
+        assert_eq!(total.trades, 2);
+        assert_eq!(net_buy_usd(&total), -1);
+        assert_eq!(LeaderboardMetric::Liquidity.score(&total), 7);
+        assert_eq!(window_days("7d"), Some(7));
+        assert_eq!(window_days("all"), Some(0));
+        assert_eq!(window_days("90d"), None);
+        sub_stats(&mut total, &trader(5, 1, 0, 0));
+        assert_eq!(total.volume_usd, 0);
+        assert_eq!(total.trades, 1);
+    }
+
+    #[test]
+    fn windows_end_on_the_last_day() {
+        let day = 1_700_006_400;
+        assert_eq!(window_start(day, 1), day);
+        assert_eq!(window_start(day, 7), day - 6 * 86_400);
+        assert_eq!(window_start(86_400, 30), 0);
+    }
+}
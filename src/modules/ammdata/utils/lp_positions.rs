@@ -0,0 +1,208 @@
+use crate::modules::ammdata::consts::{AMOUNT_SCALE, CanonicalQuoteUnit, PRICE_SCALE};
+use crate::modules::ammdata::schemas::{SchemaLpLotV1, SchemaLpPositionV1};
+use crate::schemas::SchemaAlkaneId;
+use std::collections::HashMap;
+
+/// a * b / d with a saturating product; 0 when `d` is 0.
+#[inline]
+pub fn mul_div(a: u128, b: u128, d: u128) -> u128 {
+    if d == 0 { 0 } else { a.saturating_mul(b) / d }
+}
+
+/// sqrt(a * b), falling back to sqrt(a) * sqrt(b) when the product overflows.
+pub fn sqrt_product(a: u128, b: u128) -> u128 {
+    match a.checked_mul(b) {
+        Some(p) => p.isqrt(),
+        None => a.isqrt().saturating_mul(b.isqrt()),
+    }
+}
+
+/// sqrt(reserve0 * reserve1) per LP token, PRICE_SCALE fixed point.
+pub fn sqrt_k_per_lp(reserve0: u128, reserve1: u128, lp_supply: u128) -> u128 {
+    mul_div(sqrt_product(reserve0, reserve1), PRICE_SCALE, lp_supply)
+}
+
+/// USD price of one whole token (PRICE_SCALE). Canonical quotes are priced by their unit;
+/// everything else uses `metrics_price_usd`.
+pub fn token_price_usd(
+    token: &SchemaAlkaneId,
+    canonical_quote_units: &HashMap<SchemaAlkaneId, CanonicalQuoteUnit>,
+    btc_usd_price: Option<u128>,
+    metrics_price_usd: impl FnOnce() -> u128,
+) -> u128 {
+    match canonical_quote_units.get(token) {
+        Some(CanonicalQuoteUnit::Usd) => PRICE_SCALE,
+        Some(CanonicalQuoteUnit::Btc) => btc_usd_price.unwrap_or(0),
+        None => metrics_price_usd(),
+    }
+}
+
+#[inline]
+pub fn amount_value_usd(amount: u128, price_usd: u128) -> u128 {
+    amount.saturating_mul(price_usd) / AMOUNT_SCALE
+}
+
+/// Burns `lp_tokens` from the position's lots oldest first and books the realized PnL.
+///
+/// LP received by transfer has no lot, so only the share of the burn covered by lots
+/// counts toward realized PnL; the withdrawal totals include the whole burn.
+pub fn apply_burn(
+    position: &mut SchemaLpPositionV1,
+    lp_tokens: u128,
+    token0: u128,
+    token1: u128,
+    value_usd: u128,
+) {
+    let mut remaining = lp_tokens;
+    let mut matched = 0u128;
+    let mut cost_usd = 0u128;
+    while remaining > 0 && !position.lots.is_empty() {
+        let lot = &mut position.lots[0];
+        if remaining >= lot.lp_tokens {
+            remaining -= lot.lp_tokens;
+            matched = matched.saturating_add(lot.lp_tokens);
+            cost_usd = cost_usd.saturating_add(lot.entry_value_usd);
+            position.lots.remove(0);
+            continue;
+        }
+        let take = remaining;
+        let cost = mul_div(lot.entry_value_usd, take, lot.lp_tokens);
+        lot.token0_deposited -= mul_div(lot.token0_deposited, take, lot.lp_tokens);
+        lot.token1_deposited -= mul_div(lot.token1_deposited, take, lot.lp_tokens);
+        lot.entry_value_usd -= cost;
+        lot.lp_tokens -= take;
+        matched = matched.saturating_add(take);
+        cost_usd = cost_usd.saturating_add(cost);
+        remaining = 0;
+    }
+
+    position.lp_burned = position.lp_burned.saturating_add(lp_tokens);
+    position.token0_withdrawn = position.token0_withdrawn.saturating_add(token0);
+    position.token1_withdrawn = position.token1_withdrawn.saturating_add(token1);
+    position.withdrawn_value_usd = position.withdrawn_value_usd.saturating_add(value_usd);
+    let matched_value = mul_div(value_usd, matched, lp_tokens);
+    position.realized_pnl_usd = position
+        .realized_pnl_usd
+        .saturating_add(matched_value as i128 - cost_usd as i128);
+}
+
+/// Pool state a position is valued against.
+pub struct LpPoolState {
+    pub reserve0: u128,
+    pub reserve1: u128,
+    pub lp_supply: u128,
+    pub price0_usd: u128,
+    pub price1_usd: u128,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct LpValuation {
+    pub lp_tokens: u128,
+    pub underlying0: u128,
+    pub underlying1: u128,
+    pub value_usd: u128,
+    pub entry_value_usd: u128,
+    /// Value today of the tokens originally deposited, had they been held instead.
+    pub hodl_value_usd: u128,
+    pub fees0: u128,
+    pub fees1: u128,
+    pub fees_value_usd: u128,
+    /// (value - fees) - hodl value; negative when providing liquidity lost to holding.
+    pub impermanent_loss_usd: i128,
+    pub unrealized_pnl_usd: i128,
+}
+
+/// Values the open lots of a position. Fees are the part of each lot's underlying that
+/// comes from sqrt(k) per LP growing since its mint.
+pub fn value_position(position: &SchemaLpPositionV1, pool: &LpPoolState) -> LpValuation {
+    let now_k = sqrt_k_per_lp(pool.reserve0, pool.reserve1, pool.lp_supply);
+    let mut out = LpValuation::default();
+    let mut deposited0 = 0u128;
+    let mut deposited1 = 0u128;
+    for lot in &position.lots {
+        let u0 = mul_div(pool.reserve0, lot.lp_tokens, pool.lp_supply);
+        let u1 = mul_div(pool.reserve1, lot.lp_tokens, pool.lp_supply);
+        if now_k > lot.entry_sqrt_k_per_lp && lot.entry_sqrt_k_per_lp > 0 {
+            let growth = now_k - lot.entry_sqrt_k_per_lp;
+            out.fees0 = out.fees0.saturating_add(mul_div(u0, growth, now_k));
+            out.fees1 = out.fees1.saturating_add(mul_div(u1, growth, now_k));
+        }
+        out.lp_tokens = out.lp_tokens.saturating_add(lot.lp_tokens);
+        out.underlying0 = out.underlying0.saturating_add(u0);
+        out.underlying1 = out.underlying1.saturating_add(u1);
+        out.entry_value_usd = out.entry_value_usd.saturating_add(lot.entry_value_usd);
+        deposited0 = deposited0.saturating_add(lot.token0_deposited);
+        deposited1 = deposited1.saturating_add(lot.token1_deposited);
+    }
+
+    let value = |a: u128, b: u128| {
+        amount_value_usd(a, pool.price0_usd).saturating_add(amount_value_usd(b, pool.price1_usd))
+    };
+    out.value_usd = value(out.underlying0, out.underlying1);
+    out.fees_value_usd = value(out.fees0, out.fees1);
+    out.hodl_value_usd = value(deposited0, deposited1);
+    out.impermanent_loss_usd =
+        out.value_usd as i128 - out.fees_value_usd as i128 - out.hodl_value_usd as i128;
+    out.unrealized_pnl_usd = out.value_usd as i128 - out.entry_value_usd as i128;
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lot(lp_tokens: u128, token0: u128, token1: u128, entry_value_usd: u128) -> SchemaLpLotV1 {
+        SchemaLpLotV1 {
+            lp_tokens,
+            token0_deposited: token0,
+            token1_deposited: token1,
+            entry_value_usd,
+            entry_sqrt_k_per_lp: PRICE_SCALE,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn burns_consume_lots_fifo() {
+        let mut pos = SchemaLpPositionV1 {
+            lots: vec![lot(100, 1_000, 2_000, 500), lot(100, 1_000, 2_000, 700)],
+            ..Default::default()
+        };
+        apply_burn(&mut pos, 150, 1_600, 3_000, 900);
+        assert_eq!(pos.lots.len(), 1);
+        assert_eq!(pos.lots[0].lp_tokens, 50);
+        assert_eq!(pos.lots[0].token0_deposited, 500);
+        assert_eq!(pos.lots[0].entry_value_usd, 350);
+        // 900 withdrawn against 500 + 350 of cost basis.
+        assert_eq!(pos.realized_pnl_usd, 50);
+
+        // 50 tracked + 50 untracked: only half of the withdrawal has a cost basis.
+        apply_burn(&mut pos, 100, 1_000, 2_000, 1_000);
+        assert!(pos.lots.is_empty());
+        assert_eq!(pos.realized_pnl_usd, 50 + 500 - 350);
+        assert_eq!(pos.lp_burned, 250);
+    }
+
+    #[test]
+    fn valuation_splits_fees_from_impermanent_loss() {
+        // 10% of a 1000/1000 pool with 1000 LP outstanding.
+        let mut entry = lot(100, 100 * AMOUNT_SCALE, 100 * AMOUNT_SCALE, 200 * PRICE_SCALE);
+        entry.entry_sqrt_k_per_lp =
+            sqrt_k_per_lp(1_000 * AMOUNT_SCALE, 1_000 * AMOUNT_SCALE, 1_000);
+        let pos = SchemaLpPositionV1 { lots: vec![entry], ..Default::default() };
+        // Price of token0 doubled and sqrt(k) grew 10% from fees.
+        let pool = LpPoolState {
+            reserve0: 778 * AMOUNT_SCALE,
+            reserve1: 1_556 * AMOUNT_SCALE,
+            lp_supply: 1_000,
+            price0_usd: 2 * PRICE_SCALE,
+            price1_usd: PRICE_SCALE,
+        };
+        let v = value_position(&pos, &pool);
+        assert_eq!(v.underlying0, 77 * AMOUNT_SCALE + 80_000_000);
+        assert!(v.fees0 > 0 && v.fees1 > 0);
+        assert_eq!(v.hodl_value_usd, 300 * PRICE_SCALE);
+        assert!(v.impermanent_loss_usd < 0);
+        assert_eq!(v.unrealized_pnl_usd, v.value_usd as i128 - 200 * PRICE_SCALE as i128);
+    }
+}
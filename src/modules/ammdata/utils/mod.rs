@@ -1,14 +1,29 @@
 pub mod activity;
+pub mod alerts;
+pub mod arbitrage;
 pub mod candles;
 pub mod index_activity;
+pub mod index_alerts;
+pub mod index_arbitrage;
 pub mod index_factories;
 pub mod index_finalize;
+pub mod index_leaderboards;
+pub mod index_pnl;
+pub mod index_pool_fees;
 pub mod index_pool_metrics;
+pub mod index_pool_state;
 pub mod index_pools;
+pub mod index_positions;
 pub mod index_snapshot;
 pub mod index_state;
 pub mod index_tokens;
+pub mod leaderboards;
 pub mod live_reserves;
+pub mod lp_positions;
 pub mod pathfinder;
+pub mod pnl;
+pub mod pool_fees;
 pub mod reserves;
+pub mod screener;
 pub mod search;
+pub mod twap;
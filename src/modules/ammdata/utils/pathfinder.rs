@@ -1,67 +1,92 @@
 // src/modules/ammdata/pathfinder.rs
 
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::{BinaryHeap, HashMap, HashSet};
 
+use crate::modules::ammdata::adapters::{PoolAdapter, default_pool_adapter};
+use crate::modules::ammdata::consts::PRICE_SCALE;
 use crate::modules::ammdata::schemas::SchemaPoolSnapshot;
 use crate::schemas::SchemaAlkaneId;
 
 /// Default per-hop fee in basis points (0.5% = 50 bps).
 pub const DEFAULT_FEE_BPS: u32 = 50;
 
+/// Upper bound on the number of chunks an order is split into by the split planners.
+pub const SPLIT_MAX_PARTS: u32 = 100;
+
+/// Upper bound on simple paths enumerated before ranking split candidates.
+const SPLIT_MAX_CANDIDATES: usize = 256;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SplitParams {
+    /// Fee charged on every hop; None charges each pool's own adapter fee.
+    pub fee_bps: Option<u32>,
+    pub max_hops: usize,
+    /// Maximum number of parallel paths the order may be spread over.
+    pub max_routes: usize,
+    /// Number of chunks the order is cut into (granularity of the split).
+    pub parts: u32,
+}
+
+/// Pools a route may cross: the single-key reserves snapshot (event-derived or live) plus
+/// the adapter that quotes each pool. Pools missing from `adapters` use the default adapter.
+#[derive(Clone, Copy)]
+pub struct RoutePools<'a> {
+    pub snapshot: &'a HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    pub adapters: &'a HashMap<SchemaAlkaneId, &'static dyn PoolAdapter>,
+}
+
 /* --------------------------------------------------------------------------------
    Public API (three planners)
-   NOTE: These functions accept the single-key snapshot map:
-         HashMap<SchemaAlkaneId /*pool*/, SchemaPoolSnapshot>
-   This works for both event-derived and "is_latest" live-corrected snapshots,
-   since they share the same schema and keys.
+   NOTE: Hops are quoted by each pool's adapter. `fee_bps: Some(n)` charges n on every
+   hop instead of the adapter's own fee (what-if quotes).
 -------------------------------------------------------------------------------- */
 
 pub fn plan_swap_exact_tokens_for_tokens(
-    snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    pools: RoutePools<'_>,
     token_in: SchemaAlkaneId,
     token_out: SchemaAlkaneId,
     amount_in: u128,
     amount_out_min: u128,
-    fee_bps: u32,
+    fee_bps: Option<u32>,
     max_hops: usize,
 ) -> Option<PathQuote> {
     if amount_in == 0 || token_in == token_out {
         return None;
     }
-    let g = Graph::from_snapshot(snapshot);
-    let q = best_first_exact_in(&g, token_in, token_out, amount_in, fee_bps, max_hops)?;
+    let g = Graph::new(pools, fee_bps);
+    let q = best_first_exact_in(&g, token_in, token_out, amount_in, max_hops)?;
     if q.amount_out >= amount_out_min { Some(q) } else { None }
 }
 
 pub fn plan_swap_tokens_for_exact_tokens(
-    snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    pools: RoutePools<'_>,
     token_in: SchemaAlkaneId,
     token_out: SchemaAlkaneId,
     amount_out: u128,
     amount_in_max: u128,
-    fee_bps: u32,
+    fee_bps: Option<u32>,
     max_hops: usize,
 ) -> Option<PathQuote> {
     if amount_out == 0 || token_in == token_out {
         return None;
     }
-    let g = Graph::from_snapshot(snapshot);
-    let q = best_first_exact_out(&g, token_in, token_out, amount_out, fee_bps, max_hops)?;
+    let g = Graph::new(pools, fee_bps);
+    let q = best_first_exact_out(&g, token_in, token_out, amount_out, max_hops)?;
     if q.amount_in <= amount_in_max { Some(q) } else { None }
 }
 
 pub fn plan_swap_exact_tokens_for_tokens_implicit(
-    snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    pools: RoutePools<'_>,
     token_in: SchemaAlkaneId,
     token_out: SchemaAlkaneId,
     available_in: u128,
     amount_out_min: u128,
-    fee_bps: u32,
+    fee_bps: Option<u32>,
     max_hops: usize,
 ) -> Option<PathQuote> {
     plan_swap_exact_tokens_for_tokens(
-        snapshot,
+        pools,
         token_in,
         token_out,
         available_in,
@@ -71,10 +96,10 @@ pub fn plan_swap_exact_tokens_for_tokens_implicit(
     )
 }
 
-/* ---------- Convenience wrappers using DEFAULT_FEE_BPS ---------- */
+/* ---------- Convenience wrappers charging each pool's adapter fee ---------- */
 
 pub fn plan_exact_in_default_fee(
-    snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    pools: RoutePools<'_>,
     token_in: SchemaAlkaneId,
     token_out: SchemaAlkaneId,
     amount_in: u128,
@@ -82,18 +107,18 @@ pub fn plan_exact_in_default_fee(
     max_hops: usize,
 ) -> Option<PathQuote> {
     plan_swap_exact_tokens_for_tokens(
-        snapshot,
+        pools,
         token_in,
         token_out,
         amount_in,
         amount_out_min,
-        DEFAULT_FEE_BPS,
+        None,
         max_hops,
     )
 }
 
 pub fn plan_exact_out_default_fee(
-    snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    pools: RoutePools<'_>,
     token_in: SchemaAlkaneId,
     token_out: SchemaAlkaneId,
     amount_out: u128,
@@ -101,18 +126,38 @@ pub fn plan_exact_out_default_fee(
     max_hops: usize,
 ) -> Option<PathQuote> {
     plan_swap_tokens_for_exact_tokens(
-        snapshot,
+        pools,
         token_in,
         token_out,
         amount_out,
         amount_in_max,
-        DEFAULT_FEE_BPS,
+        None,
         max_hops,
     )
 }
 
+/// Price impact of a single-path quote against the pre-trade reserves in `pools`, at each
+/// pool's adapter fee and spot price. Exact-out quotes compare the input paid with the input
+/// the spot rate implies, so both modes read as "share of value lost to the curve".
+pub fn quote_price_impact_default_fee(
+    pools: RoutePools<'_>,
+    quote: &PathQuote,
+    exact_out: bool,
+) -> Option<f64> {
+    let g = Graph::new(pools, None);
+    let rate = spot_rate(&g, &edges_of(quote))?;
+    if rate <= 0.0 {
+        return None;
+    }
+    Some(if exact_out {
+        impact(quote.amount_out as f64 / rate, quote.amount_in as f64)
+    } else {
+        impact(quote.amount_out as f64, quote.amount_in as f64 * rate)
+    })
+}
+
 pub fn plan_implicit_default_fee(
-    snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    pools: RoutePools<'_>,
     token_in: SchemaAlkaneId,
     token_out: SchemaAlkaneId,
     available_in: u128,
@@ -120,12 +165,12 @@ pub fn plan_implicit_default_fee(
     max_hops: usize,
 ) -> Option<PathQuote> {
     plan_swap_exact_tokens_for_tokens_implicit(
-        snapshot,
+        pools,
         token_in,
         token_out,
         available_in,
         amount_out_min,
-        DEFAULT_FEE_BPS,
+        None,
         max_hops,
     )
 }
@@ -140,12 +185,12 @@ pub fn plan_implicit_default_fee(
 ///
 /// Returns `Some(PathQuote)` only if max profit > 0.
 pub fn plan_best_mev_swap(
-    snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    pools: RoutePools<'_>,
     token: SchemaAlkaneId,
-    fee_bps: u32,
+    fee_bps: Option<u32>,
     max_hops: usize,
 ) -> Option<PathQuote> {
-    let g = Graph::from_snapshot(snapshot);
+    let g = Graph::new(pools, fee_bps);
 
     // Enumerate simple cycles (no token repeats) up to max_hops, length >= 2.
     let cycles = enumerate_cycles(&g, token, max_hops);
@@ -155,12 +200,12 @@ pub fn plan_best_mev_swap(
 
     for path in cycles {
         // Determine a safe cap for input search: 1/3 of the tightest inbound reserve across hops.
-        let cap = cap_for_path(&g, &path, fee_bps);
+        let cap = cap_for_path(&g, &path);
         if cap == 0 {
             continue;
         }
 
-        if let Some(q) = optimize_exact_in_on_path(&g, &path, fee_bps, cap) {
+        if let Some(q) = optimize_exact_in_on_path(&g, &path, cap) {
             let profit = q.amount_out as i128 - q.amount_in as i128;
             if profit > best_profit {
                 best_profit = profit;
@@ -172,6 +217,144 @@ pub fn plan_best_mev_swap(
     if best_profit > 0 { best } else { None }
 }
 
+/* --------------------------------------------------------------------------------
+   Split routing across parallel paths
+-------------------------------------------------------------------------------- */
+
+/// Split an exact-in order across up to `max_routes` paths.
+///
+/// The order is cut into `parts` equal chunks; each chunk goes to the path with the best
+/// marginal output against reserves already moved by the previous chunks (discrete
+/// water-filling), so paths sharing a pool are priced correctly. Falls back to the
+/// single best path if splitting does not beat it.
+pub fn plan_split_exact_in(
+    pools: RoutePools<'_>,
+    token_in: SchemaAlkaneId,
+    token_out: SchemaAlkaneId,
+    amount_in: u128,
+    amount_out_min: u128,
+    params: SplitParams,
+) -> Option<SplitQuote> {
+    if amount_in == 0 || token_in == token_out {
+        return None;
+    }
+    let SplitParams { fee_bps, max_hops, max_routes, parts } = params;
+    let g = Graph::new(pools, fee_bps);
+    let parts = parts.clamp(1, SPLIT_MAX_PARTS) as u128;
+    let chunk = (amount_in / parts).max(1);
+
+    // Candidates: paths with the best output for the first chunk.
+    let mut ranked: Vec<(u128, Vec<Edge>)> = enumerate_paths(&g, token_in, token_out, max_hops)
+        .into_iter()
+        .filter_map(|p| {
+            let q = pathquote_from_edges_exact_in(&g, &p, chunk)?;
+            (q.amount_out > 0).then_some((q.amount_out, p))
+        })
+        .collect();
+    ranked.sort_by_key(|(out, _)| Reverse(*out));
+    ranked.truncate(max_routes.max(1));
+    let candidates: Vec<Vec<Edge>> = ranked.into_iter().map(|(_, p)| p).collect();
+
+    let mut state = g.clone();
+    let mut alloc = vec![0u128; candidates.len()];
+    let mut remaining = amount_in;
+    while remaining > 0 {
+        let step = chunk.min(remaining);
+        let best = candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| pathquote_from_edges_exact_in(&state, p, step).map(|q| (i, q)))
+            .max_by_key(|(_, q)| q.amount_out);
+        let Some((i, q)) = best.filter(|(_, q)| q.amount_out > 0) else { break };
+        state.apply_quote(&q);
+        alloc[i] += step;
+        remaining -= step;
+    }
+
+    let split = if remaining == 0 {
+        let legs: Vec<(&[Edge], u128)> =
+            candidates.iter().zip(&alloc).map(|(p, a)| (p.as_slice(), *a)).collect();
+        assemble_split_exact_in(&g, &legs)
+    } else {
+        None
+    };
+    let single = best_first_exact_in(&g, token_in, token_out, amount_in, max_hops).and_then(|q| {
+        let edges = edges_of(&q);
+        assemble_split_exact_in(&g, &[(edges.as_slice(), amount_in)])
+    });
+
+    let best = match (split, single) {
+        (Some(s), Some(one)) => Some(if one.amount_out > s.amount_out { one } else { s }),
+        (s, one) => s.or(one),
+    }?;
+    if best.amount_out >= amount_out_min { Some(best) } else { None }
+}
+
+/// Split an exact-out order across up to `max_routes` paths, buying each output chunk
+/// on the path with the lowest marginal input. See `plan_split_exact_in`.
+pub fn plan_split_exact_out(
+    pools: RoutePools<'_>,
+    token_in: SchemaAlkaneId,
+    token_out: SchemaAlkaneId,
+    amount_out: u128,
+    amount_in_max: u128,
+    params: SplitParams,
+) -> Option<SplitQuote> {
+    if amount_out == 0 || token_in == token_out {
+        return None;
+    }
+    let SplitParams { fee_bps, max_hops, max_routes, parts } = params;
+    let g = Graph::new(pools, fee_bps);
+    let parts = parts.clamp(1, SPLIT_MAX_PARTS) as u128;
+    let chunk = (amount_out / parts).max(1);
+
+    let mut ranked: Vec<(u128, Vec<Edge>)> = enumerate_paths(&g, token_in, token_out, max_hops)
+        .into_iter()
+        .filter_map(|p| {
+            let q = pathquote_from_edges_exact_out(&g, &p, chunk)?;
+            Some((q.amount_in, p))
+        })
+        .collect();
+    ranked.sort_by_key(|(need, _)| *need);
+    ranked.truncate(max_routes.max(1));
+    let candidates: Vec<Vec<Edge>> = ranked.into_iter().map(|(_, p)| p).collect();
+
+    let mut state = g.clone();
+    let mut alloc = vec![0u128; candidates.len()];
+    let mut remaining = amount_out;
+    while remaining > 0 {
+        let step = chunk.min(remaining);
+        let best = candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| pathquote_from_edges_exact_out(&state, p, step).map(|q| (i, q)))
+            .min_by_key(|(_, q)| q.amount_in);
+        let Some((i, q)) = best else { break };
+        state.apply_quote(&q);
+        alloc[i] += step;
+        remaining -= step;
+    }
+
+    let split = if remaining == 0 {
+        let legs: Vec<(&[Edge], u128)> =
+            candidates.iter().zip(&alloc).map(|(p, a)| (p.as_slice(), *a)).collect();
+        assemble_split_exact_out(&g, &legs)
+    } else {
+        None
+    };
+    let single =
+        best_first_exact_out(&g, token_in, token_out, amount_out, max_hops).and_then(|q| {
+            let edges = edges_of(&q);
+            assemble_split_exact_out(&g, &[(edges.as_slice(), amount_out)])
+        });
+
+    let best = match (split, single) {
+        (Some(s), Some(one)) => Some(if one.amount_in < s.amount_in { one } else { s }),
+        (s, one) => s.or(one),
+    }?;
+    if best.amount_in <= amount_in_max { Some(best) } else { None }
+}
+
 /* --------------------------------------------------------------------------------
    Quotes & Path shapes
 -------------------------------------------------------------------------------- */
@@ -192,6 +375,23 @@ pub struct PathQuote {
     pub amount_out: u128,
 }
 
+#[derive(Clone, Debug)]
+pub struct SplitRoute {
+    pub quote: PathQuote,
+    /// 1 - realized / pre-trade price of this route (fees excluded from the impact).
+    pub price_impact: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct SplitQuote {
+    /// Largest first; each route is quoted against reserves moved by the ones before it.
+    pub routes: Vec<SplitRoute>,
+    pub amount_in: u128,
+    pub amount_out: u128,
+    /// Aggregate price impact over all routes.
+    pub price_impact: f64,
+}
+
 /* --------------------------------------------------------------------------------
    Best-first planners (Dijkstra-like on realized amounts)
 -------------------------------------------------------------------------------- */
@@ -201,7 +401,6 @@ fn best_first_exact_in(
     src: SchemaAlkaneId,
     dst: SchemaAlkaneId,
     amount_in: u128,
-    fee_bps: u32,
     max_hops: usize,
 ) -> Option<PathQuote> {
     #[derive(Clone)]
@@ -241,7 +440,7 @@ fn best_first_exact_in(
         }
 
         if at == dst && depth > 0 {
-            if let Some(q) = pathquote_from_edges_exact_in(g, &hops, amount_in, fee_bps) {
+            if let Some(q) = pathquote_from_edges_exact_in(g, &hops, amount_in) {
                 if q.amount_out > best_out_at_dst {
                     best_out_at_dst = q.amount_out;
                     best_quote = Some(q);
@@ -272,18 +471,13 @@ fn best_first_exact_in(
                 }
 
                 let edge = Edge { pool: ek.pool, token_in: ek.token_in, token_out: ek.token_out };
-                if let Some((rin, rout)) = g.reserves_for(&edge) {
-                    if rin == 0 || rout == 0 {
+                if let Some(next_amt) = g.hop_out(&edge, amount) {
+                    if next_amt == 0 {
                         continue;
                     }
-                    if let Some(next_amt) = xyk_out_exact_in(rin, rout, amount, fee_bps) {
-                        if next_amt == 0 {
-                            continue;
-                        }
-                        let mut nhops = hops.clone();
-                        nhops.push(edge);
-                        heap.push(Node { amount: next_amt, at: *to, hops: nhops });
-                    }
+                    let mut nhops = hops.clone();
+                    nhops.push(edge);
+                    heap.push(Node { amount: next_amt, at: *to, hops: nhops });
                 }
             }
         }
@@ -296,7 +490,6 @@ fn best_first_exact_out(
     src: SchemaAlkaneId,
     dst: SchemaAlkaneId,
     amount_out: u128,
-    fee_bps: u32,
     max_hops: usize,
 ) -> Option<PathQuote> {
     #[derive(Clone)]
@@ -338,7 +531,7 @@ fn best_first_exact_out(
         if at == src && depth > 0 {
             let mut fwd = hops_rev.clone();
             fwd.reverse();
-            if let Some(q) = pathquote_from_edges_exact_out(g, &fwd, amount_out, fee_bps) {
+            if let Some(q) = pathquote_from_edges_exact_out(g, &fwd, amount_out) {
                 if best_in_at_src.map_or(true, |cur| q.amount_in < cur) {
                     best_in_at_src = Some(q.amount_in);
                     best_quote = Some(q);
@@ -367,15 +560,10 @@ fn best_first_exact_out(
                 }
 
                 let edge = Edge { pool: ek.pool, token_in: ek.token_in, token_out: ek.token_out };
-                if let Some((rin, rout)) = g.reserves_for(&edge) {
-                    if rin == 0 || rout == 0 {
-                        continue;
-                    }
-                    if let Some(need_up) = xyk_in_for_exact_out(rin, rout, need_in, fee_bps) {
-                        let mut nhops = hops_rev.clone();
-                        nhops.push(edge);
-                        heap.push(Node { need_in: need_up, at: *from, hops_rev: nhops });
-                    }
+                if let Some(need_up) = g.hop_in_for(&edge, need_in) {
+                    let mut nhops = hops_rev.clone();
+                    nhops.push(edge);
+                    heap.push(Node { need_in: need_up, at: *from, hops_rev: nhops });
                 }
             }
         }
@@ -384,7 +572,7 @@ fn best_first_exact_out(
 }
 
 /* --------------------------------------------------------------------------------
-   Constant-product AMM math (Uniswap V2 style) with flat fee_bps per hop
+   Constant-product AMM math (Uniswap V2 style); the default `PoolAdapter` curve
 -------------------------------------------------------------------------------- */
 
 #[inline]
@@ -393,7 +581,12 @@ fn apply_fee(amount_in: u128, fee_bps: u32) -> u128 {
     numer / 10_000u128
 }
 
-fn xyk_out_exact_in(r_in: u128, r_out: u128, amount_in: u128, fee_bps: u32) -> Option<u128> {
+pub(crate) fn xyk_out_exact_in(
+    r_in: u128,
+    r_out: u128,
+    amount_in: u128,
+    fee_bps: u32,
+) -> Option<u128> {
     if r_in == 0 || r_out == 0 {
         return None;
     }
@@ -408,7 +601,12 @@ fn xyk_out_exact_in(r_in: u128, r_out: u128, amount_in: u128, fee_bps: u32) -> O
     Some(x.saturating_mul(r_out) / denom)
 }
 
-fn xyk_in_for_exact_out(r_in: u128, r_out: u128, amount_out: u128, fee_bps: u32) -> Option<u128> {
+pub(crate) fn xyk_in_for_exact_out(
+    r_in: u128,
+    r_out: u128,
+    amount_out: u128,
+    fee_bps: u32,
+) -> Option<u128> {
     if r_in == 0 || r_out == 0 || amount_out == 0 || amount_out >= r_out {
         return None;
     }
@@ -456,15 +654,18 @@ struct Edge {
     token_out: SchemaAlkaneId,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 struct Graph {
     neighbors: HashMap<SchemaAlkaneId, Vec<(SchemaAlkaneId, EdgeKey)>>, // out-edges
     in_neighbors: HashMap<SchemaAlkaneId, Vec<(SchemaAlkaneId, EdgeKey)>>, // in-edges
     pools: HashMap<SchemaAlkaneId, SchemaPoolSnapshot>,
+    adapters: HashMap<SchemaAlkaneId, &'static dyn PoolAdapter>,
+    fee_override: Option<u32>,
 }
 
 impl Graph {
-    fn from_snapshot(snapshot: &HashMap<SchemaAlkaneId, SchemaPoolSnapshot>) -> Self {
+    fn new(route_pools: RoutePools<'_>, fee_override: Option<u32>) -> Self {
+        let snapshot = route_pools.snapshot;
         let mut neighbors: HashMap<SchemaAlkaneId, Vec<(SchemaAlkaneId, EdgeKey)>> = HashMap::new();
         let mut in_neighbors: HashMap<SchemaAlkaneId, Vec<(SchemaAlkaneId, EdgeKey)>> =
             HashMap::new();
@@ -488,7 +689,60 @@ impl Graph {
             in_neighbors.entry(a).or_default().push((b, e_ba));
         }
 
-        Self { neighbors, in_neighbors, pools: snapshot.clone() }
+        Self {
+            neighbors,
+            in_neighbors,
+            pools: snapshot.clone(),
+            adapters: route_pools.adapters.clone(),
+            fee_override,
+        }
+    }
+
+    fn adapter(&self, pool: &SchemaAlkaneId) -> &'static dyn PoolAdapter {
+        self.adapters.get(pool).copied().unwrap_or_else(default_pool_adapter)
+    }
+
+    fn fee_bps(&self, pool: &SchemaAlkaneId) -> u32 {
+        self.fee_override.unwrap_or_else(|| self.adapter(pool).fee_bps())
+    }
+
+    /// Output of `amount_in` across one edge, quoted by the pool's adapter.
+    fn hop_out(&self, e: &Edge, amount_in: u128) -> Option<u128> {
+        let reserves = self.reserves_for(e)?;
+        self.adapter(&e.pool).amount_out(reserves, amount_in, self.fee_bps(&e.pool))
+    }
+
+    /// Input one edge needs to produce exactly `amount_out`.
+    fn hop_in_for(&self, e: &Edge, amount_out: u128) -> Option<u128> {
+        let reserves = self.reserves_for(e)?;
+        self.adapter(&e.pool).amount_in_for(reserves, amount_out, self.fee_bps(&e.pool))
+    }
+
+    /// Marginal output per unit of input across one edge, after the fee.
+    fn hop_rate(&self, e: &Edge) -> Option<f64> {
+        let snap = self.pools.get(&e.pool)?;
+        let (base_per_quote, quote_per_base) =
+            self.adapter(&e.pool).spot_prices(snap.base_reserve, snap.quote_reserve);
+        let price = if e.token_in == snap.base_id { quote_per_base } else { base_per_quote };
+        if price == 0 {
+            return None;
+        }
+        let fee_mult = 10_000u32.saturating_sub(self.fee_bps(&e.pool)) as f64 / 10_000.0;
+        Some(price as f64 / PRICE_SCALE as f64 * fee_mult)
+    }
+
+    /// Move reserves as if every hop of `q` had been executed (fees stay in the pool).
+    fn apply_quote(&mut self, q: &PathQuote) {
+        for h in &q.hops {
+            let Some(snap) = self.pools.get_mut(&h.pool) else { continue };
+            if h.token_in == snap.base_id {
+                snap.base_reserve = snap.base_reserve.saturating_add(h.amount_in);
+                snap.quote_reserve = snap.quote_reserve.saturating_sub(h.amount_out);
+            } else {
+                snap.quote_reserve = snap.quote_reserve.saturating_add(h.amount_in);
+                snap.base_reserve = snap.base_reserve.saturating_sub(h.amount_out);
+            }
+        }
     }
 
     /// Fetch reserves in the exact direction of the edge (token_in -> token_out).
@@ -512,15 +766,13 @@ fn pathquote_from_edges_exact_in(
     g: &Graph,
     edges: &[Edge],
     mut amount_in: u128,
-    fee_bps: u32,
 ) -> Option<PathQuote> {
     if edges.is_empty() {
         return None;
     }
     let mut hops_out: Vec<Hop> = Vec::with_capacity(edges.len());
     for e in edges {
-        let (rin, rout) = g.reserves_for(e)?;
-        let out = xyk_out_exact_in(rin, rout, amount_in, fee_bps)?;
+        let out = g.hop_out(e, amount_in)?;
         hops_out.push(Hop {
             pool: e.pool,
             token_in: e.token_in,
@@ -541,15 +793,13 @@ fn pathquote_from_edges_exact_out(
     g: &Graph,
     edges: &[Edge], // forward order
     mut required_out: u128,
-    fee_bps: u32,
 ) -> Option<PathQuote> {
     if edges.is_empty() {
         return None;
     }
     let mut hops_rev: Vec<Hop> = Vec::with_capacity(edges.len());
     for e in edges.iter().rev() {
-        let (rin, rout) = g.reserves_for(e)?;
-        let need_in = xyk_in_for_exact_out(rin, rout, required_out, fee_bps)?;
+        let need_in = g.hop_in_for(e, required_out)?;
         hops_rev.push(Hop {
             pool: e.pool,
             token_in: e.token_in,
@@ -567,6 +817,132 @@ fn pathquote_from_edges_exact_out(
     })
 }
 
+/* --------------------------------------------------------------------------------
+   Split assembly
+-------------------------------------------------------------------------------- */
+
+fn edges_of(q: &PathQuote) -> Vec<Edge> {
+    q.hops
+        .iter()
+        .map(|h| Edge { pool: h.pool, token_in: h.token_in, token_out: h.token_out })
+        .collect()
+}
+
+/// Output per unit of input at pre-trade reserves, after fees.
+fn spot_rate(g: &Graph, edges: &[Edge]) -> Option<f64> {
+    edges.iter().try_fold(1.0f64, |rate, e| Some(rate * g.hop_rate(e)?))
+}
+
+fn assemble_split_exact_in(g: &Graph, legs: &[(&[Edge], u128)]) -> Option<SplitQuote> {
+    let mut legs = legs.to_vec();
+    legs.sort_by_key(|(_, amount)| Reverse(*amount));
+    let mut state = g.clone();
+    let mut routes = Vec::new();
+    let (mut total_in, mut total_out, mut total_ideal) = (0u128, 0u128, 0f64);
+    for (edges, amount) in &legs {
+        if *amount == 0 {
+            continue;
+        }
+        let quote = pathquote_from_edges_exact_in(&state, edges, *amount)?;
+        state.apply_quote(&quote);
+        let ideal = *amount as f64 * spot_rate(g, edges)?;
+        total_in += quote.amount_in;
+        total_out += quote.amount_out;
+        total_ideal += ideal;
+        let price_impact = impact(quote.amount_out as f64, ideal);
+        routes.push(SplitRoute { quote, price_impact });
+    }
+    if routes.is_empty() {
+        return None;
+    }
+    Some(SplitQuote {
+        routes,
+        amount_in: total_in,
+        amount_out: total_out,
+        price_impact: impact(total_out as f64, total_ideal),
+    })
+}
+
+fn assemble_split_exact_out(g: &Graph, legs: &[(&[Edge], u128)]) -> Option<SplitQuote> {
+    let mut legs = legs.to_vec();
+    legs.sort_by_key(|(_, amount)| Reverse(*amount));
+    let mut state = g.clone();
+    let mut routes = Vec::new();
+    let (mut total_in, mut total_out, mut total_ideal_in) = (0u128, 0u128, 0f64);
+    for (edges, amount) in &legs {
+        if *amount == 0 {
+            continue;
+        }
+        let quote = pathquote_from_edges_exact_out(&state, edges, *amount)?;
+        state.apply_quote(&quote);
+        let ideal_in = *amount as f64 / spot_rate(g, edges)?;
+        total_in += quote.amount_in;
+        total_out += quote.amount_out;
+        total_ideal_in += ideal_in;
+        let price_impact = impact(ideal_in, quote.amount_in as f64);
+        routes.push(SplitRoute { quote, price_impact });
+    }
+    if routes.is_empty() {
+        return None;
+    }
+    Some(SplitQuote {
+        routes,
+        amount_in: total_in,
+        amount_out: total_out,
+        price_impact: impact(total_ideal_in, total_in as f64),
+    })
+}
+
+/// `1 - realized / ideal`, where both are expressed as "more is better" amounts.
+fn impact(realized: f64, ideal: f64) -> f64 {
+    if ideal <= 0.0 { 0.0 } else { (1.0 - realized / ideal).max(0.0) }
+}
+
+/// Enumerate simple paths `src -> dst` with 1..=max_hops edges (no token repeats).
+fn enumerate_paths(
+    g: &Graph,
+    src: SchemaAlkaneId,
+    dst: SchemaAlkaneId,
+    max_hops: usize,
+) -> Vec<Vec<Edge>> {
+    fn dfs(
+        g: &Graph,
+        cur: SchemaAlkaneId,
+        dst: SchemaAlkaneId,
+        remaining: usize,
+        visited: &mut HashSet<SchemaAlkaneId>,
+        acc: &mut Vec<Edge>,
+        out: &mut Vec<Vec<Edge>>,
+    ) {
+        if remaining == 0 || out.len() >= SPLIT_MAX_CANDIDATES {
+            return;
+        }
+        let Some(nexts) = g.neighbors.get(&cur) else { return };
+        for (to, ek) in nexts {
+            if visited.contains(to) {
+                continue;
+            }
+            acc.push(Edge { pool: ek.pool, token_in: ek.token_in, token_out: ek.token_out });
+            if *to == dst {
+                out.push(acc.clone());
+            } else {
+                visited.insert(*to);
+                dfs(g, *to, dst, remaining - 1, visited, acc, out);
+                visited.remove(to);
+            }
+            acc.pop();
+            if out.len() >= SPLIT_MAX_CANDIDATES {
+                return;
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut visited = HashSet::from([src]);
+    dfs(g, src, dst, max_hops, &mut visited, &mut Vec::new(), &mut out);
+    out
+}
+
 /* --------------------------------------------------------------------------------
    Cycle enumeration & input optimization (for MEV)
 -------------------------------------------------------------------------------- */
@@ -651,7 +1027,7 @@ fn is_immediate_backtrack_same_pool(path: &[Edge]) -> bool {
 }
 
 /// Compute a conservative cap for input search: min over hops of (res_in / 3).
-fn cap_for_path(g: &Graph, edges: &[Edge], _fee_bps: u32) -> u128 {
+fn cap_for_path(g: &Graph, edges: &[Edge]) -> u128 {
     let mut cap = u128::MAX;
     for e in edges {
         if let Some((rin, _rout)) = g.reserves_for(e) {
@@ -671,12 +1047,7 @@ fn cap_for_path(g: &Graph, edges: &[Edge], _fee_bps: u32) -> u128 {
 
 /// For a fixed path, search input x ∈ [1, cap] to maximize profit f(x)-x.
 /// Uses integer ternary search (40 iters) with a small-range fallback linear scan.
-fn optimize_exact_in_on_path(
-    g: &Graph,
-    edges: &[Edge],
-    fee_bps: u32,
-    cap: u128,
-) -> Option<PathQuote> {
+fn optimize_exact_in_on_path(g: &Graph, edges: &[Edge], cap: u128) -> Option<PathQuote> {
     if cap == 0 {
         return None;
     }
@@ -685,7 +1056,7 @@ fn optimize_exact_in_on_path(
         let mut best: Option<PathQuote> = None;
         let mut best_profit: i128 = i128::MIN;
         for x in 1..=cap {
-            if let Some(q) = pathquote_from_edges_exact_in(g, edges, x, fee_bps) {
+            if let Some(q) = pathquote_from_edges_exact_in(g, edges, x) {
                 let p = q.amount_out as i128 - q.amount_in as i128;
                 if p > best_profit {
                     best_profit = p;
@@ -706,8 +1077,8 @@ fn optimize_exact_in_on_path(
         let m1 = lo + (hi - lo) / 3;
         let m2 = hi - (hi - lo) / 3;
 
-        let q1 = pathquote_from_edges_exact_in(g, edges, m1, fee_bps)?;
-        let q2 = pathquote_from_edges_exact_in(g, edges, m2, fee_bps)?;
+        let q1 = pathquote_from_edges_exact_in(g, edges, m1)?;
+        let q2 = pathquote_from_edges_exact_in(g, edges, m2)?;
 
         let p1 = q1.amount_out as i128 - q1.amount_in as i128;
         let p2 = q2.amount_out as i128 - q2.amount_in as i128;
@@ -736,7 +1107,7 @@ fn optimize_exact_in_on_path(
     let start = lo.saturating_sub(16).max(1);
     let end = (hi + 16).min(cap);
     for x in start..=end {
-        if let Some(q) = pathquote_from_edges_exact_in(g, edges, x, fee_bps) {
+        if let Some(q) = pathquote_from_edges_exact_in(g, edges, x) {
             let p = q.amount_out as i128 - q.amount_in as i128;
             if p > best_profit {
                 best_profit = p;
@@ -777,3 +1148,102 @@ pub fn fmt_path(p: &PathQuote) -> String {
     }
     s
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(tx: u64) -> SchemaAlkaneId {
+        SchemaAlkaneId { block: 2, tx }
+    }
+
+    fn pool(base: u64, quote: u64, base_reserve: u128, quote_reserve: u128) -> SchemaPoolSnapshot {
+        SchemaPoolSnapshot { base_reserve, quote_reserve, base_id: id(base), quote_id: id(quote) }
+    }
+
+    fn params() -> SplitParams {
+        SplitParams { fee_bps: Some(DEFAULT_FEE_BPS), max_hops: 3, max_routes: 4, parts: 20 }
+    }
+
+    #[test]
+    fn split_exact_in_beats_single_path_on_parallel_pools() {
+        // two identical direct pools plus a thin two-hop route
+        let snapshot = HashMap::from([
+            (id(100), pool(1, 2, 1_000_000, 1_000_000)),
+            (id(101), pool(1, 2, 1_000_000, 1_000_000)),
+            (id(102), pool(1, 3, 100_000, 100_000)),
+            (id(103), pool(3, 2, 100_000, 100_000)),
+        ]);
+        let route = RoutePools { snapshot: &snapshot, adapters: &HashMap::new() };
+        let amount_in = 400_000;
+        let single = plan_exact_in_default_fee(route, id(1), id(2), amount_in, 0, 3).unwrap();
+        let split = plan_split_exact_in(route, id(1), id(2), amount_in, 0, params()).unwrap();
+
+        assert!(split.amount_out > single.amount_out);
+        assert_eq!(split.amount_in, amount_in);
+        assert!(split.routes.len() >= 2);
+        assert_eq!(split.routes.iter().map(|r| r.quote.amount_in).sum::<u128>(), amount_in);
+        assert!(split.price_impact > 0.0 && split.price_impact < 1.0);
+    }
+
+    #[test]
+    fn split_exact_out_needs_less_input_than_single_path() {
+        let snapshot = HashMap::from([
+            (id(100), pool(1, 2, 1_000_000, 1_000_000)),
+            (id(101), pool(2, 1, 2_000_000, 2_000_000)),
+        ]);
+        let route = RoutePools { snapshot: &snapshot, adapters: &HashMap::new() };
+        let amount_out = 500_000;
+        let single =
+            plan_exact_out_default_fee(route, id(1), id(2), amount_out, u128::MAX, 3).unwrap();
+        let split =
+            plan_split_exact_out(route, id(1), id(2), amount_out, u128::MAX, params()).unwrap();
+
+        assert!(split.amount_in < single.amount_in);
+        assert_eq!(split.amount_out, amount_out);
+    }
+
+    #[test]
+    fn split_falls_back_to_single_route_for_small_orders() {
+        let snapshot = HashMap::from([
+            (id(100), pool(1, 2, 1_000_000, 1_000_000)),
+            (id(101), pool(1, 2, 10_000, 10_000)),
+        ]);
+        let route = RoutePools { snapshot: &snapshot, adapters: &HashMap::new() };
+        let split = plan_split_exact_in(route, id(1), id(2), 1_000, 0, params()).unwrap();
+        let single = plan_exact_in_default_fee(route, id(1), id(2), 1_000, 0, 3).unwrap();
+        assert!(split.amount_out >= single.amount_out);
+    }
+
+    #[test]
+    fn quote_price_impact_grows_with_trade_size() {
+        let snapshot = HashMap::from([(id(100), pool(1, 2, 1_000_000, 1_000_000))]);
+        let route = RoutePools { snapshot: &snapshot, adapters: &HashMap::new() };
+        let small = plan_exact_in_default_fee(route, id(1), id(2), 1_000, 0, 3).unwrap();
+        let large = plan_exact_in_default_fee(route, id(1), id(2), 250_000, 0, 3).unwrap();
+        let small_impact = quote_price_impact_default_fee(route, &small, false).unwrap();
+        let large_impact = quote_price_impact_default_fee(route, &large, false).unwrap();
+        assert!(small_impact < 0.01);
+        // x*y=k: selling 25% of the reserve loses about 20% to the curve.
+        assert!(large_impact > 0.19 && large_impact < 0.21);
+
+        let exact_out =
+            plan_exact_out_default_fee(route, id(1), id(2), 200_000, u128::MAX, 3).unwrap();
+        let out_impact = quote_price_impact_default_fee(route, &exact_out, true).unwrap();
+        assert!(out_impact > 0.19 && out_impact < 0.21);
+    }
+
+    #[test]
+    fn hops_charge_the_pool_adapter_fee_unless_overridden() {
+        let snapshot = HashMap::from([(id(100), pool(1, 2, 1_000_000, 1_000_000))]);
+        let route = RoutePools { snapshot: &snapshot, adapters: &HashMap::new() };
+        let adapter = default_pool_adapter();
+        let quote = plan_exact_in_default_fee(route, id(1), id(2), 10_000, 0, 3).unwrap();
+        let expected = adapter.amount_out((1_000_000, 1_000_000), 10_000, adapter.fee_bps());
+        assert_eq!(Some(quote.amount_out), expected);
+
+        let free =
+            plan_swap_exact_tokens_for_tokens(route, id(1), id(2), 10_000, 0, Some(0), 3).unwrap();
+        assert!(free.amount_out > quote.amount_out);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::modules::ammdata::schemas::{SchemaCostBasisV1, SchemaCostLotV1};
+use crate::modules::ammdata::utils::lp_positions::{amount_value_usd, mul_div};
+
+/// Realized PnL of one sell under both accounting modes.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct SellPnl {
+    pub fifo_usd: i128,
+    pub avg_usd: i128,
+}
+
+/// Buys `amount` for `cost_usd`. An unpriced buy (`None`) opens no lot: like tokens received
+/// by transfer, it has no cost basis.
+pub fn apply_buy(
+    basis: &mut SchemaCostBasisV1,
+    height: u32,
+    timestamp: u64,
+    amount: u128,
+    cost_usd: Option<u128>,
+) {
+    if amount == 0 {
+        return;
+    }
+    basis.trade_count = basis.trade_count.saturating_add(1);
+    basis.bought = basis.bought.saturating_add(amount);
+    let Some(cost_usd) = cost_usd else {
+        basis.unpriced_bought = basis.unpriced_bought.saturating_add(amount);
+        return;
+    };
+    basis.lots.push(SchemaCostLotV1 { height, timestamp, amount, cost_usd });
+    basis.held = basis.held.saturating_add(amount);
+    basis.avg_cost_usd = basis.avg_cost_usd.saturating_add(cost_usd);
+    basis.bought_cost_usd = basis.bought_cost_usd.saturating_add(cost_usd);
+}
+
+/// Sells `amount` for `proceeds_usd`. Tokens that did not come from a tracked buy have no
+/// cost basis, so only the share of the sell covered by `held` realizes PnL. An unpriced sell
+/// (`None`) still closes the covered lots but realizes nothing.
+pub fn apply_sell(
+    basis: &mut SchemaCostBasisV1,
+    amount: u128,
+    proceeds_usd: Option<u128>,
+) -> SellPnl {
+    if amount == 0 {
+        return SellPnl::default();
+    }
+    let matched = amount.min(basis.held);
+
+    let mut remaining = matched;
+    let mut fifo_cost = 0u128;
+    while remaining > 0 && !basis.lots.is_empty() {
+        let lot = &mut basis.lots[0];
+        if remaining >= lot.amount {
+            remaining -= lot.amount;
+            fifo_cost = fifo_cost.saturating_add(lot.cost_usd);
+            basis.lots.remove(0);
+            continue;
+        }
+        let cost = mul_div(lot.cost_usd, remaining, lot.amount);
+        lot.amount -= remaining;
+        lot.cost_usd -= cost;
+        fifo_cost = fifo_cost.saturating_add(cost);
+        remaining = 0;
+    }
+
+    let avg_cost = mul_div(basis.avg_cost_usd, matched, basis.held);
+    basis.avg_cost_usd -= avg_cost;
+    basis.held -= matched;
+    basis.sold = basis.sold.saturating_add(amount);
+    basis.trade_count = basis.trade_count.saturating_add(1);
+    let Some(proceeds_usd) = proceeds_usd else {
+        basis.unpriced_sold = basis.unpriced_sold.saturating_add(amount);
+        return SellPnl::default();
+    };
+    basis.sold_proceeds_usd = basis.sold_proceeds_usd.saturating_add(proceeds_usd);
+
+    let matched_proceeds = mul_div(proceeds_usd, matched, amount);
+    let pnl = SellPnl {
+        fifo_usd: matched_proceeds as i128 - fifo_cost as i128,
+        avg_usd: matched_proceeds as i128 - avg_cost as i128,
+    };
+    basis.realized_fifo_usd = basis.realized_fifo_usd.saturating_add(pnl.fifo_usd);
+    basis.realized_avg_usd = basis.realized_avg_usd.saturating_add(pnl.avg_usd);
+    pnl
+}
+
+/// Remaining FIFO cost of the open lots.
+pub fn fifo_cost_usd(basis: &SchemaCostBasisV1) -> u128 {
+    basis.lots.iter().fold(0u128, |acc, lot| acc.saturating_add(lot.cost_usd))
+}
+
+/// Unrealized PnL of `held` at `price_usd` under both accounting modes.
+pub fn unrealized(basis: &SchemaCostBasisV1, price_usd: u128) -> SellPnl {
+    let value = amount_value_usd(basis.held, price_usd) as i128;
+    SellPnl {
+        fifo_usd: value - fifo_cost_usd(basis) as i128,
+        avg_usd: value - basis.avg_cost_usd as i128,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fifo_and_average_cost_diverge_on_partial_sells() {
+        let mut basis = SchemaCostBasisV1::default();
+        apply_buy(&mut basis, 1, 10, 100, Some(1_000));
+        apply_buy(&mut basis, 2, 20, 100, Some(3_000));
+
+        // FIFO sells the cheap lot first; average cost is 20 per token.
+        let pnl = apply_sell(&mut basis, 100, Some(2_500));
+        assert_eq!(pnl, SellPnl { fifo_usd: 1_500, avg_usd: 500 });
+        assert_eq!(basis.held, 100);
+        assert_eq!(fifo_cost_usd(&basis), 3_000);
+        assert_eq!(basis.avg_cost_usd, 2_000);
+
+        // 100 tracked + 100 without basis: half of the proceeds realize PnL.
+        let pnl = apply_sell(&mut basis, 200, Some(4_000));
+        assert_eq!(pnl, SellPnl { fifo_usd: -1_000, avg_usd: 0 });
+        assert!(basis.lots.is_empty());
+        assert_eq!(basis.held, 0);
+        assert_eq!(basis.sold, 300);
+        assert_eq!(basis.realized_fifo_usd, 500);
+        assert_eq!(basis.realized_avg_usd, 500);
+    }
+
+    #[test]
+    fn unpriced_trades_carry_no_cost_basis() {
+        let mut basis = SchemaCostBasisV1::default();
+        apply_buy(&mut basis, 1, 10, 100, None);
+        assert!(basis.lots.is_empty());
+        assert_eq!((basis.held, basis.bought, basis.unpriced_bought), (0, 100, 100));
+
+        // The unpriced buy does not cover the later priced sell.
+        apply_buy(&mut basis, 2, 20, 100, Some(1_000));
+        let pnl = apply_sell(&mut basis, 200, Some(4_000));
+        assert_eq!(pnl, SellPnl { fifo_usd: 1_000, avg_usd: 1_000 });
+
+        // An unpriced sell closes the lot without realizing a loss.
+        apply_buy(&mut basis, 3, 30, 50, Some(500));
+        let pnl = apply_sell(&mut basis, 50, None);
+        assert_eq!(pnl, SellPnl::default());
+        assert!(basis.lots.is_empty());
+        assert_eq!((basis.held, basis.unpriced_sold, basis.realized_fifo_usd), (0, 50, 1_000));
+        assert_eq!(basis.trade_count, 5);
+    }
+}
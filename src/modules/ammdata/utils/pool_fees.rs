@@ -0,0 +1,116 @@
+use crate::modules::ammdata::consts::SATS_PER_BTC;
+use crate::modules::ammdata::schemas::SchemaPoolFeesV1;
+
+/// Fee windows reported over the pool's history: (label, days, blocks at the ten-minute
+/// target).
+pub const FEE_WINDOWS: [(&str, u32, u32); 3] =
+    [("1d", 1, 144), ("7d", 7, 1_008), ("30d", 30, 4_320)];
+
+/// Part of `amount_in` the pool keeps as its fee.
+pub fn swap_fee(amount_in: u128, fee_bps: u32) -> u128 {
+    amount_in.saturating_mul(fee_bps as u128) / 10_000
+}
+
+/// A PRICE_SCALE USD value in sats, or 0 without a BTC price.
+pub fn usd_to_sats(value_usd: u128, btc_usd_price: Option<u128>) -> u128 {
+    match btc_usd_price {
+        Some(price) if price > 0 => value_usd.saturating_mul(SATS_PER_BTC) / price,
+        _ => 0,
+    }
+}
+
+pub fn add_fees(total: &mut SchemaPoolFeesV1, fees: &SchemaPoolFeesV1) {
+    total.base_fees = total.base_fees.saturating_add(fees.base_fees);
+    total.quote_fees = total.quote_fees.saturating_add(fees.quote_fees);
+    total.fees_usd = total.fees_usd.saturating_add(fees.fees_usd);
+    total.fees_sats = total.fees_sats.saturating_add(fees.fees_sats);
+    total.trades = total.trades.saturating_add(fees.trades);
+}
+
+/// Annualized fee yield on `tvl_usd` from fees earned over `days`, as a fraction.
+pub fn fee_apr(fees_usd: u128, tvl_usd: u128, days: u32) -> f64 {
+    if tvl_usd == 0 || days == 0 {
+        return 0.0;
+    }
+    fees_usd as f64 / tvl_usd as f64 * 365.0 / days as f64
+}
+
+/// Growth of the LP token's share of the pool between two `sqrt_k_per_lp` readings, as a
+/// fraction. Constant-product swaps only grow sqrt(k) through fees, so this is the fee
+/// return of holding LP, independent of the pool's price moves.
+pub fn share_price_growth(from_sqrt_k_per_lp: u128, to_sqrt_k_per_lp: u128) -> f64 {
+    if from_sqrt_k_per_lp == 0 {
+        return 0.0;
+    }
+    to_sqrt_k_per_lp as f64 / from_sqrt_k_per_lp as f64 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::ammdata::consts::{AMOUNT_SCALE, PRICE_SCALE};
+    use crate::modules::ammdata::utils::lp_positions::sqrt_k_per_lp;
+
+    const FEE_BPS: u32 = 30;
+
+    /// One constant-product swap as the AMM runs it: the fee stays in the pool and the
+    /// rest of the input prices the output. Returns the fee.
+    fn swap(reserves: &mut (u128, u128), amount_in: u128, base_in: bool) -> u128 {
+        let fee = swap_fee(amount_in, FEE_BPS);
+        let (r_in, r_out) = if base_in {
+            (&mut reserves.0, &mut reserves.1)
+        } else {
+            (&mut reserves.1, &mut reserves.0)
+        };
+        let out = (amount_in - fee) * *r_out / (*r_in + amount_in - fee);
+        *r_in += amount_in;
+        *r_out -= out;
+        fee
+    }
+
+    #[test]
+    fn fees_match_lp_share_growth() {
+        let lp_supply = 1_000 * AMOUNT_SCALE;
+        let mut reserves = (1_000 * AMOUNT_SCALE, 1_000 * AMOUNT_SCALE);
+        let start = sqrt_k_per_lp(reserves.0, reserves.1, lp_supply);
+
+        // Round trips keep the price near 1, so both tokens stay worth about 1 USD.
+        let mut fees = SchemaPoolFeesV1::default();
+        for _ in 0..10 {
+            let base_fee = swap(&mut reserves, 10 * AMOUNT_SCALE, true);
+            let quote_fee = swap(&mut reserves, 10 * AMOUNT_SCALE, false);
+            add_fees(
+                &mut fees,
+                &SchemaPoolFeesV1 {
+                    base_fees: base_fee,
+                    quote_fees: quote_fee,
+                    fees_usd: (base_fee + quote_fee) * PRICE_SCALE / AMOUNT_SCALE,
+                    fees_sats: 0,
+                    trades: 2,
+                },
+            );
+        }
+        assert_eq!(fees.trades, 20);
+        assert_eq!(fees.base_fees, 10 * swap_fee(10 * AMOUNT_SCALE, FEE_BPS));
+
+        // Fee value over pool value equals the growth of sqrt(k) per LP token.
+        let growth = share_price_growth(start, sqrt_k_per_lp(reserves.0, reserves.1, lp_supply));
+        let tvl_usd = 2_000 * PRICE_SCALE;
+        let fee_yield = fees.fees_usd as f64 / tvl_usd as f64;
+        assert!((growth - fee_yield).abs() < fee_yield * 0.01, "{growth} vs {fee_yield}");
+
+        // The same fees earned in one day or over a week.
+        let daily = fee_apr(fees.fees_usd, tvl_usd, 1);
+        assert!((daily - fee_yield * 365.0).abs() < 1e-9);
+        assert!((fee_apr(fees.fees_usd, tvl_usd, 7) - daily / 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sats_need_a_btc_price() {
+        let one_dollar = PRICE_SCALE;
+        assert_eq!(usd_to_sats(one_dollar, None), 0);
+        // At 100k USD/BTC a dollar is 1,000 sats.
+        assert_eq!(usd_to_sats(one_dollar, Some(100_000 * PRICE_SCALE)), 1_000);
+        assert_eq!(fee_apr(1, 0, 1), 0.0);
+    }
+}
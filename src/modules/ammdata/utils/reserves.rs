@@ -28,6 +28,13 @@ pub struct ReserveExtraction {
     pub k_ratio_approx: Option<f64>, // best-effort diagnostic
 }
 
+/// LP tokens a pool minted and burned in one transaction, read from its traces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LpTransfer {
+    pub minted: u128,
+    pub burned: u128,
+}
+
 #[derive(Debug, Clone)]
 pub struct NewPoolInfo {
     pub pool_id: SchemaAlkaneId,
@@ -480,3 +487,193 @@ pub fn extract_reserves_from_espo_transaction<'a>(
     }
     Ok(results)
 }
+
+/// LP tokens minted and burned by each pool in `pools`, for pools whose LP token is the pool
+/// alkane itself. See `add_lp_transfers_from_events`.
+pub fn extract_lp_transfers_from_espo_transaction(
+    transaction: &EspoAlkanesTransaction,
+    pools: &HashMap<SchemaAlkaneId, SchemaMarketDefs>,
+) -> Result<HashMap<SchemaAlkaneId, LpTransfer>> {
+    let mut out: HashMap<SchemaAlkaneId, LpTransfer> = HashMap::new();
+    for trace in transaction.traces.iter().flatten() {
+        add_lp_transfers_from_events(&trace.sandshrew_trace.events, pools, &mut out)?;
+    }
+    Ok(out)
+}
+
+/// Folds one trace into `out`. A successful pool call that returns more of its own token than
+/// it received minted the difference; one that keeps what it received burned it. Frames that
+/// revert, or sit under a frame that reverts, move nothing.
+fn add_lp_transfers_from_events(
+    events: &[EspoSandshrewLikeTraceEvent],
+    pools: &HashMap<SchemaAlkaneId, SchemaMarketDefs>,
+    out: &mut HashMap<SchemaAlkaneId, LpTransfer>,
+) -> Result<()> {
+    fn short_to_schema(id: &EspoSandshrewLikeTraceShortId) -> Option<SchemaAlkaneId> {
+        Some(SchemaAlkaneId {
+            block: crate::modules::ammdata::parse_hex_u32(&id.block)?,
+            tx: crate::modules::ammdata::parse_hex_u64(&id.tx)?,
+        })
+    }
+    fn own_amount(
+        transfers: &[crate::alkanes::trace::EspoSandshrewLikeTraceTransfer],
+        pool: SchemaAlkaneId,
+    ) -> Result<u128> {
+        let mut total = 0u128;
+        for t in transfers {
+            if short_to_schema(&t.id) == Some(pool) {
+                let raw = t.value.strip_prefix("0x").unwrap_or(&t.value);
+                total = total.saturating_add(
+                    u128::from_str_radix(raw, 16).context("parse LP transfer amount")?,
+                );
+            }
+        }
+        Ok(total)
+    }
+
+    struct Frame {
+        pool: Option<SchemaAlkaneId>,
+        incoming_lp: u128,
+        moved: Vec<(SchemaAlkaneId, LpTransfer)>,
+    }
+
+    let mut stack: Vec<Frame> = Vec::new();
+    for ev in events {
+        match ev {
+            EspoSandshrewLikeTraceEvent::Invoke(inv) => {
+                let pool = short_to_schema(&inv.context.myself)
+                    .filter(|id| inv.typ == "call" && pools.contains_key(id));
+                let incoming_lp = match pool {
+                    Some(pool) => own_amount(&inv.context.incoming_alkanes, pool)?,
+                    None => 0,
+                };
+                stack.push(Frame { pool, incoming_lp, moved: Vec::new() });
+            }
+            EspoSandshrewLikeTraceEvent::Return(ret) => {
+                let Some(mut frame) = stack.pop() else { continue };
+                if ret.status == crate::alkanes::trace::EspoSandshrewLikeTraceStatus::Failure {
+                    continue;
+                }
+                if let Some(pool) = frame.pool {
+                    let returned = own_amount(&ret.response.alkanes, pool)?;
+                    let transfer = LpTransfer {
+                        minted: returned.saturating_sub(frame.incoming_lp),
+                        burned: frame.incoming_lp.saturating_sub(returned),
+                    };
+                    if transfer != LpTransfer::default() {
+                        frame.moved.push((pool, transfer));
+                    }
+                }
+                match stack.last_mut() {
+                    Some(parent) => parent.moved.extend(frame.moved),
+                    None => {
+                        for (pool, t) in frame.moved {
+                            let entry = out.entry(pool).or_default();
+                            entry.minted = entry.minted.saturating_add(t.minted);
+                            entry.burned = entry.burned.saturating_add(t.burned);
+                        }
+                    }
+                }
+            }
+            EspoSandshrewLikeTraceEvent::Create(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alkanes::trace::{
+        EspoSandshrewLikeTraceInvokeContext, EspoSandshrewLikeTraceInvokeData,
+        EspoSandshrewLikeTraceReturnData, EspoSandshrewLikeTraceReturnResponse,
+        EspoSandshrewLikeTraceStatus, EspoSandshrewLikeTraceTransfer,
+    };
+
+    const POOL: SchemaAlkaneId = SchemaAlkaneId { block: 2, tx: 9 };
+
+    fn short(id: SchemaAlkaneId) -> EspoSandshrewLikeTraceShortId {
+        EspoSandshrewLikeTraceShortId {
+            block: format!("0x{:x}", id.block),
+            tx: format!("0x{:x}", id.tx),
+        }
+    }
+
+    fn lp(amount: u128) -> Vec<EspoSandshrewLikeTraceTransfer> {
+        vec![EspoSandshrewLikeTraceTransfer { id: short(POOL), value: format!("0x{amount:x}") }]
+    }
+
+    fn call(
+        myself: SchemaAlkaneId,
+        incoming: Vec<EspoSandshrewLikeTraceTransfer>,
+    ) -> EspoSandshrewLikeTraceEvent {
+        EspoSandshrewLikeTraceEvent::Invoke(EspoSandshrewLikeTraceInvokeData {
+            typ: "call".to_string(),
+            context: EspoSandshrewLikeTraceInvokeContext {
+                myself: short(myself),
+                caller: short(SchemaAlkaneId { block: 0, tx: 0 }),
+                inputs: Vec::new(),
+                incoming_alkanes: incoming,
+                vout: 0,
+            },
+            fuel: 0,
+        })
+    }
+
+    fn ret(ok: bool, alkanes: Vec<EspoSandshrewLikeTraceTransfer>) -> EspoSandshrewLikeTraceEvent {
+        EspoSandshrewLikeTraceEvent::Return(EspoSandshrewLikeTraceReturnData {
+            status: if ok {
+                EspoSandshrewLikeTraceStatus::Success
+            } else {
+                EspoSandshrewLikeTraceStatus::Failure
+            },
+            response: EspoSandshrewLikeTraceReturnResponse {
+                alkanes,
+                data: String::new(),
+                storage: Vec::new(),
+            },
+        })
+    }
+
+    fn transfers(events: &[EspoSandshrewLikeTraceEvent]) -> HashMap<SchemaAlkaneId, LpTransfer> {
+        let defs = SchemaMarketDefs {
+            pool_alkane_id: POOL,
+            base_alkane_id: SchemaAlkaneId { block: 2, tx: 1 },
+            quote_alkane_id: SchemaAlkaneId { block: 2, tx: 2 },
+        };
+        let pools = HashMap::from([(POOL, defs)]);
+        let mut out = HashMap::new();
+        add_lp_transfers_from_events(events, &pools, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn reads_minted_and_burned_lp_from_pool_frames() {
+        let factory = SchemaAlkaneId { block: 4, tx: 1 };
+        let mint = transfers(&[
+            call(factory, vec![]),
+            call(POOL, vec![]),
+            ret(true, lp(700)),
+            ret(true, lp(700)),
+        ]);
+        assert_eq!(mint.get(&POOL), Some(&LpTransfer { minted: 700, burned: 0 }));
+
+        let burn = transfers(&[call(POOL, lp(300)), ret(true, vec![])]);
+        assert_eq!(burn.get(&POOL), Some(&LpTransfer { minted: 0, burned: 300 }));
+    }
+
+    #[test]
+    fn reverted_frames_move_no_lp() {
+        let factory = SchemaAlkaneId { block: 4, tx: 1 };
+        let reverted_parent = transfers(&[
+            call(factory, vec![]),
+            call(POOL, vec![]),
+            ret(true, lp(700)),
+            ret(false, vec![]),
+        ]);
+        assert!(reverted_parent.is_empty());
+
+        let refunded = transfers(&[call(POOL, lp(300)), ret(true, lp(300))]);
+        assert!(refunded.is_empty());
+    }
+}
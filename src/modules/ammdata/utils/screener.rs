@@ -0,0 +1,253 @@
+use crate::modules::ammdata::schemas::SchemaTokenMetricsV1;
+use crate::modules::ammdata::storage::{TokenMetricsIndexField, parse_change_basis_points};
+use serde_json::Value;
+
+/// Candidates a screener request looks at before handing back a cursor, so selective filters
+/// page through the index instead of scanning it in one call.
+pub const SCREENER_MAX_SCAN: usize = 5_000;
+
+/// Candidates loaded per batch of metrics, holders and creation lookups.
+pub const SCREENER_CHUNK: usize = 256;
+
+/// Index a screener walks to produce its order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScreenerSort {
+    Metrics(TokenMetricsIndexField),
+    Holders,
+    CreationHeight,
+}
+
+impl ScreenerSort {
+    pub fn parse(raw: &str) -> Option<Self> {
+        let field = match raw {
+            "price" => TokenMetricsIndexField::PriceUsd,
+            "marketcap" => TokenMetricsIndexField::MarketcapUsd,
+            "fdv" => TokenMetricsIndexField::FdvUsd,
+            "volume_1d" => TokenMetricsIndexField::Volume1d,
+            "volume_7d" => TokenMetricsIndexField::Volume7d,
+            "volume_30d" => TokenMetricsIndexField::Volume30d,
+            "volume_all_time" => TokenMetricsIndexField::VolumeAllTime,
+            "change_1d" => TokenMetricsIndexField::Change1d,
+            "change_7d" => TokenMetricsIndexField::Change7d,
+            "change_30d" => TokenMetricsIndexField::Change30d,
+            "change_all_time" => TokenMetricsIndexField::ChangeAllTime,
+            "holders" => return Some(ScreenerSort::Holders),
+            "creation_height" | "age" => return Some(ScreenerSort::CreationHeight),
+            _ => return None,
+        };
+        Some(ScreenerSort::Metrics(field))
+    }
+}
+
+/// Inclusive bounds; an unset side is open.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScreenerRange<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T: PartialOrd + Copy> ScreenerRange<T> {
+    pub fn is_set(&self) -> bool {
+        self.min.is_some() || self.max.is_some()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+/// Screener filters; every set filter must hold. USD values are PRICE_SCALE integers like the
+/// token metrics, changes are percents.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScreenerFilters {
+    pub marketcap_usd: ScreenerRange<u128>,
+    pub fdv_usd: ScreenerRange<u128>,
+    pub volume_1d: ScreenerRange<u128>,
+    pub volume_7d: ScreenerRange<u128>,
+    /// In the 1e-4 percent units of `parse_change_basis_points`.
+    pub change_1d: ScreenerRange<i64>,
+    pub change_7d: ScreenerRange<i64>,
+    pub holders: ScreenerRange<u64>,
+    pub creation_height: ScreenerRange<u32>,
+    pub has_pool: Option<bool>,
+}
+
+/// What the screener knows about one token when applying filters.
+#[derive(Clone, Debug, Default)]
+pub struct ScreenerRow {
+    pub metrics: SchemaTokenMetricsV1,
+    pub holders: u64,
+    pub creation_height: Option<u32>,
+    pub has_pool: bool,
+}
+
+impl ScreenerFilters {
+    /// Parses `{"marketcap": {"min": "..", "max": ".."}, "holders": {"min": 10},
+    /// "has_pool": true, ..}`. Errors name the offending filter.
+    pub fn from_json(value: Option<&Value>) -> Result<Self, String> {
+        let mut filters = ScreenerFilters::default();
+        let Some(value) = value else {
+            return Ok(filters);
+        };
+        if value.is_null() {
+            return Ok(filters);
+        }
+        let Some(obj) = value.as_object() else {
+            return Err("invalid_filters".to_string());
+        };
+        for (name, raw) in obj {
+            let invalid = || format!("invalid_filter_{name}");
+            match name.as_str() {
+                "marketcap" => {
+                    filters.marketcap_usd = parse_range(raw, parse_u128).ok_or_else(invalid)?
+                }
+                "fdv" => filters.fdv_usd = parse_range(raw, parse_u128).ok_or_else(invalid)?,
+                "volume_1d" => {
+                    filters.volume_1d = parse_range(raw, parse_u128).ok_or_else(invalid)?
+                }
+                "volume_7d" => {
+                    filters.volume_7d = parse_range(raw, parse_u128).ok_or_else(invalid)?
+                }
+                "change_1d" => {
+                    filters.change_1d = parse_range(raw, parse_change).ok_or_else(invalid)?
+                }
+                "change_7d" => {
+                    filters.change_7d = parse_range(raw, parse_change).ok_or_else(invalid)?
+                }
+                "holders" => {
+                    filters.holders = parse_range(raw, |v| v.as_u64()).ok_or_else(invalid)?
+                }
+                "creation_height" => {
+                    filters.creation_height =
+                        parse_range(raw, |v| v.as_u64().and_then(|h| u32::try_from(h).ok()))
+                            .ok_or_else(invalid)?
+                }
+                "has_pool" => filters.has_pool = Some(raw.as_bool().ok_or_else(invalid)?),
+                _ => return Err(format!("unknown_filter_{name}")),
+            }
+        }
+        Ok(filters)
+    }
+
+    pub fn matches(&self, row: &ScreenerRow) -> bool {
+        let m = &row.metrics;
+        self.marketcap_usd.contains(m.marketcap_usd)
+            && self.fdv_usd.contains(m.fdv_usd)
+            && self.volume_1d.contains(m.volume_1d)
+            && self.volume_7d.contains(m.volume_7d)
+            && self.change_1d.contains(parse_change_basis_points(&m.change_1d))
+            && self.change_7d.contains(parse_change_basis_points(&m.change_7d))
+            && self.holders.contains(row.holders)
+            && (!self.creation_height.is_set()
+                || row.creation_height.is_some_and(|h| self.creation_height.contains(h)))
+            && self.has_pool.is_none_or(|want| want == row.has_pool)
+    }
+}
+
+fn parse_range<T>(raw: &Value, parse: impl Fn(&Value) -> Option<T>) -> Option<ScreenerRange<T>> {
+    let obj = raw.as_object()?;
+    let mut range = ScreenerRange { min: None, max: None };
+    for (bound, v) in obj {
+        if v.is_null() {
+            continue;
+        }
+        match bound.as_str() {
+            "min" => range.min = Some(parse(v)?),
+            "max" => range.max = Some(parse(v)?),
+            _ => return None,
+        }
+    }
+    Some(range)
+}
+
+fn parse_u128(v: &Value) -> Option<u128> {
+    match v {
+        Value::String(s) => s.parse::<u128>().ok(),
+        Value::Number(n) => n.as_u64().map(|x| x as u128),
+        _ => None,
+    }
+}
+
+fn parse_change(v: &Value) -> Option<i64> {
+    match v {
+        Value::String(s) => s.trim().parse::<f64>().ok().map(|_| parse_change_basis_points(s)),
+        Value::Number(n) => Some(parse_change_basis_points(&n.to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(
+        marketcap_usd: u128,
+        change_1d: &str,
+        holders: u64,
+        creation_height: u32,
+    ) -> ScreenerRow {
+        ScreenerRow {
+            metrics: SchemaTokenMetricsV1 {
+                marketcap_usd,
+                change_1d: change_1d.to_string(),
+                ..Default::default()
+            },
+            holders,
+            creation_height: Some(creation_height),
+            has_pool: true,
+        }
+    }
+
+    #[test]
+    fn filters_compose() {
+        let filters = ScreenerFilters::from_json(Some(&json!({
+            "marketcap": { "min": "1000" },
+            "change_1d": { "min": -5, "max": "12.5" },
+            "holders": { "min": 10, "max": null },
+            "creation_height": { "max": 900_000 },
+            "has_pool": true,
+        })))
+        .unwrap();
+
+        assert!(filters.matches(&row(1_000, "12.5", 10, 880_000)));
+        assert!(filters.matches(&row(5_000, "-5", 400, 900_000)));
+        assert!(!filters.matches(&row(999, "0", 400, 880_000)));
+        assert!(!filters.matches(&row(5_000, "12.51", 400, 880_000)));
+        assert!(!filters.matches(&row(5_000, "-5.01", 400, 880_000)));
+        assert!(!filters.matches(&row(5_000, "0", 9, 880_000)));
+        assert!(!filters.matches(&row(5_000, "0", 400, 900_001)));
+        assert!(!filters.matches(&ScreenerRow { has_pool: false, ..row(5_000, "0", 400, 1) }));
+        assert!(
+            !filters.matches(&ScreenerRow { creation_height: None, ..row(5_000, "0", 400, 1) })
+        );
+    }
+
+    #[test]
+    fn empty_filters_match_everything() {
+        let filters = ScreenerFilters::from_json(None).unwrap();
+        assert_eq!(filters, ScreenerFilters::default());
+        assert!(filters.matches(&ScreenerRow::default()));
+    }
+
+    #[test]
+    fn bad_filters_are_named() {
+        let err = |v: Value| ScreenerFilters::from_json(Some(&v)).unwrap_err();
+        assert_eq!(err(json!({ "supply": { "min": 1 } })), "unknown_filter_supply");
+        assert_eq!(err(json!({ "holders": { "min": "many" } })), "invalid_filter_holders");
+        assert_eq!(err(json!({ "fdv": { "above": "1" } })), "invalid_filter_fdv");
+        assert_eq!(err(json!({ "has_pool": "yes" })), "invalid_filter_has_pool");
+        assert_eq!(err(json!([1])), "invalid_filters");
+    }
+
+    #[test]
+    fn sorts_parse() {
+        assert_eq!(
+            ScreenerSort::parse("fdv"),
+            Some(ScreenerSort::Metrics(TokenMetricsIndexField::FdvUsd))
+        );
+        assert_eq!(ScreenerSort::parse("age"), Some(ScreenerSort::CreationHeight));
+        assert_eq!(ScreenerSort::parse("holders"), Some(ScreenerSort::Holders));
+        assert_eq!(ScreenerSort::parse("name"), None);
+    }
+}
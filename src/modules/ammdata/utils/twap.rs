@@ -0,0 +1,82 @@
+use crate::modules::ammdata::schemas::SchemaPoolStateV1;
+
+/// Accumulators at `height`, extrapolated from the latest record at or before it
+/// (`record_height`) with the record's closing prices. Like Uniswap v2 the accumulators
+/// wrap on overflow; only differences between two readings are meaningful.
+pub fn cumulative_at(record_height: u32, record: &SchemaPoolStateV1, height: u32) -> (u128, u128) {
+    let blocks = height.saturating_sub(record_height) as u128;
+    (
+        record
+            .cum_quote_per_base
+            .wrapping_add(record.price_quote_per_base.wrapping_mul(blocks)),
+        record
+            .cum_base_per_quote
+            .wrapping_add(record.price_base_per_quote.wrapping_mul(blocks)),
+    )
+}
+
+/// Next record for a block that moved the pool: accumulators advance by the previous
+/// record's prices for every block since it, then the new closing prices are stored.
+pub fn next_state(
+    prev: Option<(u32, &SchemaPoolStateV1)>,
+    height: u32,
+    mut next: SchemaPoolStateV1,
+) -> SchemaPoolStateV1 {
+    let (cum_q, cum_b) = match prev {
+        Some((prev_height, prev)) => cumulative_at(prev_height, prev, height),
+        None => (0, 0),
+    };
+    next.cum_quote_per_base = cum_q;
+    next.cum_base_per_quote = cum_b;
+    next
+}
+
+/// Time-weighted (per block) average prices between two accumulator readings.
+pub fn twap(from: (u128, u128), to: (u128, u128), blocks: u32) -> (u128, u128) {
+    if blocks == 0 {
+        return (0, 0);
+    }
+    let blocks = blocks as u128;
+    (to.0.wrapping_sub(from.0) / blocks, to.1.wrapping_sub(from.1) / blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(q_per_b: u128, b_per_q: u128) -> SchemaPoolStateV1 {
+        SchemaPoolStateV1 {
+            price_quote_per_base: q_per_b,
+            price_base_per_quote: b_per_q,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_one_block_spike_is_weighted_by_one_block() {
+        let s100 = next_state(None, 100, state(10, 100));
+        // Price spikes to 1000 at block 110 and is reverted by block 111.
+        let s110 = next_state(Some((100, &s100)), 110, state(1_000, 1));
+        let s111 = next_state(Some((110, &s110)), 111, state(10, 100));
+
+        let from = cumulative_at(100, &s100, 100);
+        let to = cumulative_at(111, &s111, 120);
+        // 10 blocks at 10, 1 block at 1000, 9 blocks at 10.
+        assert_eq!(twap(from, to, 20), ((10 * 19 + 1_000) / 20, (100 * 19 + 1) / 20));
+        // A window ending in the spike block has not seen the spike yet.
+        assert_eq!(twap(from, cumulative_at(110, &s110, 110), 10), (10, 100));
+    }
+
+    #[test]
+    fn accumulators_wrap_without_skewing_the_twap() {
+        let mut s100 = next_state(None, 100, state(10, 100));
+        s100.cum_quote_per_base = u128::MAX - 15;
+        s100.cum_base_per_quote = u128::MAX;
+        let s110 = next_state(Some((100, &s100)), 110, state(20, 50));
+
+        assert_eq!(s110.cum_quote_per_base, 84);
+        let from = cumulative_at(100, &s100, 100);
+        let to = cumulative_at(110, &s110, 120);
+        assert_eq!(twap(from, to, 20), ((10 * 10 + 20 * 10) / 20, (100 * 10 + 50 * 10) / 20));
+    }
+}
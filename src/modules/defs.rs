@@ -9,6 +9,7 @@ use tokio::sync::RwLock;
 use crate::alkanes::trace::EspoBlock;
 use crate::config::{get_espo_module_mdb, get_module_config};
 use crate::runtime::mdb::Mdb;
+use crate::runtime::timeseries::{SeriesDef, register_series};
 
 /// Object-safe handler: (Context, JSON) -> JSON (async)
 type HandlerFn = dyn Fn(context::Context, Value) -> BoxFuture<'static, Value> + Send + Sync;
@@ -121,6 +122,12 @@ pub trait EspoModule: Send + Sync {
     fn set_config(&mut self, _config: &serde_json::Value) -> Result<()> {
         Ok(())
     }
+
+    /// Numeric series this module writes per block (see `runtime::timeseries`).
+    /// They are queryable as "<name>.<series>" through the root `get_timeseries`.
+    fn timeseries(&self) -> &'static [SeriesDef] {
+        &[]
+    }
 }
 
 /// Registry that holds modules and the RPC router.
@@ -187,6 +194,7 @@ impl ModuleRegistry {
 
         let mdb = get_espo_module_mdb(name);
         module.set_mdb(mdb);
+        register_series(name, module.timeseries());
 
         // --- RPC prefix like "ammdata." ---
         let ns = RpcNsRegistrar::new(self.router.clone(), name);
@@ -200,4 +208,18 @@ impl ModuleRegistry {
     pub fn modules(&self) -> &[Arc<dyn EspoModule>] {
         &self.modules
     }
+
+    /// Roll every module's own tree back so blocks at or above `height` are indexed again.
+    /// Returns the number of blocks each module dropped.
+    pub fn rollback_to_height(&self, height: u32) -> Result<Vec<(&'static str, u32)>> {
+        let mut dropped = Vec::with_capacity(self.modules.len());
+        for m in &self.modules {
+            let Some(mdb) = m.get_mdb() else { continue };
+            let n = mdb.rollback_to_height(height).map_err(|e| {
+                anyhow::anyhow!("module {} rollback to {height} failed: {e}", m.get_name())
+            })?;
+            dropped.push((m.get_name(), n));
+        }
+        Ok(dropped)
+    }
 }
@@ -1,3 +1,4 @@
+use crate::runtime::timeseries::SeriesDef;
 use bitcoin::Network;
 
 //tests to run before running a full reindex if u changed balances.rs
@@ -16,3 +17,19 @@ pub fn essentials_genesis_block(network: Network) -> u32 {
 
 pub const ESSENTIALS_GENESIS_INSPECTIONS: &[(u32, u64, Option<(&str, &str)>)] =
     &[(2, 0, Some(("DIESEL", "diesel"))), (32, 0, Some(("frBTC", "FRBTC")))];
+
+pub const SERIES_HOLDERS_COUNT: &str = "holders_count";
+pub const SERIES_CIRCULATING_SUPPLY: &str = "circulating_supply";
+
+pub const ESSENTIALS_SERIES: &[SeriesDef] = &[
+    SeriesDef {
+        name: SERIES_HOLDERS_COUNT,
+        keyed_by: "alkane",
+        description: "number of addresses/alkanes holding a non-zero balance",
+    },
+    SeriesDef {
+        name: SERIES_CIRCULATING_SUPPLY,
+        keyed_by: "alkane",
+        description: "sum of all holder balances (base units)",
+    },
+];
@@ -3,14 +3,16 @@ use crate::config::{debug_enabled, get_metashrew, get_network};
 use crate::debug;
 use crate::modules::defs::{EspoModule, RpcNsRegistrar};
 use crate::modules::essentials::consts::{
-    ESSENTIALS_GENESIS_INSPECTIONS, essentials_genesis_block,
+    ESSENTIALS_GENESIS_INSPECTIONS, ESSENTIALS_SERIES, essentials_genesis_block,
 };
 use crate::modules::essentials::rpc;
 use crate::modules::essentials::storage::{
-    BlockSummary, EssentialsProvider, GetRawValueParams, cache_block_summary,
-    encode_creation_record,
+    BlockSummary, EssentialsProvider, GetMultiValuesParams, GetRawValueParams, StorageWriteEntry,
+    cache_block_summary, encode_creation_record,
 };
+use crate::modules::essentials::utils::call_stats::call_stats_rows;
 use crate::modules::essentials::utils::creation_meta::{get_cap, get_value_per_mint};
+use crate::modules::essentials::utils::failures::failed_trace_rows;
 use crate::modules::essentials::utils::inspections::{
     AlkaneCreationRecord, StoredInspectionResult, created_alkane_records_from_block,
     inspect_wasm_metadata,
@@ -20,6 +22,7 @@ use crate::modules::essentials::utils::names::{
 };
 use crate::runtime::mdb::Mdb;
 use crate::runtime::state_at::StateAt;
+use crate::runtime::timeseries::SeriesDef;
 use crate::schemas::SchemaAlkaneId;
 use anyhow::Result;
 use bitcoin::Network;
@@ -186,6 +189,13 @@ impl EspoModule for Essentials {
         // dedup directory markers:
         //   dir_row_key(alk,skey) -> ()
         let mut dir_rows: HashSet<Vec<u8>> = HashSet::new();
+        // every write with the value it replaced:
+        //   storage_history_key(alk,skey,height,trace_seq) -> StorageWriteEntry
+        // A key's first write in the block replaces the stored row; those rows are read in
+        // one batch once the block is walked (index into first_seen_kv_keys).
+        let mut storage_writes: Vec<(Vec<u8>, Option<usize>, StorageWriteEntry)> = Vec::new();
+        let mut first_seen_kv_keys: Vec<Vec<u8>> = Vec::new();
+        let mut trace_seq: u32 = 0;
         // creation records rows (by id and sequence index):
         let mut creation_rows_by_id: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
         let mut creation_rows_seq: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
@@ -228,6 +238,7 @@ impl EspoModule for Essentials {
         for tx in block.transactions.iter() {
             let Some(traces) = tx.traces.as_ref() else { continue };
             for trace in traces.iter() {
+                trace_seq += 1;
                 let mut created_in_trace: HashSet<SchemaAlkaneId> = HashSet::new();
                 for ev in trace.sandshrew_trace.events.iter() {
                     if let EspoSandshrewLikeTraceEvent::Create(create) = ev {
@@ -242,6 +253,25 @@ impl EspoModule for Essentials {
                         // Key for value row
                         let k_v = table.kv_row_key(alk, skey);
 
+                        // Previous value: earlier in this block, else the stored row
+                        let (old_value, first_seen) = match kv_rows.get(&k_v) {
+                            Some(prev) => (Some(prev.get(32..).unwrap_or_default().to_vec()), None),
+                            None => {
+                                first_seen_kv_keys.push(k_v.clone());
+                                (None, Some(first_seen_kv_keys.len() - 1))
+                            }
+                        };
+                        let write = StorageWriteEntry {
+                            txid: txid.to_byte_array(),
+                            old_value,
+                            new_value: value.clone(),
+                        };
+                        storage_writes.push((
+                            table.storage_history_key(alk, skey, block.height, trace_seq),
+                            first_seen,
+                            write,
+                        ));
+
                         // Value layout: [ txid(32) | value(...) ]
                         let mut buf = Vec::with_capacity(32 + value.len());
                         buf.extend_from_slice(&txid.to_byte_array());
@@ -766,6 +796,25 @@ impl EspoModule for Essentials {
         for k in &holders_index_keys {
             puts.push((k.clone(), Vec::new()));
         }
+        let stored_kv_rows = provider
+            .get_multi_values(GetMultiValuesParams {
+                blockhash: StateAt::Latest,
+                keys: first_seen_kv_keys,
+            })?
+            .values;
+        let mut storage_history_rows = Vec::with_capacity(storage_writes.len());
+        for (key, first_seen, mut write) in storage_writes {
+            if let Some(i) = first_seen {
+                write.old_value = stored_kv_rows[i]
+                    .as_ref()
+                    .map(|prev| prev.get(32..).unwrap_or_default().to_vec());
+            }
+            storage_history_rows.push((key, borsh::to_vec(&write)?));
+        }
+        storage_history_rows.sort_unstable();
+        puts.extend(storage_history_rows);
+        puts.extend(call_stats_rows(provider, &block)?);
+        puts.extend(failed_trace_rows(provider, &block, get_network())?);
         puts.push((table.block_summary_key(block.height), block_summary_bytes));
         if let Some(count_bytes) = creation_count_row {
             puts.push((table.alkane_creation_count_key(), count_bytes.to_vec()));
@@ -834,6 +883,10 @@ impl EspoModule for Essentials {
         self.load_index_height().ok().flatten()
     }
 
+    fn timeseries(&self) -> &'static [SeriesDef] {
+        ESSENTIALS_SERIES
+    }
+
     fn register_rpc(&self, reg: &RpcNsRegistrar) {
         rpc::register_rpc(
             reg.clone(),
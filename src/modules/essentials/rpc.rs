@@ -1,14 +1,15 @@
 use crate::modules::defs::RpcNsRegistrar;
 use crate::modules::essentials::storage::{
-    EssentialsProvider, RpcGetAddressActivityParams, RpcGetAddressBalancesParams,
-    RpcGetAddressOutpointsParams, RpcGetAddressTransactionsParams, RpcGetAlkaneAddressTxsParams,
-    RpcGetAlkaneBalanceMetashrewParams, RpcGetAlkaneBalanceTxsByTokenParams,
-    RpcGetAlkaneBalanceTxsParams, RpcGetAlkaneBalancesParams, RpcGetAlkaneBlockTxsParams,
-    RpcGetAlkaneInfoParams, RpcGetAlkaneLatestTracesParams, RpcGetAlkaneTxSummaryParams,
-    RpcGetAllAlkanesParams, RpcGetBlockSummaryParams, RpcGetBlockTracesParams,
-    RpcGetCirculatingSupplyParams, RpcGetHoldersCountParams, RpcGetHoldersParams, RpcGetKeysParams,
-    RpcGetMempoolTracesParams, RpcGetOutpointBalancesParams, RpcGetTotalReceivedParams,
-    RpcGetTransferVolumeParams, RpcPingParams,
+    EssentialsProvider, RpcGetAddressActivityParams, RpcGetAddressBalanceHistoryParams,
+    RpcGetAddressBalancesParams, RpcGetAddressOutpointsParams, RpcGetAddressTransactionsParams,
+    RpcGetAirdropListParams, RpcGetAlkaneAddressTxsParams, RpcGetAlkaneBalanceMetashrewParams,
+    RpcGetAlkaneBalanceTxsByTokenParams, RpcGetAlkaneBalanceTxsParams, RpcGetAlkaneBalancesParams,
+    RpcGetAlkaneBlockTxsParams, RpcGetAlkaneInfoParams, RpcGetAlkaneLatestTracesParams,
+    RpcGetAlkaneTxSummaryParams, RpcGetAllAlkanesParams, RpcGetBlockSummaryParams,
+    RpcGetBlockTracesParams, RpcGetCallStatsParams, RpcGetCirculatingSupplyParams,
+    RpcGetFailedTxsParams, RpcGetHoldersCountParams, RpcGetHoldersParams, RpcGetKeysParams,
+    RpcGetMempoolTracesParams, RpcGetOutpointBalancesParams, RpcGetStorageHistoryParams,
+    RpcGetTotalReceivedParams, RpcGetTransferVolumeParams, RpcPingParams,
 };
 use serde_json::{Value, json};
 use std::sync::Arc;
@@ -31,6 +32,34 @@ fn resolve_view(
         })
 }
 
+/// `tokens` entries are `"2:0"`, `"2:0=<weight>"` or `{"alkane": "2:0", "weight": 2}`;
+/// a bare `alkane` is a single token of weight 1. Unreadable entries are passed through
+/// as empty strings so the storage layer rejects the request.
+fn airdrop_tokens(payload: &Value) -> Vec<String> {
+    if let Some(tokens) = payload.get("tokens").and_then(|v| v.as_array()) {
+        return tokens
+            .iter()
+            .map(|t| match t {
+                Value::String(s) => s.clone(),
+                Value::Object(obj) => {
+                    let alkane = obj.get("alkane").and_then(|v| v.as_str()).unwrap_or_default();
+                    match obj.get("weight") {
+                        Some(Value::Number(n)) => format!("{alkane}={n}"),
+                        Some(Value::String(w)) => format!("{alkane}={w}"),
+                        _ => alkane.to_string(),
+                    }
+                }
+                _ => String::new(),
+            })
+            .collect();
+    }
+    payload
+        .get("alkane")
+        .and_then(|v| v.as_str())
+        .map(|s| vec![s.to_string()])
+        .unwrap_or_default()
+}
+
 pub fn register_rpc(reg: RpcNsRegistrar, provider: Arc<EssentialsProvider>) {
     let mdb = Arc::clone(&provider);
 
@@ -209,6 +238,53 @@ pub fn register_rpc(reg: RpcNsRegistrar, provider: Arc<EssentialsProvider>) {
         });
     }
 
+    {
+        let reg_airdrop = reg.clone();
+        let mdb_airdrop = Arc::clone(&mdb);
+        tokio::spawn(async move {
+            reg_airdrop
+                .register("get_airdrop_list", move |_cx, payload| {
+                    let mdb = Arc::clone(&mdb_airdrop);
+                    async move {
+                        let view = match resolve_view(mdb.as_ref(), &payload) {
+                            Ok(v) => v,
+                            Err(err) => return err,
+                        };
+                        let min_balance = match payload.get("min_balance") {
+                            Some(Value::String(s)) => Some(s.clone()),
+                            Some(Value::Number(n)) => Some(n.to_string()),
+                            _ => None,
+                        };
+                        let params = RpcGetAirdropListParams {
+                            tokens: airdrop_tokens(&payload),
+                            min_balance,
+                            exclude_contracts: payload
+                                .get("exclude_contracts")
+                                .and_then(|v| v.as_bool())
+                                .unwrap_or(false),
+                            exclude_addresses: payload
+                                .get("exclude_addresses")
+                                .and_then(|v| v.as_array())
+                                .map(|arr| {
+                                    arr.iter()
+                                        .filter_map(|v| v.as_str())
+                                        .map(|s| s.to_string())
+                                        .collect()
+                                })
+                                .unwrap_or_default(),
+                            csv: payload.get("format").and_then(|v| v.as_str()) == Some("csv"),
+                            limit: payload.get("limit").and_then(|v| v.as_u64()),
+                            page: payload.get("page").and_then(|v| v.as_u64()),
+                        };
+                        view.rpc_get_airdrop_list(params)
+                            .map(|resp| resp.value)
+                            .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                    }
+                })
+                .await;
+        });
+    }
+
     {
         let reg_transfer = reg.clone();
         let mdb_transfer = Arc::clone(&mdb);
@@ -353,6 +429,158 @@ pub fn register_rpc(reg: RpcNsRegistrar, provider: Arc<EssentialsProvider>) {
         });
     }
 
+    {
+        let reg_addr_hist = reg.clone();
+        let mdb_addr_hist = Arc::clone(&mdb);
+        tokio::spawn(async move {
+            reg_addr_hist
+                .register("get_address_balance_history", move |_cx, payload| {
+                    let mdb = Arc::clone(&mdb_addr_hist);
+                    async move {
+                        let view = match resolve_view(mdb.as_ref(), &payload) {
+                            Ok(v) => v,
+                            Err(err) => return err,
+                        };
+                        let height = |k: &str| {
+                            payload
+                                .get(k)
+                                .and_then(|v| v.as_u64())
+                                .and_then(|v| u32::try_from(v).ok())
+                        };
+                        let params = RpcGetAddressBalanceHistoryParams {
+                            address: payload
+                                .get("address")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                            alkane: payload
+                                .get("alkane")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                            from_height: height("from_height"),
+                            to_height: height("to_height"),
+                            limit: payload.get("limit").and_then(|v| v.as_u64()),
+                            cursor: payload
+                                .get("cursor")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                        };
+                        view.rpc_get_address_balance_history(params)
+                            .map(|resp| resp.value)
+                            .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                    }
+                })
+                .await;
+        });
+    }
+
+    {
+        let reg_call_stats = reg.clone();
+        let mdb_call_stats = Arc::clone(&mdb);
+        tokio::spawn(async move {
+            reg_call_stats
+                .register("get_call_stats", move |_cx, payload| {
+                    let mdb = Arc::clone(&mdb_call_stats);
+                    async move {
+                        let view = match resolve_view(mdb.as_ref(), &payload) {
+                            Ok(v) => v,
+                            Err(err) => return err,
+                        };
+                        let params = RpcGetCallStatsParams {
+                            alkane: payload
+                                .get("alkane")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string()),
+                            days: payload
+                                .get("days")
+                                .and_then(|v| v.as_u64())
+                                .and_then(|v| u32::try_from(v).ok()),
+                        };
+                        view.rpc_get_call_stats(params)
+                            .map(|resp| resp.value)
+                            .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                    }
+                })
+                .await;
+        });
+    }
+
+    {
+        let reg_failed = reg.clone();
+        let mdb_failed = Arc::clone(&mdb);
+        tokio::spawn(async move {
+            reg_failed
+                .register("get_failed_txs", move |_cx, payload| {
+                    let mdb = Arc::clone(&mdb_failed);
+                    async move {
+                        const KEYS: &[&str] = &[
+                            "height",
+                            "alkane",
+                            "address",
+                            "error_prefix",
+                            "error_contains",
+                            "limit",
+                            "cursor",
+                        ];
+                        if let Some(key) = payload
+                            .as_object()
+                            .and_then(|obj| obj.keys().find(|k| !KEYS.contains(&k.as_str())))
+                        {
+                            return json!({"ok": false, "error": "unknown_param", "detail": key});
+                        }
+                        let view = match resolve_view(mdb.as_ref(), &payload) {
+                            Ok(v) => v,
+                            Err(err) => return err,
+                        };
+                        let string = |k: &str| {
+                            payload.get(k).and_then(|v| v.as_str()).map(|s| s.to_string())
+                        };
+                        let params = RpcGetFailedTxsParams {
+                            alkane: string("alkane"),
+                            address: string("address"),
+                            error_prefix: string("error_prefix"),
+                            error_contains: string("error_contains"),
+                            limit: payload.get("limit").and_then(|v| v.as_u64()),
+                            cursor: string("cursor"),
+                        };
+                        view.rpc_get_failed_txs(params)
+                            .map(|resp| resp.value)
+                            .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                    }
+                })
+                .await;
+        });
+    }
+
+    {
+        let reg_storage_hist = reg.clone();
+        let mdb_storage_hist = Arc::clone(&mdb);
+        tokio::spawn(async move {
+            reg_storage_hist
+                .register("get_storage_history", move |_cx, payload| {
+                    let mdb = Arc::clone(&mdb_storage_hist);
+                    async move {
+                        let view = match resolve_view(mdb.as_ref(), &payload) {
+                            Ok(v) => v,
+                            Err(err) => return err,
+                        };
+                        let string = |k: &str| {
+                            payload.get(k).and_then(|v| v.as_str()).map(|s| s.to_string())
+                        };
+                        let params = RpcGetStorageHistoryParams {
+                            alkane: string("alkane"),
+                            key: string("key"),
+                            limit: payload.get("limit").and_then(|v| v.as_u64()),
+                            cursor: string("cursor"),
+                        };
+                        view.rpc_get_storage_history(params)
+                            .map(|resp| resp.value)
+                            .unwrap_or_else(|_| json!({"ok": false, "error": "internal_error"}))
+                    }
+                })
+                .await;
+        });
+    }
+
     {
         let reg_alk_bal = reg.clone();
         let mdb_alk_bal = Arc::clone(&mdb);
@@ -5,19 +5,29 @@ use crate::config::{
     get_address_index_chunk_size, get_bitcoind_rpc_client, get_electrum_like, get_metashrew,
     get_network,
 };
+use crate::modules::essentials::utils::airdrop::{
+    AIRDROP_RPC_MAX_HOLDERS, AirdropFilter, airdrop_csv, airdrop_hash, build_airdrop_list,
+    holder_parts, parse_airdrop_token,
+};
 use crate::modules::essentials::utils::balances::{
     SignedU128, get_address_activity_for_address, get_alkane_balances,
     get_alkane_balances_at_or_before, get_balance_for_address, get_holders_for_alkane,
     get_outpoint_address, get_total_received_for_alkane, get_transfer_volume_for_alkane,
 };
-use crate::modules::essentials::utils::inspections::{AlkaneCreationRecord, inspection_to_json};
+use crate::modules::essentials::utils::call_stats::{ALL_TIME_DAY, day_index};
+use crate::modules::essentials::utils::failures::normalize_error;
+use crate::modules::essentials::utils::inspections::{
+    AlkaneCreationRecord, inspection_to_json, load_inspection,
+};
+use crate::modules::essentials::utils::storage_schema::schema_for_alkane;
 use crate::runtime::mdb::{Mdb, MdbBatch};
 use crate::runtime::pointers::{CursorScanPage, KvPointer, ListNonMutatePointer, ListPointer};
 use crate::runtime::state_at::StateAt;
+use crate::runtime::tree_db::{next_key, prefix_end_exclusive};
 use crate::schemas::{EspoOutpoint, SchemaAlkaneId};
 use alkanes_support::proto::alkanes::AlkanesTrace;
 use bitcoin::consensus::encode::{deserialize, serialize};
-use bitcoin::hashes::Hash;
+use bitcoin::hashes::{Hash, sha256};
 use bitcoin::{Address, AddressType, BlockHash, Network, ScriptBuf, Transaction, Txid};
 use bitcoincore_rpc::RpcApi;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -102,6 +112,36 @@ fn encode_alkane_id_be(id: &SchemaAlkaneId) -> [u8; 12] {
     out
 }
 
+/// (height, tx_idx, trace_idx) of a trace within the chain.
+pub type TracePos = (u32, u32, u32);
+
+/// Bytes of the normalized revert message kept in the failed trace error index.
+const FAILED_TRACE_ERROR_KEY_LEN: usize = 64;
+/// Distinct indexed messages one `error_prefix` query may cover; past it the result is
+/// flagged `error_groups_truncated`.
+const FAILED_TRACE_MAX_ERROR_GROUPS: usize = 256;
+/// Index entries one failed trace query reads before returning a short page.
+const FAILED_TRACE_MAX_SCAN: usize = 5_000;
+
+fn encode_trace_pos(pos: TracePos) -> [u8; 12] {
+    let mut out = [0u8; 12];
+    out[..4].copy_from_slice(&pos.0.to_be_bytes());
+    out[4..8].copy_from_slice(&pos.1.to_be_bytes());
+    out[8..].copy_from_slice(&pos.2.to_be_bytes());
+    out
+}
+
+fn decode_trace_pos(bytes: &[u8]) -> Option<TracePos> {
+    if bytes.len() != 12 {
+        return None;
+    }
+    Some((
+        u32::from_be_bytes(bytes[..4].try_into().ok()?),
+        u32::from_be_bytes(bytes[4..8].try_into().ok()?),
+        u32::from_be_bytes(bytes[8..].try_into().ok()?),
+    ))
+}
+
 fn decode_alkane_id_be(bytes: &[u8]) -> Option<SchemaAlkaneId> {
     if bytes.len() != 12 {
         return None;
@@ -181,6 +221,15 @@ fn parse_paged_cursor_u64(raw: &str) -> Option<u64> {
     s.parse::<u64>().ok()
 }
 
+fn parse_balance_history_cursor(bytes: &[u8]) -> Option<(u32, u32, SchemaAlkaneId)> {
+    if bytes.len() != 20 {
+        return None;
+    }
+    let height = u32::from_be_bytes(bytes[..4].try_into().ok()?);
+    let tx_idx = u32::from_be_bytes(bytes[4..8].try_into().ok()?);
+    Some((height, tx_idx, decode_alkane_id_be(&bytes[8..])?))
+}
+
 fn encode_paged_cursor_u64(cursor: u64) -> String {
     hex::encode(cursor.to_be_bytes())
 }
@@ -264,6 +313,16 @@ pub struct EssentialsTable<'a> {
     // Core kv directory rows (0x01 = values, 0x03 = directory entries).
     pub KV_ROWS: KvPointer<'a>,
     pub DIR_ROWS: ListPointer<'a>,
+    // Every storage write per (alkane, key), by height and trace.
+    pub STORAGE_HISTORY: KvPointer<'a>,
+    // Call analytics per (alkane, opcode, day) and the callers already counted.
+    pub CALL_STATS: KvPointer<'a>,
+    pub CALL_CALLERS: KvPointer<'a>,
+    // Failed traces by (height, tx_idx, trace_idx), indexed by alkane and address.
+    pub FAILED_TRACES: KvPointer<'a>,
+    pub FAILED_TRACES_BY_ALKANE: KvPointer<'a>,
+    pub FAILED_TRACES_BY_ADDRESS: KvPointer<'a>,
+    pub FAILED_TRACES_BY_ERROR: KvPointer<'a>,
     pub INDEX_HEIGHT: KvPointer<'a>,
     // Balances + outpoint indexes (address/outpoint views).
     pub BALANCES: KvPointer<'a>,
@@ -309,6 +368,13 @@ impl<'a> EssentialsTable<'a> {
             ROOT: root.clone(),
             KV_ROWS: root.select(&[0x01]),
             DIR_ROWS: root.list_select(&[0x03]),
+            STORAGE_HISTORY: root.keyword("/storage_history/"),
+            CALL_STATS: root.keyword("/call_stats/"),
+            CALL_CALLERS: root.keyword("/call_callers/"),
+            FAILED_TRACES: root.keyword("/failed_traces/"),
+            FAILED_TRACES_BY_ALKANE: root.keyword("/failed_traces_alkane/"),
+            FAILED_TRACES_BY_ADDRESS: root.keyword("/failed_traces_address/"),
+            FAILED_TRACES_BY_ERROR: root.keyword("/failed_traces_error/"),
             INDEX_HEIGHT: root.keyword("/index_height"),
             BALANCES: root.keyword("/balances/"),
             OUTPOINT_BALANCES: root.keyword("/outpoint_balances/"),
@@ -380,6 +446,151 @@ impl<'a> EssentialsTable<'a> {
         self.DIR_ROWS.select(&suffix).key().to_vec()
     }
 
+    /// Keys shorter than `u16::MAX` bytes are stored length-prefixed; longer ones as
+    /// `u16::MAX` and their sha256, so they get their own history instead of sharing a
+    /// truncated prefix.
+    pub fn storage_history_prefix(&self, alk: &SchemaAlkaneId, skey: &[u8]) -> Vec<u8> {
+        let hashed;
+        let (len, skey) = match u16::try_from(skey.len()) {
+            Ok(len) if len < u16::MAX => (len, skey),
+            _ => {
+                hashed = sha256::Hash::hash(skey).to_byte_array();
+                (u16::MAX, hashed.as_slice())
+            }
+        };
+        let mut suffix = Vec::with_capacity(4 + 8 + 2 + skey.len());
+        suffix.extend_from_slice(&alk.block.to_be_bytes());
+        suffix.extend_from_slice(&alk.tx.to_be_bytes());
+        suffix.extend_from_slice(&len.to_be_bytes());
+        suffix.extend_from_slice(skey);
+        self.STORAGE_HISTORY.select(&suffix).key().to_vec()
+    }
+
+    /// `trace_seq` orders the traces of a block, so each (alkane, key) has at most one write per
+    /// (height, trace_seq).
+    pub fn storage_history_key(
+        &self,
+        alk: &SchemaAlkaneId,
+        skey: &[u8],
+        height: u32,
+        trace_seq: u32,
+    ) -> Vec<u8> {
+        let mut key = self.storage_history_prefix(alk, skey);
+        key.extend_from_slice(&height.to_be_bytes());
+        key.extend_from_slice(&trace_seq.to_be_bytes());
+        key
+    }
+
+    pub fn parse_storage_history_key(
+        &self,
+        alk: &SchemaAlkaneId,
+        skey: &[u8],
+        key: &[u8],
+    ) -> Option<(u32, u32)> {
+        let rest = key.strip_prefix(self.storage_history_prefix(alk, skey).as_slice())?;
+        if rest.len() != 8 {
+            return None;
+        }
+        let height = u32::from_be_bytes(rest[..4].try_into().ok()?);
+        let trace_seq = u32::from_be_bytes(rest[4..].try_into().ok()?);
+        Some((height, trace_seq))
+    }
+
+    pub fn call_stats_prefix(&self, alk: &SchemaAlkaneId) -> Vec<u8> {
+        self.CALL_STATS.select(&encode_alkane_id_be(alk)).key().to_vec()
+    }
+
+    /// `day` is a UTC day index, or `ALL_TIME_DAY` for the running totals.
+    pub fn call_stats_key(&self, alk: &SchemaAlkaneId, opcode: u128, day: u32) -> Vec<u8> {
+        let mut key = self.call_stats_prefix(alk);
+        key.extend_from_slice(&opcode.to_be_bytes());
+        key.extend_from_slice(&day.to_be_bytes());
+        key
+    }
+
+    pub fn parse_call_stats_key(&self, alk: &SchemaAlkaneId, key: &[u8]) -> Option<(u128, u32)> {
+        let rest = key.strip_prefix(self.call_stats_prefix(alk).as_slice())?;
+        if rest.len() != 16 + 4 {
+            return None;
+        }
+        let opcode = u128::from_be_bytes(rest[..16].try_into().ok()?);
+        let day = u32::from_be_bytes(rest[16..].try_into().ok()?);
+        Some((opcode, day))
+    }
+
+    pub fn call_caller_key(
+        &self,
+        alk: &SchemaAlkaneId,
+        opcode: u128,
+        day: u32,
+        caller: &[u8],
+    ) -> Vec<u8> {
+        let mut suffix = Vec::with_capacity(12 + 16 + 4 + caller.len());
+        suffix.extend_from_slice(&encode_alkane_id_be(alk));
+        suffix.extend_from_slice(&opcode.to_be_bytes());
+        suffix.extend_from_slice(&day.to_be_bytes());
+        suffix.extend_from_slice(caller);
+        self.CALL_CALLERS.select(&suffix).key().to_vec()
+    }
+
+    pub fn parse_call_caller_key(&self, key: &[u8]) -> Option<(SchemaAlkaneId, u128, u32)> {
+        let rest = key.strip_prefix(self.CALL_CALLERS.key().as_slice())?;
+        if rest.len() < 12 + 16 + 4 {
+            return None;
+        }
+        let alk = decode_alkane_id_be(&rest[..12])?;
+        let opcode = u128::from_be_bytes(rest[12..28].try_into().ok()?);
+        let day = u32::from_be_bytes(rest[28..32].try_into().ok()?);
+        Some((alk, opcode, day))
+    }
+
+    pub fn failed_trace_prefix(&self) -> Vec<u8> {
+        self.FAILED_TRACES.key().to_vec()
+    }
+
+    pub fn failed_trace_key(&self, pos: TracePos) -> Vec<u8> {
+        self.FAILED_TRACES.select(&encode_trace_pos(pos)).key().to_vec()
+    }
+
+    pub fn failed_trace_by_alkane_prefix(&self, alk: &SchemaAlkaneId) -> Vec<u8> {
+        self.FAILED_TRACES_BY_ALKANE.select(&encode_alkane_id_be(alk)).key().to_vec()
+    }
+
+    pub fn failed_trace_by_alkane_key(&self, alk: &SchemaAlkaneId, pos: TracePos) -> Vec<u8> {
+        let mut key = self.failed_trace_by_alkane_prefix(alk);
+        key.extend_from_slice(&encode_trace_pos(pos));
+        key
+    }
+
+    pub fn failed_trace_by_address_prefix(&self, address: &str) -> Vec<u8> {
+        let mut suffix = Vec::with_capacity(address.len() + 1);
+        suffix.extend_from_slice(address.as_bytes());
+        suffix.push(b'/');
+        self.FAILED_TRACES_BY_ADDRESS.select(&suffix).key().to_vec()
+    }
+
+    pub fn failed_trace_by_address_key(&self, address: &str, pos: TracePos) -> Vec<u8> {
+        let mut key = self.failed_trace_by_address_prefix(address);
+        key.extend_from_slice(&encode_trace_pos(pos));
+        key
+    }
+
+    /// Covers every indexed message starting with `error` (normalized); messages are indexed by
+    /// their first `FAILED_TRACE_ERROR_KEY_LEN` bytes, without NULs.
+    pub fn failed_trace_by_error_prefix(&self, error: &str) -> Vec<u8> {
+        let text: Vec<u8> =
+            error.bytes().filter(|b| *b != 0).take(FAILED_TRACE_ERROR_KEY_LEN).collect();
+        self.FAILED_TRACES_BY_ERROR.select(&text).key().to_vec()
+    }
+
+    /// `<message prefix> 0x00 <pos>`; the NUL ends the message so each one sorts by position.
+    pub fn failed_trace_by_error_key(&self, error: &str, pos: TracePos) -> Vec<u8> {
+        let mut key = self.failed_trace_by_error_prefix(error);
+        key.push(0);
+        key.extend_from_slice(&encode_trace_pos(pos));
+        key
+    }
+
     pub fn addr_spk_key(&self, addr: &str) -> Vec<u8> {
         self.ADDR_SPK.select(addr.as_bytes()).key().to_vec()
     }
@@ -480,6 +691,53 @@ impl<'a> EssentialsTable<'a> {
         key
     }
 
+    pub fn address_balance_history_prefix(&self, address: &str) -> Vec<u8> {
+        let mut key = Vec::with_capacity(ADDRESS_V2_PREFIX.len() + address.len() + 17);
+        key.extend_from_slice(ADDRESS_V2_PREFIX);
+        key.extend_from_slice(address.as_bytes());
+        key.extend_from_slice(b"/balance_history/");
+        key
+    }
+
+    pub fn address_balance_history_token_prefix(
+        &self,
+        address: &str,
+        alkane: &SchemaAlkaneId,
+    ) -> Vec<u8> {
+        let mut key = self.address_balance_history_prefix(address);
+        key.extend_from_slice(&encode_alkane_id_be(alkane));
+        key
+    }
+
+    pub fn address_balance_history_key(
+        &self,
+        address: &str,
+        alkane: &SchemaAlkaneId,
+        height: u32,
+        tx_idx: u32,
+    ) -> Vec<u8> {
+        let mut key = self.address_balance_history_token_prefix(address, alkane);
+        key.extend_from_slice(&height.to_be_bytes());
+        key.extend_from_slice(&tx_idx.to_be_bytes());
+        key
+    }
+
+    /// Splits a key under `address_balance_history_prefix(address)` into (alkane, height, tx_idx).
+    pub fn parse_address_balance_history_key(
+        &self,
+        address: &str,
+        key: &[u8],
+    ) -> Option<(SchemaAlkaneId, u32, u32)> {
+        let rest = key.strip_prefix(self.address_balance_history_prefix(address).as_slice())?;
+        if rest.len() != 12 + 4 + 4 {
+            return None;
+        }
+        let alkane = decode_alkane_id_be(&rest[..12])?;
+        let height = u32::from_be_bytes(rest[12..16].try_into().ok()?);
+        let tx_idx = u32::from_be_bytes(rest[16..20].try_into().ok()?);
+        Some((alkane, height, tx_idx))
+    }
+
     pub fn parse_address_balance_key(&self, key: &[u8]) -> Option<(String, SchemaAlkaneId)> {
         if !key.starts_with(ADDRESS_V2_PREFIX) {
             return None;
@@ -1818,6 +2076,31 @@ impl EssentialsProvider {
         Ok(GetListEntriesDescResult { entries })
     }
 
+    /// Bounded seek over `[start, end)`; see `Mdb::scan_range_entries`.
+    pub fn get_range_entries(
+        &self,
+        params: GetRangeEntriesParams,
+    ) -> Result<GetRangeEntriesResult> {
+        let end = params.end.as_deref();
+        let entries = match params.blockhash.resolve(self.view_blockhash) {
+            Some(blockhash) => self
+                .mdb
+                .scan_range_entries_at_blockhash(
+                    &blockhash,
+                    &params.start,
+                    end,
+                    params.limit,
+                    params.reverse,
+                )
+                .map_err(|e| anyhow!("mdb.scan_range_entries_at_blockhash failed: {e}"))?,
+            None => self
+                .mdb
+                .scan_range_entries(&params.start, end, params.limit, params.reverse)
+                .map_err(|e| anyhow!("mdb.scan_range_entries failed: {e}"))?,
+        };
+        Ok(GetRangeEntriesResult { entries })
+    }
+
     pub fn get_list_entries_desc_cursor(
         &self,
         params: GetListEntriesDescCursorParams,
@@ -1970,6 +2253,370 @@ impl EssentialsProvider {
             .map_err(|e| anyhow!("[ESSENTIALS] rocksdb put(/index_height) failed: {e}"))
     }
 
+    /// Balance changes of an address (optionally one token) in `from_height..=to_height` past
+    /// `after`, oldest first, plus each token's balance before `from_height`. Each token's log
+    /// is seeked to the range, so at most `limit + 1` changes are read per token.
+    pub fn get_address_balance_history(
+        &self,
+        params: GetAddressBalanceHistoryParams,
+    ) -> Result<GetAddressBalanceHistoryResult> {
+        crate::debug_timer_log!("get_address_balance_history");
+        let table = self.table();
+        let blockhash = params.blockhash;
+        let address = params.address.as_str();
+        let alkanes = match params.alkane {
+            Some(alkane) => vec![alkane],
+            None => self.address_balance_history_alkanes(blockhash, address)?,
+        };
+        let mut opening: BTreeMap<SchemaAlkaneId, u128> = BTreeMap::new();
+        let mut entries = Vec::new();
+        for alkane in alkanes {
+            let prefix = table.address_balance_history_token_prefix(address, &alkane);
+            let at = |height: u32, tx_idx: u32| {
+                table.address_balance_history_key(address, &alkane, height, tx_idx)
+            };
+            if params.from_height > 0 {
+                let before = self
+                    .get_range_entries(GetRangeEntriesParams {
+                        blockhash,
+                        start: prefix.clone(),
+                        end: Some(at(params.from_height, 0)),
+                        limit: 1,
+                        reverse: true,
+                    })?
+                    .entries;
+                if let Some((_, value)) = before.first() {
+                    opening
+                        .insert(alkane, AddressBalanceChangeEntry::try_from_slice(value)?.balance);
+                }
+            }
+            // Changes are ordered by (height, tx_idx, alkane), so this token resumes at the
+            // cursor's transaction, past it when the token sorts at or before the cursor's.
+            let start = match params.after {
+                Some((height, tx_idx, last)) if height >= params.from_height => {
+                    let key = at(height, tx_idx);
+                    if alkane <= last { next_key(&key) } else { key }
+                }
+                _ => at(params.from_height, 0),
+            };
+            let end = match params.to_height.checked_add(1) {
+                Some(height) => Some(at(height, 0)),
+                None => prefix_end_exclusive(&prefix),
+            };
+            let rows = self
+                .get_range_entries(GetRangeEntriesParams {
+                    blockhash,
+                    start,
+                    end,
+                    limit: params.limit.saturating_add(1),
+                    reverse: false,
+                })?
+                .entries;
+            for (key, value) in rows {
+                let Some((alkane, height, tx_idx)) =
+                    table.parse_address_balance_history_key(address, &key)
+                else {
+                    continue;
+                };
+                let entry = AddressBalanceChangeEntry::try_from_slice(&value)?;
+                entries.push((alkane, height, tx_idx, entry));
+            }
+        }
+        entries.sort_by_key(|(alkane, height, tx_idx, _)| (*height, *tx_idx, *alkane));
+        let has_more = entries.len() > params.limit;
+        entries.truncate(params.limit);
+        Ok(GetAddressBalanceHistoryResult { opening, entries, has_more })
+    }
+
+    /// Tokens with a balance change log for `address`, one seek per token.
+    fn address_balance_history_alkanes(
+        &self,
+        blockhash: StateAt,
+        address: &str,
+    ) -> Result<Vec<SchemaAlkaneId>> {
+        let table = self.table();
+        let prefix = table.address_balance_history_prefix(address);
+        let end = prefix_end_exclusive(&prefix);
+        let mut start = prefix;
+        let mut alkanes = Vec::new();
+        loop {
+            let first = self
+                .get_range_entries(GetRangeEntriesParams {
+                    blockhash,
+                    start,
+                    end: end.clone(),
+                    limit: 1,
+                    reverse: false,
+                })?
+                .entries;
+            let Some((alkane, ..)) = first
+                .first()
+                .and_then(|(key, _)| table.parse_address_balance_history_key(address, key))
+            else {
+                break;
+            };
+            alkanes.push(alkane);
+            let Some(next) =
+                prefix_end_exclusive(&table.address_balance_history_token_prefix(address, &alkane))
+            else {
+                break;
+            };
+            start = next;
+        }
+        Ok(alkanes)
+    }
+
+    /// Writes to one storage key of an alkane before `before` (height, trace_seq), newest
+    /// first. Seeks down from `before` and reads at most `limit + 1` rows.
+    pub fn get_storage_history(
+        &self,
+        params: GetStorageHistoryParams,
+    ) -> Result<GetStorageHistoryResult> {
+        crate::debug_timer_log!("get_storage_history");
+        let table = self.table();
+        let prefix = table.storage_history_prefix(&params.alkane, &params.key);
+        let end = match params.before {
+            Some((height, trace_seq)) => {
+                Some(table.storage_history_key(&params.alkane, &params.key, height, trace_seq))
+            }
+            None => prefix_end_exclusive(&prefix),
+        };
+        let rows = self
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: params.blockhash,
+                start: prefix,
+                end,
+                limit: params.limit.saturating_add(1),
+                reverse: true,
+            })?
+            .entries;
+        let has_more = rows.len() > params.limit;
+        let mut entries = Vec::with_capacity(rows.len().min(params.limit));
+        for (key, value) in rows.into_iter().take(params.limit) {
+            let Some((height, trace_seq)) =
+                table.parse_storage_history_key(&params.alkane, &params.key, &key)
+            else {
+                continue;
+            };
+            entries.push((height, trace_seq, StorageWriteEntry::try_from_slice(&value)?));
+        }
+        Ok(GetStorageHistoryResult { entries, has_more })
+    }
+
+    /// Call stats of an alkane as (opcode, day, stats) for the last `days` UTC days up to the
+    /// day of the newest indexed block, plus each opcode's `ALL_TIME_DAY` row last. Each
+    /// opcode's window is seeked on its own, so older days are never read.
+    pub fn get_call_stats(&self, params: GetCallStatsParams) -> Result<GetCallStatsResult> {
+        crate::debug_timer_log!("get_call_stats");
+        let table = self.table();
+        let blockhash = params.blockhash;
+        let alk = &params.alkane;
+        let last_day = day_index(self.indexed_tip_time()?.unwrap_or(0));
+        let first_day = last_day.saturating_sub(params.days.max(1) - 1);
+        let prefix = table.call_stats_prefix(alk);
+        let end = prefix_end_exclusive(&prefix);
+        let mut rows = Vec::new();
+        let mut start = prefix;
+        loop {
+            let first = self
+                .get_range_entries(GetRangeEntriesParams {
+                    blockhash,
+                    start,
+                    end: end.clone(),
+                    limit: 1,
+                    reverse: false,
+                })?
+                .entries;
+            let Some((opcode, _)) =
+                first.first().and_then(|(key, _)| table.parse_call_stats_key(alk, key))
+            else {
+                break;
+            };
+            let window = self
+                .get_range_entries(GetRangeEntriesParams {
+                    blockhash,
+                    start: table.call_stats_key(alk, opcode, first_day),
+                    end: Some(table.call_stats_key(alk, opcode, last_day.saturating_add(1))),
+                    limit: (last_day - first_day + 1) as usize,
+                    reverse: false,
+                })?
+                .entries;
+            for (key, value) in window {
+                if let Some((_, day)) = table.parse_call_stats_key(alk, &key) {
+                    rows.push((opcode, day, CallStats::try_from_slice(&value)?));
+                }
+            }
+            let all_time = self
+                .get_raw_value(GetRawValueParams {
+                    blockhash,
+                    key: table.call_stats_key(alk, opcode, ALL_TIME_DAY),
+                })?
+                .value;
+            if let Some(value) = all_time {
+                rows.push((opcode, ALL_TIME_DAY, CallStats::try_from_slice(&value)?));
+            }
+            let Some(next) = opcode.checked_add(1) else { break };
+            start = table.call_stats_key(alk, next, 0);
+        }
+        Ok(GetCallStatsResult { rows, first_day, last_day })
+    }
+
+    /// Header time of the newest indexed block.
+    fn indexed_tip_time(&self) -> Result<Option<u32>> {
+        let Some(height) = self
+            .get_index_height(GetIndexHeightParams { blockhash: StateAt::Latest })?
+            .height
+        else {
+            return Ok(None);
+        };
+        let summary =
+            self.get_block_summary(GetBlockSummaryParams { blockhash: StateAt::Latest, height })?;
+        Ok(summary
+            .summary
+            .and_then(|s| deserialize::<bitcoin::block::Header>(&s.header).ok())
+            .map(|header| header.time))
+    }
+
+    /// Failed traces older than `before`, newest first. The alkane, address or error index
+    /// (in that order of preference) is seeked down from `before`, or the failed traces
+    /// themselves when no indexed filter is given; the other filters, and `error_contains`
+    /// always, are checked on each entry. Stops at `limit` matches or after
+    /// `FAILED_TRACE_MAX_SCAN` positions, and returns where to resume when more may follow.
+    pub fn get_failed_traces(
+        &self,
+        params: GetFailedTracesParams,
+    ) -> Result<GetFailedTracesResult> {
+        crate::debug_timer_log!("get_failed_traces");
+        let table = self.table();
+        let blockhash = params.blockhash;
+        let error = params.error_prefix.as_deref().filter(|e| !e.is_empty());
+        let contains = params.error_contains.as_deref().filter(|e| !e.is_empty());
+        let mut error_groups_truncated = false;
+        let prefixes = match (&params.alkane, &params.address, error) {
+            (Some(alkane), _, _) => vec![table.failed_trace_by_alkane_prefix(alkane)],
+            (None, Some(address), _) => vec![table.failed_trace_by_address_prefix(address)],
+            (None, None, Some(error)) => {
+                let (groups, truncated) = self.failed_trace_error_groups(blockhash, error)?;
+                error_groups_truncated = truncated;
+                groups
+            }
+            (None, None, None) => vec![table.failed_trace_prefix()],
+        };
+        let keeps = |entry: &FailedTraceEntry| {
+            params.address.as_ref().is_none_or(|a| entry.addresses.contains(a))
+                && error.is_none_or(|e| entry.error_normalized.starts_with(e))
+                && contains.is_none_or(|c| entry.error_normalized.contains(c))
+        };
+        let done = |entries, next| GetFailedTracesResult { entries, next, error_groups_truncated };
+
+        let batch = params.limit.saturating_add(1);
+        let mut before = params.before;
+        let mut entries = Vec::new();
+        let mut scanned = 0usize;
+        loop {
+            let positions = self.failed_trace_positions(blockhash, &prefixes, before, batch)?;
+            let keys: Vec<Vec<u8>> =
+                positions.iter().map(|pos| table.failed_trace_key(*pos)).collect();
+            let values = self.get_multi_values(GetMultiValuesParams { blockhash, keys })?.values;
+            for (pos, value) in positions.iter().zip(values) {
+                scanned += 1;
+                before = Some(*pos);
+                if let Some(value) = value {
+                    let entry = FailedTraceEntry::try_from_slice(&value)?;
+                    if keeps(&entry) {
+                        if entries.len() == params.limit {
+                            let next = entries.last().map(|(pos, _)| *pos);
+                            return Ok(done(entries, next));
+                        }
+                        entries.push((*pos, entry));
+                    }
+                }
+                if scanned >= FAILED_TRACE_MAX_SCAN {
+                    return Ok(done(entries, before));
+                }
+            }
+            if positions.len() < batch {
+                return Ok(done(entries, None));
+            }
+        }
+    }
+
+    /// Up to `limit` positions older than `before` under each of `prefixes` (keys that end in
+    /// a trace position), merged newest first.
+    fn failed_trace_positions(
+        &self,
+        blockhash: StateAt,
+        prefixes: &[Vec<u8>],
+        before: Option<TracePos>,
+        limit: usize,
+    ) -> Result<Vec<TracePos>> {
+        let mut out = Vec::new();
+        for prefix in prefixes {
+            let end = match before {
+                Some(pos) => {
+                    let mut key = prefix.clone();
+                    key.extend_from_slice(&encode_trace_pos(pos));
+                    Some(key)
+                }
+                None => prefix_end_exclusive(prefix),
+            };
+            let entries = self
+                .get_range_entries(GetRangeEntriesParams {
+                    blockhash,
+                    start: prefix.clone(),
+                    end,
+                    limit,
+                    reverse: true,
+                })?
+                .entries;
+            out.extend(
+                entries
+                    .iter()
+                    .filter_map(|(key, _)| decode_trace_pos(key.strip_prefix(prefix.as_slice())?)),
+            );
+        }
+        out.sort_unstable_by(|a, b| b.cmp(a));
+        out.dedup();
+        out.truncate(limit);
+        Ok(out)
+    }
+
+    /// Prefixes of the indexed messages starting with `error`, one seek per message, and
+    /// whether more than `FAILED_TRACE_MAX_ERROR_GROUPS` messages matched (the rest are left
+    /// out).
+    fn failed_trace_error_groups(
+        &self,
+        blockhash: StateAt,
+        error: &str,
+    ) -> Result<(Vec<Vec<u8>>, bool)> {
+        let prefix = self.table().failed_trace_by_error_prefix(error);
+        let end = prefix_end_exclusive(&prefix);
+        let mut start = prefix;
+        let mut groups = Vec::new();
+        loop {
+            let first = self
+                .get_range_entries(GetRangeEntriesParams {
+                    blockhash,
+                    start,
+                    end: end.clone(),
+                    limit: 1,
+                    reverse: false,
+                })?
+                .entries;
+            let Some((key, _)) = first.into_iter().next() else { break };
+            let Some(group) = key.len().checked_sub(12).map(|len| key[..len].to_vec()) else {
+                break;
+            };
+            if groups.len() == FAILED_TRACE_MAX_ERROR_GROUPS {
+                return Ok((groups, true));
+            }
+            let Some(next) = prefix_end_exclusive(&group) else { break };
+            groups.push(group);
+            start = next;
+        }
+        Ok((groups, false))
+    }
+
     pub fn get_creation_record(
         &self,
         params: GetCreationRecordParams,
@@ -2328,6 +2975,23 @@ impl EssentialsProvider {
         Ok(GetCreationCountResult { count })
     }
 
+    /// Alkane ids at the given creation sequence numbers, `None` where the seq is unassigned.
+    pub fn get_creation_ids_by_seq(
+        &self,
+        params: GetCreationIdsBySeqParams,
+    ) -> Result<GetCreationIdsBySeqResult> {
+        crate::debug_timer_log!("get_creation_ids_by_seq");
+        let table = self.table();
+        let keys: Vec<Vec<u8>> =
+            params.seqs.iter().map(|seq| table.alkane_creation_seq_key(*seq)).collect();
+        let ids = self
+            .raw_multi_get_at(&keys, params.blockhash.resolve(self.view_blockhash))?
+            .into_iter()
+            .map(|raw| raw.and_then(|raw| decode_alkane_id_be(&raw)))
+            .collect();
+        Ok(GetCreationIdsBySeqResult { ids })
+    }
+
     pub fn get_creation_ids_in_block(
         &self,
         params: GetCreationIdsInBlockParams,
@@ -2358,9 +3022,8 @@ impl EssentialsProvider {
             .get_raw_value(GetRawValueParams {
                 blockhash: StateAt::Latest,
                 key: table.holders_count_key(&params.alkane),
-            })
-            .ok()
-            .and_then(|resp| resp.value)
+            })?
+            .value
             .and_then(|raw| HoldersCountEntry::try_from_slice(&raw).ok())
             .map(|entry| entry.count)
             .unwrap_or(0);
@@ -2431,6 +3094,39 @@ impl EssentialsProvider {
         Ok(GetHoldersOrderedPageResult { ids })
     }
 
+    /// Up to `limit` (holders count, alkane) pairs of the holders ordering past `after`,
+    /// ascending or, with `desc`, descending. Seeks from `after` instead of loading the index.
+    pub fn get_holders_ordered_after(
+        &self,
+        params: GetHoldersOrderedAfterParams,
+    ) -> Result<GetHoldersOrderedAfterResult> {
+        crate::debug_timer_log!("get_holders_ordered_after");
+        let table = self.table();
+        let prefix = table.alkane_holders_ordered_prefix();
+        let bound = params.after.map(|(count, id)| table.alkane_holders_ordered_key(count, &id));
+        let (start, end) = if params.desc {
+            (prefix.clone(), bound.or_else(|| prefix_end_exclusive(&prefix)))
+        } else {
+            (
+                bound.map(|k| next_key(&k)).unwrap_or_else(|| prefix.clone()),
+                prefix_end_exclusive(&prefix),
+            )
+        };
+        let entries = self
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: params.blockhash,
+                start,
+                end,
+                limit: params.limit,
+                reverse: params.desc,
+            })?
+            .entries
+            .iter()
+            .filter_map(|(key, _)| table.parse_alkane_holders_ordered_key(key))
+            .collect();
+        Ok(GetHoldersOrderedAfterResult { entries })
+    }
+
     pub fn get_latest_circulating_supply(
         &self,
         params: GetLatestCirculatingSupplyParams,
@@ -2672,10 +3368,12 @@ impl EssentialsProvider {
         let window = if offset >= total { &[][..] } else { &all_keys[offset..end] };
         let has_more = end < total;
 
+        let schema = schema_for_alkane(self, &alk);
+        let network = get_network();
         let mut items: Map<String, Value> = Map::with_capacity(window.len());
         for k in window.iter() {
             let kv_key = table.kv_row_key(&alk, k);
-            let (last_txid_val, value_hex, value_str_val, value_u128_val) = match self
+            let (last_txid_val, value_hex, value_str_val, value_u128_val, schema_val) = match self
                 .get_raw_value(GetRawValueParams { blockhash: StateAt::Latest, key: kv_key })
             {
                 Ok(resp) => {
@@ -2686,12 +3384,16 @@ impl EssentialsProvider {
                             fmt_bytes_hex(raw),
                             utf8_or_null(raw),
                             u128_le_or_null(raw),
+                            schema
+                                .decode(k, raw, network)
+                                .map(|decoded| decoded.to_json())
+                                .unwrap_or(Value::Null),
                         )
                     } else {
-                        (Value::Null, "0x".to_string(), Value::Null, Value::Null)
+                        (Value::Null, "0x".to_string(), Value::Null, Value::Null, Value::Null)
                     }
                 }
-                Err(_) => (Value::Null, "0x".to_string(), Value::Null, Value::Null),
+                Err(_) => (Value::Null, "0x".to_string(), Value::Null, Value::Null, Value::Null),
             };
 
             let key_hex = fmt_bytes_hex(k);
@@ -2711,6 +3413,7 @@ impl EssentialsProvider {
                     "value_hex":  value_hex,
                     "value_str":  value_str_val,
                     "value_u128": value_u128_val,
+                    "schema":     schema_val,
                     "last_txid":  last_txid_val
                 }),
             );
@@ -2720,6 +3423,8 @@ impl EssentialsProvider {
             value: json!({
                 "ok": true,
                 "alkane": format!("{}:{}", alk.block, alk.tx),
+                "contract_type": schema.code,
+                "contract_label": schema.label,
                 "page": page,
                 "limit": limit,
                 "total": total,
@@ -2729,6 +3434,242 @@ impl EssentialsProvider {
         })
     }
 
+    pub fn rpc_get_storage_history(
+        &self,
+        params: RpcGetStorageHistoryParams,
+    ) -> Result<RpcGetStorageHistoryResult> {
+        let err = |error: &str| RpcGetStorageHistoryResult {
+            value: json!({"ok": false, "error": error}),
+        };
+        let Some(alk) = params.alkane.as_deref().and_then(parse_alkane_from_str) else {
+            return Ok(err("missing_or_invalid_alkane"));
+        };
+        let Some(key) = params.key.as_deref().and_then(parse_key_str_to_bytes) else {
+            return Ok(err("missing_or_invalid_key"));
+        };
+        // Cursor = (height << 32) | trace_seq of the last item returned; pages go newest first.
+        let cursor = match params.cursor.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            None => None,
+            Some(raw) => match parse_paged_cursor_u64(raw) {
+                Some(cursor) => Some(cursor),
+                None => return Ok(err("invalid_cursor")),
+            },
+        };
+        let limit = params.limit.unwrap_or(50).clamp(1, 1_000) as usize;
+
+        let history = match self.get_storage_history(GetStorageHistoryParams {
+            blockhash: StateAt::Latest,
+            alkane: alk,
+            key: key.clone(),
+            before: cursor.map(|c| ((c >> 32) as u32, c as u32)),
+            limit,
+        }) {
+            Ok(history) => history,
+            Err(_) => return Ok(err("internal_error")),
+        };
+
+        let schema = schema_for_alkane(self, &alk);
+        let network = get_network();
+        let value_json = |raw: &[u8]| {
+            json!({
+                "hex": fmt_bytes_hex(raw),
+                "str": utf8_or_null(raw),
+                "u128": u128_le_or_null(raw),
+                "schema": schema.decode(&key, raw, network).map(|d| d.to_json()),
+            })
+        };
+        let next_cursor = if history.has_more {
+            history.entries.last().map(|(height, trace_seq, _)| {
+                encode_paged_cursor_u64(((*height as u64) << 32) | *trace_seq as u64)
+            })
+        } else {
+            None
+        };
+        let items: Vec<Value> = history
+            .entries
+            .iter()
+            .map(|(height, trace_seq, entry)| {
+                json!({
+                    "height": height,
+                    "trace_seq": trace_seq,
+                    "txid": Txid::from_byte_array(entry.txid).to_string(),
+                    "old_value": entry.old_value.as_deref().map(value_json),
+                    "new_value": value_json(&entry.new_value),
+                })
+            })
+            .collect();
+
+        Ok(RpcGetStorageHistoryResult {
+            value: json!({
+                "ok": true,
+                "alkane": format!("{}:{}", alk.block, alk.tx),
+                "key_hex": fmt_bytes_hex(&key),
+                "key_str": utf8_or_null(&key),
+                "contract_type": schema.code,
+                "has_more": history.has_more,
+                "next_cursor": next_cursor,
+                "items": items,
+            }),
+        })
+    }
+
+    pub fn rpc_get_call_stats(
+        &self,
+        params: RpcGetCallStatsParams,
+    ) -> Result<RpcGetCallStatsResult> {
+        let err =
+            |error: &str| RpcGetCallStatsResult { value: json!({"ok": false, "error": error}) };
+        let Some(alk) = params.alkane.as_deref().and_then(parse_alkane_from_str) else {
+            return Ok(err("missing_or_invalid_alkane"));
+        };
+        let days = params.days.unwrap_or(30).clamp(1, 365);
+
+        let stats = match self.get_call_stats(GetCallStatsParams {
+            blockhash: StateAt::Latest,
+            alkane: alk,
+            days,
+        }) {
+            Ok(stats) => stats,
+            Err(_) => return Ok(err("internal_error")),
+        };
+        let method_names: HashMap<u128, String> = load_inspection(self, &alk)
+            .ok()
+            .flatten()
+            .and_then(|i| i.metadata)
+            .map(|meta| meta.methods.into_iter().map(|m| (m.opcode, m.name)).collect())
+            .unwrap_or_default();
+        let method = |opcode: &u128| method_names.get(opcode).cloned();
+        let stats_json = |s: &CallStats| {
+            json!({
+                "calls": s.calls,
+                "reverts": s.reverts,
+                "revert_rate": s.revert_rate(),
+                "unique_callers": s.unique_callers,
+            })
+        };
+
+        let mut window: BTreeMap<u128, CallStats> = BTreeMap::new();
+        let mut daily: Vec<Value> = Vec::new();
+        let mut totals: Vec<(u128, CallStats)> = Vec::new();
+        for (opcode, day, s) in &stats.rows {
+            if *day == ALL_TIME_DAY {
+                totals.push((*opcode, *s));
+            } else {
+                window.entry(*opcode).or_default().merge(s);
+                let mut row = stats_json(s);
+                row["day_ts"] = json!(*day as u64 * 86_400);
+                row["opcode"] = json!(opcode.to_string());
+                row["method"] = json!(method(opcode));
+                daily.push(row);
+            }
+        }
+        totals.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then_with(|| a.0.cmp(&b.0)));
+        let methods: Vec<Value> = totals
+            .iter()
+            .map(|(opcode, s)| {
+                let mut row = stats_json(s);
+                row["opcode"] = json!(opcode.to_string());
+                row["method"] = json!(method(opcode));
+                // Unique callers are per day, so they do not add up across the window.
+                let w = window.get(opcode).copied().unwrap_or_default();
+                row["window"] = json!({ "calls": w.calls, "reverts": w.reverts });
+                row
+            })
+            .collect();
+
+        Ok(RpcGetCallStatsResult {
+            value: json!({
+                "ok": true,
+                "alkane": format!("{}:{}", alk.block, alk.tx),
+                "days": days,
+                "last_day_ts": stats.last_day as u64 * 86_400,
+                "methods": methods,
+                "daily": daily,
+            }),
+        })
+    }
+
+    pub fn rpc_get_failed_txs(
+        &self,
+        params: RpcGetFailedTxsParams,
+    ) -> Result<RpcGetFailedTxsResult> {
+        let err =
+            |error: &str| RpcGetFailedTxsResult { value: json!({"ok": false, "error": error}) };
+        let alkane = match params.alkane.as_deref() {
+            None => None,
+            Some(raw) => match parse_alkane_from_str(raw) {
+                Some(alkane) => Some(alkane),
+                None => return Ok(err("missing_or_invalid_alkane")),
+            },
+        };
+        let address = match params.address.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            None => None,
+            Some(raw) => match normalize_address(raw) {
+                Some(address) => Some(address),
+                None => return Ok(err("invalid_address_format")),
+            },
+        };
+        let needle = params.error_prefix.as_deref().map(normalize_error).filter(|s| !s.is_empty());
+        let contains =
+            params.error_contains.as_deref().map(normalize_error).filter(|s| !s.is_empty());
+        // Cursor = hex (height, tx_idx, trace_idx) of the last position read.
+        let cursor = match params.cursor.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            None => None,
+            Some(raw) => match hex::decode(raw).ok().as_deref().and_then(decode_trace_pos) {
+                Some(pos) => Some(pos),
+                None => return Ok(err("invalid_cursor")),
+            },
+        };
+        let limit = params.limit.unwrap_or(50).clamp(1, 500) as usize;
+
+        let failed = match self.get_failed_traces(GetFailedTracesParams {
+            blockhash: StateAt::Latest,
+            alkane,
+            address: address.clone(),
+            error_prefix: needle,
+            error_contains: contains,
+            before: cursor,
+            limit,
+        }) {
+            Ok(failed) => failed,
+            Err(_) => return Ok(err("internal_error")),
+        };
+        let id_str = |id: &SchemaAlkaneId| format!("{}:{}", id.block, id.tx);
+        let next_cursor = failed.next.map(|pos| hex::encode(encode_trace_pos(pos)));
+        let items: Vec<Value> = failed
+            .entries
+            .iter()
+            .map(|((height, tx_idx, trace_idx), entry)| {
+                json!({
+                    "txid": Txid::from_byte_array(entry.txid).to_string(),
+                    "height": height,
+                    "tx_idx": tx_idx,
+                    "trace_idx": trace_idx,
+                    "vout": entry.vout,
+                    "alkane": entry.target.as_ref().map(id_str),
+                    "reverted_in": entry.reverted_in.as_ref().map(id_str),
+                    "opcode": entry.opcode.map(|op| op.to_string()),
+                    "error": entry.error,
+                    "addresses": entry.addresses,
+                })
+            })
+            .collect();
+
+        Ok(RpcGetFailedTxsResult {
+            value: json!({
+                "ok": true,
+                "alkane": alkane.as_ref().map(id_str),
+                "address": address,
+                "error_prefix": params.error_prefix,
+                "error_contains": params.error_contains,
+                "error_groups_truncated": failed.error_groups_truncated,
+                "has_more": next_cursor.is_some(),
+                "next_cursor": next_cursor,
+                "items": items,
+            }),
+        })
+    }
+
     pub fn rpc_get_all_alkanes(
         &self,
         params: RpcGetAllAlkanesParams,
@@ -2942,20 +3883,118 @@ impl EssentialsProvider {
                     "alkane": format!("{}:{}", id.block, id.tx),
                     "amount": h.amount.to_string()
                 }),
-            })
-            .collect();
+            })
+            .collect();
+
+        Ok(RpcGetHoldersResult {
+            value: json!({
+                "ok": true,
+                "alkane": format!("{}:{}", alk.block, alk.tx),
+                "page": page,
+                "limit": limit,
+                "total": total,
+                "has_more": has_more,
+                "items": items
+            }),
+        })
+    }
+
+    pub fn rpc_get_airdrop_list(
+        &self,
+        params: RpcGetAirdropListParams,
+    ) -> Result<RpcGetAirdropListResult> {
+        let err =
+            |error: &str| RpcGetAirdropListResult { value: json!({"ok": false, "error": error}) };
+        let Some(tokens) = params
+            .tokens
+            .iter()
+            .map(|raw| parse_airdrop_token(raw))
+            .collect::<Option<Vec<_>>>()
+            .filter(|tokens| !tokens.is_empty())
+        else {
+            return Ok(err("missing_or_invalid_tokens"));
+        };
+        let min_balance = match params.min_balance.as_deref() {
+            None => 0,
+            Some(raw) => match raw.trim().parse::<u128>() {
+                Ok(v) => v,
+                Err(_) => return Ok(err("invalid_min_balance")),
+            },
+        };
+        let filter = AirdropFilter {
+            min_balance,
+            exclude_contracts: params.exclude_contracts,
+            exclude_addresses: params.exclude_addresses.into_iter().collect(),
+        };
+
+        // The list is rebuilt on every call, so the RPC only serves tokens with few enough
+        // holders; the holder counts are the kept totals, not a scan.
+        let holders = tokens.iter().try_fold(0u64, |acc, t| {
+            let count = self
+                .get_holders_count(GetHoldersCountParams {
+                    blockhash: StateAt::Latest,
+                    alkane: t.alkane,
+                })?
+                .count;
+            Ok::<_, anyhow::Error>(acc.saturating_add(count))
+        })?;
+        if holders > AIRDROP_RPC_MAX_HOLDERS {
+            return Ok(RpcGetAirdropListResult {
+                value: json!({
+                    "ok": false,
+                    "error": "too_many_holders",
+                    "holders": holders,
+                    "max_holders": AIRDROP_RPC_MAX_HOLDERS,
+                }),
+            });
+        }
 
-        Ok(RpcGetHoldersResult {
-            value: json!({
-                "ok": true,
-                "alkane": format!("{}:{}", alk.block, alk.tx),
-                "page": page,
-                "limit": limit,
-                "total": total,
-                "has_more": has_more,
-                "items": items
-            }),
-        })
+        let entries = match build_airdrop_list(self, &tokens, &filter) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(err("internal_error")),
+        };
+        let height = self
+            .get_index_height(GetIndexHeightParams { blockhash: StateAt::Latest })
+            .ok()
+            .and_then(|res| res.height);
+        let csv = airdrop_csv(&entries);
+        let total = entries.iter().fold(0u128, |acc, e| acc.saturating_add(e.amount));
+        let limit = params.limit.unwrap_or(1_000).clamp(1, 5_000) as usize;
+        let page = params.page.unwrap_or(1).max(1) as usize;
+        let offset = (page - 1).saturating_mul(limit);
+
+        let mut value = json!({
+            "ok": true,
+            "height": height,
+            "tokens": tokens
+                .iter()
+                .map(|t| json!({
+                    "alkane": format!("{}:{}", t.alkane.block, t.alkane.tx),
+                    "weight": t.weight.to_string()
+                }))
+                .collect::<Vec<_>>(),
+            "count": entries.len(),
+            "total": total.to_string(),
+            "hash": airdrop_hash(&csv),
+        });
+        if params.csv {
+            value["csv"] = json!(csv);
+        } else {
+            let items: Vec<Value> = entries
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .map(|e| {
+                    let (holder_type, holder) = holder_parts(&e.holder);
+                    json!({"type": holder_type, "holder": holder, "amount": e.amount.to_string()})
+                })
+                .collect();
+            value["page"] = json!(page);
+            value["limit"] = json!(limit);
+            value["has_more"] = json!(entries.len() > offset.saturating_add(limit));
+            value["items"] = json!(items);
+        }
+        Ok(RpcGetAirdropListResult { value })
     }
 
     pub fn rpc_get_transfer_volume(
@@ -3166,6 +4205,103 @@ impl EssentialsProvider {
         })
     }
 
+    pub fn rpc_get_address_balance_history(
+        &self,
+        params: RpcGetAddressBalanceHistoryParams,
+    ) -> Result<RpcGetAddressBalanceHistoryResult> {
+        let err = |error: &str| RpcGetAddressBalanceHistoryResult {
+            value: json!({"ok": false, "error": error}),
+        };
+        let Some(address_raw) = params.address.as_deref().map(str::trim).filter(|s| !s.is_empty())
+        else {
+            return Ok(err("missing_or_invalid_address"));
+        };
+        let Some(address) = normalize_address(address_raw) else {
+            return Ok(err("invalid_address_format"));
+        };
+        let alkane = match params.alkane.as_deref() {
+            None => None,
+            Some(raw) => match parse_alkane_from_str(raw) {
+                Some(alkane) => Some(alkane),
+                None => return Ok(err("missing_or_invalid_alkane")),
+            },
+        };
+        let from_height = params.from_height.unwrap_or(0);
+        let to_height = params.to_height.unwrap_or(u32::MAX);
+        if from_height > to_height {
+            return Ok(err("invalid_height_range"));
+        }
+        let limit = params.limit.unwrap_or(1_000).clamp(1, 10_000) as usize;
+        // Cursor = height, tx_idx and alkane id of the last item returned, hex encoded.
+        let after = match params.cursor.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            None => None,
+            Some(raw) => match hex::decode(raw).ok().and_then(|b| parse_balance_history_cursor(&b))
+            {
+                Some(after) => Some(after),
+                None => return Ok(err("invalid_cursor")),
+            },
+        };
+
+        let history = match self.get_address_balance_history(GetAddressBalanceHistoryParams {
+            blockhash: StateAt::Latest,
+            address: address.clone(),
+            alkane,
+            from_height,
+            to_height,
+            after,
+            limit,
+        }) {
+            Ok(history) => history,
+            Err(_) => return Ok(err("internal_error")),
+        };
+
+        let id_str = |id: &SchemaAlkaneId| format!("{}:{}", id.block, id.tx);
+        let opening: Map<String, Value> = history
+            .opening
+            .iter()
+            .map(|(alkane, balance)| (id_str(alkane), Value::String(balance.to_string())))
+            .collect();
+        let next_cursor = if history.has_more {
+            history.entries.last().map(|(alkane, height, tx_idx, _)| {
+                let mut cursor = Vec::with_capacity(20);
+                cursor.extend_from_slice(&height.to_be_bytes());
+                cursor.extend_from_slice(&tx_idx.to_be_bytes());
+                cursor.extend_from_slice(&encode_alkane_id_be(alkane));
+                hex::encode(cursor)
+            })
+        } else {
+            None
+        };
+        let items: Vec<Value> = history
+            .entries
+            .iter()
+            .map(|(alkane, height, tx_idx, entry)| {
+                json!({
+                    "alkane": id_str(alkane),
+                    "height": height,
+                    "tx_idx": tx_idx,
+                    "txid": Txid::from_byte_array(entry.txid).to_string(),
+                    "delta": entry.delta.to_string(),
+                    "balance": entry.balance.to_string(),
+                })
+            })
+            .collect();
+
+        Ok(RpcGetAddressBalanceHistoryResult {
+            value: json!({
+                "ok": true,
+                "address": address,
+                "alkane": alkane.as_ref().map(id_str),
+                "from_height": from_height,
+                "to_height": to_height,
+                "opening": Value::Object(opening),
+                "has_more": history.has_more,
+                "next_cursor": next_cursor,
+                "items": items,
+            }),
+        })
+    }
+
     pub fn rpc_get_address_balances(
         &self,
         params: RpcGetAddressBalancesParams,
@@ -3491,6 +4627,7 @@ impl EssentialsProvider {
                 "txid": txid.to_string(),
                 "height": height,
                 "outflow": Value::Object(outflow),
+                "cursor": hex::encode(&key),
             }));
         }
 
@@ -4487,6 +5624,21 @@ pub struct GetListEntriesDescCursorResult {
     pub has_more: bool,
 }
 
+pub struct GetRangeEntriesParams {
+    pub blockhash: StateAt,
+
+    pub start: Vec<u8>,
+    /// Exclusive; `None` runs to the end of the module's keyspace.
+    pub end: Option<Vec<u8>>,
+    pub limit: usize,
+    /// Walk down from `end` instead of up from `start`.
+    pub reverse: bool,
+}
+
+pub struct GetRangeEntriesResult {
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
 pub struct SetRawValueParams {
     pub blockhash: StateAt,
 
@@ -4597,6 +5749,16 @@ pub struct GetAlkaneIdsBySymbolPrefixPageParams {
     pub limit: u64,
 }
 
+pub struct GetCreationIdsBySeqParams {
+    pub blockhash: StateAt,
+
+    pub seqs: Vec<u64>,
+}
+
+pub struct GetCreationIdsBySeqResult {
+    pub ids: Vec<Option<SchemaAlkaneId>>,
+}
+
 pub struct GetCreationCountParams {
     pub blockhash: StateAt,
 }
@@ -4615,6 +5777,81 @@ pub struct GetCreationIdsInBlockResult {
     pub alkanes: Vec<SchemaAlkaneId>,
 }
 
+pub struct GetFailedTracesParams {
+    pub blockhash: StateAt,
+
+    pub alkane: Option<SchemaAlkaneId>,
+    pub address: Option<String>,
+    /// Normalized (see `normalize_error`) start of the revert message.
+    pub error_prefix: Option<String>,
+    /// Normalized substring of the revert message; checked on each entry, never seeked.
+    pub error_contains: Option<String>,
+    /// Position of the last trace already read.
+    pub before: Option<TracePos>,
+    pub limit: usize,
+}
+
+pub struct GetFailedTracesResult {
+    /// Newest first.
+    pub entries: Vec<(TracePos, FailedTraceEntry)>,
+    /// Where to resume; None when nothing older matches.
+    pub next: Option<TracePos>,
+    /// `error_prefix` matched more than `FAILED_TRACE_MAX_ERROR_GROUPS` distinct messages,
+    /// so only the first ones were searched.
+    pub error_groups_truncated: bool,
+}
+
+pub struct GetCallStatsParams {
+    pub blockhash: StateAt,
+
+    pub alkane: SchemaAlkaneId,
+    /// Days in the window, ending on the day of the newest indexed block.
+    pub days: u32,
+}
+
+pub struct GetCallStatsResult {
+    /// (opcode, day, stats) ordered by opcode then day; `ALL_TIME_DAY` rows come last.
+    pub rows: Vec<(u128, u32, CallStats)>,
+    pub first_day: u32,
+    pub last_day: u32,
+}
+
+pub struct GetStorageHistoryParams {
+    pub blockhash: StateAt,
+
+    pub alkane: SchemaAlkaneId,
+    pub key: Vec<u8>,
+    /// (height, trace_seq) of the oldest write already returned.
+    pub before: Option<(u32, u32)>,
+    pub limit: usize,
+}
+
+pub struct GetStorageHistoryResult {
+    /// (height, trace_seq, write), newest first.
+    pub entries: Vec<(u32, u32, StorageWriteEntry)>,
+    pub has_more: bool,
+}
+
+pub struct GetAddressBalanceHistoryParams {
+    pub blockhash: StateAt,
+
+    pub address: String,
+    pub alkane: Option<SchemaAlkaneId>,
+    pub from_height: u32,
+    pub to_height: u32,
+    /// (height, tx_idx, alkane) of the last change already returned.
+    pub after: Option<(u32, u32, SchemaAlkaneId)>,
+    pub limit: usize,
+}
+
+pub struct GetAddressBalanceHistoryResult {
+    /// Balance of each token with history before `from_height`, as of `from_height - 1`.
+    pub opening: BTreeMap<SchemaAlkaneId, u128>,
+    /// (alkane, height, tx_idx, change), oldest first, at most `limit`.
+    pub entries: Vec<(SchemaAlkaneId, u32, u32, AddressBalanceChangeEntry)>,
+    pub has_more: bool,
+}
+
 pub struct GetHoldersCountParams {
     pub blockhash: StateAt,
 
@@ -4635,6 +5872,18 @@ pub struct GetHoldersCountsByIdResult {
     pub counts: Vec<u64>,
 }
 
+pub struct GetHoldersOrderedAfterParams {
+    pub blockhash: StateAt,
+
+    pub after: Option<(u64, SchemaAlkaneId)>,
+    pub desc: bool,
+    pub limit: usize,
+}
+
+pub struct GetHoldersOrderedAfterResult {
+    pub entries: Vec<(u64, SchemaAlkaneId)>,
+}
+
 pub struct GetHoldersOrderedPageParams {
     pub blockhash: StateAt,
 
@@ -4778,6 +6027,54 @@ pub struct RpcGetBlockSummaryResult {
     pub value: Value,
 }
 
+pub struct RpcGetFailedTxsParams {
+    pub alkane: Option<String>,
+    pub address: Option<String>,
+    /// Start of the revert message, matched case- and whitespace-insensitively.
+    pub error_prefix: Option<String>,
+    /// Part of the revert message, matched the same way.
+    pub error_contains: Option<String>,
+    pub limit: Option<u64>,
+    pub cursor: Option<String>,
+}
+
+pub struct RpcGetFailedTxsResult {
+    pub value: Value,
+}
+
+pub struct RpcGetCallStatsParams {
+    pub alkane: Option<String>,
+    pub days: Option<u32>,
+}
+
+pub struct RpcGetCallStatsResult {
+    pub value: Value,
+}
+
+pub struct RpcGetStorageHistoryParams {
+    pub alkane: Option<String>,
+    pub key: Option<String>,
+    pub limit: Option<u64>,
+    pub cursor: Option<String>,
+}
+
+pub struct RpcGetStorageHistoryResult {
+    pub value: Value,
+}
+
+pub struct RpcGetAddressBalanceHistoryParams {
+    pub address: Option<String>,
+    pub alkane: Option<String>,
+    pub from_height: Option<u32>,
+    pub to_height: Option<u32>,
+    pub limit: Option<u64>,
+    pub cursor: Option<String>,
+}
+
+pub struct RpcGetAddressBalanceHistoryResult {
+    pub value: Value,
+}
+
 pub struct RpcGetHoldersParams {
     pub alkane: Option<String>,
     pub page: Option<u64>,
@@ -4788,6 +6085,22 @@ pub struct RpcGetHoldersResult {
     pub value: Value,
 }
 
+pub struct RpcGetAirdropListParams {
+    /// `"2:0"` or `"2:0=<weight>"` per token.
+    pub tokens: Vec<String>,
+    pub min_balance: Option<String>,
+    pub exclude_contracts: bool,
+    pub exclude_addresses: Vec<String>,
+    /// Return the full list as CSV instead of one page of items.
+    pub csv: bool,
+    pub limit: Option<u64>,
+    pub page: Option<u64>,
+}
+
+pub struct RpcGetAirdropListResult {
+    pub value: Value,
+}
+
 pub struct RpcGetTransferVolumeParams {
     pub alkane: Option<String>,
     pub page: Option<u64>,
@@ -5015,6 +6328,60 @@ pub struct OutpointPointerBlobV3 {
     pub balances: Vec<BalanceEntry>,
 }
 
+/// One transaction's net change to an address's balance of a token, with the balance after it.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct AddressBalanceChangeEntry {
+    pub txid: [u8; 32],
+    pub delta: SignedU128,
+    pub balance: u128,
+}
+
+/// A trace whose top-level call reverted.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct FailedTraceEntry {
+    pub txid: [u8; 32],
+    pub vout: u32,
+    pub target: Option<SchemaAlkaneId>,
+    /// Innermost alkane whose call failed.
+    pub reverted_in: Option<SchemaAlkaneId>,
+    pub opcode: Option<u128>,
+    /// Revert reason from the return data; empty when none was given.
+    pub error: String,
+    pub error_normalized: String,
+    /// Addresses paid by the transaction.
+    pub addresses: Vec<String>,
+}
+
+/// Calls to one opcode of an alkane within a day (or all time).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct CallStats {
+    pub calls: u64,
+    pub reverts: u64,
+    /// Distinct callers within this bucket.
+    pub unique_callers: u64,
+}
+
+impl CallStats {
+    /// Adds `other`'s counts. Unique callers are distinct per bucket, so they are left alone:
+    /// summing them would count a caller once for every bucket it appears in.
+    pub fn merge(&mut self, other: &CallStats) {
+        self.calls = self.calls.saturating_add(other.calls);
+        self.reverts = self.reverts.saturating_add(other.reverts);
+    }
+
+    pub fn revert_rate(&self) -> f64 {
+        if self.calls == 0 { 0.0 } else { self.reverts as f64 / self.calls as f64 }
+    }
+}
+
+/// One write to a contract storage key. `old_value` is None when the key had no value yet.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct StorageWriteEntry {
+    pub txid: [u8; 32],
+    pub old_value: Option<Vec<u8>>,
+    pub new_value: Vec<u8>,
+}
+
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
 pub struct AlkaneBalanceTxEntry {
     pub txid: [u8; 32],
@@ -6650,6 +8017,262 @@ mod tests {
         assert_eq!(info, decoded);
     }
 
+    #[test]
+    fn storage_history_is_per_key_and_ordered() {
+        let provider = new_provider_with_tempdb();
+        let table = provider.table();
+        let alk = SchemaAlkaneId { block: 2, tx: 9 };
+        let write = |seq: u8, old: Option<u8>| StorageWriteEntry {
+            txid: [seq; 32],
+            old_value: old.map(|v| vec![v]),
+            new_value: vec![seq],
+        };
+        let long_a = vec![b'a'; u16::MAX as usize + 1];
+        let mut long_b = long_a.clone();
+        long_b.push(b'b');
+        let rows = [
+            (b"/totalsupply".as_slice(), 120, 1, write(2, Some(1))),
+            (b"/totalsupply".as_slice(), 100, 4, write(1, None)),
+            (b"/totalsupply".as_slice(), 120, 3, write(3, Some(2))),
+            // Same prefix, different key: must not leak into /totalsupply.
+            (b"/totalsupply2".as_slice(), 110, 1, write(9, None)),
+            // Keys too long for the length prefix are hashed, not truncated into one history.
+            (long_a.as_slice(), 130, 1, write(7, None)),
+            (long_b.as_slice(), 131, 1, write(8, None)),
+        ];
+        let puts = rows
+            .iter()
+            .map(|(skey, height, seq, entry)| {
+                (
+                    table.storage_history_key(&alk, skey, *height, *seq),
+                    borsh::to_vec(entry).expect("encode write"),
+                )
+            })
+            .collect();
+        provider
+            .set_batch(SetBatchParams { blockhash: StateAt::Latest, puts, deletes: Vec::new() })
+            .expect("write history rows");
+
+        let page = |key: &[u8], before: Option<(u32, u32)>, limit: usize| {
+            let history = provider
+                .get_storage_history(GetStorageHistoryParams {
+                    blockhash: StateAt::Latest,
+                    alkane: alk,
+                    key: key.to_vec(),
+                    before,
+                    limit,
+                })
+                .expect("history");
+            let got: Vec<(u32, u32, Vec<u8>)> =
+                history.entries.into_iter().map(|(h, seq, e)| (h, seq, e.new_value)).collect();
+            (got, history.has_more)
+        };
+        assert_eq!(
+            page(b"/totalsupply", None, 10),
+            (vec![(120, 3, vec![3]), (120, 1, vec![2]), (100, 4, vec![1])], false)
+        );
+        assert_eq!(page(b"/totalsupply", None, 2).1, true);
+        assert_eq!(page(b"/totalsupply", Some((120, 1)), 2), (vec![(100, 4, vec![1])], false));
+        assert_eq!(page(&long_a, None, 10), (vec![(130, 1, vec![7])], false));
+        assert_eq!(page(&long_b, None, 10), (vec![(131, 1, vec![8])], false));
+    }
+    #[test]
+    fn address_balance_history_pages_across_tokens_in_one_tx() {
+        let provider = new_provider_with_tempdb();
+        let table = provider.table();
+        let address = "bcrt1qpaging";
+        let a = SchemaAlkaneId { block: 2, tx: 1 };
+        let b = SchemaAlkaneId { block: 2, tx: 5 };
+        let change = |seq: u8, balance: u128| AddressBalanceChangeEntry {
+            txid: [seq; 32],
+            delta: SignedU128::from_parts(false, balance),
+            balance,
+        };
+        // Both tokens change in the first transaction of height 10.
+        let rows = [(a, 10, 0, 1), (b, 10, 0, 1), (a, 10, 1, 2), (b, 11, 0, 3)];
+        let puts = rows
+            .iter()
+            .map(|(alkane, height, tx_idx, seq)| {
+                (
+                    table.address_balance_history_key(address, alkane, *height, *tx_idx),
+                    borsh::to_vec(&change(*seq, u128::from(*seq))).expect("encode change"),
+                )
+            })
+            .collect();
+        provider
+            .set_batch(SetBatchParams { blockhash: StateAt::Latest, puts, deletes: Vec::new() })
+            .expect("write history rows");
+
+        let page = |after: Option<(u32, u32, SchemaAlkaneId)>, limit: usize| {
+            let history = provider
+                .get_address_balance_history(GetAddressBalanceHistoryParams {
+                    blockhash: StateAt::Latest,
+                    address: address.to_string(),
+                    alkane: None,
+                    from_height: 0,
+                    to_height: u32::MAX,
+                    after,
+                    limit,
+                })
+                .expect("history");
+            let got: Vec<(SchemaAlkaneId, u32, u32)> = history
+                .entries
+                .into_iter()
+                .map(|(alk, h, tx_idx, _)| (alk, h, tx_idx))
+                .collect();
+            (got, history.has_more)
+        };
+        let all: Vec<(SchemaAlkaneId, u32, u32)> = rows
+            .iter()
+            .map(|(alkane, height, tx_idx, _)| (*alkane, *height, *tx_idx))
+            .collect();
+        assert_eq!(page(None, 10), (all.clone(), false));
+
+        // One change per page: resuming after a's change in the shared tx still returns b's.
+        let mut walked = Vec::new();
+        let mut after = None;
+        loop {
+            let (got, has_more) = page(after, 1);
+            assert_eq!(got.len(), 1);
+            let (alkane, height, tx_idx) = got[0];
+            walked.push(got[0]);
+            after = Some((height, tx_idx, alkane));
+            if !has_more {
+                break;
+            }
+        }
+        assert_eq!(walked, all);
+
+        // A page ending inside the shared tx resumes with the next token, not the next tx.
+        assert_eq!(page(None, 1), (vec![(a, 10, 0)], true));
+        assert_eq!(page(Some((10, 0, a)), 2), (vec![(b, 10, 0), (a, 10, 1)], true));
+        assert_eq!(page(Some((10, 0, b)), 2), (vec![(a, 10, 1), (b, 11, 0)], false));
+    }
+
+    #[test]
+    fn failed_traces_filter_by_alkane_and_address() {
+        let provider = new_provider_with_tempdb();
+        let table = provider.table();
+        let pool = SchemaAlkaneId { block: 2, tx: 5 };
+        let entry = |seed: u8, address: &str, error: &str| FailedTraceEntry {
+            txid: [seed; 32],
+            vout: 3,
+            target: Some(pool),
+            reverted_in: Some(pool),
+            opcode: Some(77),
+            error: error.to_uppercase(),
+            error_normalized: error.to_string(),
+            addresses: vec![address.to_string()],
+        };
+        let rows = [
+            ((100, 1, 0), entry(1, "bc1qa", "mint cap reached")),
+            ((105, 2, 0), entry(2, "bc1qb", "mint cap reached")),
+            ((107, 0, 1), entry(3, "bc1qa", "mint paused")),
+        ];
+        let mut puts = Vec::new();
+        for (pos, e) in &rows {
+            puts.push((table.failed_trace_key(*pos), borsh::to_vec(e).expect("encode")));
+            puts.push((table.failed_trace_by_alkane_key(&pool, *pos), Vec::new()));
+            puts.push((table.failed_trace_by_address_key(&e.addresses[0], *pos), Vec::new()));
+            puts.push((table.failed_trace_by_error_key(&e.error_normalized, *pos), Vec::new()));
+        }
+        provider
+            .set_batch(SetBatchParams { blockhash: StateAt::Latest, puts, deletes: Vec::new() })
+            .expect("write failed traces");
+
+        let page = |alkane: Option<SchemaAlkaneId>,
+                    address: Option<&str>,
+                    error: Option<&str>,
+                    before: Option<TracePos>,
+                    limit: usize| {
+            let res = provider
+                .get_failed_traces(GetFailedTracesParams {
+                    blockhash: StateAt::Latest,
+                    alkane,
+                    address: address.map(str::to_string),
+                    error_prefix: error.map(str::to_string),
+                    error_contains: None,
+                    before,
+                    limit,
+                })
+                .expect("failed traces");
+            (res.entries.into_iter().map(|(pos, _)| pos).collect::<Vec<_>>(), res.next)
+        };
+        let query = |alkane, address, error| page(alkane, address, error, None, 10).0;
+        assert_eq!(query(None, None, None), vec![(107, 0, 1), (105, 2, 0), (100, 1, 0)]);
+        assert_eq!(query(Some(pool), None, None), vec![(107, 0, 1), (105, 2, 0), (100, 1, 0)]);
+        assert_eq!(query(None, Some("bc1qa"), None), vec![(107, 0, 1), (100, 1, 0)]);
+        assert_eq!(query(Some(pool), Some("bc1qb"), None), vec![(105, 2, 0)]);
+        assert_eq!(query(None, None, Some("mint cap")), vec![(105, 2, 0), (100, 1, 0)]);
+        // Two messages under one prefix are merged by position.
+        assert_eq!(query(None, None, Some("mint")), vec![(107, 0, 1), (105, 2, 0), (100, 1, 0)]);
+        assert!(query(None, None, Some("mint capped")).is_empty());
+        assert_eq!(query(None, Some("bc1qa"), Some("mint cap")), vec![(100, 1, 0)]);
+        assert_eq!(
+            page(None, None, None, None, 2),
+            (vec![(107, 0, 1), (105, 2, 0)], Some((105, 2, 0)))
+        );
+        assert_eq!(page(None, None, None, Some((105, 2, 0)), 2), (vec![(100, 1, 0)], None));
+        assert!(query(Some(SchemaAlkaneId { block: 2, tx: 6 }), None, None).is_empty());
+
+        let containing = |alkane: Option<SchemaAlkaneId>, address: Option<&str>, part: &str| {
+            provider
+                .get_failed_traces(GetFailedTracesParams {
+                    blockhash: StateAt::Latest,
+                    alkane,
+                    address: address.map(str::to_string),
+                    error_prefix: None,
+                    error_contains: Some(part.to_string()),
+                    before: None,
+                    limit: 10,
+                })
+                .expect("failed traces")
+                .entries
+                .into_iter()
+                .map(|(pos, _)| pos)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(containing(None, None, "cap"), vec![(105, 2, 0), (100, 1, 0)]);
+        assert_eq!(containing(Some(pool), None, "paused"), vec![(107, 0, 1)]);
+        assert_eq!(containing(None, Some("bc1qa"), "cap"), vec![(100, 1, 0)]);
+        assert!(containing(None, None, "capped").is_empty());
+    }
+
+    #[test]
+    fn failed_traces_report_truncated_error_groups() {
+        let provider = new_provider_with_tempdb();
+        let table = provider.table();
+        let write = |from: usize, to: usize| {
+            let puts = (from..to)
+                .map(|i| {
+                    let error = format!("revert: code {i:04}");
+                    (table.failed_trace_by_error_key(&error, (100, i as u32, 0)), Vec::new())
+                })
+                .collect();
+            provider
+                .set_batch(SetBatchParams { blockhash: StateAt::Latest, puts, deletes: Vec::new() })
+                .expect("write failed traces");
+        };
+        let truncated = || {
+            provider
+                .get_failed_traces(GetFailedTracesParams {
+                    blockhash: StateAt::Latest,
+                    alkane: None,
+                    address: None,
+                    error_prefix: Some("revert".to_string()),
+                    error_contains: None,
+                    before: None,
+                    limit: 1,
+                })
+                .expect("failed traces")
+                .error_groups_truncated
+        };
+        write(0, FAILED_TRACE_MAX_ERROR_GROUPS);
+        assert!(!truncated());
+        write(FAILED_TRACE_MAX_ERROR_GROUPS, FAILED_TRACE_MAX_ERROR_GROUPS + 1);
+        assert!(truncated());
+    }
+
     #[test]
     fn creation_record_round_trip() {
         let rec = AlkaneCreationRecord {
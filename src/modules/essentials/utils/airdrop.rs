@@ -0,0 +1,187 @@
+//! Airdrop lists: the holders of one or more alkanes at a retained height, filtered and
+//! merged with per-token weights. The list is rendered as a CSV in a fixed order, and the
+//! sha256 of that CSV identifies it, so anyone re-running the same query can check it.
+
+use crate::modules::essentials::storage::{EssentialsProvider, HolderId};
+use crate::modules::essentials::utils::balances::for_each_holder_of_alkane;
+use crate::runtime::state_at::StateAt;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use bitcoin::hashes::{Hash as _, sha256};
+use std::collections::{BTreeSet, HashMap};
+
+/// Holder entries fetched per storage round-trip.
+const HOLDER_CHUNK: usize = 1_000;
+
+/// Most holders, summed over the requested tokens, that `essentials.get_airdrop_list` builds a
+/// list from; larger lists come from the `airdrop` command, which has no cap.
+pub const AIRDROP_RPC_MAX_HOLDERS: u64 = 50_000;
+
+pub const AIRDROP_CSV_HEADER: &str = "holder_type,holder,amount";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AirdropToken {
+    pub alkane: SchemaAlkaneId,
+    /// Multiplier applied to balances of this token before they are summed per holder.
+    pub weight: u128,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AirdropFilter {
+    /// Balances of a token below this are ignored for that token.
+    pub min_balance: u128,
+    /// Drop alkane holders (pools, vaults and other contracts).
+    pub exclude_contracts: bool,
+    pub exclude_addresses: BTreeSet<String>,
+}
+
+impl AirdropFilter {
+    fn keeps(&self, holder: &HolderId, balance: u128) -> bool {
+        if balance == 0 || balance < self.min_balance {
+            return false;
+        }
+        match holder {
+            HolderId::Alkane(_) => !self.exclude_contracts,
+            HolderId::Address(addr) => !self.exclude_addresses.contains(addr),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AirdropEntry {
+    pub holder: HolderId,
+    pub amount: u128,
+}
+
+/// Parses `"2:0"` (weight 1) or `"2:0=3"` (weight 3).
+pub fn parse_airdrop_token(raw: &str) -> Option<AirdropToken> {
+    let (id, weight) = match raw.trim().split_once('=') {
+        Some((id, weight)) => (id, weight.trim().parse::<u128>().ok().filter(|w| *w > 0)?),
+        None => (raw.trim(), 1),
+    };
+    let (block, tx) = id.trim().split_once(':')?;
+    let alkane = SchemaAlkaneId { block: block.parse().ok()?, tx: tx.parse().ok()? };
+    Some(AirdropToken { alkane, weight })
+}
+
+/// Filters each token's holders, weights the surviving balances and sums them per holder.
+/// Entries are ordered by amount (largest first), then by holder.
+pub fn merge_holders<I>(lists: I, filter: &AirdropFilter) -> Vec<AirdropEntry>
+where
+    I: IntoIterator<Item = (u128, Vec<(HolderId, u128)>)>,
+{
+    let mut totals: HashMap<HolderId, u128> = HashMap::new();
+    for (weight, holders) in lists {
+        for (holder, balance) in holders {
+            if !filter.keeps(&holder, balance) {
+                continue;
+            }
+            let total = totals.entry(holder).or_insert(0);
+            *total = total.saturating_add(balance.saturating_mul(weight));
+        }
+    }
+    let mut entries: Vec<AirdropEntry> = totals
+        .into_iter()
+        .map(|(holder, amount)| AirdropEntry { holder, amount })
+        .collect();
+    entries.sort_by(|a, b| b.amount.cmp(&a.amount).then_with(|| a.holder.cmp(&b.holder)));
+    entries
+}
+
+/// Reads the holders of every token from `provider` (use `with_height` for a past height).
+pub fn build_airdrop_list(
+    provider: &EssentialsProvider,
+    tokens: &[AirdropToken],
+    filter: &AirdropFilter,
+) -> Result<Vec<AirdropEntry>> {
+    let mut lists = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let mut holders = Vec::new();
+        for_each_holder_of_alkane(StateAt::Latest, provider, token.alkane, HOLDER_CHUNK, |h| {
+            holders.push((h.holder, h.amount));
+            Ok(())
+        })?;
+        lists.push((token.weight, holders));
+    }
+    Ok(merge_holders(lists, filter))
+}
+
+pub fn holder_parts(holder: &HolderId) -> (&'static str, String) {
+    match holder {
+        HolderId::Address(addr) => ("address", addr.clone()),
+        HolderId::Alkane(id) => ("alkane", format!("{}:{}", id.block, id.tx)),
+    }
+}
+
+/// `holder_type,holder,amount` with one line per entry; addresses and ids never need quoting.
+pub fn airdrop_csv(entries: &[AirdropEntry]) -> String {
+    let mut out = String::with_capacity(64 * (entries.len() + 1));
+    out.push_str(AIRDROP_CSV_HEADER);
+    out.push('\n');
+    for entry in entries {
+        let (holder_type, holder) = holder_parts(&entry.holder);
+        out.push_str(&format!("{holder_type},{holder},{}\n", entry.amount));
+    }
+    out
+}
+
+/// Hex sha256 of the CSV bytes, i.e. what `sha256sum` prints for the written file.
+pub fn airdrop_hash(csv: &str) -> String {
+    hex::encode(sha256::Hash::hash(csv.as_bytes()).to_byte_array())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> HolderId {
+        HolderId::Address(s.to_string())
+    }
+
+    #[test]
+    fn tokens_parse_with_optional_weight() {
+        let id = SchemaAlkaneId { block: 2, tx: 16 };
+        assert_eq!(parse_airdrop_token("2:16"), Some(AirdropToken { alkane: id, weight: 1 }));
+        assert_eq!(parse_airdrop_token(" 2:16=3 "), Some(AirdropToken { alkane: id, weight: 3 }));
+        assert_eq!(parse_airdrop_token("2:16=0"), None);
+        assert_eq!(parse_airdrop_token("2"), None);
+    }
+
+    #[test]
+    fn merge_filters_weights_and_orders() {
+        let pool = HolderId::Alkane(SchemaAlkaneId { block: 2, tx: 1 });
+        let filter = AirdropFilter {
+            min_balance: 10,
+            exclude_contracts: true,
+            exclude_addresses: BTreeSet::from(["bc1qteam".to_string()]),
+        };
+        let lists = vec![
+            (1, vec![(addr("bc1qa"), 100), (addr("bc1qb"), 5), (addr("bc1qd"), 40), (pool, 1_000)]),
+            (2, vec![(addr("bc1qb"), 50), (addr("bc1qc"), 50), (addr("bc1qteam"), 500)]),
+        ];
+        let entries = merge_holders(lists, &filter);
+        let expected = vec![
+            AirdropEntry { holder: addr("bc1qa"), amount: 100 },
+            AirdropEntry { holder: addr("bc1qb"), amount: 100 },
+            AirdropEntry { holder: addr("bc1qc"), amount: 100 },
+            AirdropEntry { holder: addr("bc1qd"), amount: 40 },
+        ];
+        assert_eq!(entries, expected);
+    }
+
+    #[test]
+    fn csv_hash_is_stable() {
+        let entries = vec![
+            AirdropEntry { holder: addr("bc1qa"), amount: 7 },
+            AirdropEntry {
+                holder: HolderId::Alkane(SchemaAlkaneId { block: 2, tx: 1 }),
+                amount: 3,
+            },
+        ];
+        let csv = airdrop_csv(&entries);
+        assert_eq!(csv, "holder_type,holder,amount\naddress,bc1qa,7\nalkane,2:1,3\n");
+        assert_eq!(airdrop_hash(&csv), airdrop_hash(&airdrop_csv(&entries)));
+        assert_eq!(airdrop_hash(&csv).len(), 64);
+        assert_ne!(airdrop_hash(&csv), airdrop_hash(&airdrop_csv(&entries[..1])));
+    }
+}
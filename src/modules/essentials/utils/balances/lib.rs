@@ -1,7 +1,8 @@
 use super::defs::{EspoTraceType, SignedU128, SignedU128MapExt};
 use super::utils::{
-    Unallocated, compute_nets, is_op_return, parse_protostones, parse_short_id,
-    schema_id_from_parts, transfers_to_sheet, tx_has_op_return, u128_to_u32,
+    AddressBalanceChanges, Unallocated, address_balance_log, compute_nets, is_op_return,
+    parse_protostones, parse_short_id, push_address_balance_change, schema_id_from_parts,
+    transfers_to_sheet, tx_has_op_return, u128_to_u32,
 };
 use crate::alkanes::trace::{
     EspoBlock, EspoHostFunctionValues, EspoSandshrewLikeTrace, EspoSandshrewLikeTraceEvent,
@@ -15,6 +16,7 @@ use crate::debug;
 use crate::modules::ammdata::config::AmmDataConfig;
 use crate::modules::ammdata::storage::{AmmDataTable, SearchIndexField};
 use crate::modules::ammdata::utils::search::collect_search_prefixes;
+use crate::modules::essentials::consts::{SERIES_CIRCULATING_SUPPLY, SERIES_HOLDERS_COUNT};
 use crate::modules::essentials::storage::{
     AddressActivityEntry, AddressAmountEntry, AddressIndexListKind, AlkaneBalanceTxEntry,
     AlkaneTxSummary, BalanceEntry, HolderEntry, HolderId,
@@ -32,6 +34,7 @@ use crate::modules::essentials::storage::{
 };
 use crate::runtime::mdb::{Mdb, MdbBatch};
 use crate::runtime::state_at::StateAt;
+use crate::runtime::timeseries::series_point;
 use crate::schemas::{EspoOutpoint, SchemaAlkaneId};
 use anyhow::{Context, Result, anyhow};
 use bitcoin::block::Header;
@@ -1060,6 +1063,8 @@ pub fn bulk_update_balances_for_block(
         HashMap::new();
     let mut address_balance_delta: HashMap<String, HashMap<SchemaAlkaneId, SignedU128>> =
         HashMap::new();
+    let mut address_balance_changes: BTreeMap<(String, SchemaAlkaneId), AddressBalanceChanges> =
+        BTreeMap::new();
 
     let push_balance_tx_entry = |map: &mut HashMap<SchemaAlkaneId, Vec<AlkaneBalanceTxEntry>>,
                                  alk: SchemaAlkaneId,
@@ -1320,6 +1325,7 @@ pub fn bulk_update_balances_for_block(
         let tx = &atx.transaction;
         let txid = tx.compute_txid();
         let txid_bytes = txid.to_byte_array();
+        let tx_idx = tx_index_by_txid.get(&txid_bytes).copied().unwrap_or(0);
         let mut tx_addrs: HashSet<String> = HashSet::new();
         let mut tx_transfer_amounts_by_alkane: BTreeMap<SchemaAlkaneId, u128> = BTreeMap::new();
         let mut tx_transfer_participants_by_alkane: HashMap<SchemaAlkaneId, HashSet<String>> =
@@ -1420,6 +1426,12 @@ pub fn bulk_update_balances_for_block(
                         if slot.is_zero() {
                             per_addr.remove(&be.alkane);
                         }
+                        push_address_balance_change(
+                            address_balance_changes.entry((addr.clone(), be.alkane)).or_default(),
+                            tx_idx,
+                            txid_bytes,
+                            SignedU128::negative(be.amount),
+                        );
                         tx_transfer_participants_by_alkane
                             .entry(be.alkane)
                             .or_default()
@@ -1474,6 +1486,12 @@ pub fn bulk_update_balances_for_block(
                         if slot.is_zero() {
                             per_addr.remove(&be.alkane);
                         }
+                        push_address_balance_change(
+                            address_balance_changes.entry((addr.clone(), be.alkane)).or_default(),
+                            tx_idx,
+                            txid_bytes,
+                            SignedU128::negative(be.amount),
+                        );
                         tx_transfer_participants_by_alkane
                             .entry(be.alkane)
                             .or_default()
@@ -1605,6 +1623,14 @@ pub fn bulk_update_balances_for_block(
                     if slot.is_zero() {
                         per_addr.remove(&alkane_id);
                     }
+                    push_address_balance_change(
+                        address_balance_changes
+                            .entry((address_str.clone(), alkane_id))
+                            .or_default(),
+                        tx_idx,
+                        txid_bytes,
+                        SignedU128::positive(delta_amount),
+                    );
                     let activity_by_addr =
                         address_activity_received_delta.entry(address_str.clone()).or_default();
                     *activity_by_addr.entry(alkane_id).or_default() = activity_by_addr
@@ -2382,6 +2408,34 @@ pub fn bulk_update_balances_for_block(
     section_start_puts = puts.len();
     section_start_deletes = deletes.len();
 
+    // B2) Per-address balance change log: one row per (address, token, tx) with the balance
+    // after the tx, walked forward from the balance before this block.
+    if !address_balance_changes.is_empty() {
+        let keys: Vec<Vec<u8>> = address_balance_changes
+            .keys()
+            .map(|(address, token)| table.address_balance_key(address, token))
+            .collect();
+        let before = provider
+            .get_multi_values(GetMultiValuesParams { blockhash: StateAt::Latest, keys })?
+            .values;
+        for (((address, token), changes), raw) in address_balance_changes.iter().zip(before) {
+            let start = raw.as_ref().and_then(|raw| decode_u128_value(raw).ok()).unwrap_or(0);
+            for (tx_idx, entry) in address_balance_log(start, changes) {
+                puts.push((
+                    table.address_balance_history_key(address, token, block.height, tx_idx),
+                    borsh::to_vec(&entry)?,
+                ));
+            }
+        }
+        if debug {
+            eprintln!(
+                "[balances] writes B2/address_balance_history: puts+{}",
+                puts.len().saturating_sub(section_start_puts)
+            );
+        }
+        section_start_puts = puts.len();
+    }
+
     // C) Persist alkane holder balances as per-token rows.
     let mut alkane_balance_full_rebuilds = 0usize;
     let mut alkane_balance_full_rebuild_entries = 0usize;
@@ -2971,10 +3025,17 @@ pub fn bulk_update_balances_for_block(
                 }
             }
         }
+        let series_key = format!("{}:{}", alkane.block, alkane.tx);
         let new_index_key = table.alkane_holders_ordered_key(new_count, alkane);
         if prev_count != new_count {
             let prev_index_key = table.alkane_holders_ordered_key(prev_count, alkane);
             deletes.push(prev_index_key);
+            puts.push(series_point(
+                SERIES_HOLDERS_COUNT,
+                &series_key,
+                block.height,
+                new_count as i128,
+            ));
         }
         puts.push((new_index_key, Vec::new()));
 
@@ -2992,6 +3053,12 @@ pub fn bulk_update_balances_for_block(
             let encoded = encode_u128_value(supply)?;
             puts.push((table.circulating_supply_key(alkane, block.height), encoded.clone()));
             puts.push((supply_latest_key, encoded));
+            puts.push(series_point(
+                SERIES_CIRCULATING_SUPPLY,
+                &series_key,
+                block.height,
+                i128::try_from(supply).unwrap_or(i128::MAX),
+            ));
         }
 
         for (holder, amount) in changed_holders {
@@ -4555,6 +4622,83 @@ pub fn get_holders_for_alkane(
     Ok((total, supply, slice))
 }
 
+/// Walks the holder list of `alk` in insertion order, `chunk` holders at a time, and hands
+/// every non-zero holder to `f`. Unlike `get_holders_for_alkane` this never materializes
+/// (or sorts) the full list, so it is safe for exports of very widely held tokens.
+/// The live self-balance override is only applied to `StateAt::Latest` reads.
+pub fn for_each_holder_of_alkane<F>(
+    blockhash: StateAt,
+    provider: &EssentialsProvider,
+    alk: SchemaAlkaneId,
+    chunk: usize,
+    mut f: F,
+) -> Result<()>
+where
+    F: FnMut(HolderEntry) -> Result<()>,
+{
+    let table = provider.table();
+    let len = provider
+        .get_raw_value(GetRawValueParams { blockhash, key: table.holder_list_len_key(&alk) })?
+        .value
+        .and_then(|bytes| <[u8; 4]>::try_from(bytes.as_slice()).ok())
+        .map(u32::from_le_bytes)
+        .unwrap_or(0);
+
+    let self_balance = match (blockhash, provider.view_blockhash()) {
+        (StateAt::Latest, None) => lookup_self_balance(&alk),
+        _ => None,
+    };
+    let chunk = chunk.max(1) as u32;
+    let mut start = 0u32;
+    while start < len {
+        let end = start.saturating_add(chunk).min(len);
+        let idx_keys: Vec<Vec<u8>> =
+            (start..end).map(|idx| table.holder_list_idx_key(&alk, idx)).collect();
+        let idx_vals = provider
+            .get_multi_values(GetMultiValuesParams { blockhash, keys: idx_keys })?
+            .values;
+        let mut holders = Vec::with_capacity(idx_vals.len());
+        let mut holder_keys = Vec::with_capacity(idx_vals.len());
+        for raw in idx_vals.into_iter().flatten() {
+            let holder = match raw.first() {
+                Some(b'a') => match std::str::from_utf8(&raw[1..]) {
+                    Ok(addr) => HolderId::Address(addr.to_string()),
+                    Err(_) => continue,
+                },
+                Some(b'k') if raw.len() == 13 => HolderId::Alkane(SchemaAlkaneId {
+                    block: u32::from_be_bytes([raw[1], raw[2], raw[3], raw[4]]),
+                    tx: u64::from_be_bytes([
+                        raw[5], raw[6], raw[7], raw[8], raw[9], raw[10], raw[11], raw[12],
+                    ]),
+                }),
+                _ => continue,
+            };
+            if self_balance.is_some() && holder == HolderId::Alkane(alk) {
+                continue;
+            }
+            holder_keys.push(table.holder_key(&alk, &holder));
+            holders.push(holder);
+        }
+        let vals = provider
+            .get_multi_values(GetMultiValuesParams { blockhash, keys: holder_keys })?
+            .values;
+        for (holder, value) in holders.into_iter().zip(vals) {
+            let Some(amount) = value.and_then(|bytes| decode_u128_value(&bytes).ok()) else {
+                continue;
+            };
+            if amount > 0 {
+                f(HolderEntry { holder, amount })?;
+            }
+        }
+        start = end;
+    }
+
+    if let Some(amount) = self_balance.filter(|amount| *amount > 0) {
+        f(HolderEntry { holder: HolderId::Alkane(alk), amount })?;
+    }
+    Ok(())
+}
+
 pub fn get_transfer_volume_for_alkane(
     blockhash: StateAt,
     provider: &EssentialsProvider,
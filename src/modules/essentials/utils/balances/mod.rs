@@ -7,3 +7,4 @@ mod tests;
 
 pub use defs::{EspoTraceType, SignedU128};
 pub use lib::*;
+pub(crate) use utils::parse_short_id;
@@ -1,5 +1,6 @@
 use super::defs::SignedU128;
 use super::lib::accumulate_alkane_balance_deltas;
+use super::utils::{AddressBalanceChanges, address_balance_log, push_address_balance_change};
 use crate::alkanes::trace::{EspoHostFunctionValues, EspoSandshrewLikeTrace, get_espo_block};
 use crate::config::{AppConfig, init_config_from};
 use crate::core::blockfetcher::BlockFetchMode;
@@ -10,6 +11,7 @@ use std::collections::HashMap;
 fn init_test_config_from_run_sh() {
     let cfg = AppConfig {
         debug_backup: None,
+        admin: None,
         readonly_metashrew_db_dir: "/data/.metashrew/v9/.metashrew-v9".to_string(),
         electrum_rpc_url: None,
         metashrew_rpc_url: "http://127.0.0.1:7044".to_string(),
@@ -24,6 +26,7 @@ fn init_test_config_from_run_sh() {
         sdb_poll_ms: 5000,
         indexer_block_delay_ms: 0,
         port: 5778,
+        grpc_host: None,
         explorer_host: Some("0.0.0.0:5779".parse().expect("parse explorer_host")),
         explorer_base_path: "/".to_string(),
         explorer_pizza_tv_endpoint: "https://tv.pizza.fun".to_string(),
@@ -502,3 +505,23 @@ fn credits_outflows_for_block_912568_trace() {
     assert_eq!(owner_out.get(&token_native).copied(), Some(SignedU128::negative(698_851_075)));
     assert_eq!(owner_out.get(&token_in).copied(), Some(SignedU128::positive(45_000_000_000_000)));
 }
+
+#[test]
+fn address_balance_log_nets_per_tx_and_tracks_balance() {
+    let mut changes = AddressBalanceChanges::new();
+    // tx 1 spends 40 and sends 10 back as change; tx 3 receives 25; tx 4 moves 5 in and out.
+    push_address_balance_change(&mut changes, 1, [1u8; 32], SignedU128::negative(40));
+    push_address_balance_change(&mut changes, 1, [1u8; 32], SignedU128::positive(10));
+    push_address_balance_change(&mut changes, 3, [3u8; 32], SignedU128::positive(25));
+    push_address_balance_change(&mut changes, 4, [4u8; 32], SignedU128::positive(5));
+    push_address_balance_change(&mut changes, 4, [4u8; 32], SignedU128::negative(5));
+    assert_eq!(changes.len(), 3);
+
+    let log = address_balance_log(100, &changes);
+    let rows: Vec<(u32, String, u128)> = log
+        .iter()
+        .map(|(tx_idx, e)| (*tx_idx, e.delta.to_string(), e.balance))
+        .collect();
+    assert_eq!(rows, vec![(1, "-30".to_string(), 70), (3, "25".to_string(), 95)]);
+    assert_eq!(log[1].1.txid, [3u8; 32]);
+}
@@ -1,8 +1,9 @@
-use super::defs::EspoTraceType;
+use super::defs::{EspoTraceType, SignedU128};
 use crate::alkanes::trace::{
     EspoSandshrewLikeTrace, EspoSandshrewLikeTraceEvent, EspoSandshrewLikeTraceReturnData,
     EspoSandshrewLikeTraceShortId, EspoSandshrewLikeTraceStatus, EspoSandshrewLikeTraceTransfer,
 };
+use crate::modules::essentials::storage::AddressBalanceChangeEntry;
 use crate::schemas::SchemaAlkaneId;
 use anyhow::{Result, anyhow};
 use bitcoin::{ScriptBuf, Transaction};
@@ -92,6 +93,45 @@ pub(super) fn is_op_return(spk: &ScriptBuf) -> bool {
     !b.is_empty() && b[0] == bitcoin::opcodes::all::OP_RETURN.to_u8()
 }
 
+/// Per-tx changes to one address/token within a block: (tx_idx, txid, net delta), tx order.
+pub(super) type AddressBalanceChanges = Vec<(u32, [u8; 32], SignedU128)>;
+
+/// Adds `delta` to the change of `tx_idx`, merging the inputs and outputs of one tx.
+pub(super) fn push_address_balance_change(
+    changes: &mut AddressBalanceChanges,
+    tx_idx: u32,
+    txid: [u8; 32],
+    delta: SignedU128,
+) {
+    match changes.last_mut() {
+        Some(last) if last.0 == tx_idx => last.2 += delta,
+        _ => changes.push((tx_idx, txid, delta)),
+    }
+}
+
+/// Walks a block's changes forward from the balance before the block, producing one log
+/// entry per tx with the resulting balance; txs that net to zero are skipped.
+pub(super) fn address_balance_log(
+    start: u128,
+    changes: &AddressBalanceChanges,
+) -> Vec<(u32, AddressBalanceChangeEntry)> {
+    let mut balance = start;
+    let mut log = Vec::with_capacity(changes.len());
+    for (tx_idx, txid, delta) in changes {
+        if delta.is_zero() {
+            continue;
+        }
+        let (is_negative, amount) = delta.as_parts();
+        balance = if is_negative {
+            balance.saturating_sub(amount)
+        } else {
+            balance.saturating_add(amount)
+        };
+        log.push((*tx_idx, AddressBalanceChangeEntry { txid: *txid, delta: *delta, balance }));
+    }
+    log
+}
+
 pub(super) fn u128_to_u32(v: u128) -> Result<u32> {
     u32::try_from(v).map_err(|_| anyhow!("downcast failed: {v} does not fit into u32"))
 }
@@ -168,7 +208,7 @@ pub(super) fn compute_nets(
     (netin, netout, status)
 }
 
-pub(super) fn parse_short_id(id: &EspoSandshrewLikeTraceShortId) -> Option<SchemaAlkaneId> {
+pub(crate) fn parse_short_id(id: &EspoSandshrewLikeTraceShortId) -> Option<SchemaAlkaneId> {
     let block = parse_hex_u32(&id.block)?;
     let tx = parse_hex_u64(&id.tx)?;
     Some(SchemaAlkaneId { block, tx })
@@ -0,0 +1,234 @@
+//! Contract call analytics: per-alkane, per-opcode call counts, reverts and unique callers,
+//! bucketed by UTC day plus an all-time bucket.
+//!
+//! Calls are read from the sandshrew-like trace: every `invoke` is matched with the `return`
+//! that closes it, so nested calls are counted against the contract they invoked. Fuel is not
+//! tracked: the trace only reports the fuel left when a call starts, not what it burned.
+
+use crate::alkanes::trace::{EspoBlock, EspoSandshrewLikeTraceEvent, EspoSandshrewLikeTraceStatus};
+use crate::modules::essentials::storage::{CallStats, EssentialsProvider, GetMultiValuesParams};
+use crate::modules::essentials::utils::balances::parse_short_id;
+use crate::runtime::state_at::StateAt;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use bitcoin::Transaction;
+use borsh::BorshDeserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Day bucket holding the all-time totals.
+pub const ALL_TIME_DAY: u32 = u32::MAX;
+
+const CALLER_ALKANE: u8 = 0;
+const CALLER_SCRIPT: u8 = 1;
+
+pub fn day_index(timestamp: u32) -> u32 {
+    timestamp / 86_400
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallRecord {
+    pub alkane: SchemaAlkaneId,
+    pub opcode: u128,
+    pub reverted: bool,
+    /// Calling alkane, or the transaction's caller script for top-level calls.
+    pub caller: Vec<u8>,
+}
+
+fn alkane_caller(id: &SchemaAlkaneId) -> Vec<u8> {
+    let mut out = Vec::with_capacity(13);
+    out.push(CALLER_ALKANE);
+    out.extend_from_slice(&id.block.to_be_bytes());
+    out.extend_from_slice(&id.tx.to_be_bytes());
+    out
+}
+
+/// The first non-OP_RETURN output script stands in for the caller of top-level calls.
+pub fn tx_caller(tx: &Transaction) -> Vec<u8> {
+    let mut out = vec![CALLER_SCRIPT];
+    if let Some(txout) = tx.output.iter().find(|o| !o.script_pubkey.is_op_return()) {
+        out.extend_from_slice(txout.script_pubkey.as_bytes());
+    }
+    out
+}
+
+/// One record per returned call; calls still open when the trace ends are not counted.
+pub fn trace_calls(events: &[EspoSandshrewLikeTraceEvent], top_caller: &[u8]) -> Vec<CallRecord> {
+    let mut open: Vec<Option<(SchemaAlkaneId, u128, Vec<u8>)>> = Vec::new();
+    let mut out = Vec::new();
+    for ev in events {
+        match ev {
+            EspoSandshrewLikeTraceEvent::Invoke(invoke) => {
+                let ctx = &invoke.context;
+                let frame = parse_short_id(&ctx.myself).map(|alkane| {
+                    let opcode = ctx
+                        .inputs
+                        .first()
+                        .and_then(|s| u128::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+                        .unwrap_or(0);
+                    let caller = match parse_short_id(&ctx.caller) {
+                        Some(id) if id.block != 0 || id.tx != 0 => alkane_caller(&id),
+                        _ => top_caller.to_vec(),
+                    };
+                    (alkane, opcode, caller)
+                });
+                open.push(frame);
+            }
+            EspoSandshrewLikeTraceEvent::Return(ret) => {
+                if let Some(Some((alkane, opcode, caller))) = open.pop() {
+                    let reverted = ret.status == EspoSandshrewLikeTraceStatus::Failure;
+                    out.push(CallRecord { alkane, opcode, reverted, caller });
+                }
+            }
+            EspoSandshrewLikeTraceEvent::Create(_) => {}
+        }
+    }
+    out
+}
+
+/// Rows to merge into the call stats of every alkane called in `block`.
+pub fn call_stats_rows(
+    provider: &EssentialsProvider,
+    block: &EspoBlock,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let table = provider.table();
+    let day = day_index(block.block_header.time);
+    let mut stats: BTreeMap<(SchemaAlkaneId, u128, u32), CallStats> = BTreeMap::new();
+    let mut callers: BTreeSet<Vec<u8>> = BTreeSet::new();
+    for tx in block.transactions.iter() {
+        let Some(traces) = tx.traces.as_ref() else { continue };
+        let top_caller = tx_caller(&tx.transaction);
+        for trace in traces.iter() {
+            for call in trace_calls(&trace.sandshrew_trace.events, &top_caller) {
+                for bucket in [day, ALL_TIME_DAY] {
+                    let entry = stats.entry((call.alkane, call.opcode, bucket)).or_default();
+                    entry.calls += 1;
+                    entry.reverts += call.reverted as u64;
+                    callers.insert(table.call_caller_key(
+                        &call.alkane,
+                        call.opcode,
+                        bucket,
+                        &call.caller,
+                    ));
+                }
+            }
+        }
+    }
+    if stats.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut puts = Vec::with_capacity(stats.len() + callers.len());
+    let caller_keys: Vec<Vec<u8>> = callers.into_iter().collect();
+    let seen = provider
+        .get_multi_values(GetMultiValuesParams {
+            blockhash: StateAt::Latest,
+            keys: caller_keys.clone(),
+        })?
+        .values;
+    for (key, prev) in caller_keys.into_iter().zip(seen) {
+        if prev.is_some() {
+            continue;
+        }
+        let Some((alkane, opcode, bucket)) = table.parse_call_caller_key(&key) else { continue };
+        if let Some(entry) = stats.get_mut(&(alkane, opcode, bucket)) {
+            entry.unique_callers += 1;
+        }
+        puts.push((key, Vec::new()));
+    }
+
+    let stat_keys: Vec<Vec<u8>> = stats
+        .keys()
+        .map(|(alkane, opcode, bucket)| table.call_stats_key(alkane, *opcode, *bucket))
+        .collect();
+    let existing = provider
+        .get_multi_values(GetMultiValuesParams {
+            blockhash: StateAt::Latest,
+            keys: stat_keys.clone(),
+        })?
+        .values;
+    for ((key, delta), prev) in stat_keys.into_iter().zip(stats.into_values()).zip(existing) {
+        let mut merged = match prev {
+            Some(bytes) => CallStats::try_from_slice(&bytes)?,
+            None => CallStats::default(),
+        };
+        merged.merge(&delta);
+        // `delta.unique_callers` counts only callers first seen in this bucket at this block.
+        merged.unique_callers = merged.unique_callers.saturating_add(delta.unique_callers);
+        puts.push((key, borsh::to_vec(&merged)?));
+    }
+    Ok(puts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alkanes::trace::{
+        EspoSandshrewLikeTraceInvokeContext, EspoSandshrewLikeTraceInvokeData,
+        EspoSandshrewLikeTraceReturnData, EspoSandshrewLikeTraceReturnResponse,
+        EspoSandshrewLikeTraceShortId,
+    };
+
+    fn short(block: u32, tx: u64) -> EspoSandshrewLikeTraceShortId {
+        EspoSandshrewLikeTraceShortId { block: format!("0x{block:x}"), tx: format!("0x{tx:x}") }
+    }
+
+    fn invoke(
+        myself: (u32, u64),
+        caller: (u32, u64),
+        opcode: u128,
+        fuel: u64,
+    ) -> EspoSandshrewLikeTraceEvent {
+        EspoSandshrewLikeTraceEvent::Invoke(EspoSandshrewLikeTraceInvokeData {
+            typ: "call".to_string(),
+            context: EspoSandshrewLikeTraceInvokeContext {
+                myself: short(myself.0, myself.1),
+                caller: short(caller.0, caller.1),
+                inputs: vec![format!("0x{opcode:x}")],
+                incoming_alkanes: Vec::new(),
+                vout: 3,
+            },
+            fuel,
+        })
+    }
+
+    fn ret(status: EspoSandshrewLikeTraceStatus) -> EspoSandshrewLikeTraceEvent {
+        EspoSandshrewLikeTraceEvent::Return(EspoSandshrewLikeTraceReturnData {
+            status,
+            response: EspoSandshrewLikeTraceReturnResponse {
+                alkanes: Vec::new(),
+                data: "0x".to_string(),
+                storage: Vec::new(),
+            },
+        })
+    }
+
+    #[test]
+    fn nested_calls_are_matched_with_their_returns() {
+        let events = vec![
+            invoke((2, 1), (0, 0), 1, 1_000),
+            invoke((2, 7), (2, 1), 77, 900),
+            ret(EspoSandshrewLikeTraceStatus::Failure),
+            ret(EspoSandshrewLikeTraceStatus::Success),
+            // Never returned: not counted.
+            invoke((2, 9), (0, 0), 5, 500),
+        ];
+        let calls = trace_calls(&events, b"\x01spk");
+        assert_eq!(
+            calls,
+            vec![
+                CallRecord {
+                    alkane: SchemaAlkaneId { block: 2, tx: 7 },
+                    opcode: 77,
+                    reverted: true,
+                    caller: alkane_caller(&SchemaAlkaneId { block: 2, tx: 1 }),
+                },
+                CallRecord {
+                    alkane: SchemaAlkaneId { block: 2, tx: 1 },
+                    opcode: 1,
+                    reverted: false,
+                    caller: b"\x01spk".to_vec(),
+                },
+            ]
+        );
+    }
+}
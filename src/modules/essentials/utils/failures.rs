@@ -0,0 +1,218 @@
+//! Failed (reverted) traces and their revert reasons.
+//!
+//! A trace fails when its top-level call returns `failure`. The reason is taken from the
+//! return data of the innermost failing call, falling back to the outer ones when it is empty.
+//! Failures are indexed by the called alkane, the alkane that reverted, every address the
+//! transaction pays, and the start of the normalized revert message.
+
+use crate::alkanes::trace::{EspoBlock, EspoSandshrewLikeTraceEvent, EspoSandshrewLikeTraceStatus};
+use crate::modules::essentials::storage::{
+    EssentialsProvider, FailedTraceEntry, spk_to_address_str,
+};
+use crate::modules::essentials::utils::balances::parse_short_id;
+use crate::schemas::SchemaAlkaneId;
+use anyhow::Result;
+use bitcoin::Network;
+use bitcoin::hashes::Hash;
+
+/// Selector of the `Error(string)` revert payload.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Longest revert reason kept per trace.
+const MAX_ERROR_LEN: usize = 512;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceFailure {
+    /// Alkane invoked by the trace's top-level call.
+    pub target: Option<SchemaAlkaneId>,
+    /// Innermost alkane whose call failed.
+    pub reverted_in: Option<SchemaAlkaneId>,
+    pub opcode: Option<u128>,
+    pub error: String,
+}
+
+/// Revert reason carried in return data (`0x`-hex), without the `Error(string)` selector.
+pub fn decode_revert_message(data_hex: &str) -> Option<String> {
+    let bytes = hex::decode(data_hex.strip_prefix("0x").unwrap_or(data_hex)).ok()?;
+    let payload = bytes.strip_prefix(ERROR_SELECTOR.as_slice()).unwrap_or(&bytes);
+    let text = String::from_utf8_lossy(payload);
+    let trimmed = text.trim_matches(|c: char| c == '\u{0}' || c.is_whitespace());
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some(trimmed.chars().take(MAX_ERROR_LEN).collect())
+}
+
+/// Lowercased, with runs of whitespace collapsed to one space; used for matching.
+pub fn normalize_error(message: &str) -> String {
+    message.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+pub fn trace_failure(events: &[EspoSandshrewLikeTraceEvent]) -> Option<TraceFailure> {
+    let mut stack: Vec<(Option<SchemaAlkaneId>, Option<u128>)> = Vec::new();
+    let mut target = None;
+    let mut opcode = None;
+    let mut reverted_in = None;
+    let mut error: Option<String> = None;
+    let mut failed = false;
+    for ev in events {
+        match ev {
+            EspoSandshrewLikeTraceEvent::Invoke(invoke) => {
+                let ctx = &invoke.context;
+                let alkane = parse_short_id(&ctx.myself);
+                let op = ctx
+                    .inputs
+                    .first()
+                    .and_then(|s| u128::from_str_radix(s.trim_start_matches("0x"), 16).ok());
+                if stack.is_empty() {
+                    target = alkane;
+                    opcode = op;
+                    // A new top-level call starts clean.
+                    reverted_in = None;
+                    error = None;
+                }
+                stack.push((alkane, op));
+            }
+            EspoSandshrewLikeTraceEvent::Return(ret) => {
+                let Some((alkane, _)) = stack.pop() else { continue };
+                let is_failure = ret.status == EspoSandshrewLikeTraceStatus::Failure;
+                if is_failure {
+                    if reverted_in.is_none() {
+                        reverted_in = alkane;
+                    }
+                    if error.is_none() {
+                        error = decode_revert_message(&ret.response.data);
+                    }
+                }
+                if stack.is_empty() {
+                    failed = is_failure;
+                }
+            }
+            EspoSandshrewLikeTraceEvent::Create(_) => {}
+        }
+    }
+    if !failed {
+        return None;
+    }
+    Some(TraceFailure { target, reverted_in, opcode, error: error.unwrap_or_default() })
+}
+
+/// Main rows and alkane/address/error index rows for every failed trace in `block`.
+pub fn failed_trace_rows(
+    provider: &EssentialsProvider,
+    block: &EspoBlock,
+    network: Network,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let table = provider.table();
+    let mut puts = Vec::new();
+    for (tx_idx, tx) in block.transactions.iter().enumerate() {
+        let Some(traces) = tx.traces.as_ref() else { continue };
+        let mut addresses: Option<Vec<String>> = None;
+        for (trace_idx, trace) in traces.iter().enumerate() {
+            let Some(failure) = trace_failure(&trace.sandshrew_trace.events) else { continue };
+            let addresses = addresses.get_or_insert_with(|| {
+                let mut out: Vec<String> = tx
+                    .transaction
+                    .output
+                    .iter()
+                    .filter(|o| !o.script_pubkey.is_op_return())
+                    .filter_map(|o| spk_to_address_str(&o.script_pubkey, network))
+                    .collect();
+                out.sort();
+                out.dedup();
+                out
+            });
+            let pos = (block.height, tx_idx as u32, trace_idx as u32);
+            let entry = FailedTraceEntry {
+                txid: tx.transaction.compute_txid().to_byte_array(),
+                vout: trace.outpoint.vout,
+                target: failure.target,
+                reverted_in: failure.reverted_in,
+                opcode: failure.opcode,
+                error_normalized: normalize_error(&failure.error),
+                error: failure.error,
+                addresses: addresses.clone(),
+            };
+            puts.push((table.failed_trace_key(pos), borsh::to_vec(&entry)?));
+            let mut alkanes: Vec<SchemaAlkaneId> =
+                entry.target.into_iter().chain(entry.reverted_in).collect();
+            alkanes.dedup();
+            for alkane in alkanes {
+                puts.push((table.failed_trace_by_alkane_key(&alkane, pos), Vec::new()));
+            }
+            for address in addresses.iter() {
+                puts.push((table.failed_trace_by_address_key(address, pos), Vec::new()));
+            }
+            puts.push((table.failed_trace_by_error_key(&entry.error_normalized, pos), Vec::new()));
+        }
+    }
+    Ok(puts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alkanes::trace::{
+        EspoSandshrewLikeTraceInvokeContext, EspoSandshrewLikeTraceInvokeData,
+        EspoSandshrewLikeTraceReturnData, EspoSandshrewLikeTraceReturnResponse,
+        EspoSandshrewLikeTraceShortId,
+    };
+
+    fn invoke(tx: u64, opcode: u128) -> EspoSandshrewLikeTraceEvent {
+        let short = |tx: u64| EspoSandshrewLikeTraceShortId {
+            block: "0x2".to_string(),
+            tx: format!("0x{tx:x}"),
+        };
+        EspoSandshrewLikeTraceEvent::Invoke(EspoSandshrewLikeTraceInvokeData {
+            typ: "call".to_string(),
+            context: EspoSandshrewLikeTraceInvokeContext {
+                myself: short(tx),
+                caller: short(0),
+                inputs: vec![format!("0x{opcode:x}")],
+                incoming_alkanes: Vec::new(),
+                vout: 3,
+            },
+            fuel: 1_000,
+        })
+    }
+
+    fn ret(status: EspoSandshrewLikeTraceStatus, data: &[u8]) -> EspoSandshrewLikeTraceEvent {
+        EspoSandshrewLikeTraceEvent::Return(EspoSandshrewLikeTraceReturnData {
+            status,
+            response: EspoSandshrewLikeTraceReturnResponse {
+                alkanes: Vec::new(),
+                data: format!("0x{}", hex::encode(data)),
+                storage: Vec::new(),
+            },
+        })
+    }
+
+    #[test]
+    fn revert_messages_are_decoded_and_normalized() {
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend_from_slice(b"ALKANES: revert:  Mint  cap reached\0");
+        let msg = decode_revert_message(&format!("0x{}", hex::encode(&data))).unwrap();
+        assert_eq!(msg, "ALKANES: revert:  Mint  cap reached");
+        assert_eq!(normalize_error(&msg), "alkanes: revert: mint cap reached");
+        assert_eq!(decode_revert_message("0x"), None);
+    }
+
+    #[test]
+    fn failure_reports_innermost_revert() {
+        use EspoSandshrewLikeTraceStatus::{Failure, Success};
+        let failed = vec![
+            invoke(1, 77),
+            invoke(9, 2),
+            ret(Failure, b"insufficient balance"),
+            ret(Failure, b""),
+        ];
+        let failure = trace_failure(&failed).unwrap();
+        assert_eq!(failure.target, Some(SchemaAlkaneId { block: 2, tx: 1 }));
+        assert_eq!(failure.reverted_in, Some(SchemaAlkaneId { block: 2, tx: 9 }));
+        assert_eq!(failure.opcode, Some(77));
+        assert_eq!(failure.error, "insufficient balance");
+
+        // An inner revert the caller recovers from is not a failed trace.
+        let recovered = vec![invoke(1, 77), invoke(9, 2), ret(Failure, b"nope"), ret(Success, b"")];
+        assert_eq!(trace_failure(&recovered), None);
+    }
+}
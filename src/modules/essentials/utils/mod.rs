@@ -1,4 +1,8 @@
+pub mod airdrop;
 pub mod balances;
+pub mod call_stats;
 pub mod creation_meta;
+pub mod failures;
 pub mod inspections;
 pub mod names;
+pub mod storage_schema;
@@ -0,0 +1,273 @@
+//! Storage schemas for known contract types.
+//!
+//! A schema maps storage key patterns to typed decoders so raw contract storage can be shown
+//! as fields ("Token 0 = 2:0") instead of hex. The contract type is picked by the exact
+//! inspection metadata name of a known contract; every schema also falls back to the keys
+//! shared by all alkanes.
+
+use crate::modules::essentials::storage::{EssentialsProvider, spk_to_address_str};
+use crate::modules::essentials::utils::inspections::load_inspection;
+use crate::schemas::SchemaAlkaneId;
+use StorageValueKind::{Address, AlkaneId, Bytes, U128, Utf8};
+use bitcoin::{Network, ScriptBuf};
+use serde_json::{Value, json};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageValueKind {
+    /// Little-endian u128 (up to 16 bytes).
+    U128,
+    /// 32 bytes: block and tx as little-endian u128s.
+    AlkaneId,
+    /// A scriptPubKey, or an address stored as UTF-8.
+    Address,
+    Utf8,
+    Bytes,
+}
+
+impl StorageValueKind {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::U128 => "u128",
+            Self::AlkaneId => "alkane_id",
+            Self::Address => "address",
+            Self::Utf8 => "utf8",
+            Self::Bytes => "bytes",
+        }
+    }
+}
+
+/// A key pattern: exact, or a prefix when it ends in `*` (e.g. `/all_pools/*`).
+pub struct StorageKeyRule {
+    pub pattern: &'static str,
+    pub label: &'static str,
+    pub kind: StorageValueKind,
+}
+
+impl StorageKeyRule {
+    fn matches(&self, key: &[u8]) -> bool {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => key.len() > prefix.len() && key.starts_with(prefix.as_bytes()),
+            None => key == self.pattern.as_bytes(),
+        }
+    }
+}
+
+pub struct ContractSchema {
+    /// Stable id returned by RPC (`amm_pool`, `orbital_instance`, ...).
+    pub code: &'static str,
+    pub label: &'static str,
+    /// Inspection metadata names (compared ignoring ASCII case) that select this schema.
+    names: &'static [&'static str],
+    pub rules: &'static [StorageKeyRule],
+}
+
+const fn rule(
+    pattern: &'static str,
+    label: &'static str,
+    kind: StorageValueKind,
+) -> StorageKeyRule {
+    StorageKeyRule { pattern, label, kind }
+}
+
+/// Keys written by the standard token, auth and proxy helpers; checked for every contract.
+const COMMON_RULES: &[StorageKeyRule] = &[
+    rule("/name", "Name", Utf8),
+    rule("/symbol", "Symbol", Utf8),
+    rule("/totalsupply", "Total supply", U128),
+    rule("/cap", "Mint cap", U128),
+    rule("/minted", "Minted", U128),
+    rule("/value-per-mint", "Value per mint", U128),
+    rule("/value_per_mint", "Value per mint", U128),
+    rule("/initialized", "Initialized", U128),
+    rule("/auth", "Auth token", AlkaneId),
+    rule("/implementation", "Implementation", AlkaneId),
+    rule("/beacon", "Beacon", AlkaneId),
+    rule("/data", "Data", Bytes),
+];
+
+pub const GENERIC_SCHEMA: ContractSchema =
+    ContractSchema { code: "generic", label: "Contract", names: &[], rules: &[] };
+
+pub const CONTRACT_SCHEMAS: &[ContractSchema] = &[
+    ContractSchema {
+        code: "amm_factory",
+        label: "AMM factory",
+        names: &["AMMFactory"],
+        rules: &[
+            rule("/pool_factory_id", "Pool template", U128),
+            rule("/all_pools_length", "Pool count", U128),
+            rule("/all_pools/*", "Pool", AlkaneId),
+            rule("/pools/*", "Pool", AlkaneId),
+        ],
+    },
+    ContractSchema {
+        code: "amm_pool",
+        label: "AMM pool",
+        names: &["AMMPool"],
+        rules: &[
+            rule("/alkane/0", "Token 0", AlkaneId),
+            rule("/alkane/1", "Token 1", AlkaneId),
+            rule("/factory_id", "Factory", AlkaneId),
+            rule("/claimablefees", "Claimable fees", U128),
+            rule("/klast", "k last", U128),
+        ],
+    },
+    ContractSchema {
+        code: "orbital_instance",
+        label: "Orbital instance",
+        names: &["OrbitalInstance"],
+        rules: &[rule("/index", "Index", U128)],
+    },
+    ContractSchema { code: "auth_token", label: "Auth token", names: &["AuthToken"], rules: &[] },
+    ContractSchema {
+        code: "frbtc",
+        label: "frBTC",
+        names: &["SyntheticBitcoin"],
+        rules: &[
+            // The scriptPubKey that wraps and unwraps pay to.
+            rule("/signer", "Signer", Address),
+            rule("/premium", "Premium", U128),
+        ],
+    },
+];
+
+/// Picks the schema of the contract whose metadata name is `name`.
+pub fn schema_for_name(name: Option<&str>) -> &'static ContractSchema {
+    let Some(name) = name.map(str::trim) else { return &GENERIC_SCHEMA };
+    CONTRACT_SCHEMAS
+        .iter()
+        .find(|schema| schema.names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+        .unwrap_or(&GENERIC_SCHEMA)
+}
+
+pub fn schema_for_alkane(
+    provider: &EssentialsProvider,
+    alkane: &SchemaAlkaneId,
+) -> &'static ContractSchema {
+    let inspection = load_inspection(provider, alkane).ok().flatten();
+    schema_for_name(inspection.as_ref().and_then(|i| i.metadata.as_ref()).map(|m| m.name.as_str()))
+}
+
+pub struct DecodedStorageValue {
+    pub label: &'static str,
+    pub kind: StorageValueKind,
+    pub value: Value,
+}
+
+impl DecodedStorageValue {
+    pub fn to_json(&self) -> Value {
+        json!({ "label": self.label, "type": self.kind.code(), "value": self.value })
+    }
+
+    /// Single-line rendering for tables.
+    pub fn display(&self) -> String {
+        match &self.value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl ContractSchema {
+    fn rule_for(&self, key: &[u8]) -> Option<&StorageKeyRule> {
+        self.rules.iter().chain(COMMON_RULES).find(|r| r.matches(key))
+    }
+
+    /// Decodes `value` by the first rule matching `key`; None when no rule matches or the
+    /// bytes do not fit the rule's type.
+    pub fn decode(
+        &self,
+        key: &[u8],
+        value: &[u8],
+        network: Network,
+    ) -> Option<DecodedStorageValue> {
+        let rule = self.rule_for(key)?;
+        let value = decode_value(rule.kind, value, network)?;
+        Some(DecodedStorageValue { label: rule.label, kind: rule.kind, value })
+    }
+}
+
+fn alkane_id_from_le(raw: &[u8]) -> Option<SchemaAlkaneId> {
+    if raw.len() != 32 {
+        return None;
+    }
+    let block = u128::from_le_bytes(raw[..16].try_into().ok()?);
+    let tx = u128::from_le_bytes(raw[16..].try_into().ok()?);
+    Some(SchemaAlkaneId { block: u32::try_from(block).ok()?, tx: u64::try_from(tx).ok()? })
+}
+
+fn id_str(id: &SchemaAlkaneId) -> String {
+    format!("{}:{}", id.block, id.tx)
+}
+
+pub fn decode_value(kind: StorageValueKind, raw: &[u8], network: Network) -> Option<Value> {
+    match kind {
+        StorageValueKind::U128 => {
+            if raw.is_empty() || raw.len() > 16 {
+                return None;
+            }
+            let mut buf = [0u8; 16];
+            buf[..raw.len()].copy_from_slice(raw);
+            Some(json!(u128::from_le_bytes(buf).to_string()))
+        }
+        StorageValueKind::AlkaneId => alkane_id_from_le(raw).map(|id| json!(id_str(&id))),
+        StorageValueKind::Address => spk_to_address_str(&ScriptBuf::from(raw.to_vec()), network)
+            .or_else(|| std::str::from_utf8(raw).ok().map(str::to_string))
+            .map(Value::String),
+        StorageValueKind::Utf8 => std::str::from_utf8(raw).ok().map(|s| json!(s)),
+        StorageValueKind::Bytes => Some(json!(format!("0x{}", hex::encode(raw)))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash as _;
+
+    fn id_bytes(block: u128, tx: u128) -> Vec<u8> {
+        let mut v = block.to_le_bytes().to_vec();
+        v.extend_from_slice(&tx.to_le_bytes());
+        v
+    }
+
+    #[test]
+    fn schemas_follow_metadata_names() {
+        assert_eq!(schema_for_name(Some("AMMFactory")).code, "amm_factory");
+        assert_eq!(schema_for_name(Some("ammpool")).code, "amm_pool");
+        assert_eq!(schema_for_name(Some("OrbitalInstance")).code, "orbital_instance");
+        assert_eq!(schema_for_name(Some("SyntheticBitcoin")).code, "frbtc");
+        // Names that merely contain a known one are not that contract.
+        assert_eq!(schema_for_name(Some("PoolParty")).code, "generic");
+        assert_eq!(schema_for_name(Some("AuthTokenFactory")).code, "generic");
+        assert_eq!(schema_for_name(Some("Diesel")).code, "generic");
+        assert_eq!(schema_for_name(None).code, "generic");
+    }
+
+    #[test]
+    fn decodes_typed_values() {
+        let net = Network::Bitcoin;
+        let pool = schema_for_name(Some("AMMPool"));
+
+        let token0 = pool.decode(b"/alkane/0", &id_bytes(2, 16), net).unwrap();
+        assert_eq!((token0.label, token0.display()), ("Token 0", "2:16".to_string()));
+
+        // Common keys apply to every schema.
+        let supply = pool.decode(b"/totalsupply", &1_000u128.to_le_bytes(), net).unwrap();
+        assert_eq!(supply.value, json!("1000"));
+        assert_eq!(supply.to_json()["type"], "u128");
+
+        let factory = schema_for_name(Some("AMMFactory"));
+        let listed = factory.decode(b"/all_pools/\x00\x00\x00\x01", &id_bytes(2, 7), net);
+        assert_eq!(listed.map(|d| d.display()), Some("2:7".to_string()));
+        assert!(factory.decode(b"/all_pools/", &id_bytes(2, 7), net).is_none());
+
+        let frbtc = schema_for_name(Some("SyntheticBitcoin"));
+        let spk = ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array([7; 20]));
+        let signer = frbtc.decode(b"/signer", spk.as_bytes(), net).unwrap();
+        assert_eq!(signer.to_json()["type"], "address");
+        assert!(signer.display().starts_with("bc1q"));
+
+        assert!(pool.decode(b"/alkane/0", &[1, 2, 3], net).is_none());
+        assert!(pool.decode(b"/unknown", &[1], net).is_none());
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+pub const DEFAULT_MAX_COMPLEXITY: usize = 2_000;
+pub const DEFAULT_MAX_DEPTH: usize = 10;
+pub const DEFAULT_MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct GraphqlConfig {
+    pub host: String,
+    pub port: u16,
+    /// Upper bound on the static query cost (sum of field costs, list fields
+    /// multiplied by their `first` argument). Queries above it are rejected.
+    pub max_complexity: usize,
+    pub max_depth: usize,
+    pub max_page_size: usize,
+}
+
+impl GraphqlConfig {
+    pub fn spec() -> &'static str {
+        "{ \"host\": \"<host>\", \"port\": <port>, \"max_complexity\": <optional usize>, \"max_depth\": <optional usize>, \"max_page_size\": <optional usize> }"
+    }
+
+    pub fn from_value(value: &Value) -> Result<Self> {
+        let obj = value.as_object().ok_or_else(|| {
+            anyhow!("graphql config must be an object; expected: {}", Self::spec())
+        })?;
+
+        let host = obj
+            .get("host")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("graphql.host missing; expected: {}", Self::spec()))?;
+
+        let port = obj
+            .get("port")
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u16::try_from(v).ok())
+            .ok_or_else(|| anyhow!("graphql.port missing/invalid; expected: {}", Self::spec()))?;
+
+        let parse_limit = |key: &str, default: usize| -> Result<usize> {
+            match obj.get(key) {
+                None | Some(Value::Null) => Ok(default),
+                Some(v) => v
+                    .as_u64()
+                    .filter(|n| *n > 0)
+                    .map(|n| n as usize)
+                    .ok_or_else(|| anyhow!("graphql.{key} must be a positive integer")),
+            }
+        };
+
+        Ok(Self {
+            host,
+            port,
+            max_complexity: parse_limit("max_complexity", DEFAULT_MAX_COMPLEXITY)?,
+            max_depth: parse_limit("max_depth", DEFAULT_MAX_DEPTH)?,
+            max_page_size: parse_limit("max_page_size", DEFAULT_MAX_PAGE_SIZE)?,
+        })
+    }
+}
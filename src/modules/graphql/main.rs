@@ -0,0 +1,103 @@
+use crate::config::{get_espo_module_mdb, get_module_config};
+use crate::modules::ammdata::storage::AmmDataProvider;
+use crate::modules::defs::{EspoModule, RpcNsRegistrar};
+use crate::modules::essentials::storage::EssentialsProvider;
+use crate::modules::graphql::config::GraphqlConfig;
+use crate::modules::graphql::schema::{GraphqlState, build_schema};
+use crate::modules::graphql::server::run as run_graphql;
+use crate::runtime::mdb::Mdb;
+use anyhow::Result;
+use bitcoin::Network;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Read-only GraphQL server over the essentials and ammdata providers. Does not
+/// index anything itself.
+pub struct Graphql {
+    config: Option<GraphqlConfig>,
+    mdb: Option<Arc<Mdb>>,
+    essentials: Option<Arc<EssentialsProvider>>,
+    ammdata: Option<Arc<AmmDataProvider>>,
+}
+
+impl Graphql {
+    pub fn new() -> Self {
+        Self { config: None, mdb: None, essentials: None, ammdata: None }
+    }
+}
+
+impl Default for Graphql {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EspoModule for Graphql {
+    fn get_name(&self) -> &'static str {
+        "graphql"
+    }
+
+    fn set_mdb(&mut self, mdb: Arc<Mdb>) {
+        let essentials = Arc::new(EssentialsProvider::new(get_espo_module_mdb("essentials")));
+        let ammdata = get_module_config("ammdata").map(|_| {
+            Arc::new(AmmDataProvider::new(get_espo_module_mdb("ammdata"), essentials.clone()))
+        });
+        self.mdb = Some(mdb);
+        self.essentials = Some(essentials);
+        self.ammdata = ammdata;
+    }
+
+    fn get_genesis_block(&self, _network: Network) -> u32 {
+        u32::MAX
+    }
+
+    fn get_mdb(&self) -> Option<Arc<Mdb>> {
+        self.mdb.clone()
+    }
+
+    fn index_block(&self, _block: crate::alkanes::trace::EspoBlock) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_index_height(&self) -> Option<u32> {
+        None
+    }
+
+    fn register_rpc(&self, _reg: &RpcNsRegistrar) {
+        let Some(cfg) = self.config.clone() else {
+            return;
+        };
+        let essentials = self
+            .essentials
+            .as_ref()
+            .expect("graphql module missing essentials provider")
+            .clone();
+
+        let addr: SocketAddr = format!("{}:{}", cfg.host, cfg.port)
+            .parse()
+            .unwrap_or_else(|e| panic!("invalid graphql host/port: {e}"));
+
+        let state = GraphqlState {
+            essentials,
+            ammdata: self.ammdata.clone(),
+            max_page_size: cfg.max_page_size,
+        };
+        let schema = build_schema(state, cfg.max_complexity, cfg.max_depth);
+
+        tokio::spawn(async move {
+            if let Err(e) = run_graphql(addr, schema).await {
+                eprintln!("[graphql] server error: {e:?}");
+            }
+        });
+        eprintln!("[graphql] listening on {}/graphql", addr);
+    }
+
+    fn config_spec(&self) -> Option<&'static str> {
+        Some(GraphqlConfig::spec())
+    }
+
+    fn set_config(&mut self, config: &serde_json::Value) -> Result<()> {
+        self.config = Some(GraphqlConfig::from_value(config)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod config;
+pub mod main;
+pub mod schema;
+pub mod server;
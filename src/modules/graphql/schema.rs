@@ -0,0 +1,774 @@
+use crate::modules::ammdata::schemas::SchemaPoolSnapshot;
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetListEntriesDescCursorParams as AmmCursorParams, decode_candle_v1,
+};
+use crate::modules::ammdata::utils::activity::{ActivityRow, decode_activity_v1};
+use crate::modules::ammdata::utils::candles::{POOL_CANDLE_FRAMES, PriceSide};
+use crate::modules::ammdata::utils::live_reserves::fetch_all_pools;
+use crate::modules::essentials::storage::{
+    EssentialsProvider, RpcGetAddressBalancesParams, RpcGetAddressOutpointsParams,
+    RpcGetAlkaneBalanceTxsParams, RpcGetAlkaneBlockTxsParams, RpcGetAlkaneInfoParams,
+    RpcGetAlkaneTxSummaryParams, RpcGetBlockSummaryParams, RpcGetHoldersParams,
+};
+use crate::runtime::state_at::StateAt;
+use crate::schemas::SchemaAlkaneId;
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Error, Json, Object, Result, Schema, SimpleObject,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Page size used by connection fields when `first` is omitted. Also the
+/// multiplier the complexity estimate uses for those fields.
+const DEFAULT_PAGE_SIZE: usize = 20;
+/// Extra cost of fields that load every pool snapshot (`fetch_all_pools`).
+const POOL_SCAN_COST: usize = 50;
+
+pub type EspoSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+#[derive(Clone)]
+pub struct GraphqlState {
+    pub essentials: Arc<EssentialsProvider>,
+    pub ammdata: Option<Arc<AmmDataProvider>>,
+    pub max_page_size: usize,
+}
+
+pub fn build_schema(state: GraphqlState, max_complexity: usize, max_depth: usize) -> EspoSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(state)
+        .limit_complexity(max_complexity)
+        .limit_depth(max_depth)
+        .finish()
+}
+
+/* ---------------- helpers ---------------- */
+
+fn state<'a>(ctx: &Context<'a>) -> &'a GraphqlState {
+    ctx.data_unchecked::<GraphqlState>()
+}
+
+fn ammdata<'a>(ctx: &Context<'a>) -> Result<&'a AmmDataProvider> {
+    state(ctx)
+        .ammdata
+        .as_deref()
+        .ok_or_else(|| Error::new("ammdata module is not enabled on this instance"))
+}
+
+/// Static cost of a connection field: every requested row pays for its selection.
+fn connection_cost(first: Option<i32>, child_complexity: usize) -> usize {
+    first
+        .map(|n| n.max(1) as usize)
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .saturating_mul(child_complexity)
+}
+
+fn page_size(ctx: &Context<'_>, first: Option<i32>) -> usize {
+    let max = state(ctx).max_page_size.max(1);
+    first.map(|n| n.max(1) as usize).unwrap_or(DEFAULT_PAGE_SIZE).min(max)
+}
+
+/// Handlers report failures in-band (`{"ok": false, "error": ...}`); lift them
+/// into GraphQL errors so clients see them in `errors[]`.
+fn rpc_value(value: Value) -> Result<Value> {
+    if value.get("ok").and_then(|v| v.as_bool()) == Some(false) {
+        let err = value.get("error").and_then(|v| v.as_str()).unwrap_or("request_failed");
+        return Err(Error::new(err.to_string()));
+    }
+    Ok(value)
+}
+
+fn str_field(v: &Value, key: &str) -> Option<String> {
+    v.get(key).and_then(|x| x.as_str()).map(|s| s.to_string())
+}
+
+fn alkane_str(id: &SchemaAlkaneId) -> String {
+    format!("{}:{}", id.block, id.tx)
+}
+
+fn parse_alkane(s: &str) -> Option<SchemaAlkaneId> {
+    let (block, tx) = s.trim().split_once(':')?;
+    let block = match block.strip_prefix("0x") {
+        Some(h) => u32::from_str_radix(h, 16).ok()?,
+        None => block.parse::<u32>().ok()?,
+    };
+    let tx = match tx.strip_prefix("0x") {
+        Some(h) => u64::from_str_radix(h, 16).ok()?,
+        None => tx.parse::<u64>().ok()?,
+    };
+    Some(SchemaAlkaneId { block, tx })
+}
+
+/// Offset cursors for lists that are computed rather than stored as a key range.
+fn decode_offset_cursor(after: Option<&str>) -> Result<usize> {
+    match after {
+        None => Ok(0),
+        Some(s) => s.parse::<usize>().map_err(|_| Error::new("invalid_cursor")),
+    }
+}
+
+/// Maps an (offset, size) window onto the page/limit pagination of the JSON-RPC
+/// readers. Returns (page, limit, rows_to_skip); a misaligned window straddles
+/// `page` and the one after it.
+fn page_window(offset: usize, size: usize) -> (u64, u64, usize) {
+    let size = size.max(1);
+    ((offset / size + 1) as u64, size as u64, offset % size)
+}
+
+/// Reads `size` rows starting at `offset` through a page/limit reader, fetching
+/// at most two pages of `size` rows. `fetch` returns the page's rows and the
+/// total row count.
+fn read_offset_window<F>(offset: usize, size: usize, mut fetch: F) -> Result<(Vec<Value>, usize)>
+where
+    F: FnMut(u64, u64) -> Result<(Vec<Value>, usize)>,
+{
+    let (page, limit, skip) = page_window(offset, size);
+    let (mut rows, total) = fetch(page, limit)?;
+    if skip > 0 && rows.len() as u64 == limit {
+        rows.extend(fetch(page + 1, limit)?.0);
+    }
+    Ok((rows.into_iter().skip(skip).take(size).collect(), total))
+}
+
+/// Key cursors for lists read with `scan_desc_cursor_page`; the cursor is the
+/// hex encoded storage key of the last row handed out.
+fn decode_key_cursor(after: Option<&str>, prefix: &[u8]) -> Result<Option<Vec<u8>>> {
+    let Some(raw) = after else { return Ok(None) };
+    let bytes = hex::decode(raw).map_err(|_| Error::new("invalid_cursor"))?;
+    if !bytes.starts_with(prefix) {
+        return Err(Error::new("invalid_cursor"));
+    }
+    Ok(Some(bytes))
+}
+
+/// Trailing numeric segment right after `prefix` ("<ts>" or "<ts>:<seq>").
+fn ts_after_prefix(key: &[u8], prefix: &[u8]) -> u64 {
+    key.get(prefix.len()..)
+        .and_then(|tail| tail.split(|b| *b == b':').next())
+        .and_then(|seg| std::str::from_utf8(seg).ok())
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0)
+}
+
+fn load_pools(ctx: &Context<'_>) -> Result<Vec<(SchemaAlkaneId, SchemaPoolSnapshot)>> {
+    let provider = ammdata(ctx)?;
+    let mut rows: Vec<(SchemaAlkaneId, SchemaPoolSnapshot)> = fetch_all_pools(provider)
+        .map_err(|e| Error::new(format!("live_fetch_failed: {e}")))?
+        .into_iter()
+        .collect();
+    rows.sort_by(|(a, _), (b, _)| a.block.cmp(&b.block).then(a.tx.cmp(&b.tx)));
+    Ok(rows)
+}
+
+/* ---------------- query root ---------------- */
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn alkane(&self, ctx: &Context<'_>, id: String) -> Result<Option<Alkane>> {
+        let Some(alkane) = parse_alkane(&id) else {
+            return Err(Error::new("missing_or_invalid_alkane"));
+        };
+        let value = state(ctx)
+            .essentials
+            .rpc_get_alkane_info(RpcGetAlkaneInfoParams { alkane: Some(alkane_str(&alkane)) })
+            .map_err(|e| Error::new(e.to_string()))?
+            .value;
+        if value.get("error").and_then(|v| v.as_str()) == Some("not_found") {
+            return Ok(None);
+        }
+        Ok(Some(Alkane { id: alkane, info: rpc_value(value)? }))
+    }
+
+    async fn address(&self, address: String) -> Address {
+        Address { address }
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>, txid: String) -> Result<Option<Transaction>> {
+        let value = state(ctx)
+            .essentials
+            .rpc_get_alkane_tx_summary(RpcGetAlkaneTxSummaryParams { txid: Some(txid) })
+            .map_err(|e| Error::new(e.to_string()))?
+            .value;
+        if value.get("error").and_then(|v| v.as_str()) == Some("not_found") {
+            return Ok(None);
+        }
+        let value = rpc_value(value)?;
+        Ok(Some(Transaction {
+            txid: str_field(&value, "txid").unwrap_or_default(),
+            height: value.get("height").and_then(|v| v.as_u64()),
+            summary: Some(value),
+        }))
+    }
+
+    async fn block(&self, ctx: &Context<'_>, height: u32) -> Result<Block> {
+        let value = state(ctx)
+            .essentials
+            .rpc_get_block_summary(RpcGetBlockSummaryParams { height: Some(height as u64) })
+            .map_err(|e| Error::new(e.to_string()))?
+            .value;
+        Ok(Block { height, summary: rpc_value(value)? })
+    }
+
+    #[graphql(complexity = "POOL_SCAN_COST + child_complexity")]
+    async fn pool(&self, ctx: &Context<'_>, id: String) -> Result<Option<Pool>> {
+        let Some(pool) = parse_alkane(&id) else {
+            return Err(Error::new("missing_or_invalid_pool"));
+        };
+        Ok(load_pools(ctx)?
+            .into_iter()
+            .find(|(id, _)| *id == pool)
+            .map(|(id, snapshot)| Pool { id, snapshot }))
+    }
+
+    #[graphql(complexity = "POOL_SCAN_COST + connection_cost(first, child_complexity)")]
+    async fn pools(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Pool>> {
+        let size = page_size(ctx, first);
+        let offset = decode_offset_cursor(after.as_deref())?;
+        let rows = load_pools(ctx)?;
+        let end = offset.saturating_add(size).min(rows.len());
+        let mut conn = Connection::new(offset > 0, end < rows.len());
+        if offset < end {
+            for (i, (id, snapshot)) in rows[offset..end].iter().enumerate() {
+                conn.edges.push(Edge::new(
+                    (offset + i + 1).to_string(),
+                    Pool { id: *id, snapshot: snapshot.clone() },
+                ));
+            }
+        }
+        Ok(conn)
+    }
+}
+
+/* ---------------- essentials entities ---------------- */
+
+pub struct Alkane {
+    id: SchemaAlkaneId,
+    info: Value,
+}
+
+#[Object]
+impl Alkane {
+    async fn id(&self) -> String {
+        alkane_str(&self.id)
+    }
+
+    async fn name(&self) -> Option<String> {
+        str_field(&self.info, "name")
+    }
+
+    async fn symbol(&self) -> Option<String> {
+        str_field(&self.info, "symbol")
+    }
+
+    async fn creation_txid(&self) -> Option<String> {
+        str_field(&self.info, "creation_txid")
+    }
+
+    async fn creation_height(&self) -> Option<u64> {
+        self.info.get("creation_height").and_then(|v| v.as_u64())
+    }
+
+    async fn holder_count(&self) -> u64 {
+        self.info.get("holder_count").and_then(|v| v.as_u64()).unwrap_or(0)
+    }
+
+    async fn inspection(&self) -> Option<Json<Value>> {
+        self.info.get("inspection").filter(|v| !v.is_null()).cloned().map(Json)
+    }
+
+    #[graphql(complexity = "connection_cost(first, child_complexity)")]
+    async fn holders(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Holder>> {
+        let size = page_size(ctx, first);
+        let offset = decode_offset_cursor(after.as_deref())?;
+        let (items, total) = read_offset_window(offset, size, |page, limit| {
+            let value = state(ctx)
+                .essentials
+                .rpc_get_holders(RpcGetHoldersParams {
+                    alkane: Some(alkane_str(&self.id)),
+                    page: Some(page),
+                    limit: Some(limit),
+                })
+                .map_err(|e| Error::new(e.to_string()))?
+                .value;
+            let value = rpc_value(value)?;
+            let total = value.get("total").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let items = value.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            Ok((items, total))
+        })?;
+        let mut conn = Connection::new(offset > 0, offset.saturating_add(size) < total);
+        for (i, item) in items.into_iter().enumerate() {
+            let holder = Holder {
+                kind: str_field(&item, "type").unwrap_or_default(),
+                address: str_field(&item, "address"),
+                alkane: str_field(&item, "alkane"),
+                amount: str_field(&item, "amount").unwrap_or_default(),
+            };
+            conn.edges.push(Edge::new((offset + i + 1).to_string(), holder));
+        }
+        Ok(conn)
+    }
+
+    /// Transactions that moved this alkane, newest first.
+    #[graphql(complexity = "connection_cost(first, child_complexity)")]
+    async fn transfers(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Transaction>> {
+        let size = page_size(ctx, first);
+        let value = state(ctx)
+            .essentials
+            .rpc_get_alkane_balance_txs(RpcGetAlkaneBalanceTxsParams {
+                alkane: Some(alkane_str(&self.id)),
+                page: None,
+                limit: Some(size as u64),
+                cursor: after.clone(),
+            })
+            .map_err(|e| Error::new(e.to_string()))?
+            .value;
+        let value = rpc_value(value)?;
+        let has_more = value.get("has_more").and_then(|v| v.as_bool()).unwrap_or(false);
+        let items = value.get("txids").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        let mut conn = Connection::new(after.is_some(), has_more);
+        for item in items {
+            // Each row carries the key cursor that resumes right after it.
+            let Some(cursor) = str_field(&item, "cursor") else { continue };
+            let tx = Transaction {
+                txid: str_field(&item, "txid").unwrap_or_default(),
+                height: item.get("height").and_then(|v| v.as_u64()),
+                summary: None,
+            };
+            conn.edges.push(Edge::new(cursor, tx));
+        }
+        Ok(conn)
+    }
+
+    /// AMM pools that list this alkane on either side.
+    #[graphql(complexity = "POOL_SCAN_COST + DEFAULT_PAGE_SIZE * child_complexity")]
+    async fn pools(&self, ctx: &Context<'_>) -> Result<Vec<Pool>> {
+        Ok(load_pools(ctx)?
+            .into_iter()
+            .filter(|(_, s)| s.base_id == self.id || s.quote_id == self.id)
+            .map(|(id, snapshot)| Pool { id, snapshot })
+            .collect())
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Holder {
+    /// "address" or "alkane"
+    kind: String,
+    address: Option<String>,
+    alkane: Option<String>,
+    amount: String,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct Balance {
+    alkane: String,
+    amount: String,
+}
+
+#[derive(SimpleObject)]
+pub struct Outpoint {
+    outpoint: String,
+    entries: Vec<Balance>,
+}
+
+pub struct Address {
+    address: String,
+}
+
+#[Object]
+impl Address {
+    async fn address(&self) -> &str {
+        &self.address
+    }
+
+    async fn balances(&self, ctx: &Context<'_>) -> Result<Vec<Balance>> {
+        let value = state(ctx)
+            .essentials
+            .rpc_get_address_balances(RpcGetAddressBalancesParams {
+                address: Some(self.address.clone()),
+                include_outpoints: Some(false),
+            })
+            .map_err(|e| Error::new(e.to_string()))?
+            .value;
+        let value = rpc_value(value)?;
+        let mut out: Vec<Balance> = value
+            .get("balances")
+            .and_then(|v| v.as_object())
+            .map(|m| {
+                m.iter()
+                    .map(|(alkane, amount)| Balance {
+                        alkane: alkane.clone(),
+                        amount: amount.as_str().unwrap_or_default().to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        out.sort_by(|a, b| a.alkane.cmp(&b.alkane));
+        Ok(out)
+    }
+
+    async fn outpoints(&self, ctx: &Context<'_>) -> Result<Vec<Outpoint>> {
+        let value = state(ctx)
+            .essentials
+            .rpc_get_address_outpoints(RpcGetAddressOutpointsParams {
+                address: Some(self.address.clone()),
+            })
+            .map_err(|e| Error::new(e.to_string()))?
+            .value;
+        let value = rpc_value(value)?;
+        let rows = value.get("outpoints").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        Ok(rows
+            .iter()
+            .map(|row| Outpoint {
+                outpoint: str_field(row, "outpoint").unwrap_or_default(),
+                entries: row
+                    .get("entries")
+                    .and_then(|v| v.as_array())
+                    .map(|es| {
+                        es.iter()
+                            .map(|e| Balance {
+                                alkane: str_field(e, "alkane").unwrap_or_default(),
+                                amount: str_field(e, "amount").unwrap_or_default(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+pub struct Transaction {
+    txid: String,
+    height: Option<u64>,
+    /// Populated when the transaction was resolved by txid; nested rows only
+    /// carry the txid and load the summary lazily.
+    summary: Option<Value>,
+}
+
+impl Transaction {
+    fn load_summary(&self, ctx: &Context<'_>) -> Result<Option<Value>> {
+        if let Some(v) = &self.summary {
+            return Ok(Some(v.clone()));
+        }
+        let value = state(ctx)
+            .essentials
+            .rpc_get_alkane_tx_summary(RpcGetAlkaneTxSummaryParams {
+                txid: Some(self.txid.clone()),
+            })
+            .map_err(|e| Error::new(e.to_string()))?
+            .value;
+        if value.get("ok").and_then(|v| v.as_bool()) == Some(true) {
+            Ok(Some(value))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[Object]
+impl Transaction {
+    async fn txid(&self) -> &str {
+        &self.txid
+    }
+
+    async fn height(&self, ctx: &Context<'_>) -> Result<Option<u64>> {
+        if self.height.is_some() {
+            return Ok(self.height);
+        }
+        Ok(self.load_summary(ctx)?.and_then(|v| v.get("height").and_then(|h| h.as_u64())))
+    }
+
+    #[graphql(complexity = 5)]
+    async fn traces(&self, ctx: &Context<'_>) -> Result<Option<Json<Value>>> {
+        Ok(self.load_summary(ctx)?.and_then(|v| v.get("traces").cloned()).map(Json))
+    }
+
+    #[graphql(complexity = 5)]
+    async fn outflows(&self, ctx: &Context<'_>) -> Result<Option<Json<Value>>> {
+        Ok(self.load_summary(ctx)?.and_then(|v| v.get("outflows").cloned()).map(Json))
+    }
+}
+
+pub struct Block {
+    height: u32,
+    summary: Value,
+}
+
+#[Object]
+impl Block {
+    async fn height(&self) -> u32 {
+        self.height
+    }
+
+    async fn found(&self) -> bool {
+        self.summary.get("found").and_then(|v| v.as_bool()).unwrap_or(false)
+    }
+
+    async fn trace_count(&self) -> u64 {
+        self.summary.get("trace_count").and_then(|v| v.as_u64()).unwrap_or(0)
+    }
+
+    async fn header_hex(&self) -> Option<String> {
+        str_field(&self.summary, "header_hex")
+    }
+
+    /// Alkane transactions in block order.
+    #[graphql(complexity = "connection_cost(first, child_complexity)")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Transaction>> {
+        let size = page_size(ctx, first);
+        let offset = decode_offset_cursor(after.as_deref())?;
+        let (txids, total) = read_offset_window(offset, size, |page, limit| {
+            let value = state(ctx)
+                .essentials
+                .rpc_get_alkane_block_txs(RpcGetAlkaneBlockTxsParams {
+                    height: Some(self.height as u64),
+                    page: Some(page),
+                    limit: Some(limit),
+                })
+                .map_err(|e| Error::new(e.to_string()))?
+                .value;
+            let value = rpc_value(value)?;
+            let total = value.get("total").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let txids = value.get("txids").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            Ok((txids, total))
+        })?;
+        let mut conn = Connection::new(offset > 0, offset.saturating_add(size) < total);
+        for (i, txid) in txids.into_iter().enumerate() {
+            let tx = Transaction {
+                txid: txid.as_str().unwrap_or_default().to_string(),
+                height: Some(self.height as u64),
+                summary: None,
+            };
+            conn.edges.push(Edge::new((offset + i + 1).to_string(), tx));
+        }
+        Ok(conn)
+    }
+}
+
+/* ---------------- ammdata entities ---------------- */
+
+pub struct Pool {
+    id: SchemaAlkaneId,
+    snapshot: SchemaPoolSnapshot,
+}
+
+#[Object]
+impl Pool {
+    async fn id(&self) -> String {
+        alkane_str(&self.id)
+    }
+
+    async fn base_id(&self) -> String {
+        alkane_str(&self.snapshot.base_id)
+    }
+
+    async fn quote_id(&self) -> String {
+        alkane_str(&self.snapshot.quote_id)
+    }
+
+    async fn base_reserve(&self) -> String {
+        self.snapshot.base_reserve.to_string()
+    }
+
+    async fn quote_reserve(&self) -> String {
+        self.snapshot.quote_reserve.to_string()
+    }
+
+    async fn base(&self, ctx: &Context<'_>) -> Result<Option<Alkane>> {
+        QueryRoot.alkane(ctx, alkane_str(&self.snapshot.base_id)).await
+    }
+
+    async fn quote(&self, ctx: &Context<'_>) -> Result<Option<Alkane>> {
+        QueryRoot.alkane(ctx, alkane_str(&self.snapshot.quote_id)).await
+    }
+
+    /// Stored base-side candles, newest bucket first. `timeframe` is one of the
+    /// stored pool frames (1m, 10m, 1h, 4h, 1d, 1w, 1M); the resampled 5m, 15m,
+    /// 30m, 12h and 3d frames are served by `ammdata.get_candles`.
+    #[graphql(complexity = "connection_cost(first, child_complexity)")]
+    async fn candles(
+        &self,
+        ctx: &Context<'_>,
+        timeframe: String,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Candle>> {
+        let provider = ammdata(ctx)?;
+        let Some(tf) = POOL_CANDLE_FRAMES.into_iter().find(|tf| tf.code() == timeframe) else {
+            return Err(Error::new("invalid_timeframe"));
+        };
+        let prefix = provider.table().candle_ns_prefix(&self.id, tf);
+        let cursor = decode_key_cursor(after.as_deref(), &prefix)?;
+        let page = provider
+            .get_list_entries_desc_cursor(AmmCursorParams {
+                blockhash: StateAt::Latest,
+                prefix: prefix.clone(),
+                cursor,
+                limit: page_size(ctx, first),
+            })
+            .map_err(|e| Error::new(e.to_string()))?;
+        let mut conn = Connection::new(after.is_some(), page.has_more);
+        for (key, value) in page.entries {
+            let Ok(c) = decode_candle_v1(&value) else { continue };
+            let candle = Candle {
+                ts: ts_after_prefix(&key, &prefix),
+                open: c.open.to_string(),
+                high: c.high.to_string(),
+                low: c.low.to_string(),
+                close: c.close.to_string(),
+                volume: c.volume.to_string(),
+            };
+            conn.edges.push(Edge::new(hex::encode(&key), candle));
+        }
+        Ok(conn)
+    }
+
+    /// Pool activity (trades and liquidity events), newest first.
+    #[graphql(complexity = "connection_cost(first, child_complexity)")]
+    async fn activity(
+        &self,
+        ctx: &Context<'_>,
+        side: Option<String>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Activity>> {
+        let provider = ammdata(ctx)?;
+        let side = match side.as_deref().map(|s| s.to_ascii_lowercase()) {
+            None => PriceSide::Base,
+            Some(s) if s == "base" => PriceSide::Base,
+            Some(s) if s == "quote" => PriceSide::Quote,
+            Some(_) => return Err(Error::new("invalid_side")),
+        };
+        let prefix = provider.table().activity_ns_prefix(&self.id);
+        let cursor = decode_key_cursor(after.as_deref(), &prefix)?;
+        let page = provider
+            .get_list_entries_desc_cursor(AmmCursorParams {
+                blockhash: StateAt::Latest,
+                prefix,
+                cursor,
+                limit: page_size(ctx, first),
+            })
+            .map_err(|e| Error::new(e.to_string()))?;
+        let mut conn = Connection::new(after.is_some(), page.has_more);
+        for (key, value) in page.entries {
+            let Ok(a) = decode_activity_v1(&value) else { continue };
+            conn.edges.push(Edge::new(
+                hex::encode(&key),
+                Activity::from(ActivityRow::from_storage(&a, side)),
+            ));
+        }
+        Ok(conn)
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Candle {
+    ts: u64,
+    open: String,
+    high: String,
+    low: String,
+    close: String,
+    volume: String,
+}
+
+#[derive(SimpleObject)]
+pub struct Activity {
+    timestamp: u64,
+    txid: String,
+    kind: String,
+    direction: Option<String>,
+    base_delta: String,
+    quote_delta: String,
+    /// buy | sell | neutral, relative to the requested side
+    side: String,
+    amount: f64,
+}
+
+impl From<ActivityRow> for Activity {
+    fn from(row: ActivityRow) -> Self {
+        let side = serde_json::to_value(&row.side)
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+        Self {
+            timestamp: row.timestamp,
+            txid: row.txid,
+            kind: row.kind,
+            direction: row.direction,
+            base_delta: row.base_delta,
+            quote_delta: row.quote_delta,
+            side,
+            amount: row.amount,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_window_aligns_with_rpc_pages() {
+        assert_eq!(page_window(0, 20), (1, 20, 0));
+        assert_eq!(page_window(40, 20), (3, 20, 0));
+        // misaligned offsets skip into the page holding them
+        assert_eq!(page_window(30, 20), (2, 20, 10));
+    }
+
+    #[test]
+    fn offset_window_reads_at_most_two_pages() {
+        let rows: Vec<Value> = (0..100).map(|i| Value::from(i as u64)).collect();
+        let mut fetched = Vec::new();
+        let (out, total) = read_offset_window(30, 20, |page, limit| {
+            fetched.push((page, limit));
+            let start = ((page - 1) * limit) as usize;
+            let end = (start + limit as usize).min(rows.len());
+            Ok((rows[start..end].to_vec(), rows.len()))
+        })
+        .unwrap();
+        assert_eq!(fetched, vec![(2, 20), (3, 20)]);
+        assert_eq!(total, 100);
+        assert_eq!(out, rows[30..50].to_vec());
+    }
+
+    #[test]
+    fn key_cursor_must_stay_inside_prefix() {
+        let prefix = b"activity:v1:2:1:".to_vec();
+        let mut key = prefix.clone();
+        key.extend_from_slice(b"1700000000:0");
+        assert_eq!(decode_key_cursor(Some(&hex::encode(&key)), &prefix).unwrap(), Some(key));
+        assert!(decode_key_cursor(Some(&hex::encode(b"other")), &prefix).is_err());
+        assert!(decode_key_cursor(Some("zz"), &prefix).is_err());
+    }
+
+    #[test]
+    fn ts_is_read_after_prefix() {
+        let prefix = b"candles:2:1:1h:".to_vec();
+        let mut key = prefix.clone();
+        key.extend_from_slice(b"1700000000:3");
+        assert_eq!(ts_after_prefix(&key, &prefix), 1_700_000_000);
+    }
+}
@@ -0,0 +1,27 @@
+use crate::modules::graphql::schema::EspoSchema;
+use async_graphql::http::GraphiQLSource;
+use async_graphql_axum::GraphQL;
+use axum::Router;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tower_http::cors::{Any, CorsLayer};
+
+async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+pub fn router(schema: EspoSchema) -> Router {
+    let cors = CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any);
+    Router::new()
+        .route("/graphql", get(graphiql).post_service(GraphQL::new(schema)))
+        .layer(cors)
+}
+
+pub async fn run(addr: SocketAddr, schema: EspoSchema) -> anyhow::Result<()> {
+    let app = router(schema);
+    let listener = TcpListener::bind(addr).await?;
+    axum::serve(listener, app.into_make_service()).await?;
+    Ok(())
+}
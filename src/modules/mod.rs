@@ -3,6 +3,7 @@ pub mod defs;
 //modules
 pub mod ammdata;
 pub mod essentials;
+pub mod graphql;
 pub mod oylapi;
 pub mod pizzafun;
 pub mod subfrost;
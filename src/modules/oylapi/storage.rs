@@ -24,7 +24,8 @@ use crate::modules::ammdata::storage::{
     TokenMetricsIndexField, decode_full_candle_v1,
 };
 use crate::modules::ammdata::utils::candles::bucket_start_for;
-use crate::modules::ammdata::utils::pathfinder::plan_exact_in_default_fee;
+use crate::modules::ammdata::utils::index_snapshot::load_pool_adapters;
+use crate::modules::ammdata::utils::pathfinder::{RoutePools, plan_exact_in_default_fee};
 use crate::modules::ammdata::utils::search::normalize_search_text;
 use crate::modules::essentials::storage::{
     EssentialsProvider, GetAlkaneIdsByNamePrefixParams, GetAlkaneIdsBySymbolPrefixParams,
@@ -2962,8 +2963,13 @@ pub async fn get_alkane_swap_pair_details(
     }
 
     let mut meta_cache: HashMap<SchemaAlkaneId, TokenMeta> = HashMap::new();
+    let adapters = match load_pool_adapters(&state.ammdata, blockhash.clone(), &filtered) {
+        Ok(adapters) => adapters,
+        Err(err) => return internal_error(err),
+    };
+    let route = RoutePools { snapshot: &filtered, adapters: &adapters };
     let mut paths = Vec::new();
-    if let Some(quote) = plan_exact_in_default_fee(&filtered, token_a, token_b, 1, 0, 3) {
+    if let Some(quote) = plan_exact_in_default_fee(route, token_a, token_b, 1, 0, 3) {
         let mut path_ids = Vec::new();
         path_ids.push(alkane_id_json(&token_a));
         for hop in &quote.hops {
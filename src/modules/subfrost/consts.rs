@@ -1,3 +1,4 @@
+use crate::runtime::timeseries::SeriesDef;
 use crate::schemas::SchemaAlkaneId;
 use bitcoin::Network;
 
@@ -9,3 +10,19 @@ pub fn get_frbtc_alkane(network: Network) -> SchemaAlkaneId {
         _ => SchemaAlkaneId { block: 32, tx: 0 },
     }
 }
+
+pub const SERIES_UNWRAP_TOTAL: &str = "unwrap_total";
+pub const SERIES_UNWRAP_TOTAL_SUCCESSFUL: &str = "unwrap_total_successful";
+
+pub const SUBFROST_SERIES: &[SeriesDef] = &[
+    SeriesDef {
+        name: SERIES_UNWRAP_TOTAL,
+        keyed_by: "",
+        description: "cumulative frBTC unwrap amount (sats), all unwraps",
+    },
+    SeriesDef {
+        name: SERIES_UNWRAP_TOTAL_SUCCESSFUL,
+        keyed_by: "",
+        description: "cumulative frBTC unwrap amount (sats), successful unwraps only",
+    },
+];
@@ -1,4 +1,6 @@
-use super::consts::get_frbtc_alkane;
+use super::consts::{
+    SERIES_UNWRAP_TOTAL, SERIES_UNWRAP_TOTAL_SUCCESSFUL, SUBFROST_SERIES, get_frbtc_alkane,
+};
 use super::rpc::register_rpc;
 use super::schemas::SchemaWrapEventV1;
 use super::storage::{
@@ -15,6 +17,7 @@ use crate::modules::defs::{EspoModule, RpcNsRegistrar};
 use crate::modules::essentials::utils::balances::clean_espo_sandshrew_like_trace;
 use crate::runtime::mdb::Mdb;
 use crate::runtime::state_at::StateAt;
+use crate::runtime::timeseries::{SeriesDef, series_point};
 use crate::schemas::SchemaAlkaneId;
 use anyhow::{Result, anyhow};
 use bitcoin::consensus::deserialize;
@@ -277,6 +280,18 @@ impl EspoModule for Subfrost {
                         }],
                     },
                 )?);
+                puts.push(series_point(
+                    SERIES_UNWRAP_TOTAL,
+                    "",
+                    block.height,
+                    i128::try_from(total_all).unwrap_or(i128::MAX),
+                ));
+                puts.push(series_point(
+                    SERIES_UNWRAP_TOTAL_SUCCESSFUL,
+                    "",
+                    block.height,
+                    i128::try_from(total_success).unwrap_or(i128::MAX),
+                ));
             }
             debug::log_elapsed(module, "update_totals", timer);
             let timer = debug::start_if(debug);
@@ -306,6 +321,10 @@ impl EspoModule for Subfrost {
         self.load_index_height().ok().flatten()
     }
 
+    fn timeseries(&self) -> &'static [SeriesDef] {
+        SUBFROST_SERIES
+    }
+
     fn register_rpc(&self, reg: &RpcNsRegistrar) {
         if let Some(provider) = self.provider.as_ref() {
             register_rpc(reg, provider.clone());
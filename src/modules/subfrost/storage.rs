@@ -442,6 +442,32 @@ impl SubfrostProvider {
         read_events_from_list(&view, &prefix, params.offset, params.limit, params.successful)
     }
 
+    /// Reads `limit` events of an event list starting at insertion index `start`
+    /// (oldest first), for callers that walk a whole list in bounded chunks.
+    pub fn get_event_list_range(
+        &self,
+        params: GetEventListRangeParams,
+    ) -> Result<GetEventListRangeResult> {
+        crate::debug_timer_log!("get_event_list_range");
+        let view = self.with_view_blockhash(params.blockhash.resolve(self.view_blockhash));
+        let table = view.table();
+        let len = view.read_u64_len(&table.list_length_key(&params.list_prefix))?;
+        let end = params.start.saturating_add(params.limit as u64).min(len);
+        if params.start >= end {
+            return Ok(GetEventListRangeResult { entries: Vec::new(), len });
+        }
+
+        let keys: Vec<Vec<u8>> = (params.start..end)
+            .map(|idx| table.list_item_key(&params.list_prefix, idx))
+            .collect();
+        let mut entries = Vec::with_capacity(keys.len());
+        for raw in view.raw_multi_get(&keys)? {
+            let Some(raw) = raw else { continue };
+            entries.push(decode_wrap_event(&raw)?);
+        }
+        Ok(GetEventListRangeResult { entries, len })
+    }
+
     pub fn get_unwrap_total_latest(
         &self,
         params: GetUnwrapTotalLatestParams,
@@ -584,6 +610,19 @@ pub struct GetUnwrapEventsByAddressParams {
     pub height_present: bool,
 }
 
+pub struct GetEventListRangeParams {
+    pub blockhash: StateAt,
+
+    pub list_prefix: Vec<u8>,
+    pub start: u64,
+    pub limit: usize,
+}
+
+pub struct GetEventListRangeResult {
+    pub entries: Vec<SchemaWrapEventV1>,
+    pub len: u64,
+}
+
 pub struct GetWrapEventsAllParams {
     pub blockhash: StateAt,
 
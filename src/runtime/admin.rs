@@ -0,0 +1,235 @@
+//! Operational control shared between the `admin.*` RPC namespace and the indexer loop.
+//!
+//! The RPC side only flips flags and queues requests; the indexer loop picks them up
+//! between blocks, so module state is never touched in the middle of a block.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Sentinel stored in the rewind target when no rewind is pending.
+pub const NO_REWIND: u32 = u32::MAX;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ModuleHealth {
+    pub name: String,
+    pub genesis_height: u32,
+    /// Last height the module reported as fully indexed.
+    pub index_height: Option<u32>,
+    pub last_indexed_at: Option<u64>,
+    pub error_count: u64,
+    pub last_error: Option<String>,
+    pub last_error_height: Option<u32>,
+    /// False while the most recent block attempted by the module failed.
+    pub healthy: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SnapshotRecord {
+    pub height: u32,
+    pub path: String,
+    pub finished_at: u64,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+pub struct IndexerControl {
+    running: AtomicBool,
+    paused: AtomicBool,
+    rewind_target: Arc<AtomicU32>,
+    snapshot_requested: AtomicBool,
+    last_snapshot: Mutex<Option<SnapshotRecord>>,
+    modules: RwLock<BTreeMap<String, ModuleHealth>>,
+}
+
+static CONTROL: OnceLock<IndexerControl> = OnceLock::new();
+
+pub fn indexer_control() -> &'static IndexerControl {
+    CONTROL.get_or_init(|| IndexerControl {
+        running: AtomicBool::new(false),
+        paused: AtomicBool::new(false),
+        rewind_target: Arc::new(AtomicU32::new(NO_REWIND)),
+        snapshot_requested: AtomicBool::new(false),
+        last_snapshot: Mutex::new(None),
+        modules: RwLock::new(BTreeMap::new()),
+    })
+}
+
+pub fn now_unix_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl IndexerControl {
+    pub fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::Relaxed);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Returns the previous value.
+    pub fn set_paused(&self, paused: bool) -> bool {
+        self.paused.swap(paused, Ordering::SeqCst)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Lowers the pending rewind target to `height`. Returns false if an equal or lower
+    /// target was already pending.
+    pub fn request_rewind(&self, height: u32) -> bool {
+        let mut current = self.rewind_target.load(Ordering::Relaxed);
+        while height < current {
+            match self.rewind_target.compare_exchange(
+                current,
+                height,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return true,
+                Err(observed) => current = observed,
+            }
+        }
+        false
+    }
+
+    pub fn take_rewind(&self) -> Option<u32> {
+        let target = self.rewind_target.swap(NO_REWIND, Ordering::SeqCst);
+        (target != NO_REWIND).then_some(target)
+    }
+
+    pub fn pending_rewind(&self) -> Option<u32> {
+        let target = self.rewind_target.load(Ordering::Relaxed);
+        (target != NO_REWIND).then_some(target)
+    }
+
+    /// Returns false if a snapshot was already queued.
+    pub fn request_snapshot(&self) -> bool {
+        !self.snapshot_requested.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn take_snapshot_request(&self) -> bool {
+        self.snapshot_requested.swap(false, Ordering::SeqCst)
+    }
+
+    pub fn snapshot_pending(&self) -> bool {
+        self.snapshot_requested.load(Ordering::Relaxed)
+    }
+
+    pub fn record_snapshot(&self, height: u32, path: String, result: Result<(), String>) {
+        let record = SnapshotRecord {
+            height,
+            path,
+            finished_at: now_unix_secs(),
+            ok: result.is_ok(),
+            error: result.err(),
+        };
+        *self.last_snapshot.lock().expect("snapshot record poisoned") = Some(record);
+    }
+
+    pub fn last_snapshot(&self) -> Option<SnapshotRecord> {
+        self.last_snapshot.lock().expect("snapshot record poisoned").clone()
+    }
+
+    pub fn register_module(&self, name: &str, genesis_height: u32, index_height: Option<u32>) {
+        let mut map = self.modules.write().expect("module health poisoned");
+        map.insert(
+            name.to_string(),
+            ModuleHealth {
+                name: name.to_string(),
+                genesis_height,
+                index_height,
+                last_indexed_at: None,
+                error_count: 0,
+                last_error: None,
+                last_error_height: None,
+                healthy: true,
+            },
+        );
+    }
+
+    pub fn record_indexed(&self, name: &str, height: u32) {
+        let mut map = self.modules.write().expect("module health poisoned");
+        if let Some(m) = map.get_mut(name) {
+            m.index_height = Some(height);
+            m.last_indexed_at = Some(now_unix_secs());
+            m.healthy = true;
+        }
+    }
+
+    pub fn record_error(&self, name: &str, height: u32, error: String) {
+        let mut map = self.modules.write().expect("module health poisoned");
+        if let Some(m) = map.get_mut(name) {
+            m.error_count = m.error_count.saturating_add(1);
+            m.last_error = Some(error);
+            m.last_error_height = Some(height);
+            m.healthy = false;
+        }
+    }
+
+    pub fn module_health(&self) -> Vec<ModuleHealth> {
+        self.modules.read().expect("module health poisoned").values().cloned().collect()
+    }
+}
+
+/// Compares a presented admin token against the configured one without early exit.
+pub fn admin_token_matches(expected: &str, presented: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), presented.as_bytes());
+    if a.is_empty() || a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control() -> IndexerControl {
+        IndexerControl {
+            running: AtomicBool::new(true),
+            paused: AtomicBool::new(false),
+            rewind_target: Arc::new(AtomicU32::new(NO_REWIND)),
+            snapshot_requested: AtomicBool::new(false),
+            last_snapshot: Mutex::new(None),
+            modules: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    #[test]
+    fn rewind_keeps_lowest_target() {
+        let c = control();
+        assert!(c.request_rewind(900));
+        assert!(!c.request_rewind(950));
+        assert!(c.request_rewind(880));
+        assert_eq!(c.take_rewind(), Some(880));
+        assert_eq!(c.take_rewind(), None);
+    }
+
+    #[test]
+    fn module_health_tracks_last_outcome() {
+        let c = control();
+        c.register_module("essentials", 880_000, Some(900_000));
+        c.record_error("essentials", 900_001, "boom".to_string());
+        let m = &c.module_health()[0];
+        assert!(!m.healthy);
+        assert_eq!(m.last_error_height, Some(900_001));
+        c.record_indexed("essentials", 900_001);
+        let m = &c.module_health()[0];
+        assert!(m.healthy);
+        assert_eq!(m.index_height, Some(900_001));
+        assert_eq!(m.error_count, 1);
+    }
+
+    #[test]
+    fn token_compare() {
+        assert!(admin_token_matches("secret", "secret"));
+        assert!(!admin_token_matches("secret", "secreT"));
+        assert!(!admin_token_matches("secret", "secret2"));
+        assert!(!admin_token_matches("", ""));
+    }
+}
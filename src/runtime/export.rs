@@ -0,0 +1,559 @@
+//! Bulk CSV / NDJSON exports.
+//!
+//! Each export walks its source list in key order with bounded range seeks, one chunk
+//! at a time, and writes rows as it goes, so memory stays flat regardless of how large
+//! the list is. The same `ExportJob` backs the `GET /export/{kind}` HTTP endpoint on the RPC
+//! server and the `espo export` CLI.
+
+use crate::config::{get_espo_module_mdb, get_module_config, get_network};
+use crate::modules::ammdata::storage::{
+    AmmDataProvider, GetMultiValuesParams as AmmMultiValuesParams,
+    GetRangeEntriesParams as AmmRangeParams, GetRawValueParams as AmmRawValueParams,
+};
+use crate::modules::ammdata::utils::activity::{ActivityRow, decode_activity_v1};
+use crate::modules::ammdata::utils::candles::PriceSide;
+use crate::modules::essentials::storage::{
+    EssentialsProvider, GetRangeEntriesParams, HolderId, load_tx_pointer_blob_v3_by_id,
+    spk_to_address_str,
+};
+use crate::modules::essentials::utils::balances::for_each_holder_of_alkane;
+use crate::modules::subfrost::storage::{GetEventListRangeParams, SubfrostProvider};
+use crate::runtime::state_at::StateAt;
+use crate::runtime::tree_db::{next_key, prefix_end_exclusive};
+use crate::schemas::SchemaAlkaneId;
+use anyhow::{Result, anyhow};
+use bitcoin::hashes::Hash;
+use bitcoin::{ScriptBuf, Txid};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Rows fetched per storage round-trip.
+const EXPORT_CHUNK: usize = 1_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportKind {
+    /// Holders of an alkane at a height.
+    Holders,
+    /// Balance-changing transactions of an alkane (`get_alkane_balance_txs`).
+    Transfers,
+    /// Swaps of a pool in a height range.
+    PoolSwaps,
+    /// frBTC wrap events.
+    Wraps,
+    /// frBTC unwrap events.
+    Unwraps,
+}
+
+impl ExportKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "holders" => Some(Self::Holders),
+            "transfers" => Some(Self::Transfers),
+            "pool_swaps" | "swaps" => Some(Self::PoolSwaps),
+            "wraps" => Some(Self::Wraps),
+            "unwraps" => Some(Self::Unwraps),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Holders => "holders",
+            Self::Transfers => "transfers",
+            Self::PoolSwaps => "pool_swaps",
+            Self::Wraps => "wraps",
+            Self::Unwraps => "unwraps",
+        }
+    }
+
+    fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Holders => &["holder_type", "holder", "amount"],
+            Self::Transfers => &["height", "tx_idx", "txid", "token", "delta"],
+            Self::PoolSwaps => &[
+                "height",
+                "timestamp",
+                "txid",
+                "kind",
+                "direction",
+                "base_delta",
+                "quote_delta",
+                "address",
+                "success",
+            ],
+            Self::Wraps | Self::Unwraps => {
+                &["index", "timestamp", "txid", "amount", "address", "address_spk", "success"]
+            }
+        }
+    }
+}
+
+/// Export filters shared by the HTTP query string and the CLI flags.
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct ExportParams {
+    /// Alkane id ("block:tx") for holders / transfers.
+    #[arg(long)]
+    pub alkane: Option<String>,
+    /// Pool id ("block:tx") for pool swaps.
+    #[arg(long)]
+    pub pool: Option<String>,
+    /// Height to read holders at (defaults to the indexed tip).
+    #[arg(long)]
+    pub height: Option<u32>,
+    /// First height (inclusive) for pool swaps.
+    #[arg(long)]
+    pub from_height: Option<u32>,
+    /// Last height (inclusive) for pool swaps.
+    #[arg(long)]
+    pub to_height: Option<u32>,
+    /// Only export successful wrap / unwrap events.
+    #[arg(long, default_value_t = false)]
+    pub successful: bool,
+}
+
+impl ExportParams {
+    pub fn from_query(query: &HashMap<String, String>) -> Result<Self, String> {
+        let get = |k: &str| query.get(k).map(|s| s.trim()).filter(|s| !s.is_empty());
+        let height = |k: &str| -> Result<Option<u32>, String> {
+            get(k).map(|s| s.parse::<u32>().map_err(|_| format!("invalid {k}"))).transpose()
+        };
+        Ok(Self {
+            alkane: get("alkane").map(str::to_string),
+            pool: get("pool").map(str::to_string),
+            height: height("height")?,
+            from_height: height("from_height")?,
+            to_height: height("to_height")?,
+            successful: matches!(get("successful"), Some("true" | "1")),
+        })
+    }
+}
+
+/// A validated export, ready to be written out.
+pub enum ExportJob {
+    Holders { essentials: EssentialsProvider, alkane: SchemaAlkaneId },
+    Transfers { essentials: EssentialsProvider, alkane: SchemaAlkaneId },
+    PoolSwaps { ammdata: AmmDataProvider, pool: SchemaAlkaneId, from_height: u32, to_height: u32 },
+    Events { kind: ExportKind, subfrost: SubfrostProvider, list_prefix: Vec<u8>, successful: bool },
+}
+
+impl ExportJob {
+    /// Validates `params` for `kind`; errors are user-facing (bad input / disabled module).
+    pub fn prepare(kind: ExportKind, params: &ExportParams) -> Result<Self, String> {
+        let essentials = EssentialsProvider::new(get_espo_module_mdb("essentials"));
+        match kind {
+            ExportKind::Holders => {
+                let alkane = required_id(params.alkane.as_deref(), "alkane")?;
+                let essentials = essentials
+                    .with_height(params.height.map(u64::from), params.height.is_some())
+                    .map_err(|e| e.to_string())?;
+                Ok(Self::Holders { essentials, alkane })
+            }
+            ExportKind::Transfers => {
+                let alkane = required_id(params.alkane.as_deref(), "alkane")?;
+                Ok(Self::Transfers { essentials, alkane })
+            }
+            ExportKind::PoolSwaps => {
+                require_module("ammdata")?;
+                let pool = required_id(params.pool.as_deref(), "pool")?;
+                if let (Some(from), Some(to)) = (params.from_height, params.to_height) {
+                    if from > to {
+                        return Err("from_height must be <= to_height".to_string());
+                    }
+                }
+                let ammdata =
+                    AmmDataProvider::new(get_espo_module_mdb("ammdata"), Arc::new(essentials));
+                // Until older swaps are in the height index an export would silently miss them.
+                let backfill = ammdata
+                    .get_raw_value(AmmRawValueParams {
+                        blockhash: StateAt::Latest,
+                        key: ammdata.table().POOL_SWAPS_BY_HEIGHT_BACKFILL.key().to_vec(),
+                    })
+                    .map_err(|e| e.to_string())?
+                    .value;
+                if !backfill.is_some_and(|resume| resume.is_empty()) {
+                    return Err(
+                        "pool swaps are still being backfilled into the height index".to_string()
+                    );
+                }
+                Ok(Self::PoolSwaps {
+                    ammdata,
+                    pool,
+                    from_height: params.from_height.unwrap_or(0),
+                    to_height: params.to_height.unwrap_or(u32::MAX),
+                })
+            }
+            ExportKind::Wraps | ExportKind::Unwraps => {
+                require_module("subfrost")?;
+                let subfrost = SubfrostProvider::new(get_espo_module_mdb("subfrost"));
+                let table = subfrost.table();
+                let list_prefix = if kind == ExportKind::Wraps {
+                    table.WRAP_EVENTS_ALL.key().to_vec()
+                } else {
+                    table.UNWRAP_EVENTS_ALL.key().to_vec()
+                };
+                Ok(Self::Events { kind, subfrost, list_prefix, successful: params.successful })
+            }
+        }
+    }
+
+    pub fn kind(&self) -> ExportKind {
+        match self {
+            Self::Holders { .. } => ExportKind::Holders,
+            Self::Transfers { .. } => ExportKind::Transfers,
+            Self::PoolSwaps { .. } => ExportKind::PoolSwaps,
+            Self::Events { kind, .. } => *kind,
+        }
+    }
+
+    /// Streams every row into `out`; returns the number of rows written.
+    pub fn write_to<W: Write>(&self, format: ExportFormat, out: W) -> Result<u64> {
+        let mut sink = RowSink::new(out, format, self.kind().columns())?;
+        match self {
+            Self::Holders { essentials, alkane } => {
+                for_each_holder_of_alkane(
+                    StateAt::Latest,
+                    essentials,
+                    *alkane,
+                    EXPORT_CHUNK,
+                    |h| {
+                        let (holder_type, holder) = match h.holder {
+                            HolderId::Address(addr) => ("address", addr),
+                            HolderId::Alkane(id) => ("alkane", id_str(&id)),
+                        };
+                        sink.row(vec![
+                            json!(holder_type),
+                            json!(holder),
+                            json!(h.amount.to_string()),
+                        ])
+                    },
+                )?;
+            }
+            Self::Transfers { essentials, alkane } => {
+                write_transfers(&mut sink, essentials, alkane)?;
+            }
+            Self::PoolSwaps { ammdata, pool, from_height, to_height } => {
+                write_pool_swaps(&mut sink, ammdata, pool, *from_height, *to_height)?;
+            }
+            Self::Events { subfrost, list_prefix, successful, .. } => {
+                write_events(&mut sink, subfrost, list_prefix, *successful)?;
+            }
+        }
+        sink.finish()
+    }
+}
+
+fn write_transfers<W: Write>(
+    sink: &mut RowSink<W>,
+    essentials: &EssentialsProvider,
+    alkane: &SchemaAlkaneId,
+) -> Result<()> {
+    let table = essentials.table();
+    let prefix = table.alkane_balance_txs_log_prefix(alkane);
+    let end = prefix_end_exclusive(&prefix);
+    // keys are (height, tx_idx, entry) big-endian: ascending key order is oldest first
+    let mut start = prefix;
+    loop {
+        let chunk = essentials
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: StateAt::Latest,
+                start: start.clone(),
+                end: end.clone(),
+                limit: EXPORT_CHUNK,
+                reverse: false,
+            })?
+            .entries;
+        let Some((last, _)) = chunk.last() else { break };
+        start = next_key(last);
+
+        for (key, _) in &chunk {
+            let Some((height_from_key, tx_idx, entry_id)) =
+                table.parse_alkane_balance_txs_log_key(alkane, key)
+            else {
+                continue;
+            };
+            let Some(blob) = load_tx_pointer_blob_v3_by_id(essentials, entry_id) else {
+                continue;
+            };
+            let txid = Txid::from_byte_array(blob.txid).to_string();
+            let height = blob.height.max(height_from_key);
+            let Some(outflows) = blob.outflows.get(alkane) else { continue };
+            for (token, delta) in outflows {
+                sink.row(vec![
+                    json!(height),
+                    json!(tx_idx),
+                    json!(txid),
+                    json!(id_str(token)),
+                    json!(delta.to_string()),
+                ])?;
+            }
+        }
+        if chunk.len() < EXPORT_CHUNK {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn write_pool_swaps<W: Write>(
+    sink: &mut RowSink<W>,
+    ammdata: &AmmDataProvider,
+    pool: &SchemaAlkaneId,
+    from_height: u32,
+    to_height: u32,
+) -> Result<()> {
+    let network = get_network();
+    let table = ammdata.table();
+    // the height index is keyed (height, ts, seq): seek straight to `from_height`
+    let prefix = table.pool_swaps_by_height_prefix(pool);
+    let mut start = table.pool_swaps_by_height_key(pool, from_height, 0, 0);
+    let end = match to_height.checked_add(1) {
+        Some(h) => Some(table.pool_swaps_by_height_key(pool, h, 0, 0)),
+        None => prefix_end_exclusive(&prefix),
+    };
+    loop {
+        let chunk = ammdata
+            .get_range_entries(AmmRangeParams {
+                blockhash: StateAt::Latest,
+                start: start.clone(),
+                end: end.clone(),
+                limit: EXPORT_CHUNK,
+                reverse: false,
+            })?
+            .entries;
+        let Some((last, _)) = chunk.last() else { break };
+        start = next_key(last);
+
+        let rows: Vec<(u32, Vec<u8>)> = chunk
+            .iter()
+            .filter_map(|(k, _)| table.parse_pool_swaps_by_height_key(pool, k))
+            .map(|(height, ts, seq)| (height, table.activity_key(pool, ts, seq)))
+            .collect();
+        let values = ammdata
+            .get_multi_values(AmmMultiValuesParams {
+                blockhash: StateAt::Latest,
+                keys: rows.iter().map(|(_, k)| k.clone()).collect(),
+            })?
+            .values;
+        for ((height, _), raw) in rows.iter().zip(values) {
+            let Some(raw) = raw else { continue };
+            let activity = decode_activity_v1(&raw)?;
+            let row = ActivityRow::from_storage(&activity, PriceSide::Base);
+            let address =
+                spk_to_address_str(&ScriptBuf::from(activity.address_spk.clone()), network);
+            sink.row(vec![
+                json!(height),
+                json!(row.timestamp),
+                json!(row.txid),
+                json!(row.kind),
+                json!(row.direction),
+                json!(row.base_delta),
+                json!(row.quote_delta),
+                json!(address),
+                json!(activity.success),
+            ])?;
+        }
+        if chunk.len() < EXPORT_CHUNK {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn write_events<W: Write>(
+    sink: &mut RowSink<W>,
+    subfrost: &SubfrostProvider,
+    list_prefix: &[u8],
+    successful: bool,
+) -> Result<()> {
+    let network = get_network();
+    let mut start = 0u64;
+    loop {
+        let page = subfrost.get_event_list_range(GetEventListRangeParams {
+            blockhash: StateAt::Latest,
+            list_prefix: list_prefix.to_vec(),
+            start,
+            limit: EXPORT_CHUNK,
+        })?;
+        let count = page.entries.len() as u64;
+        for (offset, event) in page.entries.into_iter().enumerate() {
+            if successful && !event.success {
+                continue;
+            }
+            let mut txid = event.txid;
+            txid.reverse();
+            let address = spk_to_address_str(&ScriptBuf::from(event.address_spk.clone()), network);
+            sink.row(vec![
+                json!(start + offset as u64),
+                json!(event.timestamp),
+                json!(hex::encode(txid)),
+                json!(event.amount.to_string()),
+                json!(address),
+                json!(hex::encode(&event.address_spk)),
+                json!(event.success),
+            ])?;
+        }
+        start = start.saturating_add(EXPORT_CHUNK as u64);
+        if count == 0 || start >= page.len {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/* ---------------- output ---------------- */
+
+struct RowSink<W: Write> {
+    out: W,
+    format: ExportFormat,
+    columns: &'static [&'static str],
+    rows: u64,
+}
+
+impl<W: Write> RowSink<W> {
+    fn new(mut out: W, format: ExportFormat, columns: &'static [&'static str]) -> Result<Self> {
+        if format == ExportFormat::Csv {
+            let header: Vec<String> = columns.iter().map(|c| csv_field(c)).collect();
+            writeln!(out, "{}", header.join(","))?;
+        }
+        Ok(Self { out, format, columns, rows: 0 })
+    }
+
+    fn row(&mut self, values: Vec<Value>) -> Result<()> {
+        if values.len() != self.columns.len() {
+            return Err(anyhow!(
+                "export row has {} values, expected {}",
+                values.len(),
+                self.columns.len()
+            ));
+        }
+        match self.format {
+            ExportFormat::Csv => {
+                let fields: Vec<String> = values.iter().map(csv_value).collect();
+                writeln!(self.out, "{}", fields.join(","))?;
+            }
+            ExportFormat::Ndjson => {
+                let obj: Map<String, Value> =
+                    self.columns.iter().map(|c| c.to_string()).zip(values).collect();
+                serde_json::to_writer(&mut self.out, &Value::Object(obj))?;
+                self.out.write_all(b"\n")?;
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<u64> {
+        self.out.flush()?;
+        Ok(self.rows)
+    }
+}
+
+fn csv_value(v: &Value) -> String {
+    match v {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s),
+        other => csv_field(&other.to_string()),
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/* ---------------- helpers ---------------- */
+
+fn require_module(name: &str) -> Result<(), String> {
+    if get_module_config(name).is_none() {
+        return Err(format!("{name} module is not enabled"));
+    }
+    Ok(())
+}
+
+fn required_id(raw: Option<&str>, field: &str) -> Result<SchemaAlkaneId, String> {
+    raw.and_then(parse_alkane_id)
+        .ok_or_else(|| format!("missing_or_invalid_{field}"))
+}
+
+fn parse_alkane_id(s: &str) -> Option<SchemaAlkaneId> {
+    let (block, tx) = s.trim().split_once(':')?;
+    Some(SchemaAlkaneId { block: block.parse().ok()?, tx: tx.parse().ok()? })
+}
+
+fn id_str(id: &SchemaAlkaneId) -> String {
+    format!("{}:{}", id.block, id.tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("bc1qxyz"), "bc1qxyz");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_value(&Value::Null), "");
+        assert_eq!(csv_value(&json!(true)), "true");
+    }
+
+    #[test]
+    fn sink_writes_csv_and_ndjson() {
+        let cols: &'static [&'static str] = &["a", "b"];
+
+        let mut buf = Vec::new();
+        let mut sink = RowSink::new(&mut buf, ExportFormat::Csv, cols).unwrap();
+        sink.row(vec![json!("x,y"), json!(3)]).unwrap();
+        assert_eq!(sink.finish().unwrap(), 1);
+        assert_eq!(String::from_utf8(buf).unwrap(), "a,b\n\"x,y\",3\n");
+
+        let mut buf = Vec::new();
+        let mut sink = RowSink::new(&mut buf, ExportFormat::Ndjson, cols).unwrap();
+        sink.row(vec![json!("x"), Value::Null]).unwrap();
+        sink.finish().unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "{\"a\":\"x\",\"b\":null}\n");
+    }
+
+    #[test]
+    fn kinds_parse_from_path_segments() {
+        assert_eq!(ExportKind::parse("pool-swaps"), Some(ExportKind::PoolSwaps));
+        assert_eq!(ExportKind::parse("pool_swaps"), Some(ExportKind::PoolSwaps));
+        assert_eq!(ExportKind::parse("UNWRAPS"), Some(ExportKind::Unwraps));
+        assert_eq!(ExportKind::parse("candles"), None);
+    }
+}
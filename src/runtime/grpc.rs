@@ -0,0 +1,396 @@
+//! gRPC transport for the essentials and ammdata list methods.
+//!
+//! Each rpc in `proto/espo.proto` is the typed form of a registered JSON-RPC method:
+//! handlers go through the same `RpcRegistry` and the result documents are decoded into
+//! the generated messages, which derive `Deserialize` (see `build.rs`). A result that no
+//! longer matches its message fails with `INTERNAL` rather than reading as defaults.
+//! Paginated lists also get server-streaming variants that walk every page and emit one
+//! message per row.
+
+use crate::modules::defs::RpcRegistry;
+use futures::{FutureExt, Stream, StreamExt, stream};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use std::{net::SocketAddr, pin::Pin};
+use tarpc::context;
+use tonic::{Request, Response, Status};
+
+pub mod pb {
+    tonic::include_proto!("espo");
+}
+
+use pb::espo_server::{Espo, EspoServer};
+use pb::{
+    Activity, ActivityPage, ActivityRequest, AddressBalances, AddressOutpoints, AddressRequest,
+    AlkaneBalance, Candle, CandlesPage, CandlesRequest, Outpoint,
+};
+
+const DEFAULT_STREAM_PAGE_SIZE: u64 = 500;
+const MAX_STREAM_PAGE_SIZE: u64 = 5_000;
+
+// ---- Registry bridge ----------------------------------------------------------
+
+type RowStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + 'static>>;
+
+async fn call_registry(
+    registry: &RpcRegistry,
+    method: &str,
+    params: Value,
+) -> Result<Value, Status> {
+    let methods = registry.list().await;
+    if !methods.iter().any(|m| m == method) {
+        return Err(Status::unimplemented(format!("unknown method: {method}")));
+    }
+    let cx = context::current();
+    std::panic::AssertUnwindSafe(registry.call(cx, method, params))
+        .catch_unwind()
+        .await
+        .map_err(|_| Status::internal("handler panicked"))
+}
+
+/// Handlers report domain errors as `{ "ok": false, "error": ... }`; surface those as
+/// `FAILED_PRECONDITION` so streaming clients do not mistake them for an empty list.
+fn check_ok(value: &Value) -> Result<(), Status> {
+    if value.get("ok").and_then(|v| v.as_bool()) == Some(false) {
+        let err = value.get("error").and_then(|v| v.as_str()).unwrap_or("request_failed");
+        return Err(Status::failed_precondition(err.to_string()));
+    }
+    if let Some(err) = value.get("error").and_then(|v| v.as_str()) {
+        return Err(Status::failed_precondition(err.to_string()));
+    }
+    Ok(())
+}
+
+fn clamp_page_size(page_size: u64) -> u64 {
+    if page_size == 0 { DEFAULT_STREAM_PAGE_SIZE } else { page_size.min(MAX_STREAM_PAGE_SIZE) }
+}
+
+/// Walks a page/limit paginated method and yields every row of `list_field`.
+/// Methods without `has_more` are treated as single-page responses.
+fn paged_rows(
+    registry: RpcRegistry,
+    method: String,
+    params: Map<String, Value>,
+    list_field: String,
+    page_size: u64,
+) -> RowStream<Value> {
+    struct PageState {
+        registry: RpcRegistry,
+        method: String,
+        params: Map<String, Value>,
+        list_field: String,
+        page_size: u64,
+        next_page: Option<u64>,
+    }
+
+    let state = PageState {
+        registry,
+        method,
+        params,
+        list_field,
+        page_size: clamp_page_size(page_size),
+        next_page: Some(1),
+    };
+
+    let pages = stream::unfold(state, |mut st| async move {
+        let page = st.next_page?;
+        let mut params = st.params.clone();
+        params.insert("page".to_string(), json!(page));
+        params.insert("limit".to_string(), json!(st.page_size));
+        let value = match call_registry(&st.registry, &st.method, Value::Object(params)).await {
+            Ok(v) => v,
+            Err(status) => {
+                st.next_page = None;
+                return Some((vec![Err(status)], st));
+            }
+        };
+        if let Err(status) = check_ok(&value) {
+            st.next_page = None;
+            return Some((vec![Err(status)], st));
+        }
+        let rows = rows_from_field(value.get(&st.list_field));
+        let has_more = value.get("has_more").and_then(|v| v.as_bool()).unwrap_or(false);
+        st.next_page = if has_more && !rows.is_empty() { Some(page + 1) } else { None };
+        Some((rows.into_iter().map(Ok).collect::<Vec<_>>(), st))
+    });
+
+    Box::pin(pages.flat_map(stream::iter))
+}
+
+/// Arrays are streamed as-is; `{ id: amount }` maps (address balances) are expanded
+/// into `{ "alkane": id, "amount": amount }` rows.
+fn rows_from_field(field: Option<&Value>) -> Vec<Value> {
+    match field {
+        Some(Value::Array(items)) => items.clone(),
+        Some(Value::Object(map)) => map
+            .iter()
+            .map(|(alkane, amount)| json!({ "alkane": alkane, "amount": amount }))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn insert_str(params: &mut Map<String, Value>, key: &str, value: &str) {
+    if !value.is_empty() {
+        params.insert(key.to_string(), Value::String(value.to_string()));
+    }
+}
+
+fn insert_height(params: &mut Map<String, Value>, height: Option<u64>) {
+    if let Some(h) = height {
+        params.insert("height".to_string(), json!(h));
+    }
+}
+
+/// Decodes a result document (or one of its rows) into the message that mirrors it.
+fn decode<T: DeserializeOwned>(value: Value) -> Result<T, Status> {
+    serde_json::from_value(value)
+        .map_err(|e| Status::internal(format!("result does not match its message: {e}")))
+}
+
+fn typed_rows<T>(rows: RowStream<Value>) -> RowStream<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    Box::pin(rows.map(|row| row.and_then(decode::<T>)))
+}
+
+fn address_params(req: &AddressRequest) -> Map<String, Value> {
+    let mut params = Map::new();
+    insert_str(&mut params, "address", &req.address);
+    insert_height(&mut params, req.height);
+    params
+}
+
+fn candles_params(req: &CandlesRequest) -> Map<String, Value> {
+    let mut params = Map::new();
+    insert_str(&mut params, "pool", &req.pool);
+    insert_str(&mut params, "timeframe", &req.timeframe);
+    insert_str(&mut params, "side", &req.side);
+    if let Some(now) = req.now {
+        params.insert("now".to_string(), json!(now));
+    }
+    insert_height(&mut params, req.height);
+    params
+}
+
+fn activity_params(req: &ActivityRequest) -> Map<String, Value> {
+    let mut params = Map::new();
+    insert_str(&mut params, "pool", &req.pool);
+    insert_str(&mut params, "side", &req.side);
+    insert_str(&mut params, "filter_side", &req.filter_side);
+    insert_str(&mut params, "activity_type", &req.activity_type);
+    insert_str(&mut params, "sort", &req.sort);
+    insert_str(&mut params, "dir", &req.dir);
+    insert_height(&mut params, req.height);
+    params
+}
+
+/// Unary calls fetch a single page; zero means the handler's default page and limit.
+fn insert_page(params: &mut Map<String, Value>, page: u64, page_size: u64) {
+    if page > 0 {
+        params.insert("page".to_string(), json!(page));
+    }
+    if page_size > 0 {
+        params.insert("limit".to_string(), json!(page_size));
+    }
+}
+
+// ---- Service ----------------------------------------------------------------
+
+#[derive(Clone)]
+pub struct EspoGrpc {
+    registry: RpcRegistry,
+}
+
+impl EspoGrpc {
+    pub fn new(registry: RpcRegistry) -> Self {
+        Self { registry }
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Map<String, Value>,
+    ) -> Result<Response<T>, Status> {
+        let value = call_registry(&self.registry, method, Value::Object(params)).await?;
+        check_ok(&value)?;
+        decode(value).map(Response::new)
+    }
+
+    fn rows<T>(
+        &self,
+        method: &str,
+        params: Map<String, Value>,
+        list_field: &str,
+        page_size: u64,
+    ) -> RowStream<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        typed_rows(paged_rows(
+            self.registry.clone(),
+            method.to_string(),
+            params,
+            list_field.to_string(),
+            page_size,
+        ))
+    }
+}
+
+#[tonic::async_trait]
+impl Espo for EspoGrpc {
+    type StreamAddressBalancesStream = RowStream<AlkaneBalance>;
+    type StreamAddressOutpointsStream = RowStream<Outpoint>;
+    type StreamCandlesStream = RowStream<Candle>;
+    type StreamActivityStream = RowStream<Activity>;
+
+    async fn get_address_balances(
+        &self,
+        request: Request<AddressRequest>,
+    ) -> Result<Response<AddressBalances>, Status> {
+        let params = address_params(request.get_ref());
+        self.call("essentials.get_address_balances", params).await
+    }
+
+    async fn get_address_outpoints(
+        &self,
+        request: Request<AddressRequest>,
+    ) -> Result<Response<AddressOutpoints>, Status> {
+        let params = address_params(request.get_ref());
+        self.call("essentials.get_address_outpoints", params).await
+    }
+
+    async fn get_candles(
+        &self,
+        request: Request<CandlesRequest>,
+    ) -> Result<Response<CandlesPage>, Status> {
+        let req = request.into_inner();
+        let mut params = candles_params(&req);
+        insert_page(&mut params, req.page, req.page_size);
+        self.call("ammdata.get_candles", params).await
+    }
+
+    async fn get_activity(
+        &self,
+        request: Request<ActivityRequest>,
+    ) -> Result<Response<ActivityPage>, Status> {
+        let req = request.into_inner();
+        let mut params = activity_params(&req);
+        insert_page(&mut params, req.page, req.page_size);
+        self.call("ammdata.get_activity", params).await
+    }
+
+    async fn stream_address_balances(
+        &self,
+        request: Request<AddressRequest>,
+    ) -> Result<Response<Self::StreamAddressBalancesStream>, Status> {
+        let params = address_params(request.get_ref());
+        Ok(Response::new(self.rows("essentials.get_address_balances", params, "balances", 0)))
+    }
+
+    async fn stream_address_outpoints(
+        &self,
+        request: Request<AddressRequest>,
+    ) -> Result<Response<Self::StreamAddressOutpointsStream>, Status> {
+        let params = address_params(request.get_ref());
+        Ok(Response::new(self.rows("essentials.get_address_outpoints", params, "outpoints", 0)))
+    }
+
+    async fn stream_candles(
+        &self,
+        request: Request<CandlesRequest>,
+    ) -> Result<Response<Self::StreamCandlesStream>, Status> {
+        let req = request.into_inner();
+        let params = candles_params(&req);
+        Ok(Response::new(self.rows("ammdata.get_candles", params, "candles", req.page_size)))
+    }
+
+    async fn stream_activity(
+        &self,
+        request: Request<ActivityRequest>,
+    ) -> Result<Response<Self::StreamActivityStream>, Status> {
+        let req = request.into_inner();
+        let params = activity_params(&req);
+        Ok(Response::new(self.rows("ammdata.get_activity", params, "activity", req.page_size)))
+    }
+}
+
+pub async fn run_grpc(registry: RpcRegistry, addr: SocketAddr) -> anyhow::Result<()> {
+    eprintln!("[grpc] listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(EspoServer::new(EspoGrpc::new(registry)))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::ammdata::utils::activity::{ActivityRow, ActivityUiSide};
+
+    #[test]
+    fn rows_from_balance_map_expand_to_entries() {
+        let value = json!({ "2:0": "100", "32:0": "5" });
+        let rows = rows_from_field(Some(&value));
+        assert_eq!(rows.len(), 2);
+        let balances: Vec<AlkaneBalance> =
+            rows.into_iter().map(decode).collect::<Result<_, _>>().unwrap();
+        assert!(balances.iter().any(|b| b.alkane == "2:0" && b.amount == "100"));
+        assert!(balances.iter().any(|b| b.alkane == "32:0" && b.amount == "5"));
+    }
+
+    #[test]
+    fn check_ok_maps_handler_errors() {
+        assert!(check_ok(&json!({ "ok": true })).is_ok());
+        let err = check_ok(&json!({ "ok": false, "error": "missing_or_invalid_pool" }))
+            .expect_err("handler error should surface");
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+        assert_eq!(err.message(), "missing_or_invalid_pool");
+    }
+
+    #[test]
+    fn activity_rows_decode_into_messages() {
+        let row = ActivityRow {
+            timestamp: 1_700_000_000,
+            txid: "ab".repeat(32),
+            kind: "trade_buy".to_string(),
+            direction: None,
+            base_delta: "-5".to_string(),
+            quote_delta: "10".to_string(),
+            side: ActivityUiSide::Buy,
+            amount: 0.5,
+        };
+        let activity: Activity = decode(serde_json::to_value(row).unwrap()).unwrap();
+        assert_eq!(activity.side, "buy");
+        assert_eq!(activity.direction, None);
+        assert_eq!(activity.base_delta, "-5");
+    }
+
+    #[test]
+    fn result_documents_decode_strictly() {
+        let candles = json!({
+            "ok": true,
+            "pool": "2:1",
+            "timeframe": "1h",
+            "side": "base",
+            "page": 1,
+            "limit": 2,
+            "total": 3,
+            "has_more": true,
+            "candles": [{
+                "ts": 3600, "open": "1", "high": "2", "low": "1", "close": "2", "volume": "7"
+            }]
+        });
+        let page: CandlesPage = decode(candles.clone()).unwrap();
+        assert!(page.has_more);
+        assert_eq!(page.candles[0].volume, "7");
+
+        // A renamed or dropped field is an error, not a zeroed message.
+        let mut drifted = candles;
+        drifted["candles"][0].as_object_mut().unwrap().remove("volume");
+        let err = decode::<CandlesPage>(drifted).expect_err("missing field should fail");
+        assert_eq!(err.code(), tonic::Code::Internal);
+    }
+}
@@ -5,7 +5,9 @@ use rocksdb::{
 };
 use std::{path::Path, sync::Arc};
 
-use crate::runtime::tree_db::{VersionedTreeDb, get_global_tree_db, is_tree_internal_key};
+use crate::runtime::tree_db::{
+    VersionedTreeDb, get_global_tree_db, is_tree_internal_key, prefix_end_exclusive,
+};
 
 /// ===== Cache / open-time tuning =====
 /// How big you want the LRU block cache (data + index/filter when enabled).
@@ -14,6 +16,10 @@ pub const ROCKS_BLOCK_CACHE_BYTES: usize = 1 << 30; // 1 GiB
 /// Warm the block cache for this namespace on open (iterate all keys once).
 pub const WARM_CACHE_ON_OPEN: bool = true;
 
+/// Namespace of the keys written through an [`Mdb::unversioned`] handle. Kept apart from the
+/// versioned tree's own keys so neither sees the other.
+const UNVERSIONED_PREFIX: &[u8] = b"__espo_unversioned:";
+
 /// Bloom filter bits/key (helps point lookups).
 pub const BLOOM_BITS_PER_KEY: f64 = 10.0;
 
@@ -22,6 +28,7 @@ pub struct Mdb {
     db: Arc<DB>,
     prefix: Vec<u8>,
     tree: Option<Arc<VersionedTreeDb>>,
+    unversioned: bool,
 }
 
 impl Mdb {
@@ -31,7 +38,7 @@ impl Mdb {
         tree: Option<Arc<VersionedTreeDb>>,
     ) -> Self {
         let prefix_vec = prefix.as_ref().to_vec();
-        Self { db, prefix: prefix_vec, tree }
+        Self { db, prefix: prefix_vec, tree, unversioned: false }
     }
 
     pub fn from_db(db: Arc<DB>, prefix: impl AsRef<[u8]>) -> Self {
@@ -48,7 +55,19 @@ impl Mdb {
 
     /// Clone this handle onto the same underlying RocksDB with a different namespace prefix.
     pub fn clone_with_prefix(&self, prefix: impl AsRef<[u8]>) -> Self {
-        Self::from_parts(Arc::clone(&self.db), prefix, self.tree.clone())
+        Self {
+            unversioned: self.unversioned,
+            ..Self::from_parts(Arc::clone(&self.db), prefix, self.tree.clone())
+        }
+    }
+
+    /// A handle on the same RocksDB that bypasses the versioned tree: reads see the latest
+    /// value and writes land right away, outside any block, so they survive rollbacks. For
+    /// state set by RPC rather than derived from blocks.
+    pub fn unversioned(&self) -> Self {
+        let mut prefix = UNVERSIONED_PREFIX.to_vec();
+        prefix.extend_from_slice(&self.prefix);
+        Self { db: Arc::clone(&self.db), prefix, tree: None, unversioned: true }
     }
 
     pub fn open(path: impl AsRef<Path>, prefix: impl AsRef<[u8]>) -> Result<Self, RocksError> {
@@ -350,6 +369,97 @@ impl Mdb {
         self.db.write(wb)
     }
 
+    /// Up to `limit` entries with keys in `[start, end)`, ascending, or descending from `end`
+    /// when `reverse` is set. `end = None` runs to the end of the namespace. Unlike the prefix
+    /// scans this stops after `limit` rows, so paging readers can seek instead of loading a
+    /// whole list.
+    pub fn scan_range_entries(
+        &self,
+        start: &[u8],
+        end: Option<&[u8]>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksError> {
+        let root = self.versioned_manager().map(|tree| tree.active_root());
+        self.scan_range_entries_in(root, start, end, limit, reverse)
+    }
+
+    pub fn scan_range_entries_at_blockhash(
+        &self,
+        block_hash: &BlockHash,
+        start: &[u8],
+        end: Option<&[u8]>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksError> {
+        let root = match self.versioned_manager() {
+            Some(tree) => match tree.root_for_blockhash(block_hash)? {
+                Some(root) => Some(root),
+                None => return Ok(Vec::new()),
+            },
+            None => None,
+        };
+        self.scan_range_entries_in(root, start, end, limit, reverse)
+    }
+
+    fn scan_range_entries_in(
+        &self,
+        root: Option<[u8; 32]>,
+        start: &[u8],
+        end: Option<&[u8]>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksError> {
+        let start_full = self.prefixed(start);
+        let end_full = match end {
+            Some(end) => Some(self.prefixed(end)),
+            None => prefix_end_exclusive(&self.prefix),
+        };
+        let strip = |entries: Vec<(Vec<u8>, Vec<u8>)>| {
+            entries
+                .into_iter()
+                .filter(|(k, _)| k.starts_with(&self.prefix))
+                .map(|(k, v)| (k[self.prefix.len()..].to_vec(), v))
+                .collect()
+        };
+        if let (Some(tree), Some(root)) = (self.versioned_manager(), root) {
+            let entries = if reverse {
+                tree.range_entries_rev_at_root(root, &start_full, end_full.as_deref(), limit)?
+            } else {
+                tree.range_entries_limited_at_root(root, &start_full, end_full.as_deref(), limit)?
+            };
+            return Ok(strip(entries));
+        }
+
+        let in_range =
+            |k: &[u8]| k >= start_full.as_slice() && end_full.as_deref().is_none_or(|e| k < e);
+        let mut out = Vec::new();
+        let iter = if reverse {
+            match end_full.as_deref() {
+                Some(end) => self.db.iterator(IteratorMode::From(end, Direction::Reverse)),
+                None => self.db.iterator(IteratorMode::End),
+            }
+        } else {
+            self.db.iterator(IteratorMode::From(&start_full, Direction::Forward))
+        };
+        for res in iter {
+            if out.len() >= limit {
+                break;
+            }
+            let (key, value) = res?;
+            if !in_range(&key) {
+                // reverse seeks land on `end` itself when it exists
+                let past_end = end_full.as_deref().is_some_and(|e| key.as_ref() >= e);
+                if reverse && past_end {
+                    continue;
+                }
+                break;
+            }
+            out.push((key.to_vec(), value.to_vec()));
+        }
+        Ok(strip(out))
+    }
+
     /// Iterate forward over raw DB starting from namespaced key `start` (inclusive).
     pub fn iter_from(
         &self,
@@ -414,6 +524,14 @@ impl Mdb {
         }
     }
 
+    /// See [`VersionedTreeDb::rollback_to_height`]; unversioned handles have nothing to drop.
+    pub fn rollback_to_height(&self, height: u32) -> Result<u32, RocksError> {
+        let Some(tree) = self.versioned_manager() else {
+            return Ok(0);
+        };
+        tree.rollback_to_height(height)
+    }
+
     pub fn has_blockhash(&self, block_hash: &BlockHash) -> Result<bool, RocksError> {
         let Some(tree) = self.versioned_manager() else {
             return Ok(false);
@@ -458,6 +576,9 @@ impl Mdb {
     }
 
     fn versioned_manager(&self) -> Option<Arc<VersionedTreeDb>> {
+        if self.unversioned {
+            return None;
+        }
         self.tree.clone().or_else(get_global_tree_db)
     }
 }
@@ -1,8 +1,12 @@
+pub mod admin;
 pub mod dbpaths;
+pub mod export;
+pub mod grpc;
 pub mod mdb;
 pub mod mempool;
 pub mod pointers;
 pub mod rpc;
 pub mod sdb;
 pub mod state_at;
+pub mod timeseries;
 pub mod tree_db;
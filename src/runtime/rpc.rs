@@ -1,19 +1,32 @@
 use crate::{
-    config::{get_espo_module_mdb, get_espo_next_height},
+    config::{
+        debug_enabled, get_config, get_espo_module_mdb, get_espo_next_height, get_last_safe_tip,
+        get_metashrew_sdb, get_network, set_debug_enabled,
+    },
+    consts::alkanes_genesis_block,
     modules::defs::RpcRegistry,
+    runtime::admin::{admin_token_matches, indexer_control},
+    runtime::export::{ExportFormat, ExportJob, ExportKind, ExportParams},
+    runtime::mempool::reset_mempool_store,
+    runtime::timeseries::{
+        Aggregation, aggregate_points, list_series, lookup_series, read_series_points,
+    },
 };
 use axum::{
     Router,
-    body::Bytes,
-    extract::State,
-    http::{StatusCode, header::CONTENT_TYPE},
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{
+        HeaderMap, StatusCode,
+        header::{AUTHORIZATION, CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
     response::{IntoResponse, Response},
-    routing::post,
+    routing::{get, post},
 };
-use futures::FutureExt;
+use futures::{FutureExt, SinkExt, channel::mpsc};
 use serde::Serialize;
 use serde_json::{Value, json};
-use std::{net::SocketAddr, sync::Arc};
+use std::{collections::HashMap, io::Write, net::SocketAddr, sync::Arc};
 use tarpc::context;
 use tokio::net::TcpListener;
 
@@ -47,6 +60,17 @@ const MAX_SAFE_INTEGER_U64: u64 = 9_007_199_254_740_991;
 // Built-in root method name
 const ROOT_METHOD_GET_ESPO_HEIGHT: &str = "get_espo_height";
 const ROOT_METHOD_GET_METHOD_LINE_CHART: &str = "get_method_line_chart";
+const ROOT_METHOD_GET_TIMESERIES: &str = "get_timeseries";
+const ROOT_METHOD_LIST_TIMESERIES: &str = "list_timeseries";
+
+// Operational methods; only served when `admin.token` is configured and presented as
+// `Authorization: Bearer <token>`.
+const ADMIN_METHOD_PREFIX: &str = "admin.";
+const ADMIN_UNAUTHORIZED_CODE: i64 = -32001;
+
+const TIMESERIES_DEFAULT_BUCKETS: u32 = 200;
+const TIMESERIES_MAX_BUCKETS: u32 = 5_000;
+const TIMESERIES_MAX_SERIES: usize = 16;
 
 fn err_response(id: Value, code: i64, message: &str, data: Option<Value>) -> JsonRpcResponse {
     JsonRpcResponse {
@@ -71,7 +95,10 @@ fn get_espo_tip_height_response(id: Value) -> JsonRpcResponse {
 }
 
 fn is_builtin_root_method(method: &str) -> bool {
-    method == ROOT_METHOD_GET_ESPO_HEIGHT || method == ROOT_METHOD_GET_METHOD_LINE_CHART
+    method == ROOT_METHOD_GET_ESPO_HEIGHT
+        || method == ROOT_METHOD_GET_METHOD_LINE_CHART
+        || method == ROOT_METHOD_GET_TIMESERIES
+        || method == ROOT_METHOD_LIST_TIMESERIES
 }
 
 fn parse_optional_u32_param(
@@ -367,6 +394,287 @@ async fn get_method_line_chart_response(
     }
 }
 
+fn list_timeseries_response(id: Value) -> JsonRpcResponse {
+    let series: Vec<Value> = list_series()
+        .into_iter()
+        .map(|s| {
+            json!({
+                "name": s.full_name(),
+                "module": s.module,
+                "keyed_by": if s.def.keyed_by.is_empty() { Value::Null } else { json!(s.def.keyed_by) },
+                "description": s.def.description,
+            })
+        })
+        .collect();
+
+    JsonRpcResponse {
+        jsonrpc: JSONRPC_VERSION,
+        result: Some(json!({ "series": series })),
+        error: None,
+        id,
+    }
+}
+
+/// Accepts `"module.series"`, `"module.series@key"` or `{ "name": ..., "key": ... }`.
+fn parse_timeseries_selector(value: &Value) -> Result<(String, String), String> {
+    match value {
+        Value::String(raw) => {
+            let raw = raw.trim();
+            let (name, key) = raw.split_once('@').unwrap_or((raw, ""));
+            Ok((name.trim().to_string(), key.trim().to_string()))
+        }
+        Value::Object(obj) => {
+            let name = parse_required_non_empty_string_param(obj, "name")?;
+            let key = match obj.get("key") {
+                None | Some(Value::Null) => "",
+                Some(Value::String(k)) => k.trim(),
+                Some(_) => return Err("series key must be a string".to_string()),
+            };
+            Ok((name.to_string(), key.to_string()))
+        }
+        _ => Err("series entries must be strings or objects".to_string()),
+    }
+}
+
+fn get_timeseries_response(id: Value, params: Value) -> JsonRpcResponse {
+    let params_obj = match params {
+        Value::Object(obj) => obj,
+        _ => return invalid_params(id, "params must be an object"),
+    };
+
+    let selectors: Vec<Value> = match params_obj.get("series") {
+        Some(Value::Array(items)) if !items.is_empty() => items.clone(),
+        Some(v @ Value::String(_)) | Some(v @ Value::Object(_)) => vec![v.clone()],
+        _ => return invalid_params(id, "series is required (string, object or non-empty array)"),
+    };
+    if selectors.len() > TIMESERIES_MAX_SERIES {
+        let detail = format!("at most {TIMESERIES_MAX_SERIES} series per request");
+        return invalid_params(id, &detail);
+    }
+
+    let agg = match params_obj.get("aggregation").or_else(|| params_obj.get("agg")) {
+        None | Some(Value::Null) => Aggregation::Last,
+        Some(Value::String(s)) => match Aggregation::parse(s) {
+            Some(a) => a,
+            None => return invalid_params(id, "aggregation must be last | min | max | sum | avg"),
+        },
+        Some(_) => return invalid_params(id, "aggregation must be a string"),
+    };
+
+    let range_min_param = match parse_optional_u32_param(&params_obj, "range_min") {
+        Ok(v) => v,
+        Err(detail) => return invalid_params(id, &detail),
+    };
+    let range_max_param = match parse_optional_u32_param(&params_obj, "range_max") {
+        Ok(v) => v,
+        Err(detail) => return invalid_params(id, &detail),
+    };
+    let resolution_param = match parse_optional_u32_param(&params_obj, "resolution") {
+        Ok(v) => v,
+        Err(detail) => return invalid_params(id, &detail),
+    };
+    if resolution_param == Some(0) {
+        return invalid_params(id, "resolution must be greater than 0");
+    }
+
+    let (default_min, default_max) = match indexed_height_bounds() {
+        Ok(bounds) => bounds,
+        Err(detail) => return internal_error(id, &detail),
+    };
+    let range_min = range_min_param.unwrap_or(default_min);
+    let range_max = range_max_param.unwrap_or(default_max);
+    if range_min > range_max {
+        return invalid_params(id, "range_min must be <= range_max");
+    }
+
+    // u64: the full u32 range spans 2^32 heights
+    let span = (range_max - range_min) as u64 + 1;
+    let resolution = match resolution_param {
+        Some(r) => r,
+        None => span.div_ceil(TIMESERIES_DEFAULT_BUCKETS as u64) as u32,
+    };
+    if span.div_ceil(resolution as u64) > TIMESERIES_MAX_BUCKETS as u64 {
+        let detail = format!("range/resolution yields more than {TIMESERIES_MAX_BUCKETS} buckets");
+        return invalid_params(id, &detail);
+    }
+
+    let mut out_series: Vec<Value> = Vec::with_capacity(selectors.len());
+    for selector in &selectors {
+        let (name, key) = match parse_timeseries_selector(selector) {
+            Ok(v) => v,
+            Err(detail) => return invalid_params(id, &detail),
+        };
+        let Some(series) = lookup_series(&name) else {
+            let detail = format!("unknown series: {name}");
+            return invalid_params(id, &detail);
+        };
+        if series.def.keyed_by.is_empty() != key.is_empty() {
+            let detail = if key.is_empty() {
+                format!("series {name} requires a key ({})", series.def.keyed_by)
+            } else {
+                format!("series {name} does not take a key")
+            };
+            return invalid_params(id, &detail);
+        }
+
+        let mdb = get_espo_module_mdb(series.module);
+        let points = match read_series_points(&mdb, series.def.name, &key, range_min, range_max) {
+            Ok(p) => p,
+            Err(e) => return internal_error(id, &e.to_string()),
+        };
+        let buckets = aggregate_points(&points, range_min, range_max, resolution, agg);
+        let force_string = buckets
+            .iter()
+            .filter_map(|b| b.value)
+            .any(|v| v.unsigned_abs() > MAX_SAFE_INTEGER_U64 as u128);
+        let points: Vec<Value> = buckets
+            .into_iter()
+            .map(|b| {
+                let value = match b.value {
+                    None => Value::Null,
+                    Some(v) if force_string => Value::String(v.to_string()),
+                    Some(v) => json!(v as i64),
+                };
+                json!({ "height": b.start, "end_height": b.end, "value": value })
+            })
+            .collect();
+
+        out_series.push(json!({
+            "name": name,
+            "key": if key.is_empty() { Value::Null } else { json!(key) },
+            "points": points,
+        }));
+    }
+
+    JsonRpcResponse {
+        jsonrpc: JSONRPC_VERSION,
+        result: Some(json!({
+            "range_min": range_min,
+            "range_max": range_max,
+            "resolution": resolution,
+            "aggregation": agg.code(),
+            "series": out_series,
+        })),
+        error: None,
+        id,
+    }
+}
+
+fn admin_status() -> Value {
+    let control = indexer_control();
+    let cfg = get_config();
+    let next_height = get_espo_next_height();
+    json!({
+        "indexer": {
+            "running": control.is_running(),
+            "paused": control.is_paused(),
+            "view_only": cfg.view_only,
+            "next_height": next_height,
+            "indexed_height": next_height.saturating_sub(1),
+            "safe_tip": get_last_safe_tip(),
+            "pending_rewind": control.pending_rewind(),
+        },
+        "debug": debug_enabled(),
+        "modules": control.module_health(),
+        "snapshot": {
+            "dir": cfg.snapshot_dir(),
+            "pending": control.snapshot_pending(),
+            "last": control.last_snapshot(),
+        },
+    })
+}
+
+async fn admin_response(id: Value, method: &str, params: Value) -> JsonRpcResponse {
+    let params_obj = match params {
+        Value::Object(obj) => obj,
+        Value::Null => serde_json::Map::new(),
+        _ => return invalid_params(id, "params must be an object"),
+    };
+    let control = indexer_control();
+    let action = method.strip_prefix(ADMIN_METHOD_PREFIX).unwrap_or_default();
+    // these are carried out by the indexer loop, which view-only mode never starts
+    if matches!(action, "pause" | "resume" | "rewind" | "snapshot") && !control.is_running() {
+        return err_response(id, -32000, "Indexer not running", None);
+    }
+
+    let result = match action {
+        "status" => admin_status(),
+        "pause" | "resume" => {
+            let paused = action == "pause";
+            let was_paused = control.set_paused(paused);
+            eprintln!("[admin] indexer {}", if paused { "paused" } else { "resumed" });
+            json!({ "paused": paused, "was_paused": was_paused })
+        }
+        "rewind" => {
+            let height = match parse_optional_u32_param(&params_obj, "height") {
+                Ok(Some(h)) => h,
+                Ok(None) => return invalid_params(id, "height is required"),
+                Err(detail) => return invalid_params(id, &detail),
+            };
+            let genesis = alkanes_genesis_block(get_network());
+            let next_height = get_espo_next_height();
+            if height < genesis || height >= next_height {
+                let detail = format!("height must be within [{genesis}, {next_height})");
+                return invalid_params(id, &detail);
+            }
+            let queued = control.request_rewind(height);
+            eprintln!("[admin] rewind to height {} requested (queued={})", height, queued);
+            json!({ "queued": queued, "pending_rewind": control.pending_rewind() })
+        }
+        "reset_mempool" => match tokio::task::spawn_blocking(reset_mempool_store).await {
+            Ok(Ok(())) => json!({ "reset": true }),
+            Ok(Err(e)) => return internal_error(id, &format!("{e:#}")),
+            Err(e) => return internal_error(id, &e.to_string()),
+        },
+        "sdb_catch_up" => {
+            let t0 = std::time::Instant::now();
+            match tokio::task::spawn_blocking(|| get_metashrew_sdb().catch_up_now()).await {
+                Ok(Ok(())) => {
+                    json!({ "caught_up": true, "elapsed_ms": t0.elapsed().as_millis() as u64 })
+                }
+                Ok(Err(e)) => return internal_error(id, &format!("{e:#}")),
+                Err(e) => return internal_error(id, &e.to_string()),
+            }
+        }
+        "set_debug" => {
+            let enabled = match params_obj.get("enabled") {
+                Some(Value::Bool(b)) => *b,
+                _ => return invalid_params(id, "enabled must be a boolean"),
+            };
+            let was_enabled = debug_enabled();
+            set_debug_enabled(enabled);
+            eprintln!("[admin] debug timers {}", if enabled { "enabled" } else { "disabled" });
+            json!({ "debug": enabled, "was_enabled": was_enabled })
+        }
+        "snapshot" => {
+            let Some(dir) = get_config().snapshot_dir() else {
+                return invalid_params(id, "no snapshot dir configured (admin.snapshot_dir)");
+            };
+            let queued = control.request_snapshot();
+            json!({ "queued": queued, "dir": dir })
+        }
+        _ => return method_not_found(id),
+    };
+    ok_response(id, result)
+}
+
+fn ok_response(id: Value, result: Value) -> JsonRpcResponse {
+    JsonRpcResponse { jsonrpc: JSONRPC_VERSION, result: Some(result), error: None, id }
+}
+
+/// `None` when the admin namespace is disabled, otherwise whether the request carried
+/// the configured bearer token.
+fn admin_authorization(headers: &HeaderMap) -> Option<bool> {
+    let expected = get_config().admin.as_ref()?.token.as_str();
+    let presented = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default();
+    Some(admin_token_matches(expected, presented))
+}
+
 fn parse_error() -> JsonRpcResponse {
     err_response(Value::Null, -32700, "Parse error", None)
 }
@@ -428,6 +736,7 @@ fn extract_id(obj: &serde_json::Map<String, Value>) -> Option<Value> {
 async fn handle_single_request(
     state: &RpcState,
     req_obj: &serde_json::Map<String, Value>,
+    admin: Option<bool>,
 ) -> Option<JsonRpcResponse> {
     let id_opt = extract_id(req_obj);
     // Notifications (no id): no response at all
@@ -478,6 +787,19 @@ async fn handle_single_request(
     if method == ROOT_METHOD_GET_METHOD_LINE_CHART {
         return Some(get_method_line_chart_response(state, id, params).await);
     }
+    if method == ROOT_METHOD_GET_TIMESERIES {
+        return Some(get_timeseries_response(id, params));
+    }
+    if method == ROOT_METHOD_LIST_TIMESERIES {
+        return Some(list_timeseries_response(id));
+    }
+    if method.starts_with(ADMIN_METHOD_PREFIX) {
+        return Some(match admin {
+            None => method_not_found(id),
+            Some(false) => err_response(id, ADMIN_UNAUTHORIZED_CODE, "Unauthorized", None),
+            Some(true) => admin_response(id, method, params).await,
+        });
+    }
 
     // Check method existence to produce -32601 at the protocol layer
     let method_exists = {
@@ -505,7 +827,10 @@ async fn handle_single_request(
 
 pub async fn run_rpc(registry: RpcRegistry, addr: SocketAddr) -> anyhow::Result<()> {
     let state = Arc::new(RpcState { registry });
-    let app = Router::new().route("/rpc", post(handle_rpc)).with_state(state);
+    let app = Router::new()
+        .route("/rpc", post(handle_rpc))
+        .route("/export/{kind}", get(handle_export))
+        .with_state(state);
 
     eprintln!("[rpc] listening on {}", addr);
     let listener = TcpListener::bind(addr).await?;
@@ -513,12 +838,104 @@ pub async fn run_rpc(registry: RpcRegistry, addr: SocketAddr) -> anyhow::Result<
     Ok(())
 }
 
+/// Bytes buffered per chunk before handing them to the HTTP body stream.
+const EXPORT_STREAM_CHUNK: usize = 64 * 1024;
+
+/// Blocking `Write` adapter feeding an export into a streamed response body. A closed
+/// receiver (client went away) surfaces as `BrokenPipe`, which aborts the export.
+struct ChannelWriter {
+    tx: mpsc::Sender<Result<Bytes, std::io::Error>>,
+    buf: Vec<u8>,
+}
+
+impl ChannelWriter {
+    fn send(&mut self) -> std::io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk =
+            Bytes::from(std::mem::replace(&mut self.buf, Vec::with_capacity(EXPORT_STREAM_CHUNK)));
+        futures::executor::block_on(self.tx.send(Ok(chunk)))
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))
+    }
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() >= EXPORT_STREAM_CHUNK {
+            self.send()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.send()
+    }
+}
+
+fn export_error(status: StatusCode, detail: &str) -> Response {
+    let body = serde_json::to_vec(&json!({ "ok": false, "error": detail }))
+        .unwrap_or_else(|_| b"{}".to_vec());
+    (status, [(CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+async fn handle_export(
+    Path(kind): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let Some(kind) = ExportKind::parse(&kind) else {
+        return export_error(StatusCode::NOT_FOUND, "unknown_export_kind");
+    };
+    let format = match query.get("format") {
+        None => ExportFormat::Csv,
+        Some(raw) => match ExportFormat::parse(raw) {
+            Some(f) => f,
+            None => return export_error(StatusCode::BAD_REQUEST, "format must be csv | ndjson"),
+        },
+    };
+    let params = match ExportParams::from_query(&query) {
+        Ok(p) => p,
+        Err(detail) => return export_error(StatusCode::BAD_REQUEST, &detail),
+    };
+    let job = match tokio::task::spawn_blocking(move || ExportJob::prepare(kind, &params)).await {
+        Ok(Ok(job)) => job,
+        Ok(Err(detail)) => return export_error(StatusCode::BAD_REQUEST, &detail),
+        Err(e) => return export_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+
+    let (tx, rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(8);
+    tokio::task::spawn_blocking(move || {
+        let mut writer =
+            ChannelWriter { tx: tx.clone(), buf: Vec::with_capacity(EXPORT_STREAM_CHUNK) };
+        if let Err(e) = job.write_to(format, &mut writer) {
+            eprintln!("[export] {} export aborted: {e:?}", kind.code());
+            let mut tx = tx;
+            let _ = futures::executor::block_on(tx.send(Err(std::io::Error::other(e.to_string()))));
+        }
+    });
+
+    let filename = format!("attachment; filename=\"{}.{}\"", kind.code(), format.extension());
+    (
+        StatusCode::OK,
+        [(CONTENT_TYPE, format.content_type().to_string()), (CONTENT_DISPOSITION, filename)],
+        Body::from_stream(rx),
+    )
+        .into_response()
+}
+
 #[inline]
 fn json_ok(body: Vec<u8>) -> Response {
     (StatusCode::OK, [(CONTENT_TYPE, "application/json")], body).into_response()
 }
 
-async fn handle_rpc(State(state): State<Arc<RpcState>>, body: Bytes) -> Response {
+async fn handle_rpc(
+    State(state): State<Arc<RpcState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let admin = admin_authorization(&headers);
+
     // 1) Try to parse raw JSON (to distinguish -32700 from other errors)
     let parsed: serde_json::Result<Value> = serde_json::from_slice(&body);
 
@@ -546,7 +963,7 @@ async fn handle_rpc(State(state): State<Arc<RpcState>>, body: Bytes) -> Response
             for item in items {
                 match item {
                     Value::Object(obj) => {
-                        if let Some(resp) = handle_single_request(&state, &obj).await {
+                        if let Some(resp) = handle_single_request(&state, &obj, admin).await {
                             responses.push(resp);
                         }
                     }
@@ -565,7 +982,7 @@ async fn handle_rpc(State(state): State<Arc<RpcState>>, body: Bytes) -> Response
             let body = serde_json::to_vec(&responses).unwrap();
             json_ok(body)
         }
-        Value::Object(obj) => match handle_single_request(&state, &obj).await {
+        Value::Object(obj) => match handle_single_request(&state, &obj, admin).await {
             Some(resp) => {
                 let body = serde_json::to_vec(&resp).unwrap();
                 json_ok(body)
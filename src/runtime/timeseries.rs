@@ -0,0 +1,310 @@
+//! Per-block numeric series.
+//!
+//! Modules declare the series they maintain through `EspoModule::timeseries()` and
+//! append a point (`series_point`) to their own batch whenever the value changes at a
+//! height. A series is a step function over heights: the value at height `h` is the
+//! last point written at or before `h`. Queries bucket a height range at a fixed
+//! resolution and reduce each bucket with one of `Aggregation`.
+
+use crate::runtime::mdb::Mdb;
+use crate::runtime::tree_db::prefix_end_exclusive;
+use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+
+const SERIES_NS: &[u8] = b"/timeseries/v1/";
+
+/// Static description of a series maintained by a module.
+#[derive(Clone, Copy, Debug)]
+pub struct SeriesDef {
+    /// Local name; exposed as "<module>.<name>".
+    pub name: &'static str,
+    /// What the per-series key identifies ("alkane", "pool", ...), or "" for a single series.
+    pub keyed_by: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Clone, Debug)]
+pub struct RegisteredSeries {
+    pub module: &'static str,
+    pub def: SeriesDef,
+}
+
+impl RegisteredSeries {
+    pub fn full_name(&self) -> String {
+        format!("{}.{}", self.module, self.def.name)
+    }
+}
+
+static SERIES_REGISTRY: OnceLock<RwLock<BTreeMap<String, RegisteredSeries>>> = OnceLock::new();
+
+fn registry() -> &'static RwLock<BTreeMap<String, RegisteredSeries>> {
+    SERIES_REGISTRY.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+pub fn register_series(module: &'static str, defs: &[SeriesDef]) {
+    let mut map = registry().write().expect("series registry poisoned");
+    for def in defs {
+        let series = RegisteredSeries { module, def: *def };
+        map.insert(series.full_name(), series);
+    }
+}
+
+pub fn lookup_series(full_name: &str) -> Option<RegisteredSeries> {
+    registry().read().expect("series registry poisoned").get(full_name).cloned()
+}
+
+pub fn list_series() -> Vec<RegisteredSeries> {
+    registry().read().expect("series registry poisoned").values().cloned().collect()
+}
+
+/* ---------------- storage ---------------- */
+
+fn series_prefix(name: &str, key: &str) -> Vec<u8> {
+    let mut k = SERIES_NS.to_vec();
+    k.extend_from_slice(name.as_bytes());
+    k.push(b'/');
+    k.extend_from_slice(key.as_bytes());
+    k.push(b'/');
+    k
+}
+
+/// Builds the `(key, value)` put recording `value` for `name`/`key` at `height`.
+/// `name` is the local series name; the write goes into the owning module's batch.
+pub fn series_point(name: &str, key: &str, height: u32, value: i128) -> (Vec<u8>, Vec<u8>) {
+    let mut k = series_prefix(name, key);
+    k.extend_from_slice(&height.to_be_bytes());
+    (k, value.to_le_bytes().to_vec())
+}
+
+/// Points of a series needed to evaluate it over `[from_height, to_height]`: the last
+/// point before `from_height` (the value in effect at its start) followed by every point
+/// in the range, ascending by height. Both reads seek straight to the range.
+pub fn read_series_points(
+    mdb: &Mdb,
+    name: &str,
+    key: &str,
+    from_height: u32,
+    to_height: u32,
+) -> Result<Vec<(u32, i128)>> {
+    let prefix = series_prefix(name, key);
+    let height_key = |height: u32| {
+        let mut k = prefix.clone();
+        k.extend_from_slice(&height.to_be_bytes());
+        k
+    };
+    let from_key = height_key(from_height);
+    let end_key = match to_height.checked_add(1) {
+        Some(end) => Some(height_key(end)),
+        None => prefix_end_exclusive(&prefix),
+    };
+
+    let mut entries = mdb
+        .scan_range_entries(&prefix, Some(&from_key), 1, true)
+        .map_err(|e| anyhow!("mdb.scan_range_entries failed: {e}"))?;
+    entries.extend(
+        mdb.scan_range_entries(&from_key, end_key.as_deref(), usize::MAX, false)
+            .map_err(|e| anyhow!("mdb.scan_range_entries failed: {e}"))?,
+    );
+
+    let mut points: Vec<(u32, i128)> = Vec::with_capacity(entries.len());
+    for (k, v) in entries {
+        let Some(tail) = k.get(prefix.len()..) else { continue };
+        let (Ok(h), Ok(val)) = (<[u8; 4]>::try_from(tail), <[u8; 16]>::try_from(v.as_slice()))
+        else {
+            continue;
+        };
+        points.push((u32::from_be_bytes(h), i128::from_le_bytes(val)));
+    }
+    Ok(points)
+}
+
+/* ---------------- aggregation ---------------- */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregation {
+    Last,
+    Min,
+    Max,
+    Sum,
+    Avg,
+}
+
+impl Aggregation {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "last" => Some(Self::Last),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "sum" => Some(Self::Sum),
+            "avg" | "mean" => Some(Self::Avg),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Last => "last",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeriesBucket {
+    pub start: u32,
+    pub end: u32,
+    /// `None` when the series has no value anywhere in the bucket.
+    pub value: Option<i128>,
+}
+
+/// Buckets `[from, to]` into `resolution`-block windows and reduces each window over
+/// the per-height step function. Sum and avg weight each value by the number of
+/// heights it was in effect; avg uses integer division.
+pub fn aggregate_points(
+    points: &[(u32, i128)],
+    from: u32,
+    to: u32,
+    resolution: u32,
+    agg: Aggregation,
+) -> Vec<SeriesBucket> {
+    let resolution = resolution.max(1);
+    let mut out = Vec::new();
+    if from > to {
+        return out;
+    }
+
+    // index of the first point strictly after the current height
+    let mut idx = points.partition_point(|(h, _)| *h <= from);
+    let mut current: Option<i128> = idx.checked_sub(1).map(|i| points[i].1);
+
+    let mut start = from;
+    loop {
+        let end = start.saturating_add(resolution - 1).min(to);
+        while let Some(&(h, v)) = points.get(idx) {
+            if h > start {
+                break;
+            }
+            current = Some(v);
+            idx += 1;
+        }
+
+        let mut last = current;
+        let mut min = current;
+        let mut max = current;
+        let mut sum: i128 = 0;
+        let mut covered: u64 = 0;
+        let mut seg_start = start;
+        loop {
+            let next_change = points.get(idx).map(|(h, _)| *h).filter(|h| *h <= end);
+            let seg_end = next_change.map(|h| h - 1).unwrap_or(end);
+            if let Some(v) = current {
+                if seg_end >= seg_start {
+                    let len = (seg_end - seg_start) as u64 + 1;
+                    sum = sum.saturating_add(v.saturating_mul(len as i128));
+                    covered += len;
+                }
+            }
+            let Some(h) = next_change else { break };
+            let v = points[idx].1;
+            idx += 1;
+            seg_start = h;
+            current = Some(v);
+            last = current;
+            min = Some(min.map_or(v, |m| m.min(v)));
+            max = Some(max.map_or(v, |m| m.max(v)));
+        }
+
+        let value = match agg {
+            Aggregation::Last => last,
+            Aggregation::Min => min,
+            Aggregation::Max => max,
+            Aggregation::Sum => (covered > 0).then_some(sum),
+            Aggregation::Avg => (covered > 0).then(|| sum / covered as i128),
+        };
+        out.push(SeriesBucket { start, end, value });
+
+        if end >= to {
+            break;
+        }
+        start = end + 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(buckets: &[SeriesBucket]) -> Vec<Option<i128>> {
+        buckets.iter().map(|b| b.value).collect()
+    }
+
+    #[test]
+    fn full_height_range_does_not_overflow() {
+        let points = [(0, 1)];
+        let b = aggregate_points(&points, 0, u32::MAX, 1 << 31, Aggregation::Sum);
+        assert_eq!(b.len(), 2);
+        assert_eq!(values(&b), vec![Some(1i128 << 31), Some(1i128 << 31)]);
+    }
+
+    #[test]
+    fn last_carries_forward() {
+        let points = [(10, 5), (13, 7)];
+        let b = aggregate_points(&points, 8, 15, 2, Aggregation::Last);
+        assert_eq!(
+            b.iter().map(|b| (b.start, b.end)).collect::<Vec<_>>(),
+            vec![(8, 9), (10, 11), (12, 13), (14, 15)]
+        );
+        assert_eq!(values(&b), vec![None, Some(5), Some(7), Some(7)]);
+    }
+
+    #[test]
+    fn min_max_include_value_at_bucket_start() {
+        let points = [(1, 10), (5, 2), (6, 20)];
+        assert_eq!(values(&aggregate_points(&points, 4, 7, 4, Aggregation::Min)), vec![Some(2)]);
+        assert_eq!(values(&aggregate_points(&points, 4, 7, 4, Aggregation::Max)), vec![Some(20)]);
+    }
+
+    #[test]
+    fn sum_and_avg_are_height_weighted() {
+        // heights 1..=4: 10, 10, 30, 30
+        let points = [(1, 10), (3, 30)];
+        assert_eq!(values(&aggregate_points(&points, 1, 4, 4, Aggregation::Sum)), vec![Some(80)]);
+        assert_eq!(values(&aggregate_points(&points, 1, 4, 4, Aggregation::Avg)), vec![Some(20)]);
+        // heights before the first point do not count towards the average
+        assert_eq!(values(&aggregate_points(&points, 0, 2, 3, Aggregation::Avg)), vec![Some(10)]);
+    }
+
+    #[test]
+    fn series_point_keys_sort_by_height() {
+        let (a, _) = series_point("holders_count", "2:0", 255, 1);
+        let (b, _) = series_point("holders_count", "2:0", 256, 1);
+        assert!(a < b);
+    }
+
+    #[test]
+    fn reads_seek_to_the_requested_range() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mdb = Mdb::open(dir.path(), b"test:").expect("open mdb");
+        for (height, value) in [(5, 1), (10, 2), (20, 3), (30, 4)] {
+            let (k, v) = series_point("holders_count", "2:0", height, value);
+            mdb.put(&k, &v).expect("put");
+        }
+        let (k, v) = series_point("holders_count", "2:1", 15, 9);
+        mdb.put(&k, &v).expect("put");
+
+        // The point in effect at the start of the range, then the points inside it.
+        let points = read_series_points(&mdb, "holders_count", "2:0", 12, 25).unwrap();
+        assert_eq!(points, vec![(10, 2), (20, 3)]);
+        let points = read_series_points(&mdb, "holders_count", "2:0", 0, 5).unwrap();
+        assert_eq!(points, vec![(5, 1)]);
+        let points = read_series_points(&mdb, "holders_count", "2:0", 30, u32::MAX).unwrap();
+        assert_eq!(points, vec![(20, 3), (30, 4)]);
+        let points = read_series_points(&mdb, "holders_count", "2:1", 0, u32::MAX).unwrap();
+        assert_eq!(points, vec![(15, 9)]);
+    }
+}
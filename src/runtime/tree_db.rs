@@ -207,7 +207,16 @@ fn child_index_for_key(keys: &[Vec<u8>], key: &[u8]) -> usize {
     lo
 }
 
-fn prefix_end_exclusive(prefix: &[u8]) -> Option<Vec<u8>> {
+/// Smallest key strictly greater than `key`; resumes an ascending scan after `key`.
+pub fn next_key(key: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(key.len() + 1);
+    out.extend_from_slice(key);
+    out.push(0);
+    out
+}
+
+/// Smallest key greater than every key starting with `prefix` (`None` when all bytes are 0xFF).
+pub fn prefix_end_exclusive(prefix: &[u8]) -> Option<Vec<u8>> {
     let mut end = prefix.to_vec();
     for i in (0..end.len()).rev() {
         if end[i] != 0xFF {
@@ -336,6 +345,55 @@ impl VersionedTreeDb {
         self.db.write(wb)
     }
 
+    /// Makes the block below `height` active again and forgets every block at or above it, so
+    /// the indexer processes them from scratch. Node pages are left in place. Returns the
+    /// number of blocks dropped.
+    pub fn rollback_to_height(&self, height: u32) -> Result<u32, RocksError> {
+        let Some((_, tip)) = self.indexed_height_bounds()? else {
+            return Ok(0);
+        };
+        if height > tip {
+            return Ok(0);
+        }
+        let base_block = match height.checked_sub(1) {
+            Some(below) => self.blockhash_for_height(below)?,
+            None => None,
+        };
+        let base_root = match base_block {
+            Some(hash) => self.root_for_blockhash(&hash)?,
+            None => None,
+        };
+
+        let mut wb = WriteBatch::default();
+        let mut dropped = 0u32;
+        for h in height..=tip {
+            let Some(hash) = self.blockhash_for_height(h)? else { continue };
+            let arr = hash.to_byte_array();
+            wb.delete(height_block_key(h));
+            wb.delete(block_root_key(&arr));
+            wb.delete(block_height_key(&arr));
+            wb.delete(block_parent_key(&arr));
+            dropped += 1;
+        }
+
+        let mut st = self.state.write().expect("tree state poisoned");
+        st.current_block = None;
+        st.active_root = base_root.unwrap_or_else(empty_root_id);
+        st.active_block = base_root.and(base_block).map(|hash| hash.to_byte_array());
+        wb.put(META_ACTIVE_ROOT, st.active_root);
+        match st.active_block {
+            Some(active_block) => wb.put(META_ACTIVE_BLOCK, active_block),
+            None => wb.delete(META_ACTIVE_BLOCK),
+        }
+        // The pinned view may belong to a dropped block.
+        st.pinned_root = None;
+        st.pin_until_height = None;
+        wb.delete(META_PINNED_ROOT);
+        wb.delete(META_PIN_UNTIL_HEIGHT);
+        self.db.write(wb)?;
+        Ok(dropped)
+    }
+
     pub fn blockhash_for_height(&self, height: u32) -> Result<Option<BlockHash>, RocksError> {
         let Some(bytes) = self.db.get(height_block_key(height))? else {
             return Ok(None);
@@ -667,8 +725,22 @@ impl VersionedTreeDb {
         root: [u8; 32],
         start_inclusive: &[u8],
         end_exclusive: Option<&[u8]>,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksError> {
+        self.range_entries_limited_at_root(root, start_inclusive, end_exclusive, usize::MAX)
+    }
+
+    /// First `limit` entries of `[start_inclusive, end_exclusive)`, ascending.
+    pub fn range_entries_limited_at_root(
+        &self,
+        root: [u8; 32],
+        start_inclusive: &[u8],
+        end_exclusive: Option<&[u8]>,
+        limit: usize,
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksError> {
         let mut out = Vec::new();
+        if limit == 0 {
+            return Ok(out);
+        }
         let Some((mut cursor, mut path)) = self.find_leaf_with_path(root, start_inclusive)? else {
             return Ok(out);
         };
@@ -690,6 +762,9 @@ impl VersionedTreeDb {
                 }
                 if let Some(v) = &entry.value {
                     out.push((entry.key.clone(), v.clone()));
+                    if out.len() >= limit {
+                        return Ok(out);
+                    }
                 }
             }
 
@@ -703,6 +778,53 @@ impl VersionedTreeDb {
         Ok(out)
     }
 
+    /// Last `limit` entries of `[start_inclusive, end_exclusive)`, descending.
+    pub fn range_entries_rev_at_root(
+        &self,
+        root: [u8; 32],
+        start_inclusive: &[u8],
+        end_exclusive: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksError> {
+        let mut out = Vec::new();
+        if limit == 0 {
+            return Ok(out);
+        }
+        let found = match end_exclusive {
+            Some(end) => self.find_leaf_with_path(root, end)?,
+            None => self.find_last_leaf_with_path(root)?,
+        };
+        let Some((mut cursor, mut path)) = found else {
+            return Ok(out);
+        };
+
+        loop {
+            let leaf = self.load_leaf(&cursor)?;
+            let end_idx = match end_exclusive {
+                Some(end) => leaf.entries.partition_point(|entry| entry.key.as_slice() < end),
+                None => leaf.entries.len(),
+            };
+            for entry in leaf.entries[..end_idx].iter().rev() {
+                if entry.key.as_slice() < start_inclusive {
+                    return Ok(out);
+                }
+                if let Some(v) = &entry.value {
+                    out.push((entry.key.clone(), v.clone()));
+                    if out.len() >= limit {
+                        return Ok(out);
+                    }
+                }
+            }
+
+            let Some(prev) = self.prev_leaf_from_path(&mut path)? else {
+                break;
+            };
+            cursor = prev;
+        }
+
+        Ok(out)
+    }
+
     fn apply_mutation(&self, key: &[u8], value: Option<Vec<u8>>) -> Result<(), RocksError> {
         let mut st = self.state.write().expect("tree state poisoned");
         if let Some(ctx) = st.current_block.as_mut() {
@@ -1000,6 +1122,56 @@ impl VersionedTreeDb {
         }
     }
 
+    fn find_last_leaf_with_path(
+        &self,
+        root: [u8; 32],
+    ) -> Result<Option<([u8; 32], Vec<([u8; 32], usize)>)>, RocksError> {
+        let mut path = Vec::new();
+        let mut current = root;
+        loop {
+            match self.load_node(&current)? {
+                BptreeNode::Leaf(_) => return Ok(Some((current, path))),
+                BptreeNode::Internal(internal) => {
+                    let Some(last) = internal.children.last().copied() else {
+                        return Ok(None);
+                    };
+                    path.push((current, internal.children.len() - 1));
+                    current = last;
+                }
+            }
+        }
+    }
+
+    fn prev_leaf_from_path(
+        &self,
+        path: &mut Vec<([u8; 32], usize)>,
+    ) -> Result<Option<[u8; 32]>, RocksError> {
+        while let Some((internal_id, child_idx)) = path.pop() {
+            if child_idx == 0 {
+                continue;
+            }
+            let internal = self.load_internal(&internal_id)?;
+            let prev_idx = child_idx - 1;
+            let Some(mut node_id) = internal.children.get(prev_idx).copied() else {
+                continue;
+            };
+            path.push((internal_id, prev_idx));
+            loop {
+                match self.load_node(&node_id)? {
+                    BptreeNode::Leaf(_) => return Ok(Some(node_id)),
+                    BptreeNode::Internal(prev_internal) => {
+                        let Some(last) = prev_internal.children.last().copied() else {
+                            return Ok(None);
+                        };
+                        path.push((node_id, prev_internal.children.len() - 1));
+                        node_id = last;
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
     fn next_leaf_from_path(
         &self,
         path: &mut Vec<([u8; 32], usize)>,
@@ -1183,6 +1355,38 @@ mod tests {
         assert_eq!(entries.len(), 975);
     }
 
+    #[test]
+    fn limited_ranges_walk_both_directions() {
+        let (_dir, tree) = new_tree();
+        let mut changes = Vec::new();
+        for i in 0..1500u32 {
+            changes.push((format!("log/{i:05}").into_bytes(), Some(vec![(i % 251) as u8])));
+        }
+        changes.push((b"other".to_vec(), Some(vec![0])));
+        tree.apply_batch(&changes).expect("seed");
+        let root = tree.active_root();
+        let end = prefix_end_exclusive(b"log/");
+
+        let fwd = tree
+            .range_entries_limited_at_root(root, b"log/00700", end.as_deref(), 3)
+            .expect("forward");
+        let keys: Vec<Vec<u8>> = fwd.into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![b"log/00700".to_vec(), b"log/00701".to_vec(), b"log/00702".to_vec()]);
+
+        let rev = tree
+            .range_entries_rev_at_root(root, b"log/", Some(b"log/00700".as_slice()), 3)
+            .expect("reverse");
+        let keys: Vec<Vec<u8>> = rev.into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![b"log/00699".to_vec(), b"log/00698".to_vec(), b"log/00697".to_vec()]);
+
+        let all_rev = tree
+            .range_entries_rev_at_root(root, b"log/", end.as_deref(), usize::MAX)
+            .expect("all");
+        assert_eq!(all_rev.len(), 1500);
+        assert_eq!(all_rev[0].0, b"log/01499".to_vec());
+        assert_eq!(all_rev[1499].0, b"log/00000".to_vec());
+    }
+
     #[test]
     fn block_roots_preserve_historical_reads() {
         let (_dir, tree) = new_tree();
@@ -1206,6 +1410,36 @@ mod tests {
         assert_eq!(tree.get_at_root(r2, key).expect("get h2"), Some(vec![2]));
     }
 
+    #[test]
+    fn rollback_forgets_blocks_from_target_height() {
+        let (_dir, tree) = new_tree();
+        let key = b"essentials:/k";
+        let genesis = BlockHash::from_byte_array([0u8; 32]);
+        let hashes: Vec<BlockHash> =
+            (1..=3u8).map(|i| BlockHash::from_byte_array([i; 32])).collect();
+        let mut parent = genesis;
+        for (i, hash) in hashes.iter().enumerate() {
+            tree.begin_block(i as u32 + 1, hash, &parent).expect("begin block");
+            tree.apply_batch(&[(key.to_vec(), Some(vec![i as u8 + 1]))]).expect("apply");
+            tree.finish_block().expect("finish block");
+            parent = *hash;
+        }
+
+        assert_eq!(tree.rollback_to_height(2).expect("rollback"), 2);
+        assert_eq!(tree.indexed_height_bounds().expect("bounds"), Some((1, 1)));
+        assert_eq!(tree.active_blockhash(), Some(hashes[0]));
+        assert_eq!(tree.get(key).expect("get"), Some(vec![1]));
+        assert!(tree.root_for_blockhash(&hashes[1]).expect("root h2").is_none());
+        assert!(tree.root_for_blockhash(&hashes[2]).expect("root h3").is_none());
+
+        // Re-indexing the dropped height starts from the surviving parent.
+        tree.begin_block(2, &hashes[1], &hashes[0]).expect("begin again");
+        let working = tree.root_for_blockhash(&hashes[1]).expect("root lookup").expect("root");
+        assert_eq!(tree.get_at_root(working, key).expect("get working"), Some(vec![1]));
+        tree.abort_block();
+        assert_eq!(tree.rollback_to_height(5).expect("past tip"), 0);
+    }
+
     #[test]
     fn root_for_in_progress_blockhash_is_visible() {
         let (_dir, tree) = new_tree();
@@ -361,5 +361,127 @@ fn init_with_cellpack_pairs_w_input(
     Block { header, txdata: transactions }
 }
 
+// Helpers for trading against a deployed AMM
+
+/// Deadline passed to factory calls; later than any test height.
+pub const TEST_DEADLINE: u128 = 1_000_000;
+
+/// Distinct P2WPKH script for test actor `seed`.
+pub fn test_spk(seed: u8) -> bitcoin::ScriptBuf {
+    bitcoin::ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array([seed; 20]))
+}
+
+/// Packs a string of at most 15 bytes into one null-terminated cellpack input.
+fn short_string_input(s: &str) -> u128 {
+    let len = s.len().min(15);
+    let mut bytes = [0u8; 16];
+    bytes[..len].copy_from_slice(&s.as_bytes()[..len]);
+    u128::from_le_bytes(bytes)
+}
+
+/// Clones the auth token template into a new `[2, n]` alkane and mints `amount` to the caller.
+pub fn mint_token_cellpack(symbol: &str, amount: u128) -> Cellpack {
+    let name = short_string_input(symbol);
+    Cellpack {
+        target: AlkaneId { block: 6, tx: AUTH_TOKEN_FACTORY_ID },
+        inputs: vec![0, name, name, amount],
+    }
+}
+
+/// Factory `create_new_pool`; the transaction must carry both tokens.
+pub fn create_pool_cellpack(
+    factory: &AlkaneId,
+    token_a: &AlkaneId,
+    token_b: &AlkaneId,
+    amount_a: u128,
+    amount_b: u128,
+) -> Cellpack {
+    Cellpack {
+        target: AlkaneId { block: factory.block, tx: factory.tx },
+        inputs: vec![1, token_a.block, token_a.tx, token_b.block, token_b.tx, amount_a, amount_b],
+    }
+}
+
+/// Factory `swap_exact_tokens_for_tokens` along `path`; the transaction must carry `path[0]`.
+pub fn swap_exact_cellpack(
+    factory: &AlkaneId,
+    path: &[AlkaneId],
+    amount_in: u128,
+    amount_out_min: u128,
+) -> Cellpack {
+    let mut inputs = vec![13, path.len() as u128];
+    for id in path {
+        inputs.extend([id.block, id.tx]);
+    }
+    inputs.extend([amount_in, amount_out_min, TEST_DEADLINE]);
+    Cellpack { target: AlkaneId { block: factory.block, tx: factory.tx }, inputs }
+}
+
+/// Transaction spending `inputs` and calling `cellpack`. The alkanes held by the inputs are
+/// passed to the call; what it returns, and any refund, goes to output 0 paid to `recipient`.
+pub fn cellpack_tx(
+    cellpack: Cellpack,
+    inputs: &[OutPoint],
+    recipient: bitcoin::ScriptBuf,
+) -> bitcoin::Transaction {
+    use bitcoin::{Amount, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness};
+    use ordinals::Runestone;
+    use protorune_support::protostone::{Protostone, Protostones};
+
+    let mut tx_inputs: Vec<TxIn> = inputs
+        .iter()
+        .map(|outpoint| TxIn {
+            previous_output: *outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        })
+        .collect();
+    if tx_inputs.is_empty() {
+        tx_inputs.push(TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        });
+    }
+
+    let protostone = Protostone {
+        burn: None,
+        message: cellpack.encipher(),
+        edicts: vec![],
+        refund: Some(0),
+        pointer: Some(0),
+        from: None,
+        protocol_tag: 1,
+    };
+    let protocol_field = vec![protostone].encipher().expect("Failed to encode protostones");
+    let runestone = Runestone {
+        edicts: vec![],
+        etching: None,
+        mint: None,
+        pointer: Some(0),
+        protocol: Some(protocol_field),
+    };
+
+    Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: bitcoin::locktime::absolute::LockTime::ZERO,
+        input: tx_inputs,
+        output: vec![
+            TxOut { value: Amount::from_sat(100_000_000), script_pubkey: recipient },
+            TxOut { value: Amount::ZERO, script_pubkey: runestone.encipher() },
+        ],
+    }
+}
+
+/// Block stamped with `time` holding a coinbase followed by `txs`.
+pub fn block_with_txs(txs: Vec<bitcoin::Transaction>, time: u32) -> Block {
+    let mut block = init_with_cellpack_pairs(Vec::new());
+    block.header.time = time;
+    block.txdata.extend(txs);
+    block
+}
+
 // WASM getters have been moved to fixtures.rs for better organization
 // They are now loaded via include_bytes! from test_data/ directory
@@ -36,6 +36,7 @@ impl TestConfigBuilder {
             sdb_poll_ms: 100, // Fast polling for tests
             indexer_block_delay_ms: 0,
             port: 0, // Let OS assign port
+            grpc_host: None,
             explorer_host: None,
             explorer_base_path: "/".to_string(),
             explorer_pizza_tv_endpoint: "https://tv.pizza.fun".to_string(),
@@ -45,6 +46,7 @@ impl TestConfigBuilder {
             debug: false,
             debug_ignore_ms: 0,
             debug_backup: None,
+            admin: None,
             safe_tip_hook_script: None,
             block_source_mode: BlockFetchMode::RpcOnly,
             compact_tx_trace_rows: true,
@@ -101,6 +103,12 @@ impl TestConfigBuilder {
         self
     }
 
+    /// Set the config block for a module, as it would appear under `modules` in config.json
+    pub fn with_module_config(mut self, name: &str, value: serde_json::Value) -> Self {
+        self.config.modules.insert(name.to_string(), value);
+        self
+    }
+
     /// Add a temporary directory that will be managed by the builder
     pub fn add_temp_dir(&mut self, temp_dir: TempDir) {
         self.temp_dirs.push(temp_dir);
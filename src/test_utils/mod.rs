@@ -37,7 +37,9 @@ pub use metashrew_runtime::TestMetashrewRuntime;
 pub use amm_helpers::{
     AMM_FACTORY_ID, AMM_FACTORY_LOGIC_IMPL_TX, AMM_FACTORY_PROXY_TX, AUTH_TOKEN_FACTORY_ID,
     AmmDeployment, BinaryAndCellpack, POOL_BEACON_PROXY_TX, POOL_UPGRADEABLE_BEACON_TX,
-    deploy_amm_infrastructure, deploy_factory_proxy, init_with_cellpack_pairs, setup_amm,
+    TEST_DEADLINE, block_with_txs, cellpack_tx, create_pool_cellpack, deploy_amm_infrastructure,
+    deploy_factory_proxy, init_with_cellpack_pairs, mint_token_cellpack, setup_amm,
+    swap_exact_cellpack, test_spk,
 };
 
 // Re-export trace helpers
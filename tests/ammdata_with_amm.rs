@@ -10,11 +10,33 @@ mod tests {
     use alkanes_support::cellpack::Cellpack;
     use alkanes_support::id::AlkaneId;
     use anyhow::Result;
-    use bitcoin::OutPoint;
     use bitcoin::hashes::Hash;
+    use bitcoin::{BlockHash, Network, OutPoint};
+    use espo::modules::ammdata::consts::{
+        CanonicalQuoteUnit, PRICE_SCALE, amm_default_fee_bps, ammdata_genesis_block,
+        canonical_quotes, configured_amm_factories, current_default_fee_bps, get_amm_contract,
+    };
+    use espo::modules::ammdata::main::AmmData;
+    use espo::modules::ammdata::storage::{
+        AmmDataProvider, GetAmmFactoriesParams, GetFactoryPoolsParams, GetPoolDefsParams,
+        GetRangeEntriesParams, GetRawValueParams, RpcGetAddressPnlParams, RpcGetAlertEventsParams,
+        RpcGetAlertsParams, RpcGetCandlesParams, RpcGetPoolFeesParams, RpcGetTokenScreenerParams,
+        RpcGetTraderLeaderboardParams, RpcRegisterAlertParams, RpcRemoveAlertParams,
+        SetBatchParams, address_str_from_spk, encode_u128_value,
+    };
+    use espo::modules::defs::{EspoModule, ModuleRegistry};
+    use espo::modules::essentials::main::Essentials;
+    use espo::modules::essentials::storage::{EssentialsProvider, RpcGetAirdropListParams};
+    use espo::runtime::mdb::Mdb;
+    use espo::runtime::state_at::StateAt;
+    use espo::runtime::tree_db::{VersionedTreeDb, prefix_end_exclusive};
+    use espo::schemas::SchemaAlkaneId;
     use espo::test_utils::*;
     use metashrew_core::index_pointer::AtomicPointer;
     use metashrew_support::index_pointer::KeyValuePointer;
+    use rocksdb::{DB, Options};
+    use serde_json::json;
+    use std::sync::Arc;
 
     /// Clear test environment and index initial blocks
     fn setup_test_environment() -> Result<()> {
@@ -109,6 +131,1099 @@ mod tests {
         println!("[TEST] ⚠ Requires trace extraction infrastructure");
         Ok(())
     }
+
+    // ============================================================================
+    // Non-mainnet AMM config (ammdata.networks.regtest)
+    // ============================================================================
+
+    const START_HEIGHT: u32 = 4;
+    /// Fake regtest USD stable ("2:1") used as the second canonical quote.
+    const REGTEST_USD: SchemaAlkaneId = SchemaAlkaneId { block: 2, tx: 1 };
+    const REGTEST_FEE_BPS: u32 = 30;
+
+    /// `setup_amm` deploys the factory proxy in block START_HEIGHT + 5.
+    fn expected_factory() -> SchemaAlkaneId {
+        SchemaAlkaneId { block: START_HEIGHT + 5, tx: AMM_FACTORY_PROXY_TX as u64 }
+    }
+
+    /// Initialize global config once per test binary with a regtest `ammdata.networks` block,
+    /// loading it through the module as the registry does.
+    fn init_regtest_amm_config() -> Result<()> {
+        let factory = expected_factory();
+        let ammdata_config = json!({
+            "networks": {
+                "regtest": {
+                    "amm_factories": [format!("{}:{}", factory.block, factory.tx)],
+                    "genesis_height": 0,
+                    "canonical_quotes": [
+                        { "id": "32:0", "unit": "btc" },
+                        { "id": "2:1", "unit": "usd" }
+                    ],
+                    "default_fee_bps": REGTEST_FEE_BPS
+                }
+            }
+        });
+        AmmData::new().set_config(&ammdata_config)?;
+        let (config, temp_dirs) =
+            TestConfigBuilder::new().with_module_config("ammdata", ammdata_config).build();
+
+        // init_config_from expects a real rocksdb at the metashrew path
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        DB::open(&opts, &config.readonly_metashrew_db_dir)?;
+        std::mem::forget(temp_dirs);
+
+        match espo::config::init_config_from(config) {
+            Ok(_) => Ok(()),
+            Err(e) if e.to_string().contains("already initialized") => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[test]
+    fn test_regtest_amm_params_come_from_config() -> Result<()> {
+        init_regtest_amm_config()?;
+
+        assert_eq!(ammdata_genesis_block(Network::Regtest), 0);
+        assert_eq!(get_amm_contract(Network::Regtest)?, expected_factory());
+        assert_eq!(configured_amm_factories(Network::Regtest), vec![expected_factory()]);
+        assert_eq!(amm_default_fee_bps(Network::Regtest), REGTEST_FEE_BPS);
+        assert_eq!(current_default_fee_bps(), REGTEST_FEE_BPS);
+
+        let quotes = canonical_quotes(Network::Regtest);
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].unit, CanonicalQuoteUnit::Btc);
+        assert_eq!(quotes[1].id, REGTEST_USD);
+        assert_eq!(quotes[1].unit, CanonicalQuoteUnit::Usd);
+
+        // Networks without an override keep the built-in values.
+        assert!(get_amm_contract(Network::Signet).is_err());
+        assert_eq!(ammdata_genesis_block(Network::Bitcoin), 904_647);
+        assert_eq!(canonical_quotes(Network::Bitcoin).len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_network_override_fails_config_load() {
+        let err = AmmData::new()
+            .set_config(&json!({ "networks": { "regtest": { "default_fee_bps": 20_000 } } }))
+            .unwrap_err();
+        assert!(err.to_string().contains("default_fee_bps"), "{err}");
+    }
+
+    // ============================================================================
+    // Trading scenario: after the deployment the regtest USD stable and a second token
+    // are minted, pooled 1:1 through the factory, then swapped by two traders.
+    // ============================================================================
+
+    const MINT_HEIGHT: u32 = START_HEIGHT + 6;
+    const POOL_HEIGHT: u32 = START_HEIGHT + 7;
+    const FIRST_SWAP_HEIGHT: u32 = START_HEIGHT + 8;
+    /// Scenario blocks are ten minutes apart from here; deployment blocks carry time 0.
+    const SCENARIO_TIME: u32 = 1_700_000_000;
+    const UNIT: u128 = 100_000_000;
+    const POOL_RESERVE: u128 = 10_000 * UNIT;
+    const MINT_AMOUNT: u128 = 10 * POOL_RESERVE;
+    /// The factory auth token takes 2:0, so the USD stable mints to REGTEST_USD and this next.
+    const SCENARIO_TOKEN: SchemaAlkaneId = SchemaAlkaneId { block: 2, tx: 2 };
+    const CREATOR: u8 = 10;
+    const TRADER_A: u8 = 11;
+    const TRADER_B: u8 = 12;
+    /// One swap per block: (trader, pays the USD stable, amount in).
+    const SWAPS: &[(u8, bool, u128)] =
+        &[(TRADER_A, true, 100 * UNIT), (TRADER_B, true, 300 * UNIT), (TRADER_A, false, 50 * UNIT)];
+
+    fn alkane(id: SchemaAlkaneId) -> AlkaneId {
+        AlkaneId { block: id.block as u128, tx: id.tx as u128 }
+    }
+
+    fn scenario_time(height: u32) -> u32 {
+        SCENARIO_TIME + (height - MINT_HEIGHT) * 600
+    }
+
+    fn scenario_tip() -> u32 {
+        FIRST_SWAP_HEIGHT + SWAPS.len() as u32 - 1
+    }
+
+    /// Blocks of the trading scenario. Each transaction spends the previous one's output 0,
+    /// so the traders' leftovers flow along the chain.
+    fn trading_blocks() -> Vec<(u32, bitcoin::Block)> {
+        let factory = alkane(expected_factory());
+        let creator = test_spk(CREATOR);
+        let mint_usd = cellpack_tx(mint_token_cellpack("USD", MINT_AMOUNT), &[], creator.clone());
+        let mint_token = cellpack_tx(mint_token_cellpack("TOK", MINT_AMOUNT), &[], creator.clone());
+        let create = cellpack_tx(
+            create_pool_cellpack(
+                &factory,
+                &alkane(REGTEST_USD),
+                &alkane(SCENARIO_TOKEN),
+                POOL_RESERVE,
+                POOL_RESERVE,
+            ),
+            &[
+                OutPoint { txid: mint_usd.compute_txid(), vout: 0 },
+                OutPoint { txid: mint_token.compute_txid(), vout: 0 },
+            ],
+            creator,
+        );
+        let mut funds = OutPoint { txid: create.compute_txid(), vout: 0 };
+        let mut blocks = vec![
+            (MINT_HEIGHT, block_with_txs(vec![mint_usd, mint_token], scenario_time(MINT_HEIGHT))),
+            (POOL_HEIGHT, block_with_txs(vec![create], scenario_time(POOL_HEIGHT))),
+        ];
+        for (i, (trader, pays_usd, amount_in)) in SWAPS.iter().enumerate() {
+            let height = FIRST_SWAP_HEIGHT + i as u32;
+            let path = if *pays_usd {
+                [alkane(REGTEST_USD), alkane(SCENARIO_TOKEN)]
+            } else {
+                [alkane(SCENARIO_TOKEN), alkane(REGTEST_USD)]
+            };
+            let swap = cellpack_tx(
+                swap_exact_cellpack(&factory, &path, *amount_in, 0),
+                &[funds],
+                test_spk(*trader),
+            );
+            funds = OutPoint { txid: swap.compute_txid(), vout: 0 };
+            blocks.push((height, block_with_txs(vec![swap], scenario_time(height))));
+        }
+        blocks
+    }
+
+    /// The single pool the scenario created.
+    fn scenario_pool(provider: &AmmDataProvider) -> Result<SchemaAlkaneId> {
+        let pools = provider
+            .get_factory_pools(GetFactoryPoolsParams {
+                blockhash: StateAt::Latest,
+                factory: expected_factory(),
+            })?
+            .pools;
+        assert_eq!(pools.len(), 1, "expected one pool, got {pools:?}");
+        Ok(pools[0])
+    }
+
+    /// Called with each height and the provider before ammdata indexes that block.
+    type BeforeBlock<'a> = &'a mut dyn FnMut(u32, &AmmDataProvider) -> Result<()>;
+
+    fn index_regtest_amm() -> Result<(AmmDataProvider, tempfile::TempDir)> {
+        index_regtest_chain(false, &mut |_, _| Ok(()))
+    }
+
+    fn index_traded_amm() -> Result<(AmmDataProvider, tempfile::TempDir)> {
+        index_regtest_chain(true, &mut |_, _| Ok(()))
+    }
+
+    /// The traded chain indexed the way the indexer does it: each module in its own db with
+    /// a versioned tree and one tree block per chain block, so `with_height` views work.
+    fn index_versioned_traded_amm() -> Result<(AmmDataProvider, tempfile::TempDir)> {
+        index_chain(true, true, &mut |_, _| Ok(()))
+    }
+
+    fn index_regtest_chain(
+        with_trades: bool,
+        before_block: BeforeBlock<'_>,
+    ) -> Result<(AmmDataProvider, tempfile::TempDir)> {
+        index_chain(with_trades, false, before_block)
+    }
+
+    /// Deploys the AMM on a fresh runtime, optionally runs the trading scenario, and runs
+    /// essentials + ammdata over every block. The temp dir holds the espo db and must
+    /// outlive the provider.
+    fn index_chain(
+        with_trades: bool,
+        versioned: bool,
+        before_block: BeforeBlock<'_>,
+    ) -> Result<(AmmDataProvider, tempfile::TempDir)> {
+        init_regtest_amm_config()?;
+
+        let metashrew_runtime = TestMetashrewRuntime::new()?;
+        let mut blocks = std::collections::HashMap::new();
+        for h in 0..START_HEIGHT {
+            let block = protorune::test_helpers::create_block_with_coinbase_tx(h);
+            metashrew_runtime.index_block(&block, h)?;
+            blocks.insert(h, block);
+        }
+        let deployment = setup_amm(&metashrew_runtime, START_HEIGHT)?;
+        let factory = expected_factory();
+        assert_eq!(deployment.factory_proxy_id.block, factory.block as u128);
+        assert_eq!(deployment.factory_proxy_id.tx, factory.tx as u128);
+        blocks.extend(deployment.blocks.clone());
+        let mut tip = START_HEIGHT + 5;
+        if with_trades {
+            for (height, block) in trading_blocks() {
+                metashrew_runtime.index_block(&block, height)?;
+                blocks.insert(height, block);
+                tip = height;
+            }
+        }
+
+        let temp_dir = tempfile::tempdir()?;
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let (essentials_mdb, ammdata_mdb) = if versioned {
+            let open = |name: &str| -> Result<Arc<Mdb>> {
+                let db = Arc::new(DB::open(&opts, temp_dir.path().join(name))?);
+                let tree = Arc::new(VersionedTreeDb::new(db.clone())?);
+                Ok(Arc::new(Mdb::from_db_with_tree(db, b"", tree)))
+            };
+            (open("essentials")?, open("ammdata")?)
+        } else {
+            let db = Arc::new(DB::open(&opts, temp_dir.path().join("espo_db"))?);
+            (
+                Arc::new(Mdb::from_db(db.clone(), b"essentials:")),
+                Arc::new(Mdb::from_db(db.clone(), b"ammdata:")),
+            )
+        };
+
+        let mut essentials = Essentials::new();
+        essentials.set_mdb(essentials_mdb.clone());
+        let essentials_provider = Arc::new(EssentialsProvider::new(essentials_mdb.clone()));
+        let mut ammdata = AmmData::new();
+        ammdata.set_mdb(ammdata_mdb.clone());
+        ammdata.set_essentials(essentials_provider.clone());
+        assert_eq!(ammdata.get_genesis_block(Network::Regtest), 0);
+
+        let ammdata_provider = AmmDataProvider::new(ammdata_mdb.clone(), essentials_provider);
+
+        let mut parent = BlockHash::all_zeros();
+        for h in 0..=tip {
+            let block =
+                blocks.get(&h).ok_or_else(|| anyhow::anyhow!("missing block at height {h}"))?;
+            let traces = metashrew_runtime.get_traces_for_block(h)?;
+            let espo_block = build_espo_block(h, block, traces)?;
+            // No-ops on the unversioned handles.
+            let hash = block.block_hash();
+            essentials_mdb.begin_block(h, &hash, &parent)?;
+            essentials.index_block(espo_block.clone())?;
+            essentials_mdb.finish_block()?;
+            before_block(h, &ammdata_provider)?;
+            ammdata_mdb.begin_block(h, &hash, &parent)?;
+            ammdata.index_block(espo_block)?;
+            ammdata_mdb.finish_block()?;
+            parent = hash;
+        }
+        Ok((ammdata_provider, temp_dir))
+    }
+
+    #[test]
+    fn test_regtest_factory_from_config_is_indexed() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_regtest_amm()?;
+        let factory = expected_factory();
+        let factories = ammdata_provider
+            .get_amm_factories(GetAmmFactoriesParams { blockhash: StateAt::Latest })?
+            .factories;
+        assert!(
+            factories.contains(&factory),
+            "configured regtest factory {factory:?} not registered; got {factories:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_regtest_pool_and_candles_are_indexed() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_traded_amm()?;
+        let pool = scenario_pool(&ammdata_provider)?;
+        let defs = ammdata_provider
+            .get_pool_defs(GetPoolDefsParams { blockhash: StateAt::Latest, pool })?
+            .defs
+            .expect("pool defs indexed");
+        let mut tokens = [defs.base_alkane_id, defs.quote_alkane_id];
+        tokens.sort();
+        assert_eq!(tokens, [REGTEST_USD, SCENARIO_TOKEN]);
+
+        // One single-block candle per swap, newest first, each with traded volume.
+        let pool_id = format!("{}:{}", pool.block, pool.tx);
+        let candles = ammdata_provider
+            .rpc_get_candles(RpcGetCandlesParams {
+                pool: Some(pool_id.clone()),
+                timeframe: None,
+                limit: Some(SWAPS.len() as u64),
+                size: None,
+                page: None,
+                side: None,
+                now: None,
+                blocks: Some(1),
+            })?
+            .value;
+        assert_eq!(candles["ok"], true, "{candles}");
+        let items = candles["candles"].as_array().cloned().unwrap_or_default();
+        assert_eq!(items.len(), SWAPS.len(), "{candles}");
+        for (i, candle) in items.iter().enumerate() {
+            assert_eq!(candle["height"], scenario_tip() - i as u32);
+            assert_ne!(candle["volume"], "0", "{candle}");
+        }
+
+        // Later pages read only their own window but still report the whole series.
+        let second = ammdata_provider
+            .rpc_get_candles(RpcGetCandlesParams {
+                pool: Some(pool_id.clone()),
+                timeframe: None,
+                limit: Some(1),
+                size: None,
+                page: Some(2),
+                side: None,
+                now: None,
+                blocks: Some(1),
+            })?
+            .value;
+        assert_eq!(second["candles"][0]["height"], scenario_tip() - 1, "{second}");
+        assert_eq!(second["candles"][0]["volume"], items[1]["volume"], "{second}");
+        assert_eq!(second["total"], candles["total"], "{second}");
+        assert_eq!(second["has_more"], true, "{second}");
+
+        // The swaps fall within one hour, so the hourly series holds a single candle.
+        let hourly = ammdata_provider
+            .rpc_get_candles(RpcGetCandlesParams {
+                pool: Some(pool_id),
+                timeframe: Some("1h".to_string()),
+                limit: Some(1),
+                size: None,
+                page: None,
+                side: None,
+                now: Some(scenario_time(scenario_tip()) as u64),
+                blocks: None,
+            })?
+            .value;
+        assert_eq!(hourly["ok"], true, "{hourly}");
+        let hourly_items = hourly["candles"].as_array().cloned().unwrap_or_default();
+        assert_eq!(hourly_items.len(), 1, "{hourly}");
+        assert_ne!(hourly_items[0]["volume"], "0", "{hourly}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_pool_swaps_height_index_backfills_older_swaps() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        // Before the last swap block, drop the height index and its backfill marker, as on
+        // a database indexed before the index existed.
+        let (ammdata_provider, _temp_dir) = index_regtest_chain(true, &mut |height, provider| {
+            if height != scenario_tip() {
+                return Ok(());
+            }
+            let table = provider.table();
+            let prefix = table.POOL_SWAPS_BY_HEIGHT.key().to_vec();
+            let mut deletes: Vec<Vec<u8>> = provider
+                .get_range_entries(GetRangeEntriesParams {
+                    blockhash: StateAt::Latest,
+                    start: prefix.clone(),
+                    end: prefix_end_exclusive(&prefix),
+                    limit: usize::MAX,
+                    reverse: false,
+                })?
+                .entries
+                .into_iter()
+                .map(|(k, _)| k)
+                .collect();
+            assert_eq!(deletes.len(), SWAPS.len() - 1);
+            deletes.push(table.POOL_SWAPS_BY_HEIGHT_BACKFILL.key().to_vec());
+            provider.set_batch(SetBatchParams {
+                blockhash: StateAt::Latest,
+                puts: Vec::new(),
+                deletes,
+            })
+        })?;
+
+        let pool = scenario_pool(&ammdata_provider)?;
+        let table = ammdata_provider.table();
+        let prefix = table.pool_swaps_by_height_prefix(&pool);
+        let heights: Vec<u32> = ammdata_provider
+            .get_range_entries(GetRangeEntriesParams {
+                blockhash: StateAt::Latest,
+                start: prefix.clone(),
+                end: prefix_end_exclusive(&prefix),
+                limit: usize::MAX,
+                reverse: false,
+            })?
+            .entries
+            .iter()
+            .filter_map(|(k, _)| table.parse_pool_swaps_by_height_key(&pool, k))
+            .map(|(height, _, _)| height)
+            .collect();
+        let expected: Vec<u32> = (FIRST_SWAP_HEIGHT..=scenario_tip()).collect();
+        assert_eq!(heights, expected);
+
+        let marker = ammdata_provider
+            .get_raw_value(GetRawValueParams {
+                blockhash: StateAt::Latest,
+                key: table.POOL_SWAPS_BY_HEIGHT_BACKFILL.key().to_vec(),
+            })?
+            .value;
+        assert_eq!(marker, Some(Vec::new()), "backfill should have finished");
+        Ok(())
+    }
+
+    #[test]
+    fn test_pool_fees_rpc_on_indexed_amm() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_traded_amm()?;
+
+        let missing = ammdata_provider
+            .rpc_get_pool_fees(RpcGetPoolFeesParams { pool: None, days: None })?
+            .value;
+        assert_eq!(missing["error"], "missing_or_invalid_pool");
+
+        // The pool keeps fee_bps of each swap's input, in the token it received.
+        let pool = scenario_pool(&ammdata_provider)?;
+        let defs = ammdata_provider
+            .get_pool_defs(GetPoolDefsParams { blockhash: StateAt::Latest, pool })?
+            .defs
+            .expect("pool defs indexed");
+        let fee_bps = current_default_fee_bps() as u128;
+        let (mut base_fees, mut quote_fees) = (0u128, 0u128);
+        for (_, pays_usd, amount_in) in SWAPS {
+            let token_in = if *pays_usd { REGTEST_USD } else { SCENARIO_TOKEN };
+            let fee = amount_in * fee_bps / 10_000;
+            if token_in == defs.base_alkane_id {
+                base_fees += fee;
+            } else {
+                quote_fees += fee;
+            }
+        }
+        assert!(base_fees > 0 && quote_fees > 0);
+
+        let value = ammdata_provider
+            .rpc_get_pool_fees(RpcGetPoolFeesParams {
+                pool: Some(format!("{}:{}", pool.block, pool.tx)),
+                days: Some(7),
+            })?
+            .value;
+        assert_eq!(value["ok"], true, "{value}");
+        assert_eq!(value["height"], scenario_tip());
+        // Every swap falls inside the shortest window.
+        for window in ["1d", "7d", "30d"] {
+            let w = &value["windows"][window];
+            assert_eq!(w["base_fees"], base_fees.to_string(), "{window}: {w}");
+            assert_eq!(w["quote_fees"], quote_fees.to_string(), "{window}: {w}");
+            assert_eq!(w["trades"], SWAPS.len(), "{window}: {w}");
+            assert_ne!(w["fees_usd"], "0", "{window}: {w}");
+        }
+        let daily = value["daily"].as_array().cloned().unwrap_or_default();
+        assert_eq!(daily.len(), 1, "{value}");
+        assert_eq!(daily[0]["base_fees"], base_fees.to_string());
+        assert_eq!(daily[0]["quote_fees"], quote_fees.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_screener_pages_by_age() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_regtest_amm()?;
+        let screen = |sort: &str, filters: serde_json::Value, cursor: Option<String>| {
+            ammdata_provider
+                .rpc_get_token_screener(RpcGetTokenScreenerParams {
+                    sort: Some(sort.to_string()),
+                    dir: Some("asc".to_string()),
+                    filters: Some(filters),
+                    cursor,
+                    limit: Some(1),
+                })
+                .map(|resp| resp.value)
+        };
+        assert_eq!(screen("name", json!({}), None)?["error"], "invalid_sort");
+        assert_eq!(
+            screen("age", json!({ "supply": { "min": 1 } }), None)?["error"],
+            "unknown_filter_supply"
+        );
+
+        // The deployment created contracts but no pools; page through them oldest first.
+        let mut items = Vec::new();
+        let mut cursor = None;
+        for _ in 0..100 {
+            let page = screen("age", json!({ "has_pool": false }), cursor)?;
+            assert_eq!(page["ok"], true, "{page}");
+            items.extend(page["items"].as_array().cloned().unwrap_or_default());
+            cursor = page["next_cursor"].as_str().map(|s| s.to_string());
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert!(cursor.is_none(), "screener never ran out of pages");
+        let factory = expected_factory();
+        let factory_id = format!("{}:{}", factory.block, factory.tx);
+        assert!(items.iter().any(|item| item["id"] == factory_id), "{items:?}");
+        let heights: Vec<u64> =
+            items.iter().filter_map(|item| item["creation_height"].as_u64()).collect();
+        assert_eq!(heights.len(), items.len());
+        assert!(heights.windows(2).all(|w| w[0] <= w[1]), "{heights:?}");
+
+        // Nothing is priced yet, so a market cap floor screens everything out.
+        let priced = screen("marketcap", json!({ "marketcap": { "min": "1" } }), None)?;
+        assert_eq!(priced["items"], json!([]));
+        assert!(priced["next_cursor"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_screener_ranks_traded_tokens() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_traded_amm()?;
+        // Pages of one item, descending, until the screener hands back no cursor.
+        let screen_all =
+            |sort: &str, filters: serde_json::Value| -> Result<Vec<serde_json::Value>> {
+                let mut items = Vec::new();
+                let mut cursor = None;
+                for _ in 0..100 {
+                    let page = ammdata_provider
+                        .rpc_get_token_screener(RpcGetTokenScreenerParams {
+                            sort: Some(sort.to_string()),
+                            dir: Some("desc".to_string()),
+                            filters: Some(filters.clone()),
+                            cursor,
+                            limit: Some(1),
+                        })?
+                        .value;
+                    assert_eq!(page["ok"], true, "{page}");
+                    items.extend(page["items"].as_array().cloned().unwrap_or_default());
+                    cursor = page["next_cursor"].as_str().map(|s| s.to_string());
+                    if cursor.is_none() {
+                        return Ok(items);
+                    }
+                }
+                panic!("screener never ran out of pages");
+            };
+        let token_id = format!("{}:{}", SCENARIO_TOKEN.block, SCENARIO_TOKEN.tx);
+        let usd_id = format!("{}:{}", REGTEST_USD.block, REGTEST_USD.tx);
+        let values = |items: &[serde_json::Value], field: &str| -> Vec<u128> {
+            items
+                .iter()
+                .map(|item| match &item[field] {
+                    serde_json::Value::String(s) => s.parse().unwrap_or(0),
+                    v => v.as_u64().unwrap_or(0) as u128,
+                })
+                .collect()
+        };
+
+        // The traded token ranks by its swap volume and by FDV at the pool price.
+        for (sort, field) in [("volume_1d", "volume_1d"), ("fdv", "fdv_usd")] {
+            let items = screen_all(sort, json!({ sort: { "min": "1" } }))?;
+            assert!(items.iter().any(|item| item["id"] == token_id), "{sort}: {items:?}");
+            assert!(items.iter().all(|item| item["has_pool"] == true), "{sort}: {items:?}");
+            let ranked = values(&items, field);
+            assert!(ranked.iter().all(|v| *v > 0), "{sort}: {ranked:?}");
+            assert!(ranked.windows(2).all(|w| w[0] >= w[1]), "{sort}: {ranked:?}");
+        }
+
+        // Both pooled tokens are held by the creator, the traders and the pool.
+        let items = screen_all("holders", json!({ "has_pool": true, "holders": { "min": 1 } }))?;
+        for id in [&token_id, &usd_id] {
+            assert!(items.iter().any(|item| item["id"] == *id), "{id}: {items:?}");
+        }
+        let ranked = values(&items, "holders");
+        assert!(ranked.windows(2).all(|w| w[0] >= w[1]), "{ranked:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_address_pnl_marks_holdings_as_of_to_height() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_versioned_traded_amm()?;
+        let trader_a = address_str_from_spk(test_spk(TRADER_A).as_bytes());
+        let token_id = format!("{}:{}", SCENARIO_TOKEN.block, SCENARIO_TOKEN.tx);
+        let pnl = |provider: &AmmDataProvider, to_height: Option<u32>| {
+            provider
+                .rpc_get_address_pnl(RpcGetAddressPnlParams {
+                    address: Some(trader_a.clone()),
+                    token: None,
+                    from_height: None,
+                    to_height: to_height.map(u64::from),
+                    limit: None,
+                    page: None,
+                })
+                .map(|resp| resp.value)
+        };
+        let token_row = |value: &serde_json::Value| {
+            value["tokens"]
+                .as_array()
+                .and_then(|rows| rows.iter().find(|r| r["token"] == token_id.as_str()).cloned())
+                .unwrap_or_default()
+        };
+
+        // Trader A buys the token in the first swap block and sells part of it at the tip, so
+        // a range ending at the first swap marks the full position at that block's price.
+        let ranged = pnl(&ammdata_provider, Some(FIRST_SWAP_HEIGHT))?;
+        assert_eq!(ranged["ok"], true, "{ranged}");
+        let past = ammdata_provider.with_height(Some(FIRST_SWAP_HEIGHT as u64), true)?;
+        let at_height = pnl(&past, None)?;
+        for field in ["unrealized_fifo_usd", "unrealized_avg_usd"] {
+            assert_eq!(ranged[field], at_height[field], "{field}: {ranged} vs {at_height}");
+        }
+        let (ranged_row, past_row) = (token_row(&ranged), token_row(&at_height));
+        for field in ["held", "price_usd", "fifo_cost_usd", "unrealized_fifo_usd"] {
+            assert_eq!(ranged_row[field], past_row[field], "{field}: {ranged}");
+        }
+        assert_ne!(ranged_row["held"], "0", "{ranged}");
+        assert_eq!(ranged_row["sold"], "0", "{ranged}");
+
+        let latest_row = token_row(&pnl(&ammdata_provider, None)?);
+        assert_ne!(latest_row["held"], ranged_row["held"], "the tip sell is past to_height");
+        assert_eq!(
+            pnl(&ammdata_provider, Some(u32::MAX))?["tokens"],
+            pnl(&ammdata_provider, None)?["tokens"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_trader_leaderboard_ranks_traded_tokens() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_traded_amm()?;
+        let pool = scenario_pool(&ammdata_provider)?;
+        let pool_id = format!("{}:{}", pool.block, pool.tx);
+        let token_id = format!("{}:{}", SCENARIO_TOKEN.block, SCENARIO_TOKEN.tx);
+        let board = |pool: Option<&str>, token: Option<&str>, window: &str, metric: &str| {
+            ammdata_provider
+                .rpc_get_trader_leaderboard(RpcGetTraderLeaderboardParams {
+                    pool: pool.map(|s| s.to_string()),
+                    token: token.map(|s| s.to_string()),
+                    window: Some(window.to_string()),
+                    metric: Some(metric.to_string()),
+                    limit: None,
+                })
+                .map(|resp| resp.value)
+        };
+        let token = Some(token_id.as_str());
+        assert_eq!(board(None, None, "7d", "volume")?["error"], "missing_or_invalid_pool_or_token");
+        assert_eq!(
+            board(token, token, "7d", "volume")?["error"],
+            "missing_or_invalid_pool_or_token"
+        );
+        assert_eq!(board(None, token, "90d", "volume")?["error"], "invalid_window");
+        assert_eq!(board(None, token, "7d", "pnl")?["error"], "invalid_metric");
+
+        let addresses = |resp: &serde_json::Value| -> Vec<String> {
+            resp["items"]
+                .as_array()
+                .map(|items| {
+                    items.iter().filter_map(|i| i["address"].as_str().map(String::from)).collect()
+                })
+                .unwrap_or_default()
+        };
+        let trader_a = address_str_from_spk(test_spk(TRADER_A).as_bytes());
+        let trader_b = address_str_from_spk(test_spk(TRADER_B).as_bytes());
+
+        // The scenario's blocks are years old, so windows anchored to the wall clock would
+        // be empty; every window ends on the indexed tip's day and holds all three swaps.
+        for window in ["1d", "7d", "30d", "all"] {
+            let resp = board(None, token, window, "volume")?;
+            assert_eq!(resp["ok"], true, "{resp}");
+            assert_eq!(resp["token"], token_id);
+            // B paid 300 USD in one swap; A paid 100 USD and sold back 50 TOK.
+            assert_eq!(addresses(&resp), vec![trader_b.clone(), trader_a.clone()], "{resp}");
+            assert_eq!(resp["items"][0]["rank"], 1);
+            assert_eq!(resp["items"][1]["trades"], 2, "{resp}");
+
+            let trades = board(None, token, window, "trades")?;
+            assert_eq!(addresses(&trades), vec![trader_a.clone(), trader_b.clone()], "{trades}");
+            // Both ended up net buyers of the token.
+            let net_sell = board(None, token, window, "net_sell")?;
+            assert_eq!(net_sell["items"], json!([]), "{net_sell}");
+
+            let pool = board(Some(pool_id.as_str()), None, window, "volume")?;
+            assert_eq!(pool["pool"], pool_id);
+            assert_eq!(addresses(&pool), vec![trader_b.clone(), trader_a.clone()], "{pool}");
+        }
+
+        // The USD stable's board is the mirror image: both paid in more USD than they got back.
+        let usd_id = format!("{}:{}", REGTEST_USD.block, REGTEST_USD.tx);
+        let usd_net_sell = board(None, Some(usd_id.as_str()), "7d", "net_sell")?;
+        assert_eq!(addresses(&usd_net_sell), vec![trader_b.clone(), trader_a], "{usd_net_sell}");
+        let usd_net_buy = board(None, Some(usd_id.as_str()), "7d", "net_buy")?;
+        assert_eq!(usd_net_buy["items"], json!([]), "{usd_net_buy}");
+
+        // `limit` reads only the top of the rank index.
+        let top = ammdata_provider
+            .rpc_get_trader_leaderboard(RpcGetTraderLeaderboardParams {
+                pool: None,
+                token: Some(token_id.clone()),
+                window: Some("7d".to_string()),
+                metric: Some("volume".to_string()),
+                limit: Some(1),
+            })?
+            .value;
+        assert_eq!(addresses(&top), vec![trader_b], "{top}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_rewind_rolls_back_each_module_tree() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        init_regtest_amm_config()?;
+        let mut mods = ModuleRegistry::new();
+        mods.register_module(Essentials::new());
+        mods.register_module(AmmData::new());
+
+        let hashes: Vec<BlockHash> =
+            (1..=3u8).map(|i| BlockHash::from_byte_array([i; 32])).collect();
+        let mut parent = BlockHash::from_byte_array([0u8; 32]);
+        for (i, hash) in hashes.iter().enumerate() {
+            for m in mods.modules() {
+                let mdb = m.get_mdb().expect("registered modules carry their mdb");
+                mdb.begin_block(i as u32 + 1, hash, &parent)?;
+                mdb.finish_block()?;
+            }
+            parent = *hash;
+        }
+
+        let dropped = mods.rollback_to_height(2)?;
+        assert_eq!(dropped, vec![("essentials", 2), ("ammdata", 2)]);
+        for m in mods.modules() {
+            let mdb = m.get_mdb().expect("registered modules carry their mdb");
+            assert!(mdb.has_blockhash(&hashes[0])?, "{}", m.get_name());
+            assert!(!mdb.has_blockhash(&hashes[1])?, "{}", m.get_name());
+            assert!(!mdb.has_blockhash(&hashes[2])?, "{}", m.get_name());
+            assert_eq!(mdb.indexed_height_bounds()?, Some((1, 1)), "{}", m.get_name());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_alert_rpcs_validate_and_poll_empty() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_regtest_amm()?;
+        let register = |watcher: &str, condition: serde_json::Value| {
+            ammdata_provider
+                .rpc_register_alert(RpcRegisterAlertParams {
+                    watcher: Some(watcher.to_string()),
+                    token: None,
+                    condition: Some(condition),
+                })
+                .map(|resp| resp.value)
+        };
+        assert_eq!(
+            register("bad watcher", json!({ "kind": "large_swap", "min_sats": 1 }))?["error"],
+            "missing_or_invalid_watcher"
+        );
+        assert_eq!(register("desk", json!({ "kind": "moon" }))?["error"], "invalid_condition_kind");
+        assert_eq!(
+            register("desk", json!({ "kind": "price_above", "token": "2:0" }))?["error"],
+            "invalid_condition_price_usd"
+        );
+
+        // Watchers exist once they register an alert; nobody has.
+        let alerts = ammdata_provider
+            .rpc_get_alerts(RpcGetAlertsParams { watcher: Some("desk".into()), token: None })?;
+        assert_eq!(alerts.value["error"], "unknown_watcher");
+        let poll = ammdata_provider
+            .rpc_get_alert_events(RpcGetAlertEventsParams {
+                watcher: Some("desk".to_string()),
+                token: Some("00".repeat(32)),
+                cursor: None,
+                limit: None,
+            })?
+            .value;
+        assert_eq!(poll["error"], "unknown_watcher");
+        Ok(())
+    }
+
+    #[test]
+    fn test_alerts_fire_on_traded_pool() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let scale = PRICE_SCALE;
+        let token_id = format!("{}:{}", SCENARIO_TOKEN.block, SCENARIO_TOKEN.tx);
+        // The pool opens at 1 USD per token; the buys push it past 1.05 and the last sell
+        // leaves it there, so the alert fires once.
+        let above = json!({
+            "kind": "price_above",
+            "token": token_id,
+            "price_usd": (scale * 105 / 100).to_string(),
+        });
+        let below = json!({
+            "kind": "price_below",
+            "token": token_id,
+            "price_usd": (scale / 2).to_string(),
+        });
+        let mut issued: Option<(String, String)> = None;
+        let (ammdata_provider, _temp_dir) = index_regtest_chain(true, &mut |height, provider| {
+            if height != FIRST_SWAP_HEIGHT {
+                return Ok(());
+            }
+            let register = |token: Option<String>, condition: &serde_json::Value| {
+                provider
+                    .rpc_register_alert(RpcRegisterAlertParams {
+                        watcher: Some("desk".to_string()),
+                        token,
+                        condition: Some(condition.clone()),
+                    })
+                    .map(|resp| resp.value)
+            };
+            let first = register(None, &above)?;
+            assert_eq!(first["ok"], true, "{first}");
+            assert_eq!(first["created"], true, "{first}");
+            let token = first["token"].as_str().expect("first alert issues a token").to_string();
+            // Later calls for the watcher need its token.
+            assert_eq!(register(None, &below)?["error"], "invalid_token");
+            let second = register(Some(token.clone()), &below)?;
+            assert_eq!(second["created"], true, "{second}");
+            assert!(second["token"].is_null(), "{second}");
+            let again = register(Some(token.clone()), &above)?;
+            assert_eq!(again["created"], false, "{again}");
+            assert_eq!(again["alert_id"], first["alert_id"]);
+            issued = Some((token, first["alert_id"].as_str().unwrap_or_default().to_string()));
+            Ok(())
+        })?;
+        let (token, above_id) = issued.expect("alerts registered before the first swap");
+
+        let alerts = ammdata_provider
+            .rpc_get_alerts(RpcGetAlertsParams {
+                watcher: Some("desk".into()),
+                token: Some(token.clone()),
+            })?
+            .value;
+        assert_eq!(alerts["items"].as_array().map(|a| a.len()), Some(2), "{alerts}");
+        let wrong = ammdata_provider
+            .rpc_get_alerts(RpcGetAlertsParams {
+                watcher: Some("desk".into()),
+                token: Some("00".repeat(32)),
+            })?
+            .value;
+        assert_eq!(wrong["error"], "invalid_token");
+
+        let poll = |cursor: Option<String>| {
+            ammdata_provider
+                .rpc_get_alert_events(RpcGetAlertEventsParams {
+                    watcher: Some("desk".to_string()),
+                    token: Some(token.clone()),
+                    cursor,
+                    limit: None,
+                })
+                .map(|resp| resp.value)
+        };
+        let page = poll(None)?;
+        assert_eq!(page["ok"], true, "{page}");
+        let items = page["items"].as_array().cloned().unwrap_or_default();
+        assert_eq!(items.len(), 1, "{page}");
+        assert_eq!(items[0]["alert_id"], above_id.as_str());
+        let height = items[0]["height"].as_u64().unwrap_or(0) as u32;
+        assert!((FIRST_SWAP_HEIGHT..=scenario_tip()).contains(&height), "{page}");
+        let price: u128 = items[0]["value"].as_str().unwrap_or("0").parse()?;
+        assert!(price >= scale * 105 / 100, "{page}");
+
+        // Polling from the returned cursor finds nothing newer.
+        let cursor = page["cursor"].as_str().map(|c| c.to_string());
+        assert!(cursor.is_some(), "{page}");
+        let next = poll(cursor.clone())?;
+        assert_eq!(next["items"], json!([]), "{next}");
+        assert_eq!(next["cursor"].as_str().map(|c| c.to_string()), cursor);
+        assert_eq!(poll(Some("zz".to_string()))?["error"], "invalid_cursor");
+
+        let remove = |alert_id: &str, token: &str| {
+            ammdata_provider
+                .rpc_remove_alert(RpcRemoveAlertParams {
+                    watcher: Some("desk".to_string()),
+                    token: Some(token.to_string()),
+                    alert_id: Some(alert_id.to_string()),
+                })
+                .map(|resp| resp.value)
+        };
+        assert_eq!(remove(&above_id, &"00".repeat(32))?["error"], "invalid_token");
+        assert_eq!(remove("00000000000000ff", &token)?["error"], "unknown_alert");
+        assert_eq!(remove(&above_id, &token)?["removed"], true);
+        let alerts = ammdata_provider
+            .rpc_get_alerts(RpcGetAlertsParams {
+                watcher: Some("desk".into()),
+                token: Some(token),
+            })?
+            .value;
+        assert_eq!(alerts["items"].as_array().map(|a| a.len()), Some(1), "{alerts}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_airdrop_list_at_a_past_height() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let (ammdata_provider, _temp_dir) = index_versioned_traded_amm()?;
+        let essentials = ammdata_provider.essentials();
+        let token_id = format!("{}:{}", SCENARIO_TOKEN.block, SCENARIO_TOKEN.tx);
+        let airdrop = |view: &EssentialsProvider| {
+            view.rpc_get_airdrop_list(RpcGetAirdropListParams {
+                tokens: vec![token_id.clone()],
+                min_balance: None,
+                exclude_contracts: false,
+                exclude_addresses: Vec::new(),
+                csv: false,
+                limit: None,
+                page: None,
+            })
+            .map(|resp| resp.value)
+        };
+
+        // Right after the mint the creator holds the whole supply.
+        let minted = airdrop(&essentials.with_height(Some(u64::from(MINT_HEIGHT)), true)?)?;
+        assert_eq!(minted["ok"], true, "{minted}");
+        assert_eq!(minted["height"], MINT_HEIGHT, "{minted}");
+        assert_eq!(minted["count"], 1, "{minted}");
+        let creator = address_str_from_spk(test_spk(CREATOR).as_bytes());
+        assert_eq!(
+            minted["items"],
+            json!([{ "type": "address", "holder": creator, "amount": MINT_AMOUNT.to_string() }]),
+            "{minted}"
+        );
+
+        // At the tip the pool and the traders hold some too.
+        let latest = airdrop(essentials)?;
+        assert!(latest["count"].as_u64().unwrap_or(0) > 1, "{latest}");
+        let trader = address_str_from_spk(test_spk(TRADER_A).as_bytes());
+        let holders = latest["items"].as_array().cloned().unwrap_or_default();
+        assert!(holders.iter().any(|item| item["holder"] == trader.as_str()), "{latest}");
+        assert_ne!(latest["hash"], minted["hash"]);
+
+        let unindexed = essentials.with_height(Some(u64::from(scenario_tip() + 1)), true);
+        assert!(unindexed.is_err());
+        Ok(())
+    }
+
+    /// Registers `conditions` for the "desk" watcher and returns its token and the alert ids.
+    fn register_desk_alerts(
+        provider: &AmmDataProvider,
+        conditions: &[serde_json::Value],
+    ) -> Result<(String, Vec<String>)> {
+        let mut token: Option<String> = None;
+        let mut ids = Vec::new();
+        for condition in conditions {
+            let resp = provider
+                .rpc_register_alert(RpcRegisterAlertParams {
+                    watcher: Some("desk".to_string()),
+                    token: token.clone(),
+                    condition: Some(condition.clone()),
+                })?
+                .value;
+            assert_eq!(resp["created"], true, "{resp}");
+            if let Some(issued) = resp["token"].as_str() {
+                token = Some(issued.to_string());
+            }
+            ids.push(resp["alert_id"].as_str().unwrap_or_default().to_string());
+        }
+        Ok((token.expect("first alert issues a token"), ids))
+    }
+
+    fn desk_events(provider: &AmmDataProvider, token: &str) -> Result<Vec<serde_json::Value>> {
+        let page = provider
+            .rpc_get_alert_events(RpcGetAlertEventsParams {
+                watcher: Some("desk".to_string()),
+                token: Some(token.to_string()),
+                cursor: None,
+                limit: None,
+            })?
+            .value;
+        assert_eq!(page["ok"], true, "{page}");
+        Ok(page["items"].as_array().cloned().unwrap_or_default())
+    }
+
+    #[test]
+    fn test_tvl_change_and_large_swap_alerts_fire() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        // 100k USD per BTC, so the 300 USD buy is worth 300k sats and the other swaps less.
+        let btc_usd = 100_000 * PRICE_SCALE;
+        let mut registered: Option<(String, Vec<String>)> = None;
+        let (ammdata_provider, _temp_dir) = index_regtest_chain(true, &mut |height, provider| {
+            if height != FIRST_SWAP_HEIGHT {
+                return Ok(());
+            }
+            // No feed prices regtest heights; ammdata falls back to the last stored price.
+            provider.set_batch(SetBatchParams {
+                blockhash: StateAt::Latest,
+                puts: vec![(
+                    provider.table().btc_usd_price_key(u64::from(height - 1)),
+                    encode_u128_value(btc_usd)?,
+                )],
+                deletes: Vec::new(),
+            })?;
+            let pool = scenario_pool(provider)?;
+            let pool = format!("{}:{}", pool.block, pool.tx);
+            registered = Some(register_desk_alerts(
+                provider,
+                &[
+                    json!({ "kind": "tvl_change", "pool": pool, "change_bps": 1 }),
+                    json!({ "kind": "large_swap", "min_sats": "150000" }),
+                ],
+            )?);
+            Ok(())
+        })?;
+        let (token, ids) = registered.expect("alerts registered before the first swap");
+
+        let events = desk_events(&ammdata_provider, &token)?;
+        let of = |id: &str| -> Vec<&serde_json::Value> {
+            events.iter().filter(|ev| ev["alert_id"] == id).collect()
+        };
+        let tvl = of(&ids[0]);
+        assert!(!tvl.is_empty(), "{events:?}");
+        for ev in &tvl {
+            let height = ev["height"].as_u64().unwrap_or(0) as u32;
+            assert!((FIRST_SWAP_HEIGHT..=scenario_tip()).contains(&height), "{ev}");
+            let bps: u128 = ev["value"].as_str().unwrap_or("0").parse()?;
+            assert!(bps >= 1, "{ev}");
+            assert!(ev["txid"].is_null(), "{ev}");
+        }
+        let swaps = of(&ids[1]);
+        assert_eq!(swaps.len(), 1, "{events:?}");
+        assert_eq!(swaps[0]["height"].as_u64(), Some(u64::from(FIRST_SWAP_HEIGHT + 1)));
+        let sats: u128 = swaps[0]["value"].as_str().unwrap_or("0").parse()?;
+        assert!(sats >= 150_000, "{}", swaps[0]);
+        assert!(swaps[0]["txid"].is_string(), "{}", swaps[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_alerts_survive_a_rewind_on_the_versioned_tree() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        let _guard = rt.enter();
+
+        let mut registered: Option<(String, Vec<String>)> = None;
+        let (ammdata_provider, _temp_dir) = index_chain(true, true, &mut |height, provider| {
+            if height != FIRST_SWAP_HEIGHT {
+                return Ok(());
+            }
+            let pool = scenario_pool(provider)?;
+            let pool = format!("{}:{}", pool.block, pool.tx);
+            registered = Some(register_desk_alerts(
+                provider,
+                &[json!({ "kind": "tvl_change", "pool": pool, "change_bps": 1 })],
+            )?);
+            Ok(())
+        })?;
+        let (token, _) = registered.expect("alert registered before the first swap");
+        // Registered between blocks, yet evaluated by the blocks after it.
+        assert!(!desk_events(&ammdata_provider, &token)?.is_empty());
+
+        // The swaps' blocks are dropped with their events; the alert stays.
+        ammdata_provider.mdb().rollback_to_height(FIRST_SWAP_HEIGHT)?;
+        assert_eq!(desk_events(&ammdata_provider, &token)?, Vec::<serde_json::Value>::new());
+        let alerts = ammdata_provider
+            .rpc_get_alerts(RpcGetAlertsParams {
+                watcher: Some("desk".into()),
+                token: Some(token),
+            })?
+            .value;
+        assert_eq!(alerts["items"].as_array().map(|a| a.len()), Some(1), "{alerts}");
+        Ok(())
+    }
 }
 
 // Helper function that shows how trace extraction would work
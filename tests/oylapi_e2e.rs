@@ -95,6 +95,7 @@ mod tests {
             sdb_poll_ms: 100,
             indexer_block_delay_ms: 0,
             port: 9090,
+            grpc_host: None,
             explorer_host: None,
             explorer_base_path: String::from("/"),
             explorer_pizza_tv_endpoint: String::from("https://tv.pizza.fun"),
@@ -104,6 +105,7 @@ mod tests {
             debug: false,
             debug_ignore_ms: 0,
             debug_backup: None,
+            admin: None,
             safe_tip_hook_script: None,
             block_source_mode: espo::core::blockfetcher::BlockFetchMode::Auto,
             compact_tx_trace_rows: true,
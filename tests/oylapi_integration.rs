@@ -55,6 +55,7 @@ fn init_global_config() {
 
         let config = espo::config::AppConfig {
             debug_backup: None,
+            admin: None,
             debug_ignore_ms: 0,
             safe_tip_hook_script: None,
             readonly_metashrew_db_dir: metashrew_db.to_str().unwrap().to_string(),
@@ -71,6 +72,7 @@ fn init_global_config() {
             sdb_poll_ms: 100,
             indexer_block_delay_ms: 0,
             port: 9090,
+            grpc_host: None,
             explorer_host: None,
             explorer_base_path: String::from("/"),
             explorer_pizza_tv_endpoint: String::from("https://tv.pizza.fun"),